@@ -17,6 +17,9 @@ struct BenchConfig {
     pub worker_threads: usize,
     pub n_operations: u64,
     pub n_client: u64,
+    /// Number of requests a client sends in one `client_write_many()` call.
+    /// `1` means to use `client_write()`.
+    pub batch_size: u64,
     pub members: BTreeSet<u64>,
 }
 
@@ -24,8 +27,8 @@ impl Display for BenchConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "workers: {}, clients: {}, n: {}, raft_members: {:?}",
-            self.worker_threads, self.n_client, self.n_operations, self.members
+            "workers: {}, clients: {}, n: {}, batch: {}, raft_members: {:?}",
+            self.worker_threads, self.n_client, self.n_operations, self.batch_size, self.members
         )
     }
 }
@@ -37,6 +40,7 @@ fn bench_cluster_of_1() -> anyhow::Result<()> {
         worker_threads: 32,
         n_operations: 100_000,
         n_client: 256,
+        batch_size: 1,
         members: btreeset! {0},
     })?;
     Ok(())
@@ -49,6 +53,7 @@ fn bench_cluster_of_3() -> anyhow::Result<()> {
         worker_threads: 32,
         n_operations: 100_000,
         n_client: 256,
+        batch_size: 1,
         members: btreeset! {0,1,2},
     })?;
     Ok(())
@@ -61,11 +66,25 @@ fn bench_cluster_of_5() -> anyhow::Result<()> {
         worker_threads: 32,
         n_operations: 100_000,
         n_client: 256,
+        batch_size: 1,
         members: btreeset! {0,1,2,3,4},
     })?;
     Ok(())
 }
 
+#[test]
+#[ignore]
+fn bench_batch_cluster_of_3() -> anyhow::Result<()> {
+    bench_with_config(&BenchConfig {
+        worker_threads: 32,
+        n_operations: 100_000,
+        n_client: 256,
+        batch_size: 100,
+        members: btreeset! {0,1,2},
+    })?;
+    Ok(())
+}
+
 fn bench_with_config(bench_config: &BenchConfig) -> anyhow::Result<()> {
     let rt = Builder::new_multi_thread()
         .worker_threads(bench_config.worker_threads)
@@ -78,7 +97,7 @@ fn bench_with_config(bench_config: &BenchConfig) -> anyhow::Result<()> {
     rt.block_on(do_bench(bench_config))
 }
 
-/// Benchmark client_write.
+/// Benchmark client_write, or client_write_many if `batch_size` is greater than 1.
 ///
 /// Cluster config:
 /// - Log: in-memory BTree
@@ -98,6 +117,7 @@ async fn do_bench(bench_config: &BenchConfig) -> anyhow::Result<()> {
     router.new_cluster(config.clone(), bench_config.members.clone()).await?;
 
     let n = bench_config.n_operations;
+    let batch_size = bench_config.batch_size;
     let total = n * bench_config.n_client;
 
     let leader = router.get_raft(0);
@@ -108,14 +128,33 @@ async fn do_bench(bench_config: &BenchConfig) -> anyhow::Result<()> {
     for _nc in 0..bench_config.n_client {
         let l = leader.clone();
         let h = tokio::spawn(async move {
-            for _i in 0..n {
-                l.client_write(ClientRequest {})
-                    .await
-                    .map_err(|e| {
-                        eprintln!("client_write error: {:?}", e);
+            if batch_size <= 1 {
+                for _i in 0..n {
+                    l.client_write(ClientRequest {})
+                        .await
+                        .map_err(|e| {
+                            eprintln!("client_write error: {:?}", e);
+                            e
+                        })
+                        .unwrap();
+                }
+                return;
+            }
+
+            let mut sent = 0;
+            while sent < n {
+                let size = std::cmp::min(batch_size, n - sent);
+                let batch = (0..size).map(|_| ClientRequest {}).collect::<Vec<_>>();
+
+                let results = l.client_write_many(batch).await.unwrap();
+                for res in results {
+                    res.map_err(|e| {
+                        eprintln!("client_write_many error: {:?}", e);
                         e
                     })
                     .unwrap();
+                }
+                sent += size;
             }
         });
 
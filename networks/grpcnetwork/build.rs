@@ -0,0 +1,12 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto/raft.proto");
+
+    // Use the vendored `protoc` unless one is specified with `PROTOC`,
+    // so that building this crate does not require `protoc` to be installed.
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+
+    tonic_build::compile_protos("proto/raft.proto")?;
+    Ok(())
+}
@@ -0,0 +1,399 @@
+use std::error::Error;
+use std::future::Future;
+use std::io::SeekFrom;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use openraft::error::CheckIsLeaderError;
+use openraft::error::InstallSnapshotError;
+use openraft::error::NetworkError;
+use openraft::error::PayloadTooLarge;
+use openraft::error::RPCError;
+use openraft::error::RaftError;
+use openraft::error::RemoteError;
+use openraft::error::ReplicationClosed;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::error::StreamingError;
+use openraft::error::Timeout;
+use openraft::error::Unreachable;
+use openraft::network::RPCOption;
+use openraft::network::RPCTypes;
+use openraft::raft::AppendEntriesRequest;
+use openraft::raft::AppendEntriesResponse;
+use openraft::raft::HandoffLeaderRequest;
+use openraft::raft::HandoffLeaderResponse;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::raft::InstallSnapshotResponse;
+use openraft::raft::ReadIndexRequest;
+use openraft::raft::ReadIndexResponse;
+use openraft::raft::SnapshotResponse;
+use openraft::raft::TransferLeaderRequest;
+use openraft::raft::VoteRequest;
+use openraft::raft::VoteResponse;
+use openraft::storage::SnapshotSignature;
+use openraft::AnyError;
+use openraft::BasicNode;
+use openraft::OptionalSend;
+use openraft::RaftNetwork;
+use openraft::RaftNetworkFactory;
+use openraft::Snapshot;
+use openraft::StorageError;
+use openraft::StorageIOError;
+use openraft::Vote;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tonic::transport::Channel;
+use tonic::transport::Endpoint;
+use tonic::Code;
+use tonic::Status;
+
+use crate::convert::append_entries_request_to_pb;
+use crate::convert::SnapshotDeltaRequest;
+use crate::pb;
+use crate::pb::raft_service_client::RaftServiceClient;
+use crate::AppDataCodec;
+use crate::GrpcTypeConfig;
+
+/// Builds a [`GrpcNetwork`] for every target node, connecting to [`BasicNode::addr`].
+pub struct GrpcNetworkFactory<C, Codec>
+where C: GrpcTypeConfig
+{
+    /// The id of the local node, used in reported errors.
+    id: u64,
+    codec: Arc<Codec>,
+    connect_timeout: Duration,
+    _p: PhantomData<C>,
+}
+
+impl<C, Codec> GrpcNetworkFactory<C, Codec>
+where
+    C: GrpcTypeConfig,
+    Codec: AppDataCodec<C::D>,
+{
+    /// Create a factory for the node `id`, which encodes application data with `codec`.
+    pub fn new(id: u64, codec: Codec) -> Self {
+        Self {
+            id,
+            codec: Arc::new(codec),
+            connect_timeout: Duration::from_millis(1_000),
+            _p: PhantomData,
+        }
+    }
+
+    /// Set the timeout to establish a connection to a remote node. By default it is 1 second.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+}
+
+impl<C, Codec> RaftNetworkFactory<C> for GrpcNetworkFactory<C, Codec>
+where
+    C: GrpcTypeConfig,
+    C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+    Codec: AppDataCodec<C::D>,
+{
+    type Network = GrpcNetwork<C, Codec>;
+
+    async fn new_client(&mut self, target: u64, node: &BasicNode) -> Self::Network {
+        let uri = format!("http://{}", node.addr);
+
+        // The channel connects on the first RPC and reconnects after a failure.
+        let client = match Endpoint::from_shared(uri) {
+            Ok(endpoint) => Ok(RaftServiceClient::new(
+                endpoint.connect_timeout(self.connect_timeout).connect_lazy(),
+            )),
+            Err(e) => {
+                tracing::warn!("invalid address of node {}: {}: {}", target, node.addr, e);
+                Err(AnyError::new(&e))
+            }
+        };
+
+        GrpcNetwork {
+            id: self.id,
+            target,
+            client,
+            codec: self.codec.clone(),
+            _p: PhantomData,
+        }
+    }
+}
+
+/// Sends RPCs to a single target node with a tonic client.
+///
+/// It implements [`RaftNetwork`] and thus [`RaftNetworkV2`] with the blanket implementation:
+/// a snapshot is sent in chunks, each chunk in an `InstallSnapshot` RPC.
+///
+/// A failed RPC is reported as an [`RPCError`]:
+/// - [`Unreachable`], if it can not connect to the target, or the target returns `Unavailable`.
+///   Openraft backs off before retrying.
+/// - [`Timeout`], if it does not finish in [`RPCOption::soft_ttl()`].
+/// - [`PayloadTooLarge`], if an `AppendEntries` message exceeds the size limit of the client or the
+///   server. Openraft sends fewer entries in the next RPC.
+/// - [`RemoteError`], if the remote `Raft` returns an error in the response body, such as a
+///   rejected snapshot chunk, a `ReadIndex` request sent to a non-leader, or a fatal error.
+/// - [`NetworkError`] for any other failure, including an `Internal` status.
+///
+/// A snapshot delta is sent as a whole in one `SnapshotDelta` RPC. If it exceeds the message size
+/// limit, it returns [`Unreachable`] and Openraft sends a full snapshot instead.
+///
+/// [`RaftNetworkV2`]: openraft::network::v2::RaftNetworkV2
+pub struct GrpcNetwork<C, Codec>
+where C: GrpcTypeConfig
+{
+    id: u64,
+    target: u64,
+    client: Result<RaftServiceClient<Channel>, AnyError>,
+    codec: Arc<Codec>,
+    _p: PhantomData<C>,
+}
+
+impl<C, Codec> GrpcNetwork<C, Codec>
+where
+    C: GrpcTypeConfig,
+    Codec: AppDataCodec<C::D>,
+{
+    fn client<E: Error>(&self) -> Result<RaftServiceClient<Channel>, RPCError<C, E>> {
+        match &self.client {
+            Ok(client) => Ok(client.clone()),
+            Err(e) => Err(RPCError::Unreachable(Unreachable::new(e))),
+        }
+    }
+
+    /// Build a request that tells the server to give up after `option.soft_ttl()`.
+    fn request<T>(msg: T, option: &RPCOption) -> tonic::Request<T> {
+        let mut req = tonic::Request::new(msg);
+        req.set_timeout(option.soft_ttl());
+        req
+    }
+
+    /// Wait for the response, cancelling it after `option.soft_ttl()`.
+    async fn send<T>(
+        &self,
+        action: RPCTypes,
+        option: &RPCOption,
+        fu: impl Future<Output = Result<tonic::Response<T>, Status>>,
+    ) -> Result<Result<T, Status>, Timeout<C>> {
+        let timeout = option.soft_ttl();
+        let res = tokio::time::timeout(timeout, fu).await.map_err(|_elapsed| self.timeout(action, timeout))?;
+        Ok(res.map(|resp| resp.into_inner()))
+    }
+
+    fn status_to_rpc_error<E: Error>(&self, action: RPCTypes, option: &RPCOption, status: Status) -> RPCError<C, E> {
+        match status.code() {
+            Code::Unavailable => RPCError::Unreachable(Unreachable::new(&status)),
+            Code::DeadlineExceeded => RPCError::Timeout(self.timeout(action, option.soft_ttl())),
+            _ => RPCError::Network(NetworkError::new(&status)),
+        }
+    }
+
+    /// Decode a response body, which carries the result returned by the remote `Raft`.
+    fn decode<T, E, P>(&self, resp: P) -> Result<T, RPCError<C, E>>
+    where
+        E: Error,
+        Result<T, E>: TryFrom<P, Error = AnyError>,
+    {
+        let res: Result<T, E> = resp.try_into().map_err(codec_error)?;
+        res.map_err(|e| RPCError::RemoteError(RemoteError::new(self.target, e)))
+    }
+
+    fn timeout(&self, action: RPCTypes, timeout: Duration) -> Timeout<C> {
+        Timeout {
+            action,
+            id: self.id,
+            target: self.target,
+            timeout,
+        }
+    }
+}
+
+/// An error that occurs when encoding a request or decoding a response.
+fn codec_error<C, E>(e: AnyError) -> RPCError<C, E>
+where
+    C: GrpcTypeConfig,
+    E: Error,
+{
+    RPCError::Network(NetworkError::new(&e))
+}
+
+impl<C, Codec> RaftNetwork<C> for GrpcNetwork<C, Codec>
+where
+    C: GrpcTypeConfig,
+    C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+    Codec: AppDataCodec<C::D>,
+{
+    async fn append_entries(
+        &mut self,
+        rpc: AppendEntriesRequest<C>,
+        option: RPCOption,
+    ) -> Result<AppendEntriesResponse<C>, RPCError<C, RaftError<C>>> {
+        let mut client = self.client()?;
+
+        let n = rpc.entries.len() as u64;
+        let req = append_entries_request_to_pb(rpc, &*self.codec).map_err(codec_error)?;
+
+        let action = RPCTypes::AppendEntries;
+        let res = self.send(action, &option, client.append_entries(Self::request(req, &option))).await?;
+
+        let resp = match res {
+            Ok(resp) => resp,
+            // A message exceeding the size limit is rejected by tonic with `OutOfRange` or
+            // `ResourceExhausted`. Ask Openraft to send fewer entries.
+            Err(status) if n > 1 && matches!(status.code(), Code::OutOfRange | Code::ResourceExhausted) => {
+                tracing::debug!("AppendEntries with {} entries is too large: {}", n, status);
+                return Err(RPCError::PayloadTooLarge(PayloadTooLarge::new_entries_hint(n / 2)));
+            }
+            Err(status) => return Err(self.status_to_rpc_error(action, &option, status)),
+        };
+
+        self.decode(resp)
+    }
+
+    async fn install_snapshot(
+        &mut self,
+        rpc: InstallSnapshotRequest<C>,
+        option: RPCOption,
+    ) -> Result<InstallSnapshotResponse<C>, RPCError<C, RaftError<C, InstallSnapshotError>>> {
+        let mut client = self.client()?;
+
+        let action = RPCTypes::InstallSnapshot;
+        let req = pb::InstallSnapshotRequest::from(rpc);
+        let resp = self
+            .send(action, &option, client.install_snapshot(Self::request(req, &option)))
+            .await?
+            .map_err(|status| self.status_to_rpc_error(action, &option, status))?;
+
+        self.decode(resp)
+    }
+
+    async fn snapshot_delta(
+        &mut self,
+        vote: Vote<u64>,
+        base: SnapshotSignature<u64>,
+        mut delta: Snapshot<C>,
+        cancel: impl Future<Output = ReplicationClosed> + OptionalSend + 'static,
+        option: RPCOption,
+    ) -> Result<SnapshotResponse<C>, StreamingError<C, RaftError<C, SnapshotBaseMismatch<C>>>> {
+        let mut client = self.client()?;
+
+        let mut data = Vec::new();
+        let read = async {
+            delta.snapshot.seek(SeekFrom::Start(0)).await?;
+            delta.snapshot.read_to_end(&mut data).await
+        };
+        read.await.map_err(|e| {
+            let io_err = StorageIOError::read_snapshot(Some(delta.meta.signature()), &e);
+            StorageError::from(io_err)
+        })?;
+
+        let req = pb::SnapshotDeltaRequest::from(SnapshotDeltaRequest {
+            vote,
+            base,
+            meta: delta.meta,
+            data,
+        });
+
+        let action = RPCTypes::SnapshotDelta;
+        let res = tokio::select! {
+            res = self.send(action, &option, client.snapshot_delta(Self::request(req, &option))) => res?,
+            closed = cancel => return Err(StreamingError::Closed(closed)),
+        };
+
+        let resp = match res {
+            Ok(resp) => resp,
+            // A delta exceeding the message size limit can not be split. Send a full snapshot in
+            // chunks instead.
+            Err(status) if matches!(status.code(), Code::OutOfRange | Code::ResourceExhausted) => {
+                tracing::debug!("snapshot delta is too large: {}", status);
+                return Err(StreamingError::Unreachable(Unreachable::new(&status)));
+            }
+            Err(status) => return Err(self.status_to_rpc_error(action, &option, status).into()),
+        };
+
+        Ok(self.decode(resp)?)
+    }
+
+    async fn vote(
+        &mut self,
+        rpc: VoteRequest<C>,
+        option: RPCOption,
+    ) -> Result<VoteResponse<C>, RPCError<C, RaftError<C>>> {
+        let mut client = self.client()?;
+
+        let req = pb::VoteRequest::from(rpc);
+        let resp = self
+            .send(RPCTypes::Vote, &option, client.vote(Self::request(req, &option)))
+            .await?
+            .map_err(|status| self.status_to_rpc_error(RPCTypes::Vote, &option, status))?;
+
+        self.decode(resp)
+    }
+
+    async fn pre_vote(
+        &mut self,
+        rpc: VoteRequest<C>,
+        option: RPCOption,
+    ) -> Result<VoteResponse<C>, RPCError<C, RaftError<C>>> {
+        let mut client = self.client()?;
+
+        let req = pb::VoteRequest::from(rpc);
+        let resp = self
+            .send(RPCTypes::PreVote, &option, client.pre_vote(Self::request(req, &option)))
+            .await?
+            .map_err(|status| self.status_to_rpc_error(RPCTypes::PreVote, &option, status))?;
+
+        self.decode(resp)
+    }
+
+    async fn read_index(
+        &mut self,
+        rpc: ReadIndexRequest<C>,
+        option: RPCOption,
+    ) -> Result<ReadIndexResponse<C>, RPCError<C, RaftError<C, CheckIsLeaderError<C>>>> {
+        let mut client = self.client()?;
+
+        let action = RPCTypes::ReadIndex;
+        let req = pb::ReadIndexRequest::from(rpc);
+        let resp = self
+            .send(action, &option, client.read_index(Self::request(req, &option)))
+            .await?
+            .map_err(|status| self.status_to_rpc_error(action, &option, status))?;
+
+        self.decode(resp)
+    }
+
+    async fn transfer_leader(
+        &mut self,
+        rpc: TransferLeaderRequest<C>,
+        option: RPCOption,
+    ) -> Result<(), RPCError<C, RaftError<C>>> {
+        let mut client = self.client()?;
+
+        let action = RPCTypes::TransferLeader;
+        let req = pb::TransferLeaderRequest::from(rpc);
+        let resp = self
+            .send(action, &option, client.transfer_leader(Self::request(req, &option)))
+            .await?
+            .map_err(|status| self.status_to_rpc_error(action, &option, status))?;
+
+        self.decode(resp)
+    }
+
+    async fn handoff_leader(
+        &mut self,
+        rpc: HandoffLeaderRequest<C>,
+        option: RPCOption,
+    ) -> Result<HandoffLeaderResponse, RPCError<C, RaftError<C>>> {
+        let mut client = self.client()?;
+
+        let action = RPCTypes::HandoffLeader;
+        let req = pb::HandoffLeaderRequest::from(rpc);
+        let resp = self
+            .send(action, &option, client.handoff_leader(Self::request(req, &option)))
+            .await?
+            .map_err(|status| self.status_to_rpc_error(action, &option, status))?;
+
+        self.decode(resp)
+    }
+}
@@ -0,0 +1,9 @@
+use openraft::AnyError;
+
+/// Encodes and decodes the application data `D` carried by a log entry, which travels as
+/// opaque bytes.
+pub trait AppDataCodec<D>: Send + Sync + 'static {
+    fn encode(&self, data: &D) -> Result<Vec<u8>, AnyError>;
+
+    fn decode(&self, buf: &[u8]) -> Result<D, AnyError>;
+}
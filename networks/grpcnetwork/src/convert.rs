@@ -0,0 +1,1029 @@
+//! Conversion between openraft types and protobuf messages.
+//!
+//! Decoding a message returns an error if a required field is absent.
+//!
+//! A response is converted from and to the `Result` returned by the remote `Raft`, so that an
+//! error of the remote `Raft`, including a [`Fatal`], is carried in the response body.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use openraft::error::CheckIsLeaderError;
+use openraft::error::Fatal;
+use openraft::error::ForwardToLeader;
+use openraft::error::InstallSnapshotError;
+use openraft::error::QuorumNotEnough;
+use openraft::error::RaftError;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::error::SnapshotMismatch;
+use openraft::raft::AppendEntriesRequest;
+use openraft::raft::AppendEntriesResponse;
+use openraft::raft::HandoffLeaderRequest;
+use openraft::raft::HandoffLeaderResponse;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::raft::InstallSnapshotResponse;
+use openraft::raft::ReadIndexRequest;
+use openraft::raft::ReadIndexResponse;
+use openraft::raft::SnapshotResponse;
+use openraft::raft::TransferLeaderRequest;
+use openraft::raft::VoteRequest;
+use openraft::raft::VoteResponse;
+use openraft::storage::SnapshotSignature;
+use openraft::AnyError;
+use openraft::BasicNode;
+use openraft::CommittedLeaderId;
+use openraft::Entry;
+use openraft::EntryPayload;
+use openraft::ErrorSubject;
+use openraft::ErrorVerb;
+use openraft::LeaderId;
+use openraft::LogId;
+use openraft::Membership;
+use openraft::RaftTypeConfig;
+use openraft::SnapshotMeta;
+use openraft::SnapshotSegmentId;
+use openraft::StorageError;
+use openraft::StorageIOError;
+use openraft::StoredMembership;
+use openraft::Vote;
+
+use crate::pb;
+use crate::AppDataCodec;
+
+/// The type config this crate works with: node ids are `u64`, nodes are [`BasicNode`] and log
+/// entries are [`Entry`].
+///
+/// It is implemented for every [`RaftTypeConfig`] with these types.
+pub trait GrpcTypeConfig: RaftTypeConfig<NodeId = u64, Node = BasicNode, Entry = Entry<Self>> {}
+
+impl<C> GrpcTypeConfig for C where C: RaftTypeConfig<NodeId = u64, Node = BasicNode, Entry = Entry<C>> {}
+
+fn required<T>(v: Option<T>, field: &str) -> Result<T, AnyError> {
+    v.ok_or_else(|| AnyError::error(format!("missing field: {}", field)))
+}
+
+fn log_id_from_pb(log_id: Option<pb::LogId>) -> Result<Option<LogId<u64>>, AnyError> {
+    log_id.map(LogId::try_from).transpose()
+}
+
+impl<C> From<Fatal<C>> for pb::Fatal
+where C: GrpcTypeConfig
+{
+    fn from(fatal: Fatal<C>) -> Self {
+        use pb::fatal::Kind;
+
+        let kind = match fatal {
+            Fatal::StorageError(e) => Kind::StorageError(e.to_string()),
+            Fatal::Panicked => Kind::Panicked(pb::Empty {}),
+            Fatal::Stopped => Kind::Stopped(pb::Empty {}),
+        };
+
+        pb::Fatal { kind: Some(kind) }
+    }
+}
+
+/// A storage error is carried as its message, and is decoded as an IO error with no subject.
+impl<C> TryFrom<pb::Fatal> for Fatal<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(fatal: pb::Fatal) -> Result<Self, Self::Error> {
+        use pb::fatal::Kind;
+
+        let fatal = match required(fatal.kind, "Fatal.kind")? {
+            Kind::StorageError(message) => {
+                let source = StorageIOError::new(ErrorSubject::None, ErrorVerb::Read, AnyError::error(message));
+                Fatal::StorageError(StorageError::IO { source })
+            }
+            Kind::Panicked(_) => Fatal::Panicked,
+            Kind::Stopped(_) => Fatal::Stopped,
+        };
+        Ok(fatal)
+    }
+}
+
+impl From<LeaderId<u64>> for pb::LeaderId {
+    fn from(leader_id: LeaderId<u64>) -> Self {
+        pb::LeaderId {
+            term: leader_id.term,
+            node_id: leader_id.voted_for(),
+        }
+    }
+}
+
+impl TryFrom<pb::LeaderId> for LeaderId<u64> {
+    type Error = AnyError;
+
+    fn try_from(leader_id: pb::LeaderId) -> Result<Self, Self::Error> {
+        #[cfg(not(feature = "single-term-leader"))]
+        {
+            let node_id = required(leader_id.node_id, "LeaderId.node_id")?;
+            Ok(LeaderId::new(leader_id.term, node_id))
+        }
+
+        #[cfg(feature = "single-term-leader")]
+        {
+            Ok(LeaderId {
+                term: leader_id.term,
+                voted_for: leader_id.node_id,
+            })
+        }
+    }
+}
+
+impl From<CommittedLeaderId<u64>> for pb::CommittedLeaderId {
+    fn from(leader_id: CommittedLeaderId<u64>) -> Self {
+        #[cfg(not(feature = "single-term-leader"))]
+        let node_id = Some(leader_id.node_id);
+
+        #[cfg(feature = "single-term-leader")]
+        let node_id = None;
+
+        pb::CommittedLeaderId {
+            term: leader_id.term,
+            node_id,
+        }
+    }
+}
+
+impl TryFrom<pb::CommittedLeaderId> for CommittedLeaderId<u64> {
+    type Error = AnyError;
+
+    fn try_from(leader_id: pb::CommittedLeaderId) -> Result<Self, Self::Error> {
+        #[cfg(not(feature = "single-term-leader"))]
+        let node_id = required(leader_id.node_id, "CommittedLeaderId.node_id")?;
+
+        // With single-term-leader, the node id is not part of a committed leader id.
+        #[cfg(feature = "single-term-leader")]
+        let node_id = leader_id.node_id.unwrap_or_default();
+
+        Ok(CommittedLeaderId::new(leader_id.term, node_id))
+    }
+}
+
+impl From<Vote<u64>> for pb::Vote {
+    fn from(vote: Vote<u64>) -> Self {
+        pb::Vote {
+            leader_id: Some(vote.leader_id.into()),
+            committed: vote.committed,
+        }
+    }
+}
+
+impl TryFrom<pb::Vote> for Vote<u64> {
+    type Error = AnyError;
+
+    fn try_from(vote: pb::Vote) -> Result<Self, Self::Error> {
+        let leader_id = required(vote.leader_id, "Vote.leader_id")?;
+        Ok(Vote {
+            leader_id: leader_id.try_into()?,
+            committed: vote.committed,
+        })
+    }
+}
+
+impl From<LogId<u64>> for pb::LogId {
+    fn from(log_id: LogId<u64>) -> Self {
+        pb::LogId {
+            leader_id: Some(log_id.leader_id.into()),
+            index: log_id.index,
+        }
+    }
+}
+
+impl TryFrom<pb::LogId> for LogId<u64> {
+    type Error = AnyError;
+
+    fn try_from(log_id: pb::LogId) -> Result<Self, Self::Error> {
+        let leader_id = required(log_id.leader_id, "LogId.leader_id")?;
+        Ok(LogId {
+            leader_id: leader_id.try_into()?,
+            index: log_id.index,
+        })
+    }
+}
+
+impl<C> From<&Membership<C>> for pb::Membership
+where C: GrpcTypeConfig
+{
+    fn from(membership: &Membership<C>) -> Self {
+        let configs = membership
+            .get_joint_config()
+            .iter()
+            .map(|config| pb::NodeIdSet {
+                node_ids: config.iter().copied().collect(),
+            })
+            .collect();
+
+        let nodes = membership
+            .nodes()
+            .map(|(node_id, node)| {
+                (*node_id, pb::BasicNode {
+                    addr: node.addr.clone(),
+                })
+            })
+            .collect();
+
+        pb::Membership {
+            configs,
+            nodes,
+            witnesses: membership.witness_ids().collect(),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::Membership> for Membership<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(membership: pb::Membership) -> Result<Self, Self::Error> {
+        let configs = membership
+            .configs
+            .into_iter()
+            .map(|config| config.node_ids.into_iter().collect::<BTreeSet<_>>())
+            .collect::<Vec<_>>();
+
+        if configs.is_empty() {
+            return Err(AnyError::error("missing field: Membership.configs"));
+        }
+
+        let nodes = membership
+            .nodes
+            .into_iter()
+            .map(|(node_id, node)| (node_id, BasicNode::new(node.addr)))
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Membership::new(configs, nodes).with_witnesses(membership.witnesses))
+    }
+}
+
+impl<C> From<&StoredMembership<C>> for pb::StoredMembership
+where C: GrpcTypeConfig
+{
+    fn from(membership: &StoredMembership<C>) -> Self {
+        pb::StoredMembership {
+            log_id: membership.log_id().map(|log_id| log_id.into()),
+            membership: Some(membership.membership().into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::StoredMembership> for StoredMembership<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(membership: pb::StoredMembership) -> Result<Self, Self::Error> {
+        let log_id = log_id_from_pb(membership.log_id)?;
+        let m = required(membership.membership, "StoredMembership.membership")?;
+        Ok(StoredMembership::new(log_id, m.try_into()?))
+    }
+}
+
+impl<C> From<SnapshotMeta<C>> for pb::SnapshotMeta
+where C: GrpcTypeConfig
+{
+    fn from(meta: SnapshotMeta<C>) -> Self {
+        pb::SnapshotMeta {
+            last_log_id: meta.last_log_id.map(|log_id| log_id.into()),
+            last_membership: Some((&meta.last_membership).into()),
+            snapshot_id: meta.snapshot_id,
+        }
+    }
+}
+
+impl<C> TryFrom<pb::SnapshotMeta> for SnapshotMeta<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(meta: pb::SnapshotMeta) -> Result<Self, Self::Error> {
+        let last_membership = required(meta.last_membership, "SnapshotMeta.last_membership")?;
+        Ok(SnapshotMeta {
+            last_log_id: log_id_from_pb(meta.last_log_id)?,
+            last_membership: last_membership.try_into()?,
+            snapshot_id: meta.snapshot_id,
+        })
+    }
+}
+
+fn entry_to_pb<C, Codec>(entry: &Entry<C>, codec: &Codec) -> Result<pb::Entry, AnyError>
+where
+    C: GrpcTypeConfig,
+    Codec: AppDataCodec<C::D> + ?Sized,
+{
+    let payload = match &entry.payload {
+        EntryPayload::Blank => pb::entry::Payload::Blank(pb::Empty {}),
+        EntryPayload::Normal(data) => pb::entry::Payload::Normal(codec.encode(data)?),
+        EntryPayload::Membership(m) => pb::entry::Payload::Membership(m.into()),
+    };
+
+    Ok(pb::Entry {
+        log_id: Some(entry.log_id.into()),
+        payload: Some(payload),
+    })
+}
+
+fn entry_from_pb<C, Codec>(entry: pb::Entry, codec: &Codec) -> Result<Entry<C>, AnyError>
+where
+    C: GrpcTypeConfig,
+    Codec: AppDataCodec<C::D> + ?Sized,
+{
+    let log_id = required(entry.log_id, "Entry.log_id")?;
+
+    let payload = match required(entry.payload, "Entry.payload")? {
+        pb::entry::Payload::Blank(_) => EntryPayload::Blank,
+        pb::entry::Payload::Normal(buf) => EntryPayload::Normal(codec.decode(&buf)?),
+        pb::entry::Payload::Membership(m) => EntryPayload::Membership(m.try_into()?),
+    };
+
+    Ok(Entry {
+        log_id: log_id.try_into()?,
+        payload,
+    })
+}
+
+pub(crate) fn append_entries_request_to_pb<C, Codec>(
+    req: AppendEntriesRequest<C>,
+    codec: &Codec,
+) -> Result<pb::AppendEntriesRequest, AnyError>
+where
+    C: GrpcTypeConfig,
+    Codec: AppDataCodec<C::D> + ?Sized,
+{
+    let entries = req.entries.iter().map(|entry| entry_to_pb(entry, codec)).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(pb::AppendEntriesRequest {
+        vote: Some(req.vote.into()),
+        prev_log_id: req.prev_log_id.map(|log_id| log_id.into()),
+        entries,
+        leader_commit: req.leader_commit.map(|log_id| log_id.into()),
+    })
+}
+
+pub(crate) fn append_entries_request_from_pb<C, Codec>(
+    req: pb::AppendEntriesRequest,
+    codec: &Codec,
+) -> Result<AppendEntriesRequest<C>, AnyError>
+where
+    C: GrpcTypeConfig,
+    Codec: AppDataCodec<C::D> + ?Sized,
+{
+    let vote = required(req.vote, "AppendEntriesRequest.vote")?;
+    let entries = req.entries.into_iter().map(|entry| entry_from_pb(entry, codec)).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AppendEntriesRequest {
+        vote: vote.try_into()?,
+        prev_log_id: log_id_from_pb(req.prev_log_id)?,
+        entries,
+        leader_commit: log_id_from_pb(req.leader_commit)?,
+    })
+}
+
+impl<C> From<Result<AppendEntriesResponse<C>, RaftError<C>>> for pb::AppendEntriesResponse
+where C: GrpcTypeConfig
+{
+    fn from(res: Result<AppendEntriesResponse<C>, RaftError<C>>) -> Self {
+        use pb::append_entries_response::Result as R;
+
+        let result = match res {
+            Ok(AppendEntriesResponse::Success) => R::Success(pb::Empty {}),
+            Ok(AppendEntriesResponse::PartialSuccess(matching)) => R::PartialSuccess(pb::PartialSuccess {
+                matching: matching.map(|log_id| log_id.into()),
+            }),
+            Ok(AppendEntriesResponse::Conflict) => R::Conflict(pb::Empty {}),
+            Ok(AppendEntriesResponse::HigherVote(vote)) => R::HigherVote(vote.into()),
+            Err(RaftError::APIError(infallible)) => match infallible {},
+            Err(RaftError::Fatal(fatal)) => R::Fatal(fatal.into()),
+        };
+
+        pb::AppendEntriesResponse { result: Some(result) }
+    }
+}
+
+impl<C> TryFrom<pb::AppendEntriesResponse> for Result<AppendEntriesResponse<C>, RaftError<C>>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::AppendEntriesResponse) -> Result<Self, Self::Error> {
+        use pb::append_entries_response::Result as R;
+
+        let res = match required(resp.result, "AppendEntriesResponse.result")? {
+            R::Success(_) => Ok(AppendEntriesResponse::Success),
+            R::PartialSuccess(p) => Ok(AppendEntriesResponse::PartialSuccess(log_id_from_pb(p.matching)?)),
+            R::Conflict(_) => Ok(AppendEntriesResponse::Conflict),
+            R::HigherVote(vote) => Ok(AppendEntriesResponse::HigherVote(vote.try_into()?)),
+            R::Fatal(fatal) => Err(RaftError::Fatal(fatal.try_into()?)),
+        };
+        Ok(res)
+    }
+}
+
+impl<C> From<VoteRequest<C>> for pb::VoteRequest
+where C: GrpcTypeConfig
+{
+    fn from(req: VoteRequest<C>) -> Self {
+        pb::VoteRequest {
+            vote: Some(req.vote.into()),
+            last_log_id: req.last_log_id.map(|log_id| log_id.into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::VoteRequest> for VoteRequest<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(req: pb::VoteRequest) -> Result<Self, Self::Error> {
+        let vote = required(req.vote, "VoteRequest.vote")?;
+        Ok(VoteRequest::new(vote.try_into()?, log_id_from_pb(req.last_log_id)?))
+    }
+}
+
+impl<C> From<VoteResponse<C>> for pb::VoteReply
+where C: GrpcTypeConfig
+{
+    fn from(resp: VoteResponse<C>) -> Self {
+        pb::VoteReply {
+            vote: Some(resp.vote.into()),
+            vote_granted: resp.vote_granted,
+            last_log_id: resp.last_log_id.map(|log_id| log_id.into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::VoteReply> for VoteResponse<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::VoteReply) -> Result<Self, Self::Error> {
+        let vote = required(resp.vote, "VoteReply.vote")?;
+        Ok(VoteResponse {
+            vote: vote.try_into()?,
+            vote_granted: resp.vote_granted,
+            last_log_id: log_id_from_pb(resp.last_log_id)?,
+        })
+    }
+}
+
+impl<C> From<Result<VoteResponse<C>, RaftError<C>>> for pb::VoteResponse
+where C: GrpcTypeConfig
+{
+    fn from(res: Result<VoteResponse<C>, RaftError<C>>) -> Self {
+        use pb::vote_response::Result as R;
+
+        let result = match res {
+            Ok(resp) => R::Ok(resp.into()),
+            Err(RaftError::APIError(infallible)) => match infallible {},
+            Err(RaftError::Fatal(fatal)) => R::Fatal(fatal.into()),
+        };
+
+        pb::VoteResponse { result: Some(result) }
+    }
+}
+
+impl<C> TryFrom<pb::VoteResponse> for Result<VoteResponse<C>, RaftError<C>>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::VoteResponse) -> Result<Self, Self::Error> {
+        use pb::vote_response::Result as R;
+
+        let res = match required(resp.result, "VoteResponse.result")? {
+            R::Ok(resp) => Ok(resp.try_into()?),
+            R::Fatal(fatal) => Err(RaftError::Fatal(fatal.try_into()?)),
+        };
+        Ok(res)
+    }
+}
+
+impl<C> From<InstallSnapshotRequest<C>> for pb::InstallSnapshotRequest
+where C: GrpcTypeConfig
+{
+    fn from(req: InstallSnapshotRequest<C>) -> Self {
+        pb::InstallSnapshotRequest {
+            vote: Some(req.vote.into()),
+            meta: Some(req.meta.into()),
+            offset: req.offset,
+            data: req.data,
+            done: req.done,
+        }
+    }
+}
+
+impl<C> TryFrom<pb::InstallSnapshotRequest> for InstallSnapshotRequest<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(req: pb::InstallSnapshotRequest) -> Result<Self, Self::Error> {
+        let vote = required(req.vote, "InstallSnapshotRequest.vote")?;
+        let meta = required(req.meta, "InstallSnapshotRequest.meta")?;
+        Ok(InstallSnapshotRequest {
+            vote: vote.try_into()?,
+            meta: meta.try_into()?,
+            offset: req.offset,
+            data: req.data,
+            done: req.done,
+        })
+    }
+}
+
+impl<C> From<InstallSnapshotResponse<C>> for pb::SnapshotResponse
+where C: GrpcTypeConfig
+{
+    fn from(resp: InstallSnapshotResponse<C>) -> Self {
+        pb::SnapshotResponse {
+            vote: Some(resp.vote.into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::SnapshotResponse> for InstallSnapshotResponse<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::SnapshotResponse) -> Result<Self, Self::Error> {
+        let vote = required(resp.vote, "SnapshotResponse.vote")?;
+        Ok(InstallSnapshotResponse { vote: vote.try_into()? })
+    }
+}
+
+impl<C> From<Result<InstallSnapshotResponse<C>, RaftError<C, InstallSnapshotError>>> for pb::InstallSnapshotResponse
+where C: GrpcTypeConfig
+{
+    fn from(res: Result<InstallSnapshotResponse<C>, RaftError<C, InstallSnapshotError>>) -> Self {
+        use pb::install_snapshot_response::Result as R;
+
+        let result = match res {
+            Ok(resp) => R::Ok(resp.into()),
+            Err(RaftError::APIError(InstallSnapshotError::SnapshotMismatch(mismatch))) => {
+                R::SnapshotMismatch(mismatch.into())
+            }
+            Err(RaftError::Fatal(fatal)) => R::Fatal(fatal.into()),
+        };
+
+        pb::InstallSnapshotResponse { result: Some(result) }
+    }
+}
+
+impl<C> TryFrom<pb::InstallSnapshotResponse> for Result<InstallSnapshotResponse<C>, RaftError<C, InstallSnapshotError>>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::InstallSnapshotResponse) -> Result<Self, Self::Error> {
+        use pb::install_snapshot_response::Result as R;
+
+        let res = match required(resp.result, "InstallSnapshotResponse.result")? {
+            R::Ok(resp) => Ok(resp.try_into()?),
+            R::SnapshotMismatch(mismatch) => Err(RaftError::APIError(InstallSnapshotError::SnapshotMismatch(
+                mismatch.try_into()?,
+            ))),
+            R::Fatal(fatal) => Err(RaftError::Fatal(fatal.try_into()?)),
+        };
+        Ok(res)
+    }
+}
+
+impl From<SnapshotSignature<u64>> for pb::SnapshotSignature {
+    fn from(signature: SnapshotSignature<u64>) -> Self {
+        pb::SnapshotSignature {
+            last_log_id: signature.last_log_id.map(|log_id| log_id.into()),
+            last_membership_log_id: signature.last_membership_log_id.map(|log_id| log_id.into()),
+            snapshot_id: signature.snapshot_id,
+        }
+    }
+}
+
+impl TryFrom<pb::SnapshotSignature> for SnapshotSignature<u64> {
+    type Error = AnyError;
+
+    fn try_from(signature: pb::SnapshotSignature) -> Result<Self, Self::Error> {
+        Ok(SnapshotSignature {
+            last_log_id: log_id_from_pb(signature.last_log_id)?,
+            last_membership_log_id: log_id_from_pb(signature.last_membership_log_id)?,
+            snapshot_id: signature.snapshot_id,
+        })
+    }
+}
+
+/// A snapshot delta upon the snapshot `base`, sent as a whole in one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SnapshotDeltaRequest<C>
+where C: GrpcTypeConfig
+{
+    pub(crate) vote: Vote<u64>,
+    pub(crate) base: SnapshotSignature<u64>,
+    pub(crate) meta: SnapshotMeta<C>,
+    pub(crate) data: Vec<u8>,
+}
+
+impl<C> From<SnapshotDeltaRequest<C>> for pb::SnapshotDeltaRequest
+where C: GrpcTypeConfig
+{
+    fn from(req: SnapshotDeltaRequest<C>) -> Self {
+        pb::SnapshotDeltaRequest {
+            vote: Some(req.vote.into()),
+            base: Some(req.base.into()),
+            meta: Some(req.meta.into()),
+            data: req.data,
+        }
+    }
+}
+
+impl<C> TryFrom<pb::SnapshotDeltaRequest> for SnapshotDeltaRequest<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(req: pb::SnapshotDeltaRequest) -> Result<Self, Self::Error> {
+        let vote = required(req.vote, "SnapshotDeltaRequest.vote")?;
+        let base = required(req.base, "SnapshotDeltaRequest.base")?;
+        let meta = required(req.meta, "SnapshotDeltaRequest.meta")?;
+        Ok(SnapshotDeltaRequest {
+            vote: vote.try_into()?,
+            base: base.try_into()?,
+            meta: meta.try_into()?,
+            data: req.data,
+        })
+    }
+}
+
+impl<C> From<SnapshotResponse<C>> for pb::SnapshotDeltaReply
+where C: GrpcTypeConfig
+{
+    fn from(resp: SnapshotResponse<C>) -> Self {
+        pb::SnapshotDeltaReply {
+            vote: Some(resp.vote.into()),
+            snapshot: resp.snapshot.map(|signature| signature.into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::SnapshotDeltaReply> for SnapshotResponse<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::SnapshotDeltaReply) -> Result<Self, Self::Error> {
+        let vote = required(resp.vote, "SnapshotDeltaReply.vote")?;
+        let snapshot = resp.snapshot.map(SnapshotSignature::try_from).transpose()?;
+        Ok(SnapshotResponse::new(vote.try_into()?).with_snapshot(snapshot))
+    }
+}
+
+impl<C> From<SnapshotBaseMismatch<C>> for pb::SnapshotBaseMismatch
+where C: GrpcTypeConfig
+{
+    fn from(mismatch: SnapshotBaseMismatch<C>) -> Self {
+        pb::SnapshotBaseMismatch {
+            expect: Some(mismatch.expect.into()),
+            got: Some(mismatch.got.into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::SnapshotBaseMismatch> for SnapshotBaseMismatch<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(mismatch: pb::SnapshotBaseMismatch) -> Result<Self, Self::Error> {
+        Ok(SnapshotBaseMismatch {
+            expect: required(mismatch.expect, "SnapshotBaseMismatch.expect")?.try_into()?,
+            got: required(mismatch.got, "SnapshotBaseMismatch.got")?.try_into()?,
+        })
+    }
+}
+
+impl<C> From<Result<SnapshotResponse<C>, RaftError<C, SnapshotBaseMismatch<C>>>> for pb::SnapshotDeltaResponse
+where C: GrpcTypeConfig
+{
+    fn from(res: Result<SnapshotResponse<C>, RaftError<C, SnapshotBaseMismatch<C>>>) -> Self {
+        use pb::snapshot_delta_response::Result as R;
+
+        let result = match res {
+            Ok(resp) => R::Ok(resp.into()),
+            Err(RaftError::APIError(mismatch)) => R::BaseMismatch(mismatch.into()),
+            Err(RaftError::Fatal(fatal)) => R::Fatal(fatal.into()),
+        };
+
+        pb::SnapshotDeltaResponse { result: Some(result) }
+    }
+}
+
+impl<C> TryFrom<pb::SnapshotDeltaResponse> for Result<SnapshotResponse<C>, RaftError<C, SnapshotBaseMismatch<C>>>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::SnapshotDeltaResponse) -> Result<Self, Self::Error> {
+        use pb::snapshot_delta_response::Result as R;
+
+        let res = match required(resp.result, "SnapshotDeltaResponse.result")? {
+            R::Ok(resp) => Ok(resp.try_into()?),
+            R::BaseMismatch(mismatch) => Err(RaftError::APIError(mismatch.try_into()?)),
+            R::Fatal(fatal) => Err(RaftError::Fatal(fatal.try_into()?)),
+        };
+        Ok(res)
+    }
+}
+
+impl From<SnapshotSegmentId> for pb::SnapshotSegmentId {
+    fn from(segment: SnapshotSegmentId) -> Self {
+        pb::SnapshotSegmentId {
+            id: segment.id,
+            offset: segment.offset,
+        }
+    }
+}
+
+impl From<pb::SnapshotSegmentId> for SnapshotSegmentId {
+    fn from(segment: pb::SnapshotSegmentId) -> Self {
+        SnapshotSegmentId {
+            id: segment.id,
+            offset: segment.offset,
+        }
+    }
+}
+
+impl From<SnapshotMismatch> for pb::SnapshotMismatch {
+    fn from(mismatch: SnapshotMismatch) -> Self {
+        pb::SnapshotMismatch {
+            expect: Some(mismatch.expect.into()),
+            got: Some(mismatch.got.into()),
+        }
+    }
+}
+
+impl TryFrom<pb::SnapshotMismatch> for SnapshotMismatch {
+    type Error = AnyError;
+
+    fn try_from(mismatch: pb::SnapshotMismatch) -> Result<Self, Self::Error> {
+        Ok(SnapshotMismatch {
+            expect: required(mismatch.expect, "SnapshotMismatch.expect")?.into(),
+            got: required(mismatch.got, "SnapshotMismatch.got")?.into(),
+        })
+    }
+}
+
+impl<C> From<ReadIndexRequest<C>> for pb::ReadIndexRequest
+where C: GrpcTypeConfig
+{
+    fn from(req: ReadIndexRequest<C>) -> Self {
+        pb::ReadIndexRequest { from: req.from }
+    }
+}
+
+impl<C> From<pb::ReadIndexRequest> for ReadIndexRequest<C>
+where C: GrpcTypeConfig
+{
+    fn from(req: pb::ReadIndexRequest) -> Self {
+        ReadIndexRequest::new(req.from)
+    }
+}
+
+impl<C> From<ReadIndexResponse<C>> for pb::ReadLogId
+where C: GrpcTypeConfig
+{
+    fn from(resp: ReadIndexResponse<C>) -> Self {
+        pb::ReadLogId {
+            read_log_id: resp.read_log_id.map(|log_id| log_id.into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::ReadLogId> for ReadIndexResponse<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::ReadLogId) -> Result<Self, Self::Error> {
+        Ok(ReadIndexResponse {
+            read_log_id: log_id_from_pb(resp.read_log_id)?,
+        })
+    }
+}
+
+impl<C> From<ForwardToLeader<C>> for pb::ForwardToLeader
+where C: GrpcTypeConfig
+{
+    fn from(forward: ForwardToLeader<C>) -> Self {
+        pb::ForwardToLeader {
+            leader_id: forward.leader_id,
+            leader_node: forward.leader_node.map(|node| pb::BasicNode { addr: node.addr }),
+        }
+    }
+}
+
+impl<C> From<pb::ForwardToLeader> for ForwardToLeader<C>
+where C: GrpcTypeConfig
+{
+    fn from(forward: pb::ForwardToLeader) -> Self {
+        ForwardToLeader {
+            leader_id: forward.leader_id,
+            leader_node: forward.leader_node.map(|node| BasicNode::new(node.addr)),
+        }
+    }
+}
+
+impl<C> From<QuorumNotEnough<C>> for pb::QuorumNotEnough
+where C: GrpcTypeConfig
+{
+    fn from(e: QuorumNotEnough<C>) -> Self {
+        pb::QuorumNotEnough {
+            cluster: e.cluster,
+            got: e.got.into_iter().collect(),
+        }
+    }
+}
+
+impl<C> From<pb::QuorumNotEnough> for QuorumNotEnough<C>
+where C: GrpcTypeConfig
+{
+    fn from(e: pb::QuorumNotEnough) -> Self {
+        QuorumNotEnough {
+            cluster: e.cluster,
+            got: e.got.into_iter().collect(),
+        }
+    }
+}
+
+impl<C> From<Result<ReadIndexResponse<C>, RaftError<C, CheckIsLeaderError<C>>>> for pb::ReadIndexResponse
+where C: GrpcTypeConfig
+{
+    fn from(res: Result<ReadIndexResponse<C>, RaftError<C, CheckIsLeaderError<C>>>) -> Self {
+        use pb::read_index_response::Result as R;
+
+        let result = match res {
+            Ok(resp) => R::Ok(resp.into()),
+            Err(RaftError::APIError(CheckIsLeaderError::ForwardToLeader(forward))) => {
+                R::ForwardToLeader(forward.into())
+            }
+            Err(RaftError::APIError(CheckIsLeaderError::QuorumNotEnough(e))) => R::QuorumNotEnough(e.into()),
+            Err(RaftError::Fatal(fatal)) => R::Fatal(fatal.into()),
+        };
+
+        pb::ReadIndexResponse { result: Some(result) }
+    }
+}
+
+impl<C> TryFrom<pb::ReadIndexResponse> for Result<ReadIndexResponse<C>, RaftError<C, CheckIsLeaderError<C>>>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::ReadIndexResponse) -> Result<Self, Self::Error> {
+        use pb::read_index_response::Result as R;
+
+        let res = match required(resp.result, "ReadIndexResponse.result")? {
+            R::Ok(resp) => Ok(resp.try_into()?),
+            R::ForwardToLeader(forward) => {
+                Err(RaftError::APIError(CheckIsLeaderError::ForwardToLeader(forward.into())))
+            }
+            R::QuorumNotEnough(e) => Err(RaftError::APIError(CheckIsLeaderError::QuorumNotEnough(e.into()))),
+            R::Fatal(fatal) => Err(RaftError::Fatal(fatal.try_into()?)),
+        };
+        Ok(res)
+    }
+}
+
+impl<C> From<TransferLeaderRequest<C>> for pb::TransferLeaderRequest
+where C: GrpcTypeConfig
+{
+    fn from(req: TransferLeaderRequest<C>) -> Self {
+        pb::TransferLeaderRequest {
+            from_leader: Some((*req.from_leader()).into()),
+            to_node_id: *req.to_node_id(),
+            last_log_id: req.last_log_id().map(|log_id| (*log_id).into()),
+        }
+    }
+}
+
+impl<C> TryFrom<pb::TransferLeaderRequest> for TransferLeaderRequest<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(req: pb::TransferLeaderRequest) -> Result<Self, Self::Error> {
+        let from_leader = required(req.from_leader, "TransferLeaderRequest.from_leader")?;
+        Ok(TransferLeaderRequest::new(
+            from_leader.try_into()?,
+            req.to_node_id,
+            log_id_from_pb(req.last_log_id)?,
+        ))
+    }
+}
+
+impl<C> From<Result<(), RaftError<C>>> for pb::TransferLeaderResponse
+where C: GrpcTypeConfig
+{
+    fn from(res: Result<(), RaftError<C>>) -> Self {
+        use pb::transfer_leader_response::Result as R;
+
+        let result = match res {
+            Ok(()) => R::Ok(pb::Empty {}),
+            Err(RaftError::APIError(infallible)) => match infallible {},
+            Err(RaftError::Fatal(fatal)) => R::Fatal(fatal.into()),
+        };
+
+        pb::TransferLeaderResponse { result: Some(result) }
+    }
+}
+
+impl<C> TryFrom<pb::TransferLeaderResponse> for Result<(), RaftError<C>>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::TransferLeaderResponse) -> Result<Self, Self::Error> {
+        use pb::transfer_leader_response::Result as R;
+
+        let res = match required(resp.result, "TransferLeaderResponse.result")? {
+            R::Ok(_) => Ok(()),
+            R::Fatal(fatal) => Err(RaftError::Fatal(fatal.try_into()?)),
+        };
+        Ok(res)
+    }
+}
+
+impl<C> From<HandoffLeaderRequest<C>> for pb::HandoffLeaderRequest
+where C: GrpcTypeConfig
+{
+    fn from(req: HandoffLeaderRequest<C>) -> Self {
+        pb::HandoffLeaderRequest {
+            vote: Some(req.vote.into()),
+            from: req.from,
+            priority: req.priority,
+        }
+    }
+}
+
+impl<C> TryFrom<pb::HandoffLeaderRequest> for HandoffLeaderRequest<C>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(req: pb::HandoffLeaderRequest) -> Result<Self, Self::Error> {
+        let vote = required(req.vote, "HandoffLeaderRequest.vote")?;
+        Ok(HandoffLeaderRequest::new(vote.try_into()?, req.from, req.priority))
+    }
+}
+
+impl From<HandoffLeaderResponse> for pb::HandoffLeaderReply {
+    fn from(resp: HandoffLeaderResponse) -> Self {
+        pb::HandoffLeaderReply {
+            priority: resp.priority,
+            accepted: resp.accepted,
+        }
+    }
+}
+
+impl From<pb::HandoffLeaderReply> for HandoffLeaderResponse {
+    fn from(resp: pb::HandoffLeaderReply) -> Self {
+        HandoffLeaderResponse {
+            priority: resp.priority,
+            accepted: resp.accepted,
+        }
+    }
+}
+
+impl<C> From<Result<HandoffLeaderResponse, RaftError<C>>> for pb::HandoffLeaderResponse
+where C: GrpcTypeConfig
+{
+    fn from(res: Result<HandoffLeaderResponse, RaftError<C>>) -> Self {
+        use pb::handoff_leader_response::Result as R;
+
+        let result = match res {
+            Ok(resp) => R::Ok(resp.into()),
+            Err(RaftError::APIError(infallible)) => match infallible {},
+            Err(RaftError::Fatal(fatal)) => R::Fatal(fatal.into()),
+        };
+
+        pb::HandoffLeaderResponse { result: Some(result) }
+    }
+}
+
+impl<C> TryFrom<pb::HandoffLeaderResponse> for Result<HandoffLeaderResponse, RaftError<C>>
+where C: GrpcTypeConfig
+{
+    type Error = AnyError;
+
+    fn try_from(resp: pb::HandoffLeaderResponse) -> Result<Self, Self::Error> {
+        use pb::handoff_leader_response::Result as R;
+
+        let res = match required(resp.result, "HandoffLeaderResponse.result")? {
+            R::Ok(resp) => Ok(resp.into()),
+            R::Fatal(fatal) => Err(RaftError::Fatal(fatal.try_into()?)),
+        };
+        Ok(res)
+    }
+}
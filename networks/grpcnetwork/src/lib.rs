@@ -0,0 +1,22 @@
+#![doc = include_str!("../README.md")]
+#![deny(unused_crate_dependencies)]
+#![deny(unused_qualifications)]
+
+#[cfg(test)] mod test;
+
+mod client;
+mod codec;
+mod convert;
+mod server;
+
+/// Protobuf messages, tonic client and tonic server generated from `proto/raft.proto`.
+#[allow(unused_qualifications)]
+pub mod pb {
+    tonic::include_proto!("openraft");
+}
+
+pub use client::GrpcNetwork;
+pub use client::GrpcNetworkFactory;
+pub use codec::AppDataCodec;
+pub use convert::GrpcTypeConfig;
+pub use server::GrpcService;
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use openraft::error::RaftError;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::raft::HandoffLeaderRequest;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::raft::ReadIndexRequest;
+use openraft::raft::SnapshotResponse;
+use openraft::raft::TransferLeaderRequest;
+use openraft::raft::VoteRequest;
+use openraft::AnyError;
+use openraft::Raft;
+use openraft::Snapshot;
+use openraft::StorageError;
+use openraft::StorageIOError;
+use tokio::io::AsyncWriteExt;
+use tonic::Request;
+use tonic::Response;
+use tonic::Status;
+
+use crate::convert::append_entries_request_from_pb;
+use crate::convert::SnapshotDeltaRequest;
+use crate::pb;
+use crate::pb::raft_service_server::RaftService;
+use crate::pb::raft_service_server::RaftServiceServer;
+use crate::AppDataCodec;
+use crate::GrpcTypeConfig;
+
+/// A tonic service that forwards the received RPCs to a [`Raft`].
+///
+/// An error returned by the [`Raft`], including a fatal error, is sent back in the response body.
+/// A request that can not be decoded is rejected with `InvalidArgument`.
+///
+/// Add it to a tonic server with [`GrpcService::into_server()`]:
+///
+/// ```ignore
+/// tonic::transport::Server::builder()
+///     .add_service(GrpcService::new(raft, codec).into_server())
+///     .serve(addr)
+///     .await?;
+/// ```
+pub struct GrpcService<C, Codec>
+where C: GrpcTypeConfig
+{
+    raft: Raft<C>,
+    codec: Arc<Codec>,
+}
+
+impl<C, Codec> GrpcService<C, Codec>
+where
+    C: GrpcTypeConfig,
+    C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+    Codec: AppDataCodec<C::D>,
+{
+    /// Create a service for `raft`, which decodes application data with `codec`.
+    pub fn new(raft: Raft<C>, codec: Codec) -> Self {
+        Self {
+            raft,
+            codec: Arc::new(codec),
+        }
+    }
+
+    pub fn into_server(self) -> RaftServiceServer<Self> {
+        RaftServiceServer::new(self)
+    }
+
+    /// Install a snapshot delta, which is received in a single request, with
+    /// [`Raft::install_snapshot_delta`].
+    async fn install_snapshot_delta(
+        &self,
+        req: SnapshotDeltaRequest<C>,
+    ) -> Result<SnapshotResponse<C>, RaftError<C, SnapshotBaseMismatch<C>>> {
+        let mut data = self.raft.begin_receiving_snapshot().await.map_err(|e| {
+            // Safe unwrap: `RaftError<Infallible>` is always a Fatal.
+            RaftError::Fatal(e.into_fatal().unwrap())
+        })?;
+
+        let written = async {
+            data.write_all(&req.data).await?;
+            data.shutdown().await
+        };
+        written.await.map_err(|e| {
+            let io_err = StorageIOError::write_snapshot(Some(req.meta.signature()), &e);
+            StorageError::from(io_err)
+        })?;
+
+        let delta = Snapshot {
+            meta: req.meta,
+            snapshot: data,
+        };
+        self.raft.install_snapshot_delta(req.vote, req.base, delta).await
+    }
+}
+
+fn invalid_argument(e: AnyError) -> Status {
+    Status::invalid_argument(e.to_string())
+}
+
+#[tonic::async_trait]
+impl<C, Codec> RaftService for GrpcService<C, Codec>
+where
+    C: GrpcTypeConfig,
+    C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+    Codec: AppDataCodec<C::D>,
+{
+    async fn append_entries(
+        &self,
+        request: Request<pb::AppendEntriesRequest>,
+    ) -> Result<Response<pb::AppendEntriesResponse>, Status> {
+        let req = append_entries_request_from_pb(request.into_inner(), &*self.codec).map_err(invalid_argument)?;
+        let res = self.raft.append_entries(req).await;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn vote(&self, request: Request<pb::VoteRequest>) -> Result<Response<pb::VoteResponse>, Status> {
+        let req = VoteRequest::try_from(request.into_inner()).map_err(invalid_argument)?;
+        let res = self.raft.vote(req).await;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn pre_vote(&self, request: Request<pb::VoteRequest>) -> Result<Response<pb::VoteResponse>, Status> {
+        let req = VoteRequest::try_from(request.into_inner()).map_err(invalid_argument)?;
+        let res = self.raft.pre_vote(req).await;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn install_snapshot(
+        &self,
+        request: Request<pb::InstallSnapshotRequest>,
+    ) -> Result<Response<pb::InstallSnapshotResponse>, Status> {
+        let req = InstallSnapshotRequest::try_from(request.into_inner()).map_err(invalid_argument)?;
+        let res = self.raft.install_snapshot(req).await;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn snapshot_delta(
+        &self,
+        request: Request<pb::SnapshotDeltaRequest>,
+    ) -> Result<Response<pb::SnapshotDeltaResponse>, Status> {
+        let req = SnapshotDeltaRequest::try_from(request.into_inner()).map_err(invalid_argument)?;
+        let res = self.install_snapshot_delta(req).await;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn read_index(
+        &self,
+        request: Request<pb::ReadIndexRequest>,
+    ) -> Result<Response<pb::ReadIndexResponse>, Status> {
+        let req = ReadIndexRequest::from(request.into_inner());
+        let res = self.raft.handle_read_index(req).await;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn transfer_leader(
+        &self,
+        request: Request<pb::TransferLeaderRequest>,
+    ) -> Result<Response<pb::TransferLeaderResponse>, Status> {
+        let req = TransferLeaderRequest::try_from(request.into_inner()).map_err(invalid_argument)?;
+        let res = self.raft.handle_transfer_leader(req).await.map_err(RaftError::Fatal);
+        Ok(Response::new(res.into()))
+    }
+
+    async fn handoff_leader(
+        &self,
+        request: Request<pb::HandoffLeaderRequest>,
+    ) -> Result<Response<pb::HandoffLeaderResponse>, Status> {
+        let req = HandoffLeaderRequest::try_from(request.into_inner()).map_err(invalid_argument)?;
+        let res = self.raft.handle_handoff_leader(req).await;
+        Ok(Response::new(res.into()))
+    }
+}
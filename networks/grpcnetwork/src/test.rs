@@ -0,0 +1,360 @@
+use std::io::Cursor;
+
+use maplit::btreemap;
+use maplit::btreeset;
+use openraft::error::CheckIsLeaderError;
+use openraft::error::Fatal;
+use openraft::error::ForwardToLeader;
+use openraft::error::InstallSnapshotError;
+use openraft::error::QuorumNotEnough;
+use openraft::error::RaftError;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::error::SnapshotMismatch;
+use openraft::raft::AppendEntriesRequest;
+use openraft::raft::AppendEntriesResponse;
+use openraft::raft::HandoffLeaderRequest;
+use openraft::raft::HandoffLeaderResponse;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::raft::InstallSnapshotResponse;
+use openraft::raft::ReadIndexRequest;
+use openraft::raft::ReadIndexResponse;
+use openraft::raft::SnapshotResponse;
+use openraft::raft::TransferLeaderRequest;
+use openraft::raft::VoteRequest;
+use openraft::raft::VoteResponse;
+use openraft::storage::SnapshotSignature;
+use openraft::AnyError;
+use openraft::BasicNode;
+use openraft::CommittedLeaderId;
+use openraft::Entry;
+use openraft::EntryPayload;
+use openraft::ErrorSubject;
+use openraft::ErrorVerb;
+use openraft::LogId;
+use openraft::Membership;
+use openraft::SnapshotMeta;
+use openraft::SnapshotSegmentId;
+use openraft::StorageError;
+use openraft::StorageIOError;
+use openraft::StoredMembership;
+use openraft::Vote;
+
+use crate::convert::append_entries_request_from_pb;
+use crate::convert::append_entries_request_to_pb;
+use crate::convert::SnapshotDeltaRequest;
+use crate::pb;
+use crate::AppDataCodec;
+
+openraft::declare_raft_types!(TypeConfig);
+
+struct StringCodec;
+
+impl AppDataCodec<String> for StringCodec {
+    fn encode(&self, data: &String) -> Result<Vec<u8>, AnyError> {
+        Ok(data.as_bytes().to_vec())
+    }
+
+    fn decode(&self, buf: &[u8]) -> Result<String, AnyError> {
+        String::from_utf8(buf.to_vec()).map_err(|e| AnyError::new(&e))
+    }
+}
+
+fn log_id(term: u64, node_id: u64, index: u64) -> LogId<u64> {
+    LogId {
+        leader_id: CommittedLeaderId::new(term, node_id),
+        index,
+    }
+}
+
+fn membership() -> Membership<TypeConfig> {
+    Membership::new(vec![btreeset! {1,2,3}, btreeset! {3,4}], btreemap! {
+        1 => BasicNode::new("127.0.0.1:1"),
+        2 => BasicNode::new("127.0.0.1:2"),
+        3 => BasicNode::new("127.0.0.1:3"),
+        4 => BasicNode::new("127.0.0.1:4"),
+        5 => BasicNode::new("127.0.0.1:5"),
+    })
+    .with_witnesses([4])
+}
+
+#[test]
+fn test_append_entries_round_trip() -> anyhow::Result<()> {
+    let req = AppendEntriesRequest::<TypeConfig> {
+        vote: Vote::new_committed(3, 1),
+        prev_log_id: Some(log_id(2, 1, 4)),
+        entries: vec![
+            Entry {
+                log_id: log_id(3, 1, 5),
+                payload: EntryPayload::Blank,
+            },
+            Entry {
+                log_id: log_id(3, 1, 6),
+                payload: EntryPayload::Normal("foo".to_string()),
+            },
+            Entry {
+                log_id: log_id(3, 1, 7),
+                payload: EntryPayload::Membership(membership()),
+            },
+        ],
+        leader_commit: None,
+    };
+
+    let got: AppendEntriesRequest<TypeConfig> =
+        append_entries_request_from_pb(append_entries_request_to_pb(req.clone(), &StringCodec)?, &StringCodec)?;
+
+    assert_eq!(req.vote, got.vote);
+    assert_eq!(req.prev_log_id, got.prev_log_id);
+    assert_eq!(req.entries, got.entries);
+    assert_eq!(req.leader_commit, got.leader_commit);
+
+    let EntryPayload::Membership(m) = &got.entries[2].payload else {
+        panic!("expect membership entry");
+    };
+    assert_eq!(vec![4], m.witness_ids().collect::<Vec<_>>());
+
+    for res in [
+        Ok(AppendEntriesResponse::<TypeConfig>::Success),
+        Ok(AppendEntriesResponse::PartialSuccess(Some(log_id(3, 1, 6)))),
+        Ok(AppendEntriesResponse::PartialSuccess(None)),
+        Ok(AppendEntriesResponse::Conflict),
+        Ok(AppendEntriesResponse::HigherVote(Vote::new(5, 2))),
+        Err(RaftError::Fatal(Fatal::Stopped)),
+    ] {
+        let p = pb::AppendEntriesResponse::from(res);
+        let got = Result::<AppendEntriesResponse<TypeConfig>, RaftError<TypeConfig>>::try_from(p.clone())?;
+        assert_eq!(p, pb::AppendEntriesResponse::from(got));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_vote_round_trip() -> anyhow::Result<()> {
+    let req = VoteRequest::<TypeConfig>::new(Vote::new(5, 2), Some(log_id(3, 1, 6)));
+    let got = VoteRequest::<TypeConfig>::try_from(pb::VoteRequest::from(req.clone()))?;
+    assert_eq!(req, got);
+
+    for res in [
+        Ok(VoteResponse::<TypeConfig> {
+            vote: Vote::new_committed(5, 2),
+            vote_granted: true,
+            last_log_id: None,
+        }),
+        Err(RaftError::Fatal(Fatal::Panicked)),
+    ] {
+        let got =
+            Result::<VoteResponse<TypeConfig>, RaftError<TypeConfig>>::try_from(pb::VoteResponse::from(res.clone()))?;
+        assert_eq!(res, got);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_install_snapshot_round_trip() -> anyhow::Result<()> {
+    let req = InstallSnapshotRequest::<TypeConfig> {
+        vote: Vote::new_committed(3, 1),
+        meta: SnapshotMeta {
+            last_log_id: Some(log_id(3, 1, 7)),
+            last_membership: StoredMembership::new(Some(log_id(3, 1, 7)), membership()),
+            snapshot_id: "snapshot-1".to_string(),
+        },
+        offset: 1024,
+        data: b"chunk".to_vec(),
+        done: true,
+    };
+    let got = InstallSnapshotRequest::<TypeConfig>::try_from(pb::InstallSnapshotRequest::from(req.clone()))?;
+    assert_eq!(req, got);
+
+    let mismatch = SnapshotMismatch {
+        expect: SnapshotSegmentId {
+            id: "snapshot-1".to_string(),
+            offset: 0,
+        },
+        got: SnapshotSegmentId {
+            id: "snapshot-1".to_string(),
+            offset: 1024,
+        },
+    };
+    let got = SnapshotMismatch::try_from(pb::SnapshotMismatch::from(mismatch.clone()))?;
+    assert_eq!(mismatch, got);
+
+    for res in [
+        Ok(InstallSnapshotResponse::<TypeConfig> {
+            vote: Vote::new_committed(3, 1),
+        }),
+        Err(RaftError::APIError(InstallSnapshotError::SnapshotMismatch(mismatch))),
+        Err(RaftError::Fatal(Fatal::Stopped)),
+    ] {
+        let p = pb::InstallSnapshotResponse::from(res);
+        let got = Result::<InstallSnapshotResponse<TypeConfig>, RaftError<TypeConfig, InstallSnapshotError>>::try_from(
+            p.clone(),
+        )?;
+        assert_eq!(p, pb::InstallSnapshotResponse::from(got));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_delta_round_trip() -> anyhow::Result<()> {
+    let base = SnapshotSignature {
+        last_log_id: Some(log_id(3, 1, 7)),
+        last_membership_log_id: Some(log_id(3, 1, 5)),
+        snapshot_id: "snapshot-1".to_string(),
+    };
+    let meta = SnapshotMeta::<TypeConfig> {
+        last_log_id: Some(log_id(3, 1, 9)),
+        last_membership: StoredMembership::new(Some(log_id(3, 1, 5)), membership()),
+        snapshot_id: "snapshot-2".to_string(),
+    };
+
+    let req = SnapshotDeltaRequest::<TypeConfig> {
+        vote: Vote::new_committed(3, 1),
+        base: base.clone(),
+        meta: meta.clone(),
+        data: b"delta".to_vec(),
+    };
+    let got = SnapshotDeltaRequest::<TypeConfig>::try_from(pb::SnapshotDeltaRequest::from(req.clone()))?;
+    assert_eq!(req, got);
+
+    let no_snapshot = SnapshotSignature {
+        last_log_id: None,
+        last_membership_log_id: None,
+        snapshot_id: "".to_string(),
+    };
+
+    for res in [
+        Ok(SnapshotResponse::<TypeConfig>::new(Vote::new_committed(3, 1)).with_snapshot(Some(meta.signature()))),
+        Ok(SnapshotResponse::new(Vote::new(4, 2))),
+        Err(RaftError::APIError(SnapshotBaseMismatch {
+            expect: no_snapshot,
+            got: base,
+        })),
+        Err(RaftError::Fatal(Fatal::Stopped)),
+    ] {
+        let p = pb::SnapshotDeltaResponse::from(res);
+        let got =
+            Result::<SnapshotResponse<TypeConfig>, RaftError<TypeConfig, SnapshotBaseMismatch<TypeConfig>>>::try_from(
+                p.clone(),
+            )?;
+        assert_eq!(p, pb::SnapshotDeltaResponse::from(got));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_read_index_round_trip() -> anyhow::Result<()> {
+    let req = ReadIndexRequest::<TypeConfig>::new(2);
+    let got = ReadIndexRequest::<TypeConfig>::from(pb::ReadIndexRequest::from(req.clone()));
+    assert_eq!(req, got);
+
+    for resp in [
+        ReadIndexResponse::<TypeConfig> {
+            read_log_id: Some(log_id(3, 1, 7)),
+        },
+        ReadIndexResponse::<TypeConfig> { read_log_id: None },
+    ] {
+        let got = ReadIndexResponse::<TypeConfig>::try_from(pb::ReadLogId::from(resp.clone()))?;
+        assert_eq!(resp, got);
+    }
+
+    for forward in [
+        ForwardToLeader::<TypeConfig>::new(1, BasicNode::new("127.0.0.1:1")),
+        ForwardToLeader::<TypeConfig>::empty(),
+    ] {
+        let got = ForwardToLeader::<TypeConfig>::from(pb::ForwardToLeader::from(forward.clone()));
+        assert_eq!(forward, got);
+    }
+
+    let quorum = QuorumNotEnough::<TypeConfig> {
+        cluster: "{1,2,3}".to_string(),
+        got: btreeset! {1},
+    };
+    let got = QuorumNotEnough::<TypeConfig>::from(pb::QuorumNotEnough::from(quorum.clone()));
+    assert_eq!(quorum, got);
+
+    for res in [
+        Ok(ReadIndexResponse::<TypeConfig> { read_log_id: None }),
+        Err(RaftError::APIError(CheckIsLeaderError::ForwardToLeader(
+            ForwardToLeader::empty(),
+        ))),
+        Err(RaftError::APIError(CheckIsLeaderError::QuorumNotEnough(quorum))),
+        Err(RaftError::Fatal(Fatal::Stopped)),
+    ] {
+        let p = pb::ReadIndexResponse::from(res);
+        let got =
+            Result::<ReadIndexResponse<TypeConfig>, RaftError<TypeConfig, CheckIsLeaderError<TypeConfig>>>::try_from(
+                p.clone(),
+            )?;
+        assert_eq!(p, pb::ReadIndexResponse::from(got));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_leader_change_round_trip() -> anyhow::Result<()> {
+    let req = TransferLeaderRequest::<TypeConfig>::new(Vote::new_committed(3, 1), 2, Some(log_id(3, 1, 7)));
+    let got = TransferLeaderRequest::<TypeConfig>::try_from(pb::TransferLeaderRequest::from(req.clone()))?;
+    assert_eq!(req, got);
+
+    let req = HandoffLeaderRequest::<TypeConfig>::new(Vote::new_committed(3, 1), 1, 10);
+    let got = HandoffLeaderRequest::<TypeConfig>::try_from(pb::HandoffLeaderRequest::from(req.clone()))?;
+    assert_eq!(req, got);
+
+    for res in [Ok(()), Err(RaftError::Fatal(Fatal::Stopped))] {
+        let got = Result::<(), RaftError<TypeConfig>>::try_from(pb::TransferLeaderResponse::from(res.clone()))?;
+        assert_eq!(res, got);
+    }
+
+    for res in [
+        Ok(HandoffLeaderResponse {
+            priority: 10,
+            accepted: true,
+        }),
+        Err(RaftError::Fatal(Fatal::Panicked)),
+    ] {
+        let got = Result::<HandoffLeaderResponse, RaftError<TypeConfig>>::try_from(pb::HandoffLeaderResponse::from(
+            res.clone(),
+        ))?;
+        assert_eq!(res, got);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fatal_round_trip() -> anyhow::Result<()> {
+    for fatal in [Fatal::<TypeConfig>::Panicked, Fatal::Stopped] {
+        let got = Fatal::<TypeConfig>::try_from(pb::Fatal::from(fatal.clone()))?;
+        assert_eq!(fatal, got);
+    }
+
+    // A storage error is carried as its message.
+    let source = StorageIOError::new(ErrorSubject::Store, ErrorVerb::Write, AnyError::error("disk full"));
+    let fatal = Fatal::<TypeConfig>::StorageError(StorageError::IO { source });
+
+    let got = Fatal::<TypeConfig>::try_from(pb::Fatal::from(fatal.clone()))?;
+    let Fatal::StorageError(e) = got else {
+        panic!("expect storage error");
+    };
+    assert!(e.to_string().contains(&fatal.to_string()), "got: {}", e);
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_missing_field() -> anyhow::Result<()> {
+    let res = VoteRequest::<TypeConfig>::try_from(pb::VoteRequest {
+        vote: None,
+        last_log_id: None,
+    });
+    assert_eq!("missing field: VoteRequest.vote", res.unwrap_err().to_string());
+
+    let res = Membership::<TypeConfig>::try_from(pb::Membership::default());
+    assert!(res.is_err());
+
+    Ok(())
+}
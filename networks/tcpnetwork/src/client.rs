@@ -0,0 +1,361 @@
+use std::error::Error;
+use std::future::Future;
+use std::io;
+use std::io::SeekFrom;
+use std::sync::Arc;
+
+use openraft::error::CheckIsLeaderError;
+use openraft::error::InstallSnapshotError;
+use openraft::error::NetworkError;
+use openraft::error::RPCError;
+use openraft::error::RaftError;
+use openraft::error::RemoteError;
+use openraft::error::ReplicationClosed;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::error::StreamingError;
+use openraft::error::Timeout;
+use openraft::error::Unreachable;
+use openraft::network::RPCOption;
+use openraft::network::RPCTypes;
+use openraft::raft::AppendEntriesRequest;
+use openraft::raft::AppendEntriesResponse;
+use openraft::raft::HandoffLeaderRequest;
+use openraft::raft::HandoffLeaderResponse;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::raft::InstallSnapshotResponse;
+use openraft::raft::ReadIndexRequest;
+use openraft::raft::ReadIndexResponse;
+use openraft::raft::SnapshotResponse;
+use openraft::raft::TransferLeaderRequest;
+use openraft::raft::VoteRequest;
+use openraft::raft::VoteResponse;
+use openraft::storage::SnapshotSignature;
+use openraft::AnyError;
+use openraft::BasicNode;
+use openraft::OptionalSend;
+use openraft::RaftNetwork;
+use openraft::RaftNetworkFactory;
+use openraft::RaftTypeConfig;
+use openraft::Snapshot;
+use openraft::StorageError;
+use openraft::StorageIOError;
+use openraft::Vote;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tokio::net::TcpStream;
+
+use crate::codec;
+use crate::frame;
+use crate::pool::ConnectionPool;
+use crate::protocol::Request;
+use crate::protocol::Response;
+use crate::protocol::SnapshotDeltaRequest;
+use crate::TcpNetworkConfig;
+
+/// Resolve the address to connect to, from the id and the node of a target.
+type Resolve<C> = dyn Fn(<C as RaftTypeConfig>::NodeId, &<C as RaftTypeConfig>::Node) -> String + Send + Sync;
+
+/// Builds a [`TcpNetwork`] for every target node.
+///
+/// All of the [`TcpNetwork`]s built by the same factory share one connection pool.
+pub struct TcpNetworkFactory<C>
+where C: RaftTypeConfig
+{
+    /// The id of the local node, used in reported errors.
+    id: C::NodeId,
+    config: Arc<TcpNetworkConfig>,
+    pool: Arc<ConnectionPool>,
+    resolve: Arc<Resolve<C>>,
+}
+
+impl<C> TcpNetworkFactory<C>
+where C: RaftTypeConfig
+{
+    /// Create a factory for the node `id`.
+    ///
+    /// `resolve` returns the address, such as `"127.0.0.1:21001"`, of a target node.
+    pub fn new(
+        id: C::NodeId,
+        config: TcpNetworkConfig,
+        resolve: impl Fn(C::NodeId, &C::Node) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let pool = ConnectionPool::new(config.max_idle_connections);
+        Self {
+            id,
+            config: Arc::new(config),
+            pool: Arc::new(pool),
+            resolve: Arc::new(resolve),
+        }
+    }
+}
+
+impl<C> TcpNetworkFactory<C>
+where C: RaftTypeConfig<Node = BasicNode>
+{
+    /// Create a factory for the node `id` that connects to [`BasicNode::addr`].
+    pub fn with_basic_node(id: C::NodeId, config: TcpNetworkConfig) -> Self {
+        Self::new(id, config, |_id, node: &BasicNode| node.addr.clone())
+    }
+}
+
+impl<C> RaftNetworkFactory<C> for TcpNetworkFactory<C>
+where
+    C: RaftTypeConfig,
+    C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+{
+    type Network = TcpNetwork<C>;
+
+    async fn new_client(&mut self, target: C::NodeId, node: &C::Node) -> Self::Network {
+        TcpNetwork {
+            id: self.id,
+            target,
+            addr: (self.resolve)(target, node),
+            config: self.config.clone(),
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+/// Sends RPCs to a single target node, each RPC as a request frame followed by a response
+/// frame on a pooled connection.
+///
+/// It implements [`RaftNetwork`] and thus [`RaftNetworkV2`] with the blanket implementation:
+/// a snapshot is streamed in chunks of [`Config::snapshot_max_chunk_size`], each chunk in an
+/// `InstallSnapshot` RPC.
+///
+/// A snapshot delta is sent as a whole in one `SnapshotDelta` RPC. If it does not fit in
+/// [`TcpNetworkConfig::max_frame_size`], it returns [`Unreachable`] and Openraft sends a full
+/// snapshot instead.
+///
+/// [`RaftNetworkV2`]: openraft::network::v2::RaftNetworkV2
+/// [`Config::snapshot_max_chunk_size`]: openraft::Config::snapshot_max_chunk_size
+pub struct TcpNetwork<C>
+where C: RaftTypeConfig
+{
+    id: C::NodeId,
+    target: C::NodeId,
+    addr: String,
+    config: Arc<TcpNetworkConfig>,
+    pool: Arc<ConnectionPool>,
+}
+
+impl<C> TcpNetwork<C>
+where C: RaftTypeConfig
+{
+    /// Send a request and wait for its response, cancelling it after `option.soft_ttl()`.
+    ///
+    /// `hard_ttl()` is left to Openraft to abort an RPC that does not respond to cancellation.
+    async fn call<E: Error>(
+        &self,
+        action: RPCTypes,
+        req: Request<C>,
+        option: &RPCOption,
+    ) -> Result<Response<C>, RPCError<C, E>> {
+        let payload = codec::encode(&req).map_err(|e| NetworkError::new(&e))?;
+        self.call_encoded(action, &payload, option).await
+    }
+
+    /// Send an encoded request and wait for its response, like [`Self::call`].
+    async fn call_encoded<E: Error>(
+        &self,
+        action: RPCTypes,
+        payload: &[u8],
+        option: &RPCOption,
+    ) -> Result<Response<C>, RPCError<C, E>> {
+        let timeout = option.soft_ttl();
+        let buf = tokio::time::timeout(timeout, self.round_trip(payload)).await.map_err(|_elapsed| Timeout {
+            action,
+            id: self.id,
+            target: self.target,
+            timeout,
+        })??;
+
+        let resp = codec::decode(&buf).map_err(|e| NetworkError::new(&e))?;
+        Ok(resp)
+    }
+
+    /// Exchange a request frame for a response frame.
+    ///
+    /// An idle connection may have been closed by the remote, thus if it fails, the request is sent
+    /// once more on a new connection. Raft RPCs are idempotent, so it is safe even if the first
+    /// one was delivered.
+    async fn round_trip<E: Error>(&self, payload: &[u8]) -> Result<Vec<u8>, RPCError<C, E>> {
+        if let Some(mut conn) = self.pool.take(&self.addr) {
+            match self.exchange(&mut conn, payload).await {
+                Ok(buf) => {
+                    self.pool.put(&self.addr, conn);
+                    return Ok(buf);
+                }
+                Err(e) => {
+                    tracing::debug!("idle connection to {} failed: {}, reconnect", self.addr, e);
+                }
+            }
+        }
+
+        let mut conn = self.connect().await.map_err(|e| Unreachable::new(&e))?;
+        let buf = self.exchange(&mut conn, payload).await.map_err(|e| NetworkError::new(&e))?;
+        self.pool.put(&self.addr, conn);
+        Ok(buf)
+    }
+
+    async fn exchange(&self, conn: &mut TcpStream, payload: &[u8]) -> io::Result<Vec<u8>> {
+        frame::write_frame(conn, payload).await?;
+        frame::read_frame(conn, self.config.max_frame_size).await
+    }
+
+    async fn connect(&self) -> io::Result<TcpStream> {
+        let connecting = TcpStream::connect(self.addr.as_str());
+        let conn = tokio::time::timeout(self.config.connect_timeout, connecting).await.map_err(|_elapsed| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "connect to {} timeout after {:?}",
+                    self.addr, self.config.connect_timeout
+                ),
+            )
+        })??;
+        conn.set_nodelay(true)?;
+        Ok(conn)
+    }
+
+    /// Convert an error returned by the remote `Raft` into an [`RPCError`].
+    fn remote<T, E: Error>(&self, res: Result<T, E>) -> Result<T, RPCError<C, E>> {
+        res.map_err(|e| RPCError::RemoteError(RemoteError::new(self.target, e)))
+    }
+
+    /// A response that does not match the request means a broken peer.
+    fn unexpected<T, E: Error>(&self, expect: &str, resp: Response<C>) -> Result<T, RPCError<C, E>> {
+        let e = AnyError::error(format!("expect {} response, got: {}", expect, resp.name()));
+        Err(RPCError::Network(NetworkError::new(&e)))
+    }
+}
+
+impl<C> RaftNetwork<C> for TcpNetwork<C>
+where
+    C: RaftTypeConfig,
+    C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+{
+    async fn append_entries(
+        &mut self,
+        rpc: AppendEntriesRequest<C>,
+        option: RPCOption,
+    ) -> Result<AppendEntriesResponse<C>, RPCError<C, RaftError<C>>> {
+        match self.call(RPCTypes::AppendEntries, Request::AppendEntries(rpc), &option).await? {
+            Response::AppendEntries(res) => self.remote(res),
+            resp => self.unexpected("AppendEntries", resp),
+        }
+    }
+
+    async fn vote(
+        &mut self,
+        rpc: VoteRequest<C>,
+        option: RPCOption,
+    ) -> Result<VoteResponse<C>, RPCError<C, RaftError<C>>> {
+        match self.call(RPCTypes::Vote, Request::Vote(rpc), &option).await? {
+            Response::Vote(res) => self.remote(res),
+            resp => self.unexpected("Vote", resp),
+        }
+    }
+
+    async fn pre_vote(
+        &mut self,
+        rpc: VoteRequest<C>,
+        option: RPCOption,
+    ) -> Result<VoteResponse<C>, RPCError<C, RaftError<C>>> {
+        match self.call(RPCTypes::PreVote, Request::PreVote(rpc), &option).await? {
+            Response::PreVote(res) => self.remote(res),
+            resp => self.unexpected("PreVote", resp),
+        }
+    }
+
+    async fn install_snapshot(
+        &mut self,
+        rpc: InstallSnapshotRequest<C>,
+        option: RPCOption,
+    ) -> Result<InstallSnapshotResponse<C>, RPCError<C, RaftError<C, InstallSnapshotError>>> {
+        match self.call(RPCTypes::InstallSnapshot, Request::InstallSnapshot(rpc), &option).await? {
+            Response::InstallSnapshot(res) => self.remote(res),
+            resp => self.unexpected("InstallSnapshot", resp),
+        }
+    }
+
+    async fn read_index(
+        &mut self,
+        rpc: ReadIndexRequest<C>,
+        option: RPCOption,
+    ) -> Result<ReadIndexResponse<C>, RPCError<C, RaftError<C, CheckIsLeaderError<C>>>> {
+        match self.call(RPCTypes::ReadIndex, Request::ReadIndex(rpc), &option).await? {
+            Response::ReadIndex(res) => self.remote(res),
+            resp => self.unexpected("ReadIndex", resp),
+        }
+    }
+
+    async fn handoff_leader(
+        &mut self,
+        rpc: HandoffLeaderRequest<C>,
+        option: RPCOption,
+    ) -> Result<HandoffLeaderResponse, RPCError<C, RaftError<C>>> {
+        match self.call(RPCTypes::HandoffLeader, Request::HandoffLeader(rpc), &option).await? {
+            Response::HandoffLeader(res) => self.remote(res),
+            resp => self.unexpected("HandoffLeader", resp),
+        }
+    }
+
+    async fn transfer_leader(
+        &mut self,
+        rpc: TransferLeaderRequest<C>,
+        option: RPCOption,
+    ) -> Result<(), RPCError<C, RaftError<C>>> {
+        match self.call(RPCTypes::TransferLeader, Request::TransferLeader(rpc), &option).await? {
+            Response::TransferLeader(res) => self.remote(res),
+            resp => self.unexpected("TransferLeader", resp),
+        }
+    }
+
+    async fn snapshot_delta(
+        &mut self,
+        vote: Vote<C::NodeId>,
+        base: SnapshotSignature<C::NodeId>,
+        mut delta: Snapshot<C>,
+        cancel: impl Future<Output = ReplicationClosed> + OptionalSend + 'static,
+        option: RPCOption,
+    ) -> Result<SnapshotResponse<C>, StreamingError<C, RaftError<C, SnapshotBaseMismatch<C>>>> {
+        let mut data = Vec::new();
+        let read = async {
+            delta.snapshot.seek(SeekFrom::Start(0)).await?;
+            delta.snapshot.read_to_end(&mut data).await
+        };
+        read.await.map_err(|e| {
+            let io_err = StorageIOError::read_snapshot(Some(delta.meta.signature()), &e);
+            StorageError::from(io_err)
+        })?;
+
+        let req = Request::SnapshotDelta(SnapshotDeltaRequest {
+            vote,
+            base,
+            meta: delta.meta,
+            data,
+        });
+        let payload = codec::encode(&req).map_err(|e| NetworkError::new(&e))?;
+
+        // The server would close the connection upon a frame it can not accept.
+        if payload.len() > self.config.max_frame_size {
+            let e = AnyError::error(format!(
+                "snapshot delta of {} bytes exceeds max_frame_size {}",
+                payload.len(),
+                self.config.max_frame_size
+            ));
+            return Err(StreamingError::Unreachable(Unreachable::new(&e)));
+        }
+
+        let resp = tokio::select! {
+            resp = self.call_encoded(RPCTypes::SnapshotDelta, &payload, &option) => resp?,
+            closed = cancel => return Err(StreamingError::Closed(closed)),
+        };
+
+        match resp {
+            Response::SnapshotDelta(res) => Ok(self.remote(res)?),
+            resp => Ok(self.unexpected("SnapshotDelta", resp)?),
+        }
+    }
+}
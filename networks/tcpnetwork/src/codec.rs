@@ -0,0 +1,14 @@
+//! Encode and decode messages into frame payloads.
+
+use std::io;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub(crate) fn encode<T: Serialize>(v: &T) -> io::Result<Vec<u8>> {
+    serde_json::to_vec(v).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(buf: &[u8]) -> io::Result<T> {
+    serde_json::from_slice(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+/// Configuration of the TCP network transport, shared by the client and the server side.
+#[derive(Clone, Debug)]
+pub struct TcpNetworkConfig {
+    /// The timeout to establish a connection to a remote node.
+    ///
+    /// An RPC is also bounded by [`RPCOption::soft_ttl()`], whichever is shorter.
+    ///
+    /// [`RPCOption::soft_ttl()`]: openraft::network::RPCOption::soft_ttl
+    pub connect_timeout: Duration,
+
+    /// The max size in bytes of a single frame.
+    ///
+    /// A peer that sends a larger frame is considered broken and the connection is closed.
+    pub max_frame_size: usize,
+
+    /// The max number of idle connections kept for every remote node.
+    pub max_idle_connections: usize,
+}
+
+impl Default for TcpNetworkConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_millis(1_000),
+            max_frame_size: 64 * 1024 * 1024,
+            max_idle_connections: 8,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Length-prefixed framing: every frame is a 4-byte big-endian length followed by the payload.
+
+use std::io;
+
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+
+/// Write `payload` as a single frame and flush it.
+pub(crate) async fn write_frame<W>(w: &mut W, payload: &[u8]) -> io::Result<()>
+where W: AsyncWrite + Unpin + ?Sized {
+    let len = u32::try_from(payload.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("frame size {} exceeds u32::MAX", payload.len()),
+        )
+    })?;
+
+    w.write_all(&len.to_be_bytes()).await?;
+    w.write_all(payload).await?;
+    w.flush().await
+}
+
+/// Read a single frame and return its payload.
+///
+/// A frame larger than `max_frame_size` is rejected with [`io::ErrorKind::InvalidData`].
+/// If the peer closes the connection before a frame starts, it returns
+/// [`io::ErrorKind::UnexpectedEof`].
+pub(crate) async fn read_frame<R>(r: &mut R, max_frame_size: usize) -> io::Result<Vec<u8>>
+where R: AsyncRead + Unpin + ?Sized {
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len).await?;
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > max_frame_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame size {} exceeds max_frame_size {}", len, max_frame_size),
+        ));
+    }
+
+    let mut payload = vec![0u8; len];
+    r.read_exact(&mut payload).await?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::read_frame;
+    use super::write_frame;
+
+    #[tokio::test]
+    async fn test_frame_round_trip() -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, b"hello").await?;
+        write_frame(&mut buf, b"").await?;
+        assert_eq!(&buf[..4], &[0, 0, 0, 5]);
+
+        let mut r = &buf[..];
+        assert_eq!(b"hello".to_vec(), read_frame(&mut r, 1024).await?);
+        assert_eq!(Vec::<u8>::new(), read_frame(&mut r, 1024).await?);
+
+        let err = read_frame(&mut r, 1024).await.unwrap_err();
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_frame_too_large() -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, b"hello").await?;
+
+        let err = read_frame(&mut &buf[..], 4).await.unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        Ok(())
+    }
+}
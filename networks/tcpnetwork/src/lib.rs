@@ -0,0 +1,18 @@
+#![doc = include_str!("../README.md")]
+#![deny(unused_crate_dependencies)]
+#![deny(unused_qualifications)]
+
+#[cfg(test)] mod test;
+
+mod client;
+mod codec;
+mod config;
+mod frame;
+mod pool;
+mod protocol;
+mod server;
+
+pub use client::TcpNetwork;
+pub use client::TcpNetworkFactory;
+pub use config::TcpNetworkConfig;
+pub use server::TcpServer;
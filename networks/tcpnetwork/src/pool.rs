@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tokio::net::TcpStream;
+
+/// Idle connections to remote nodes, keyed by address.
+///
+/// A connection is taken out of the pool for the duration of an RPC and is put back only if the
+/// RPC completes, so that a connection in an unknown state, e.g., after a timeout, is never
+/// reused.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionPool {
+    max_idle: usize,
+    idle: Mutex<HashMap<String, Vec<TcpStream>>>,
+}
+
+impl ConnectionPool {
+    pub(crate) fn new(max_idle: usize) -> Self {
+        Self {
+            max_idle,
+            idle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take an idle connection to `addr` out of the pool.
+    pub(crate) fn take(&self, addr: &str) -> Option<TcpStream> {
+        let mut idle = self.idle.lock().unwrap();
+        idle.get_mut(addr).and_then(|conns| conns.pop())
+    }
+
+    /// Put a connection back to the pool. It is dropped if there are already enough idle ones.
+    pub(crate) fn put(&self, addr: &str, conn: TcpStream) {
+        let mut idle = self.idle.lock().unwrap();
+        let conns = idle.entry(addr.to_string()).or_default();
+        if conns.len() < self.max_idle {
+            conns.push(conn);
+        }
+    }
+}
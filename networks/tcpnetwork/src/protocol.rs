@@ -0,0 +1,82 @@
+//! Messages exchanged between a [`TcpNetwork`] and a [`TcpServer`].
+//!
+//! [`TcpNetwork`]: crate::TcpNetwork
+//! [`TcpServer`]: crate::TcpServer
+
+use openraft::error::CheckIsLeaderError;
+use openraft::error::InstallSnapshotError;
+use openraft::error::RaftError;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::raft::AppendEntriesRequest;
+use openraft::raft::AppendEntriesResponse;
+use openraft::raft::HandoffLeaderRequest;
+use openraft::raft::HandoffLeaderResponse;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::raft::InstallSnapshotResponse;
+use openraft::raft::ReadIndexRequest;
+use openraft::raft::ReadIndexResponse;
+use openraft::raft::SnapshotResponse;
+use openraft::raft::TransferLeaderRequest;
+use openraft::raft::VoteRequest;
+use openraft::raft::VoteResponse;
+use openraft::storage::SnapshotSignature;
+use openraft::RaftTypeConfig;
+use openraft::SnapshotMeta;
+use openraft::Vote;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A request sent by a client, one per frame.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub(crate) enum Request<C: RaftTypeConfig> {
+    AppendEntries(AppendEntriesRequest<C>),
+    Vote(VoteRequest<C>),
+    PreVote(VoteRequest<C>),
+    InstallSnapshot(InstallSnapshotRequest<C>),
+    ReadIndex(ReadIndexRequest<C>),
+    HandoffLeader(HandoffLeaderRequest<C>),
+    TransferLeader(TransferLeaderRequest<C>),
+    SnapshotDelta(SnapshotDeltaRequest<C>),
+}
+
+/// A snapshot delta upon the snapshot `base`, sent as a whole in one frame.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub(crate) struct SnapshotDeltaRequest<C: RaftTypeConfig> {
+    pub(crate) vote: Vote<C::NodeId>,
+    pub(crate) base: SnapshotSignature<C::NodeId>,
+    pub(crate) meta: SnapshotMeta<C>,
+    pub(crate) data: Vec<u8>,
+}
+
+/// The response to a [`Request`] of the same variant, carrying the result returned by the
+/// remote `Raft`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub(crate) enum Response<C: RaftTypeConfig> {
+    AppendEntries(Result<AppendEntriesResponse<C>, RaftError<C>>),
+    Vote(Result<VoteResponse<C>, RaftError<C>>),
+    PreVote(Result<VoteResponse<C>, RaftError<C>>),
+    InstallSnapshot(Result<InstallSnapshotResponse<C>, RaftError<C, InstallSnapshotError>>),
+    ReadIndex(Result<ReadIndexResponse<C>, RaftError<C, CheckIsLeaderError<C>>>),
+    HandoffLeader(Result<HandoffLeaderResponse, RaftError<C>>),
+    TransferLeader(Result<(), RaftError<C>>),
+    SnapshotDelta(Result<SnapshotResponse<C>, RaftError<C, SnapshotBaseMismatch<C>>>),
+}
+
+impl<C: RaftTypeConfig> Response<C> {
+    /// The name of the variant, for reporting an unexpected response.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Response::AppendEntries(_) => "AppendEntries",
+            Response::Vote(_) => "Vote",
+            Response::PreVote(_) => "PreVote",
+            Response::InstallSnapshot(_) => "InstallSnapshot",
+            Response::ReadIndex(_) => "ReadIndex",
+            Response::HandoffLeader(_) => "HandoffLeader",
+            Response::TransferLeader(_) => "TransferLeader",
+            Response::SnapshotDelta(_) => "SnapshotDelta",
+        }
+    }
+}
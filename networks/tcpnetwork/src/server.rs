@@ -0,0 +1,217 @@
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use openraft::error::InstallSnapshotError;
+use openraft::error::RaftError;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::network::snapshot_transport::Chunked;
+use openraft::network::snapshot_transport::SnapshotTransport;
+use openraft::network::snapshot_transport::Streaming;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::raft::InstallSnapshotResponse;
+use openraft::raft::SnapshotResponse;
+use openraft::Raft;
+use openraft::RaftTypeConfig;
+use openraft::Snapshot;
+use openraft::StorageError;
+use openraft::StorageIOError;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+
+use crate::codec;
+use crate::frame;
+use crate::protocol::Request;
+use crate::protocol::Response;
+use crate::protocol::SnapshotDeltaRequest;
+use crate::TcpNetworkConfig;
+
+/// Accepts connections from [`TcpNetwork`]s and dispatches the received RPCs to a [`Raft`].
+///
+/// Requests on one connection are handled one by one, in the order they are received.
+/// A client opens more connections to send RPCs concurrently.
+///
+/// [`TcpNetwork`]: crate::TcpNetwork
+pub struct TcpServer<C>
+where C: RaftTypeConfig
+{
+    raft: Raft<C>,
+    config: Arc<TcpNetworkConfig>,
+
+    /// The snapshot being received in chunks, shared by all connections.
+    streaming: Arc<Mutex<Option<Streaming<C>>>>,
+}
+
+impl<C> TcpServer<C>
+where
+    C: RaftTypeConfig,
+    C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+{
+    /// How long to pause before accepting again after an error, e.g., too many open files.
+    const ACCEPT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new(raft: Raft<C>, config: TcpNetworkConfig) -> Self {
+        Self {
+            raft,
+            config: Arc::new(config),
+            streaming: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Serve RPCs on connections accepted from `listener`, until `shutdown` is ready.
+    ///
+    /// When it returns, all of the connections are closed.
+    /// An error accepting a connection, such as running out of file descriptors, is logged, and it
+    /// keeps accepting after a short pause.
+    pub async fn serve(&self, listener: TcpListener, shutdown: impl Future<Output = ()>) -> io::Result<()> {
+        let mut connections = JoinSet::new();
+
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    tracing::info!("TcpServer shutdown, close {} connections", connections.len());
+                    connections.shutdown().await;
+                    return Ok(());
+                }
+                accepted = listener.accept() => {
+                    let (conn, peer) = match accepted {
+                        Ok(x) => x,
+                        Err(e) => {
+                            tracing::warn!("TcpServer failed to accept connection: {}, retry later", e);
+                            tokio::time::sleep(Self::ACCEPT_RETRY_INTERVAL).await;
+                            continue;
+                        }
+                    };
+                    tracing::debug!("TcpServer accepted connection from {}", peer);
+
+                    if let Err(e) = conn.set_nodelay(true) {
+                        tracing::warn!("failed to set TCP_NODELAY for connection from {}: {}", peer, e);
+                    }
+
+                    let fu = Self::serve_connection(self.raft.clone(), self.config.clone(), self.streaming.clone(), conn);
+                    connections.spawn(fu);
+                }
+                Some(_finished) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+    }
+
+    async fn serve_connection(
+        raft: Raft<C>,
+        config: Arc<TcpNetworkConfig>,
+        streaming: Arc<Mutex<Option<Streaming<C>>>>,
+        mut conn: TcpStream,
+    ) {
+        loop {
+            let buf = match frame::read_frame(&mut conn, config.max_frame_size).await {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                    tracing::debug!("connection closed by peer");
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!("failed to read request: {}, close connection", e);
+                    return;
+                }
+            };
+
+            let req = match codec::decode::<Request<C>>(&buf) {
+                Ok(req) => req,
+                Err(e) => {
+                    tracing::warn!("failed to decode request: {}, close connection", e);
+                    return;
+                }
+            };
+
+            let resp = Self::handle(&raft, &streaming, req).await;
+
+            let res = match codec::encode(&resp) {
+                Ok(payload) => frame::write_frame(&mut conn, &payload).await,
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = res {
+                tracing::warn!("failed to send response: {}, close connection", e);
+                return;
+            }
+        }
+    }
+
+    async fn handle(raft: &Raft<C>, streaming: &Mutex<Option<Streaming<C>>>, req: Request<C>) -> Response<C> {
+        match req {
+            Request::AppendEntries(rpc) => Response::AppendEntries(raft.append_entries(rpc).await),
+            Request::Vote(rpc) => Response::Vote(raft.vote(rpc).await),
+            Request::PreVote(rpc) => Response::PreVote(raft.pre_vote(rpc).await),
+            Request::InstallSnapshot(rpc) => {
+                Response::InstallSnapshot(Self::install_snapshot_chunk(raft, streaming, rpc).await)
+            }
+            Request::ReadIndex(rpc) => Response::ReadIndex(raft.handle_read_index(rpc).await),
+            Request::HandoffLeader(rpc) => Response::HandoffLeader(raft.handle_handoff_leader(rpc).await),
+            Request::TransferLeader(rpc) => {
+                let res = raft.handle_transfer_leader(rpc).await.map_err(RaftError::Fatal);
+                Response::TransferLeader(res)
+            }
+            Request::SnapshotDelta(req) => Response::SnapshotDelta(Self::install_snapshot_delta(raft, req).await),
+        }
+    }
+
+    /// Receive a snapshot chunk, and install the snapshot with [`Raft::install_full_snapshot`]
+    /// once the last chunk is received.
+    async fn install_snapshot_chunk(
+        raft: &Raft<C>,
+        streaming: &Mutex<Option<Streaming<C>>>,
+        req: InstallSnapshotRequest<C>,
+    ) -> Result<InstallSnapshotResponse<C>, RaftError<C, InstallSnapshotError>> {
+        let req_vote = req.vote;
+        let my_vote = raft.with_raft_state(|st| *st.vote_ref()).await?;
+
+        // Reject a chunk from a stale leader early, before receiving its data.
+        if req_vote < my_vote {
+            tracing::info!("vote {} is rejected by local vote: {}", req_vote, my_vote);
+            return Ok(InstallSnapshotResponse { vote: my_vote });
+        }
+
+        let finished = {
+            let mut streaming = streaming.lock().await;
+            Chunked::receive_snapshot(&mut *streaming, raft, req).await?
+        };
+
+        match finished {
+            Some(snapshot) => Ok(raft.install_full_snapshot(req_vote, snapshot).await?.into()),
+            None => Ok(InstallSnapshotResponse { vote: my_vote }),
+        }
+    }
+
+    /// Install a snapshot delta, which is received in a single request, with
+    /// [`Raft::install_snapshot_delta`].
+    async fn install_snapshot_delta(
+        raft: &Raft<C>,
+        req: SnapshotDeltaRequest<C>,
+    ) -> Result<SnapshotResponse<C>, RaftError<C, SnapshotBaseMismatch<C>>> {
+        let mut data = raft.begin_receiving_snapshot().await.map_err(|e| {
+            // Safe unwrap: `RaftError<Infallible>` is always a Fatal.
+            RaftError::Fatal(e.into_fatal().unwrap())
+        })?;
+
+        let written = async {
+            data.write_all(&req.data).await?;
+            data.shutdown().await
+        };
+        written.await.map_err(|e| {
+            let io_err = StorageIOError::write_snapshot(Some(req.meta.signature()), &e);
+            StorageError::from(io_err)
+        })?;
+
+        let delta = Snapshot {
+            meta: req.meta,
+            snapshot: data,
+        };
+        raft.install_snapshot_delta(req.vote, req.base, delta).await
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::future;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+
+use openraft::error::RaftError;
+use openraft::error::ReplicationClosed;
+use openraft::error::StreamingError;
+use openraft::network::v2::RaftNetworkV2;
+use openraft::network::RPCOption;
+use openraft::CommittedLeaderId;
+use openraft::Config;
+use openraft::LogId;
+use openraft::Raft;
+use openraft::RaftNetworkFactory;
+use openraft::ServerState;
+use openraft::Snapshot;
+use openraft::SnapshotMeta;
+use openraft::Vote;
+use openraft_memstore::ClientRequest;
+use openraft_memstore::MemNodeId;
+use openraft_memstore::MemStateMachine;
+use openraft_memstore::MemStoreStateMachine;
+use openraft_memstore::TypeConfig;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::TcpNetworkConfig;
+use crate::TcpNetworkFactory;
+use crate::TcpServer;
+
+/// A cluster of nodes talking to each other over localhost TCP.
+struct Cluster {
+    config: Arc<Config>,
+    addrs: Arc<BTreeMap<MemNodeId, String>>,
+    listeners: BTreeMap<MemNodeId, TcpListener>,
+    rafts: BTreeMap<MemNodeId, Raft<TypeConfig>>,
+    state_machines: BTreeMap<MemNodeId, Arc<MemStateMachine>>,
+    shutdown: Vec<oneshot::Sender<()>>,
+}
+
+impl Cluster {
+    /// Bind a listener for every node, so that every address is known before a node starts.
+    async fn bind(config: Config, ids: impl IntoIterator<Item = MemNodeId>) -> anyhow::Result<Self> {
+        let mut addrs = BTreeMap::new();
+        let mut listeners = BTreeMap::new();
+
+        for id in ids {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            addrs.insert(id, listener.local_addr()?.to_string());
+            listeners.insert(id, listener);
+        }
+
+        Ok(Self {
+            config: Arc::new(config.validate()?),
+            addrs: Arc::new(addrs),
+            listeners,
+            rafts: BTreeMap::new(),
+            state_machines: BTreeMap::new(),
+            shutdown: vec![],
+        })
+    }
+
+    async fn start(&mut self, id: MemNodeId) -> anyhow::Result<Raft<TypeConfig>> {
+        let addrs = self.addrs.clone();
+        let network = TcpNetworkFactory::<TypeConfig>::new(id, TcpNetworkConfig::default(), move |target, _node| {
+            addrs[&target].clone()
+        });
+
+        let (log_store, sm) = openraft_memstore::new_mem_store();
+        let raft = Raft::new(id, self.config.clone(), network, log_store, sm.clone()).await?;
+
+        let listener = self.listeners.remove(&id).unwrap();
+        let (tx, rx) = oneshot::channel();
+        let server = TcpServer::new(raft.clone(), TcpNetworkConfig::default());
+        tokio::spawn(async move {
+            server
+                .serve(listener, async move {
+                    let _ = rx.await;
+                })
+                .await
+        });
+
+        self.shutdown.push(tx);
+        self.rafts.insert(id, raft.clone());
+        self.state_machines.insert(id, sm);
+        Ok(raft)
+    }
+
+    async fn shutdown(self) -> anyhow::Result<()> {
+        for raft in self.rafts.values() {
+            raft.shutdown().await?;
+        }
+        for tx in self.shutdown {
+            let _ = tx.send(());
+        }
+        Ok(())
+    }
+}
+
+fn request(serial: u64) -> ClientRequest {
+    ClientRequest {
+        client: "foo".to_string(),
+        serial,
+        status: format!("status-{}", serial),
+    }
+}
+
+/// A snapshot of the memstore state machine `sm`.
+fn snapshot(id: &str, sm: &MemStoreStateMachine) -> anyhow::Result<Snapshot<TypeConfig>> {
+    Ok(Snapshot {
+        meta: SnapshotMeta {
+            last_log_id: sm.last_applied_log,
+            last_membership: sm.last_membership.clone(),
+            snapshot_id: id.to_string(),
+        },
+        snapshot: Box::new(Cursor::new(serde_json::to_vec(sm)?)),
+    })
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_secs(10))
+}
+
+/// Elect a leader and replicate logs over TCP.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_tcp_network_replicate() -> anyhow::Result<()> {
+    let mut cluster = Cluster::bind(Config::default(), [0, 1, 2]).await?;
+
+    for id in [0, 1, 2] {
+        cluster.start(id).await?;
+    }
+
+    let n0 = cluster.rafts[&0].clone();
+    n0.initialize(BTreeSet::from([0, 1, 2])).await?;
+    n0.wait(timeout()).state(ServerState::Leader, "n0 becomes leader").await?;
+
+    let mut last = None;
+    for serial in 0..10 {
+        let resp = n0.client_write(request(serial)).await?;
+        last = Some(resp.log_id.index);
+    }
+
+    for (id, raft) in cluster.rafts.iter() {
+        raft.wait(timeout()).applied_index(last, format!("n{} applied all logs", id)).await?;
+    }
+
+    cluster.shutdown().await
+}
+
+/// A learner that lacks the purged logs receives a snapshot in several chunks over TCP.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_tcp_network_snapshot() -> anyhow::Result<()> {
+    let config = Config {
+        snapshot_max_chunk_size: 64,
+        ..Default::default()
+    };
+    let mut cluster = Cluster::bind(config, [0, 1]).await?;
+
+    let n0 = cluster.start(0).await?;
+    n0.initialize(BTreeSet::from([0])).await?;
+    n0.wait(timeout()).state(ServerState::Leader, "n0 becomes leader").await?;
+
+    let mut last = None;
+    for serial in 0..10 {
+        let resp = n0.client_write(request(serial)).await?;
+        last = Some(resp.log_id);
+    }
+    let last = last.unwrap();
+
+    n0.trigger().snapshot().await?;
+    n0.wait(timeout()).snapshot(last, "n0 built snapshot").await?;
+    n0.trigger().purge_log(last.index).await?;
+    n0.wait(timeout()).purged(Some(last), "n0 purged logs").await?;
+
+    let n1 = cluster.start(1).await?;
+    n0.add_learner(1, (), true).await?;
+    n1.wait(timeout()).snapshot(last, "n1 installed snapshot").await?;
+
+    cluster.shutdown().await
+}
+
+/// A snapshot delta is sent in one RPC over TCP, and it is rejected if its base is not the current
+/// snapshot of the target.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_tcp_network_snapshot_delta() -> anyhow::Result<()> {
+    let mut cluster = Cluster::bind(Config::default(), [1]).await?;
+    let n1 = cluster.start(1).await?;
+
+    let vote = Vote::new_committed(1, 0);
+    let log_id = |index| Some(LogId::new(CommittedLeaderId::new(1, 0), index));
+
+    let sm1 = MemStoreStateMachine {
+        last_applied_log: log_id(5),
+        client_status: HashMap::from([
+            ("foo".to_string(), "a".to_string()),
+            ("bar".to_string(), "b".to_string()),
+        ]),
+        ..Default::default()
+    };
+    let sm2 = MemStoreStateMachine {
+        last_applied_log: log_id(10),
+        client_status: HashMap::from([("foo".to_string(), "c".to_string())]),
+        ..Default::default()
+    };
+
+    let snapshot1 = snapshot("s1", &sm1)?;
+    let base = snapshot1.meta.signature();
+    n1.install_full_snapshot(vote, snapshot1).await?;
+
+    let mut factory = TcpNetworkFactory::<TypeConfig>::new(0, TcpNetworkConfig::default(), {
+        let addrs = cluster.addrs.clone();
+        move |target, _node| addrs[&target].clone()
+    });
+    let mut net = factory.new_client(1, &()).await;
+
+    let delta = || -> anyhow::Result<Snapshot<TypeConfig>> {
+        let mut delta = snapshot("s2", &sm2)?;
+        delta.snapshot = Box::new(Cursor::new(serde_json::to_vec(&sm2.delta_since(&sm1))?));
+        Ok(delta)
+    };
+    let cancel = future::pending::<ReplicationClosed>;
+    let option = || RPCOption::new(Duration::from_secs(10));
+
+    tracing::info!("--- a delta upon a snapshot the target does not have is rejected");
+    {
+        let mut wrong_base = base.clone();
+        wrong_base.snapshot_id = "s0".to_string();
+
+        let res = net.snapshot_delta(vote, wrong_base, delta()?, cancel(), option()).await;
+        let Err(StreamingError::RemoteError(e)) = res else {
+            panic!("expect RemoteError, got: {:?}", res);
+        };
+        let RaftError::APIError(mismatch) = e.source else {
+            panic!("expect SnapshotBaseMismatch, got: {:?}", e.source);
+        };
+        assert_eq!(base, mismatch.expect);
+    }
+
+    tracing::info!("--- a delta upon the current snapshot of the target is installed");
+    {
+        let delta = delta()?;
+        let want = delta.meta.signature();
+
+        let resp = net.snapshot_delta(vote, base, delta, cancel(), option()).await?;
+        assert_eq!(Some(want), resp.snapshot);
+
+        let got = cluster.state_machines[&1].get_state_machine().await;
+        assert_eq!(sm2.last_applied_log, got.last_applied_log);
+        assert_eq!(sm2.client_status, got.client_status);
+    }
+
+    cluster.shutdown().await
+}
@@ -25,6 +25,18 @@ pub enum ChangeMembers<NID: NodeId, N: Node> {
     /// Replace voter ids with a new set. The node of every new voter has to already be a learner.
     ReplaceAllVoters(BTreeSet<NID>),
 
+    /// Add witnesses with corresponding nodes.
+    ///
+    /// A witness is a voter that stores only log ids and membership, without application data,
+    /// and never becomes a leader. A witness has to be a new node: an existing voter or learner
+    /// can not be added as a witness.
+    AddWitnesses(BTreeMap<NID, N>),
+
+    /// Remove witnesses. A removed witness is not retained as a learner.
+    ///
+    /// Node ids that are not witness are ignored.
+    RemoveWitnesses(BTreeSet<NID>),
+
     /// Add nodes to membership, as learners.
     ///
     /// it **WONT** replace existing node.
@@ -1,7 +1,9 @@
 //! Raft runtime configuration.
 
-use std::ops::Deref;
+use std::any::Any;
+use std::fmt;
 use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyerror::AnyError;
@@ -9,16 +11,18 @@ use clap::Parser;
 use rand::Rng;
 
 use crate::config::error::ConfigError;
-use crate::raft_state::LogStateReader;
 use crate::AsyncRuntime;
-use crate::LogIdOptionExt;
-use crate::NodeId;
+use crate::RaftState;
+use crate::RaftTypeConfig;
 
 /// Log compaction and snapshot policy.
 ///
 /// This governs when periodic snapshots will be taken, and also governs the conditions which
 /// would cause a leader to send an `InstallSnapshot` RPC to a follower based on replication lag.
 ///
+/// A policy is checked every time new logs are committed, and a snapshot is built only if there
+/// are committed logs that are not included in the last snapshot.
+///
 /// Additional policies may become available in the future.
 #[derive(Clone, Debug)]
 #[derive(PartialEq, Eq)]
@@ -28,6 +32,31 @@ pub enum SnapshotPolicy {
     /// the last snapshot.
     LogsSinceLast(u64),
 
+    /// A snapshot will be generated once the approximate total size in bytes of the logs after
+    /// the last snapshot reaches the specified value.
+    ///
+    /// The size of an entry is [`RaftEntry::approx_size()`]. The sizes of the logs written before
+    /// this node started are loaded from the log store when it starts.
+    ///
+    /// [`RaftEntry::approx_size()`]: crate::entry::RaftEntry::approx_size
+    BytesSinceLast(u64),
+
+    /// A snapshot will be generated once the specified time has elapsed since the last snapshot,
+    /// or since this node started if no snapshot is built or installed since then.
+    TimeSinceLast(Duration),
+
+    /// A snapshot will be generated when the user defined predicate returns `true`.
+    ///
+    /// This policy can not be serialized or parsed from command line.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(SnapshotPredicate),
+
+    /// A snapshot will be generated if any of the policies decides to.
+    Any(Vec<SnapshotPolicy>),
+
+    /// A snapshot will be generated if all of the policies decide to.
+    All(Vec<SnapshotPolicy>),
+
     /// Openraft will never trigger a snapshot building.
     /// With this option, the application calls
     /// [`Raft::trigger().snapshot()`](`crate::raft::trigger::Trigger::snapshot`) to manually
@@ -36,17 +65,104 @@ pub enum SnapshotPolicy {
 }
 
 impl SnapshotPolicy {
-    pub(crate) fn should_snapshot<NID>(&self, state: &impl Deref<Target = impl LogStateReader<NID>>) -> bool
-    where NID: NodeId {
+    pub(crate) fn should_snapshot<C>(&self, state: &RaftState<C>, logs: &LogsSinceSnapshot) -> bool
+    where C: RaftTypeConfig {
+        if logs.committed_logs == 0 {
+            return false;
+        }
+
+        self.is_satisfied(state, logs)
+    }
+
+    fn is_satisfied(&self, state: &dyn Any, logs: &LogsSinceSnapshot) -> bool {
         match self {
-            SnapshotPolicy::LogsSinceLast(threshold) => {
-                state.committed().next_index() >= state.snapshot_last_log_id().next_index() + threshold
-            }
+            SnapshotPolicy::LogsSinceLast(threshold) => logs.committed_logs >= *threshold,
+            SnapshotPolicy::BytesSinceLast(threshold) => logs.bytes >= *threshold,
+            SnapshotPolicy::TimeSinceLast(threshold) => logs.elapsed >= *threshold,
+            SnapshotPolicy::Custom(predicate) => predicate.call(state, logs),
+            SnapshotPolicy::Any(policies) => policies.iter().any(|p| p.is_satisfied(state, logs)),
+            SnapshotPolicy::All(policies) => policies.iter().all(|p| p.is_satisfied(state, logs)),
             SnapshotPolicy::Never => false,
         }
     }
+
+    /// Return `true` if this policy may read [`LogsSinceSnapshot::bytes`].
+    ///
+    /// The sizes of the existing logs are loaded from the log store at startup only in this case.
+    pub(crate) fn uses_log_bytes(&self) -> bool {
+        match self {
+            SnapshotPolicy::BytesSinceLast(_) => true,
+            SnapshotPolicy::Custom(_) => true,
+            SnapshotPolicy::Any(policies) | SnapshotPolicy::All(policies) => {
+                policies.iter().any(|p| p.uses_log_bytes())
+            }
+            SnapshotPolicy::LogsSinceLast(_) | SnapshotPolicy::TimeSinceLast(_) | SnapshotPolicy::Never => false,
+        }
+    }
+}
+
+/// The logs since the last snapshot, on which a [`SnapshotPolicy`] decides whether to build a
+/// snapshot.
+#[derive(Clone, Debug, Default)]
+#[derive(PartialEq, Eq)]
+pub struct LogsSinceSnapshot {
+    /// The number of committed logs that are not included in the last snapshot.
+    pub committed_logs: u64,
+
+    /// The approximate total size in bytes of the logs that are not included in the last
+    /// snapshot.
+    pub bytes: u64,
+
+    /// The time elapsed since the last snapshot, or since this node started if no snapshot is
+    /// built or installed since then.
+    pub elapsed: Duration,
+}
+
+/// A user defined predicate for [`SnapshotPolicy::Custom`].
+///
+/// The predicate is called with a read-only view of the [`RaftState`] and the logs since the last
+/// snapshot. The [`RaftTypeConfig`] of the predicate must be the same as the `Raft` it is
+/// configured for, otherwise it always returns `false`.
+///
+/// Two predicates are equal only if they are the same instance.
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+pub struct SnapshotPredicate(Arc<dyn Fn(&dyn Any, &LogsSinceSnapshot) -> bool + Send + Sync + 'static>);
+
+impl SnapshotPredicate {
+    pub fn new<C, F>(f: F) -> Self
+    where
+        C: RaftTypeConfig,
+        F: Fn(&RaftState<C>, &LogsSinceSnapshot) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(move |state: &dyn Any, logs: &LogsSinceSnapshot| {
+            let Some(state) = state.downcast_ref::<RaftState<C>>() else {
+                tracing::error!("SnapshotPredicate is called with a RaftState of another RaftTypeConfig");
+                return false;
+            };
+            f(state, logs)
+        }))
+    }
+
+    fn call(&self, state: &dyn Any, logs: &LogsSinceSnapshot) -> bool {
+        (self.0)(state, logs)
+    }
+}
+
+impl fmt::Debug for SnapshotPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SnapshotPredicate")
+    }
 }
 
+impl PartialEq for SnapshotPredicate {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SnapshotPredicate {}
+
 /// Parse number with unit such as 5.3 KB
 fn parse_bytes_with_unit(src: &str) -> Result<u64, ConfigError> {
     let res = byte_unit::Byte::from_str(src).map_err(|e| ConfigError::InvalidNumber {
@@ -57,31 +173,98 @@ fn parse_bytes_with_unit(src: &str) -> Result<u64, ConfigError> {
     Ok(res.get_bytes() as u64)
 }
 
+const SNAPSHOT_POLICY_SYNTAX: &str =
+    "never|since_last:<num>|bytes_since_last:<size>|time_since_last:<ms>|any(<policy>,...)|all(<policy>,...)";
+
 fn parse_snapshot_policy(src: &str) -> Result<SnapshotPolicy, ConfigError> {
+    let invalid = || ConfigError::InvalidSnapshotPolicy {
+        syntax: SNAPSHOT_POLICY_SYNTAX.to_string(),
+        invalid: src.to_string(),
+    };
+
     if src == "never" {
         return Ok(SnapshotPolicy::Never);
     }
 
+    for (prefix, is_any) in [("any(", true), ("all(", false)] {
+        if let Some(body) = src.strip_prefix(prefix) {
+            let body = body.strip_suffix(')').ok_or_else(invalid)?;
+
+            let policies = split_top_level(body)
+                .ok_or_else(invalid)?
+                .into_iter()
+                .map(parse_snapshot_policy)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if policies.is_empty() {
+                return Err(invalid());
+            }
+
+            return Ok(if is_any {
+                SnapshotPolicy::Any(policies)
+            } else {
+                SnapshotPolicy::All(policies)
+            });
+        }
+    }
+
     let elts = src.split(':').collect::<Vec<_>>();
     if elts.len() != 2 {
-        return Err(ConfigError::InvalidSnapshotPolicy {
-            syntax: "never|since_last:<num>".to_string(),
-            invalid: src.to_string(),
-        });
+        return Err(invalid());
     }
 
-    if elts[0] != "since_last" {
-        return Err(ConfigError::InvalidSnapshotPolicy {
-            syntax: "never|since_last:<num>".to_string(),
+    let parse_u64 = |x: &str| {
+        x.parse::<u64>().map_err(|e| ConfigError::InvalidNumber {
             invalid: src.to_string(),
-        });
+            reason: e.to_string(),
+        })
+    };
+
+    match elts[0] {
+        "since_last" => Ok(SnapshotPolicy::LogsSinceLast(parse_u64(elts[1])?)),
+        "bytes_since_last" => Ok(SnapshotPolicy::BytesSinceLast(parse_bytes_with_unit(elts[1])?)),
+        "time_since_last" => Ok(SnapshotPolicy::TimeSinceLast(Duration::from_millis(parse_u64(
+            elts[1],
+        )?))),
+        _ => Err(invalid()),
     }
+}
 
-    let n_logs = elts[1].parse::<u64>().map_err(|e| ConfigError::InvalidNumber {
-        invalid: src.to_string(),
-        reason: e.to_string(),
-    })?;
-    Ok(SnapshotPolicy::LogsSinceLast(n_logs))
+/// Split a comma separated list at the top level, i.e., commas inside parentheses are not
+/// separators.
+///
+/// It returns `None` if the parentheses are not balanced.
+fn split_top_level(src: &str) -> Option<Vec<&str>> {
+    let mut res = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in src.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                if depth == 0 {
+                    return None;
+                }
+                depth -= 1;
+            }
+            ',' if depth == 0 => {
+                res.push(&src[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+
+    if !src.is_empty() {
+        res.push(&src[start..]);
+    }
+
+    Some(res)
 }
 
 /// The runtime configuration for a Raft node.
@@ -148,9 +331,54 @@ pub struct Config {
     ///
     /// If this is too low, it will take longer for the nodes to be brought up to
     /// consistency with the rest of the cluster.
+    ///
+    /// If `target_payload_bytes` is set, the size of the entries decides how many entries a
+    /// payload has, and this value is only a safety limit: it should be raised high enough not to
+    /// cut a payload of tiny entries short.
     #[clap(long, default_value = "300")]
     pub max_payload_entries: u64,
 
+    /// The size in bytes an AppendEntries RPC is filled up to during replication.
+    ///
+    /// Entries are read from the log store and added to an AppendEntries RPC until their total
+    /// [`RaftEntry::approx_size()`] reaches this value, thus an RPC of many tiny entries is as
+    /// large as an RPC of a few big ones. `max_payload_entries` is still the upper limit of the
+    /// number of entries in an RPC.
+    ///
+    /// It is disabled by default, by setting it to `0`.
+    ///
+    /// [`RaftEntry::approx_size()`]: crate::entry::RaftEntry::approx_size
+    #[clap(long, default_value = "0", value_parser=parse_bytes_with_unit)]
+    pub target_payload_bytes: u64,
+
+    /// The maximum size in bytes of the entries in an AppendEntries RPC.
+    ///
+    /// An entry is not added to an AppendEntries RPC if it would make the total
+    /// [`RaftEntry::approx_size()`] of the entries exceed this value. A single entry larger than
+    /// this value is still sent alone, otherwise replication could not make progress.
+    ///
+    /// It is disabled by default, by setting it to `0`.
+    ///
+    /// [`RaftEntry::approx_size()`]: crate::entry::RaftEntry::approx_size
+    #[clap(long, default_value = "0", value_parser=parse_bytes_with_unit)]
+    pub max_payload_bytes: u64,
+
+    /// The maximum number of AppendEntries RPCs in flight to a single target.
+    ///
+    /// Once the leader finds the last matching log on a target, it keeps up to this many
+    /// AppendEntries RPCs of at most `max_payload_entries` entries each in flight, without waiting
+    /// for the previous one to be responded: every time the oldest RPC is responded, the next one
+    /// is sent. This keeps a high latency link busy without raising `max_payload_entries`.
+    ///
+    /// Every in flight RPC uses its own network client, built by
+    /// [`RaftNetworkFactory::new_client()`].
+    ///
+    /// The default value 1 disables pipelining.
+    ///
+    /// [`RaftNetworkFactory::new_client()`]: crate::network::RaftNetworkFactory::new_client
+    #[clap(long, default_value = "1")]
+    pub max_inflight_append_entries: u64,
+
     /// The distance behind in log replication a follower must fall before it is considered lagging
     ///
     /// A follower falls behind this index are replicated with snapshot.
@@ -173,6 +401,32 @@ pub struct Config {
     #[clap(long, default_value = "3MiB", value_parser=parse_bytes_with_unit)]
     pub snapshot_max_chunk_size: u64,
 
+    /// The maximum bandwidth in bytes per second of all of the snapshots sent by a leader.
+    ///
+    /// It keeps snapshot transmitting to several followers from saturating the network and
+    /// starving heartbeats. The limit is applied to every chunk sent by the snapshot transports in
+    /// [`snapshot_transport`]; an application defined transport should call
+    /// [`RPCOption::snapshot_throttle()`].
+    ///
+    /// It is disabled by default, by setting it to `0`.
+    ///
+    /// [`snapshot_transport`]: crate::network::snapshot_transport
+    /// [`RPCOption::snapshot_throttle()`]: crate::network::RPCOption::snapshot_throttle
+    #[clap(long, default_value = "0", value_parser=parse_bytes_with_unit)]
+    pub snapshot_max_bytes_per_sec: u64,
+
+    /// The maximum number of snapshots a leader sends at the same time.
+    ///
+    /// Other snapshots wait in a queue until one of the sending snapshots is finished.
+    /// The number of sending and queued snapshots is reported in
+    /// [`RaftMetrics::snapshot_streams`].
+    ///
+    /// It is disabled by default, by setting it to `0`.
+    ///
+    /// [`RaftMetrics::snapshot_streams`]: crate::metrics::RaftMetrics::snapshot_streams
+    #[clap(long, default_value = "0")]
+    pub max_concurrent_snapshot_streams: u64,
+
     /// The maximum number of logs to keep that are already included in **snapshot**.
     ///
     /// Logs that are not in snapshot will never be purged.
@@ -183,6 +437,40 @@ pub struct Config {
     #[clap(long, default_value = "1")]
     pub purge_batch_size: u64,
 
+    /// The timeout in milliseconds for transferring leadership to another node.
+    ///
+    /// It includes the time for the target node to catch up with the leader's logs and the time
+    /// for it to be elected as the new leader.
+    #[clap(long, default_value = "3000")]
+    pub transfer_leader_timeout: u64,
+
+    /// The clock drift margin in milliseconds for a lease read.
+    ///
+    /// A leader serves a read with [`ReadPolicy::LeaseRead`] without a round of heartbeats,
+    /// if the time acknowledged by a quorum is within `election_timeout_max -
+    /// lease_read_clock_drift`. It must be smaller than `election_timeout_max`.
+    ///
+    /// [`ReadPolicy::LeaseRead`]: crate::raft::ReadPolicy::LeaseRead
+    #[clap(long, default_value = "20")]
+    pub lease_read_clock_drift: u64,
+
+    /// The election priority of this node. A node with a higher priority is preferred to be the
+    /// leader.
+    ///
+    /// - The randomized election timeout of a node with priority `p` is in the range
+    ///   `[election_timeout_min, election_timeout_min + (election_timeout_max -
+    ///   election_timeout_min) / (p + 1))`, i.e., a lower-priority node waits longer.
+    /// - A voter with a non-zero priority reports it to the leader with
+    ///   [`RaftNetworkV2::handoff_leader()`]. The leader transfers its leadership to the voter with
+    ///   the highest reported priority, if it is higher than the leader's own and the voter has
+    ///   replicated all logs.
+    ///
+    /// `0`, the default, is the lowest priority and is never reported.
+    ///
+    /// [`RaftNetworkV2::handoff_leader()`]: crate::network::v2::RaftNetworkV2::handoff_leader
+    #[clap(long, default_value = "0")]
+    pub election_priority: u64,
+
     /// Enable or disable tick.
     ///
     /// If ticking is disabled, timeout based events are all disabled:
@@ -227,6 +515,47 @@ pub struct Config {
            default_missing_value = "true"
     )]
     pub enable_elect: bool,
+
+    /// Whether a follower runs a PreVote round before it starts an election.
+    ///
+    /// With PreVote enabled, a follower whose election timeout passed asks the other voters
+    /// whether they would grant its vote, without increasing its term.
+    /// It only starts an election, i.e., increases its term and persists the new vote,
+    /// if a quorum replies that they would.
+    /// This prevents a node that was partitioned from disrupting the cluster when it comes back.
+    ///
+    /// It requires [`RaftNetwork::pre_vote()`] to be implemented.
+    ///
+    /// [`RaftNetwork::pre_vote()`]: crate::network::RaftNetwork::pre_vote
+    // clap 4 requires `num_args = 0..=1`, or it complains about missing arg error
+    // https://github.com/clap-rs/clap/discussions/4374
+    #[clap(long,
+           default_value_t = false,
+           action = clap::ArgAction::Set,
+           num_args = 0..=1,
+           default_missing_value = "true"
+    )]
+    pub enable_pre_vote: bool,
+
+    /// Whether the leader sends a snapshot delta, instead of a full snapshot, to a follower that
+    /// already has an earlier snapshot.
+    ///
+    /// The delta is provided by [`RaftStateMachine::get_snapshot_delta()`]. If it is not
+    /// available, or the follower rejects it, a full snapshot is sent.
+    ///
+    /// It requires [`RaftNetworkV2::snapshot_delta()`] to be implemented.
+    ///
+    /// [`RaftStateMachine::get_snapshot_delta()`]: crate::storage::RaftStateMachine::get_snapshot_delta
+    /// [`RaftNetworkV2::snapshot_delta()`]: crate::network::v2::RaftNetworkV2::snapshot_delta
+    // clap 4 requires `num_args = 0..=1`, or it complains about missing arg error
+    // https://github.com/clap-rs/clap/discussions/4374
+    #[clap(long,
+           default_value_t = false,
+           action = clap::ArgAction::Set,
+           num_args = 0..=1,
+           default_missing_value = "true"
+    )]
+    pub enable_snapshot_delta: bool,
 }
 
 /// Updatable config for a raft runtime.
@@ -252,8 +581,13 @@ impl Default for Config {
 
 impl Config {
     /// Generate a new random election timeout within the configured min & max.
+    ///
+    /// The range is narrowed towards `election_timeout_min` by [`Config::election_priority`],
+    /// so that a higher-priority node is more likely to start an election first.
     pub fn new_rand_election_timeout<RT: AsyncRuntime>(&self) -> u64 {
-        RT::thread_rng().gen_range(self.election_timeout_min..self.election_timeout_max)
+        let span = (self.election_timeout_max - self.election_timeout_min) / self.election_priority.saturating_add(1);
+        let max = self.election_timeout_min + std::cmp::max(span, 1);
+        RT::thread_rng().gen_range(self.election_timeout_min..max)
     }
 
     /// Get the timeout for sending and installing the last snapshot segment.
@@ -275,6 +609,11 @@ impl Config {
         }
     }
 
+    /// Get the timeout for transferring leadership to another node.
+    pub fn transfer_leader_timeout(&self) -> Duration {
+        Duration::from_millis(self.transfer_leader_timeout)
+    }
+
     /// Build a `Config` instance from a series of command line arguments.
     ///
     /// The first element in `args` must be the application name.
@@ -306,6 +645,24 @@ impl Config {
             return Err(ConfigError::MaxPayloadIs0);
         }
 
+        if self.max_inflight_append_entries == 0 {
+            return Err(ConfigError::MaxInflightAppendEntriesIs0);
+        }
+
+        if self.max_payload_bytes > 0 && self.target_payload_bytes > self.max_payload_bytes {
+            return Err(ConfigError::TargetPayloadBytesGTMax {
+                target_payload_bytes: self.target_payload_bytes,
+                max_payload_bytes: self.max_payload_bytes,
+            });
+        }
+
+        if self.lease_read_clock_drift >= self.election_timeout_max {
+            return Err(ConfigError::LeaseReadClockDrift {
+                lease_read_clock_drift: self.lease_read_clock_drift,
+                election_timeout_max: self.election_timeout_max,
+            });
+        }
+
         Ok(self)
     }
 }
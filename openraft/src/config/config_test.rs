@@ -1,8 +1,14 @@
 use core::time::Duration;
 
 use crate::config::error::ConfigError;
+use crate::engine::testing::UTConfig;
 use crate::Config;
+use crate::LogsSinceSnapshot;
+use crate::RaftState;
+use crate::ServerState;
 use crate::SnapshotPolicy;
+use crate::SnapshotPredicate;
+use crate::TokioRuntime;
 
 #[test]
 fn test_config_defaults() {
@@ -44,6 +50,42 @@ fn test_invalid_election_timeout_config_produces_expected_error() {
         election_timeout_min: 1000,
         heartbeat_interval: 1500
     });
+
+    let config = Config {
+        election_timeout_min: 1000,
+        election_timeout_max: 2000,
+        lease_read_clock_drift: 2000,
+        ..Default::default()
+    };
+
+    let res = config.validate();
+    let err = res.unwrap_err();
+    assert_eq!(err, ConfigError::LeaseReadClockDrift {
+        lease_read_clock_drift: 2000,
+        election_timeout_max: 2000
+    });
+
+    let config = Config {
+        max_inflight_append_entries: 0,
+        ..Default::default()
+    };
+
+    let res = config.validate();
+    let err = res.unwrap_err();
+    assert_eq!(err, ConfigError::MaxInflightAppendEntriesIs0);
+
+    let config = Config {
+        target_payload_bytes: 2048,
+        max_payload_bytes: 1024,
+        ..Default::default()
+    };
+
+    let res = config.validate();
+    let err = res.unwrap_err();
+    assert_eq!(err, ConfigError::TargetPayloadBytesGTMax {
+        target_payload_bytes: 2048,
+        max_payload_bytes: 1024
+    });
 }
 
 #[test]
@@ -62,6 +104,9 @@ fn test_build() -> anyhow::Result<()> {
         "--snapshot-max-chunk-size=204",
         "--max-in-snapshot-log-to-keep=205",
         "--purge-batch-size=207",
+        "--transfer-leader-timeout=208",
+        "--lease-read-clock-drift=9",
+        "--election-priority=3",
     ])?;
 
     assert_eq!("bar", config.cluster_name);
@@ -80,6 +125,9 @@ fn test_build() -> anyhow::Result<()> {
     assert_eq!(204, config.snapshot_max_chunk_size);
     assert_eq!(205, config.max_in_snapshot_log_to_keep);
     assert_eq!(207, config.purge_batch_size);
+    assert_eq!(208, config.transfer_leader_timeout);
+    assert_eq!(9, config.lease_read_clock_drift);
+    assert_eq!(3, config.election_priority);
 
     // Test config methods
     #[allow(deprecated)]
@@ -87,6 +135,7 @@ fn test_build() -> anyhow::Result<()> {
         let mut c = config;
         assert_eq!(Duration::from_millis(199), c.send_snapshot_timeout());
         assert_eq!(Duration::from_millis(200), c.install_snapshot_timeout());
+        assert_eq!(Duration::from_millis(208), c.transfer_leader_timeout());
 
         c.send_snapshot_timeout = 0;
         assert_eq!(
@@ -106,9 +155,142 @@ fn test_config_snapshot_policy() -> anyhow::Result<()> {
     let config = Config::build(&["foo", "--snapshot-policy=since_last:3"])?;
     assert_eq!(SnapshotPolicy::LogsSinceLast(3), config.snapshot_policy);
 
+    let config = Config::build(&["foo", "--snapshot-policy=bytes_since_last:1KiB"])?;
+    assert_eq!(SnapshotPolicy::BytesSinceLast(1024), config.snapshot_policy);
+
+    let config = Config::build(&["foo", "--snapshot-policy=time_since_last:100"])?;
+    assert_eq!(
+        SnapshotPolicy::TimeSinceLast(Duration::from_millis(100)),
+        config.snapshot_policy
+    );
+
+    let config = Config::build(&["foo", "--snapshot-policy=any(since_last:3,time_since_last:100)"])?;
+    assert_eq!(
+        SnapshotPolicy::Any(vec![
+            SnapshotPolicy::LogsSinceLast(3),
+            SnapshotPolicy::TimeSinceLast(Duration::from_millis(100)),
+        ]),
+        config.snapshot_policy
+    );
+
+    let config = Config::build(&[
+        "foo",
+        "--snapshot-policy=all(any(since_last:3,bytes_since_last:10),time_since_last:100)",
+    ])?;
+    assert_eq!(
+        SnapshotPolicy::All(vec![
+            SnapshotPolicy::Any(vec![
+                SnapshotPolicy::LogsSinceLast(3),
+                SnapshotPolicy::BytesSinceLast(10)
+            ]),
+            SnapshotPolicy::TimeSinceLast(Duration::from_millis(100)),
+        ]),
+        config.snapshot_policy
+    );
+
     let res = Config::build(&["foo", "--snapshot-policy=bar:3"]);
     assert!(res.is_err());
 
+    let res = Config::build(&["foo", "--snapshot-policy=any()"]);
+    assert!(res.is_err());
+
+    let res = Config::build(&["foo", "--snapshot-policy=any(since_last:3"]);
+    assert!(res.is_err());
+
+    let res = Config::build(&["foo", "--snapshot-policy=all(any(since_last:3),never))"]);
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_policy_should_snapshot() -> anyhow::Result<()> {
+    let state = RaftState::<UTConfig>::default();
+    let logs = LogsSinceSnapshot {
+        committed_logs: 3,
+        bytes: 100,
+        elapsed: Duration::from_millis(50),
+    };
+
+    assert!(SnapshotPolicy::LogsSinceLast(3).should_snapshot(&state, &logs));
+    assert!(!SnapshotPolicy::LogsSinceLast(4).should_snapshot(&state, &logs));
+
+    assert!(SnapshotPolicy::BytesSinceLast(100).should_snapshot(&state, &logs));
+    assert!(!SnapshotPolicy::BytesSinceLast(101).should_snapshot(&state, &logs));
+
+    assert!(SnapshotPolicy::TimeSinceLast(Duration::from_millis(50)).should_snapshot(&state, &logs));
+    assert!(!SnapshotPolicy::TimeSinceLast(Duration::from_millis(51)).should_snapshot(&state, &logs));
+
+    assert!(
+        SnapshotPolicy::Custom(SnapshotPredicate::new(|_s: &RaftState<UTConfig>, l| l.bytes
+            / l.committed_logs
+            > 30))
+        .should_snapshot(&state, &logs)
+    );
+    assert!(
+        !SnapshotPolicy::Custom(SnapshotPredicate::new(|_s: &RaftState<UTConfig>, l| l.bytes
+            / l.committed_logs
+            > 40))
+        .should_snapshot(&state, &logs)
+    );
+
+    let any = SnapshotPolicy::Any(vec![
+        SnapshotPolicy::LogsSinceLast(4),
+        SnapshotPolicy::BytesSinceLast(100),
+    ]);
+    assert!(any.should_snapshot(&state, &logs));
+
+    let all = SnapshotPolicy::All(vec![
+        SnapshotPolicy::LogsSinceLast(4),
+        SnapshotPolicy::BytesSinceLast(100),
+    ]);
+    assert!(!all.should_snapshot(&state, &logs));
+
+    // The predicate reads the RaftState.
+    let leader = RaftState::<UTConfig> {
+        server_state: ServerState::Leader,
+        ..Default::default()
+    };
+    let on_leader = SnapshotPolicy::Custom(SnapshotPredicate::new(|s: &RaftState<UTConfig>, _l| {
+        s.server_state == ServerState::Leader
+    }));
+    assert!(on_leader.should_snapshot(&leader, &logs));
+    assert!(!on_leader.should_snapshot(&state, &logs));
+
+    // A predicate for another RaftTypeConfig never triggers a snapshot.
+    let other = SnapshotPolicy::Custom(SnapshotPredicate::new(|_s: &RaftState<UTConfig<u64>>, _l| true));
+    assert!(!other.should_snapshot(&state, &logs));
+
+    assert!(!SnapshotPolicy::Never.should_snapshot(&state, &logs));
+
+    // Nothing to snapshot if there is no committed log since the last snapshot.
+    let no_logs = LogsSinceSnapshot {
+        committed_logs: 0,
+        ..logs
+    };
+    assert!(!SnapshotPolicy::TimeSinceLast(Duration::from_millis(0)).should_snapshot(&state, &no_logs));
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_policy_uses_log_bytes() -> anyhow::Result<()> {
+    assert!(!SnapshotPolicy::LogsSinceLast(3).uses_log_bytes());
+    assert!(!SnapshotPolicy::TimeSinceLast(Duration::from_millis(50)).uses_log_bytes());
+    assert!(!SnapshotPolicy::Never.uses_log_bytes());
+
+    assert!(SnapshotPolicy::BytesSinceLast(100).uses_log_bytes());
+    assert!(SnapshotPolicy::Custom(SnapshotPredicate::new(|_s: &RaftState<UTConfig>, _l| true)).uses_log_bytes());
+
+    let any = SnapshotPolicy::Any(vec![
+        SnapshotPolicy::LogsSinceLast(4),
+        SnapshotPolicy::All(vec![SnapshotPolicy::Never, SnapshotPolicy::BytesSinceLast(100)]),
+    ]);
+    assert!(any.uses_log_bytes());
+
+    let all = SnapshotPolicy::All(vec![SnapshotPolicy::LogsSinceLast(4), SnapshotPolicy::Never]);
+    assert!(!all.uses_log_bytes());
+
     Ok(())
 }
 
@@ -162,3 +344,58 @@ fn test_config_enable_elect() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_config_enable_pre_vote() -> anyhow::Result<()> {
+    let config = Config::build(&["foo", "--enable-pre-vote=false"])?;
+    assert_eq!(false, config.enable_pre_vote);
+
+    let config = Config::build(&["foo", "--enable-pre-vote=true"])?;
+    assert_eq!(true, config.enable_pre_vote);
+
+    let config = Config::build(&["foo", "--enable-pre-vote"])?;
+    assert_eq!(true, config.enable_pre_vote);
+
+    let config = Config::build(&["foo"])?;
+    assert_eq!(false, config.enable_pre_vote);
+
+    Ok(())
+}
+
+#[test]
+fn test_config_enable_snapshot_delta() -> anyhow::Result<()> {
+    let config = Config::build(&["foo", "--enable-snapshot-delta=false"])?;
+    assert_eq!(false, config.enable_snapshot_delta);
+
+    let config = Config::build(&["foo", "--enable-snapshot-delta=true"])?;
+    assert_eq!(true, config.enable_snapshot_delta);
+
+    let config = Config::build(&["foo", "--enable-snapshot-delta"])?;
+    assert_eq!(true, config.enable_snapshot_delta);
+
+    let config = Config::build(&["foo"])?;
+    assert_eq!(false, config.enable_snapshot_delta);
+
+    Ok(())
+}
+
+#[test]
+fn test_new_rand_election_timeout_with_priority() -> anyhow::Result<()> {
+    let mut config = Config {
+        election_timeout_min: 100,
+        election_timeout_max: 200,
+        ..Default::default()
+    };
+
+    for priority in [0, 1, 3, 99, 1000, u64::MAX] {
+        config.election_priority = priority;
+        let max = 100 + std::cmp::max(100 / priority.saturating_add(1), 1);
+
+        for _ in 0..100 {
+            let t = config.new_rand_election_timeout::<TokioRuntime>();
+            assert!((100..max).contains(&t), "priority: {}, timeout: {}", priority, t);
+        }
+    }
+
+    Ok(())
+}
@@ -14,12 +14,27 @@ pub enum ConfigError {
     #[error("max_payload_entries must be > 0")]
     MaxPayloadIs0,
 
+    #[error("max_inflight_append_entries must be > 0")]
+    MaxInflightAppendEntriesIs0,
+
+    #[error("target_payload_bytes({target_payload_bytes}) must be <= max_payload_bytes({max_payload_bytes})")]
+    TargetPayloadBytesGTMax {
+        target_payload_bytes: u64,
+        max_payload_bytes: u64,
+    },
+
     #[error("election_timeout_min({election_timeout_min}) must be > heartbeat_interval({heartbeat_interval})")]
     ElectionTimeoutLTHeartBeat {
         election_timeout_min: u64,
         heartbeat_interval: u64,
     },
 
+    #[error("lease_read_clock_drift({lease_read_clock_drift}) must be < election_timeout_max({election_timeout_max})")]
+    LeaseReadClockDrift {
+        lease_read_clock_drift: u64,
+        election_timeout_max: u64,
+    },
+
     #[error("snapshot policy string is invalid: '{invalid:?}' expect: '{syntax}'")]
     InvalidSnapshotPolicy { invalid: String, syntax: String },
 
@@ -4,6 +4,8 @@ mod error;
 #[cfg(test)] mod config_test;
 
 pub use config::Config;
+pub use config::LogsSinceSnapshot;
 pub(crate) use config::RuntimeConfig;
 pub use config::SnapshotPolicy;
+pub use config::SnapshotPredicate;
 pub use error::ConfigError;
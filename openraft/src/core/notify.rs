@@ -20,6 +20,14 @@ where C: RaftTypeConfig
         sender_vote: Vote<C::NodeId>,
     },
 
+    PreVoteResponse {
+        target: C::NodeId,
+        resp: VoteResponse<C>,
+
+        /// The vote proposed in the PreVote request.
+        sender_vote: Vote<C::NodeId>,
+    },
+
     /// Seen a higher `vote`.
     HigherVote {
         /// The ID of the target node from which the new term was observed.
@@ -48,6 +56,11 @@ where C: RaftTypeConfig
         /// ith tick
         i: u64,
     },
+
+    /// The number of snapshot streams being sent or queued changed.
+    ///
+    /// It wakes up RaftCore to report the updated metrics.
+    SnapshotStreams,
 }
 
 impl<C> Notify<C>
@@ -70,6 +83,13 @@ where C: RaftTypeConfig
             } => {
                 write!(f, "VoteResponse: from: {}: {}, res-vote: {}", target, resp, vote)
             }
+            Self::PreVoteResponse {
+                target,
+                resp,
+                sender_vote: vote,
+            } => {
+                write!(f, "PreVoteResponse: from: {}: {}, res-vote: {}", target, resp, vote)
+            }
             Self::HigherVote {
                 ref target,
                 higher: ref new_vote,
@@ -90,6 +110,9 @@ where C: RaftTypeConfig
             Self::Tick { i } => {
                 write!(f, "Tick {}", i)
             }
+            Self::SnapshotStreams => {
+                write!(f, "SnapshotStreams changed")
+            }
         }
     }
 }
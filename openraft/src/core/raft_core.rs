@@ -1,5 +1,6 @@
 use std::borrow::Borrow;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -50,15 +51,22 @@ use crate::error::Fatal;
 use crate::error::ForwardToLeader;
 use crate::error::Infallible;
 use crate::error::InitializeError;
+use crate::error::LeaderUnknown;
+use crate::error::NetworkError;
 use crate::error::QuorumNotEnough;
 use crate::error::RPCError;
+use crate::error::RaftError;
+use crate::error::ReadIndexError;
 use crate::error::Timeout;
+use crate::error::TransferLeaderError;
 use crate::log_id::LogIdOptionExt;
 use crate::log_id::RaftLogId;
+use crate::metrics::PerfMetrics;
 use crate::metrics::RaftDataMetrics;
 use crate::metrics::RaftMetrics;
 use crate::metrics::RaftServerMetrics;
 use crate::metrics::ReplicationMetrics;
+use crate::metrics::ReplicationTargetMetrics;
 use crate::network::v2::RaftNetworkV2;
 use crate::network::RPCOption;
 use crate::network::RPCTypes;
@@ -67,10 +75,16 @@ use crate::progress::entry::ProgressEntry;
 use crate::progress::Inflight;
 use crate::progress::Progress;
 use crate::quorum::QuorumSet;
+use crate::raft::event::EventSender;
 use crate::raft::responder::Responder;
 use crate::raft::AppendEntriesRequest;
 use crate::raft::AppendEntriesResponse;
 use crate::raft::ClientWriteResponse;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::RaftEvent;
+use crate::raft::ReadIndexRequest;
+use crate::raft::ReadPolicy;
+use crate::raft::TransferLeaderRequest;
 use crate::raft::VoteRequest;
 use crate::raft_state::LogIOId;
 use crate::raft_state::LogStateReader;
@@ -78,6 +92,7 @@ use crate::replication;
 use crate::replication::request::Replicate;
 use crate::replication::request_id::RequestId;
 use crate::replication::response::ReplicationResult;
+use crate::replication::snapshot_throttle::SnapshotThrottle;
 use crate::replication::ReplicationCore;
 use crate::replication::ReplicationHandle;
 use crate::replication::ReplicationSessionId;
@@ -88,6 +103,7 @@ use crate::storage::RaftLogStorage;
 use crate::storage::RaftStateMachine;
 use crate::type_config::alias::AsyncRuntimeOf;
 use crate::type_config::alias::InstantOf;
+use crate::type_config::alias::LogIdOf;
 use crate::type_config::alias::OneshotReceiverOf;
 use crate::type_config::alias::ResponderOf;
 use crate::AsyncRuntime;
@@ -99,6 +115,7 @@ use crate::OptionalSend;
 use crate::RaftTypeConfig;
 use crate::StorageError;
 use crate::StorageIOError;
+use crate::StoredMembership;
 use crate::Vote;
 
 /// A temp struct to hold the data for a node that is being applied.
@@ -144,13 +161,21 @@ pub(crate) struct LeaderData<C: RaftTypeConfig> {
 
     /// The time to send next heartbeat.
     pub(crate) next_heartbeat: InstantOf<C>,
+
+    /// The vote with which this node became a leader.
+    pub(crate) vote: Vote<C::NodeId>,
+
+    /// The targets whose last replication RPC failed.
+    pub(crate) unreachable: BTreeSet<C::NodeId>,
 }
 
 impl<C: RaftTypeConfig> LeaderData<C> {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(vote: Vote<C::NodeId>) -> Self {
         Self {
             replications: BTreeMap::new(),
             next_heartbeat: InstantOf::<C>::now(),
+            vote,
+            unreachable: BTreeSet::new(),
         }
     }
 }
@@ -183,11 +208,15 @@ where
 
     pub(crate) engine: Engine<C>,
 
-    /// Channels to send result back to client when logs are applied.
-    pub(crate) client_resp_channels: BTreeMap<u64, ResponderOf<C>>,
+    /// Channels to send result back to client when logs are applied, and the time when each write
+    /// request is received.
+    pub(crate) client_resp_channels: BTreeMap<u64, (InstantOf<C>, ResponderOf<C>)>,
 
     pub(crate) leader_data: Option<LeaderData<C>>,
 
+    /// Limits the snapshots sent by this node when it is a leader.
+    pub(crate) snapshot_throttle: Arc<SnapshotThrottle<C>>,
+
     #[allow(dead_code)]
     pub(crate) tx_api: mpsc::UnboundedSender<RaftMsg<C>>,
     pub(crate) rx_api: mpsc::UnboundedReceiver<RaftMsg<C>>,
@@ -202,6 +231,13 @@ where
     pub(crate) tx_metrics: watch::Sender<RaftMetrics<C>>,
     pub(crate) tx_data_metrics: watch::Sender<RaftDataMetrics<C>>,
     pub(crate) tx_server_metrics: watch::Sender<RaftServerMetrics<C>>,
+    pub(crate) tx_perf_metrics: watch::Sender<PerfMetrics<C>>,
+
+    /// Latency histograms and throughput counters, sent to `tx_perf_metrics` when changed.
+    pub(crate) perf_metrics: PerfMetrics<C>,
+
+    /// Sends lifecycle events to the streams returned by `Raft::subscribe_events()`.
+    pub(crate) events: EventSender<C>,
 
     pub(crate) command_state: CommandState,
 
@@ -223,7 +259,7 @@ where
         let res = self.do_main(rx_shutdown).instrument(span).await;
 
         // Flush buffered metrics
-        self.report_metrics(None);
+        self.report_metrics(None, None);
 
         // Safe unwrap: res is Result<Infallible, _>
         let err = res.unwrap_err();
@@ -243,6 +279,9 @@ where
             let _ = self.tx_metrics.send(curr);
         }
 
+        self.events.send(RaftEvent::Fatal { error: err.clone() });
+        self.events.close();
+
         tracing::info!("RaftCore shutdown complete");
 
         Err(err)
@@ -257,7 +296,7 @@ where
         self.run_engine_commands().await?;
 
         // Initialize metrics.
-        self.report_metrics(None);
+        self.report_metrics(None, None);
 
         self.runtime_loop(rx_shutdown).await
     }
@@ -267,6 +306,9 @@ where
     /// Send heartbeat to all voters. We respond once we have
     /// a quorum of agreement.
     ///
+    /// With [`ReadPolicy::LeaseRead`], respond at once if the leader lease acknowledged by a quorum
+    /// is still valid.
+    ///
     /// Why:
     /// To ensure linearizability, a read request proposed at time `T1` confirms this node's
     /// leadership to guarantee that all the committed entries proposed before `T1` are present in
@@ -274,12 +316,12 @@ where
     // TODO: the second condition is such a read request can only read from state machine only when the last log it sees
     //       at `T1` is committed.
     #[tracing::instrument(level = "trace", skip(self, tx))]
-    pub(super) async fn handle_check_is_leader_request(&mut self, tx: ClientReadTx<C>) {
+    pub(super) async fn handle_check_is_leader_request(&mut self, read_policy: ReadPolicy, tx: ClientReadTx<C>) {
         // Setup sentinel values to track when we've received majority confirmation of leadership.
 
         let resp = {
             let l = self.engine.leader_handler();
-            let lh = match l {
+            let mut lh = match l {
                 Ok(leading_handler) => leading_handler,
                 Err(forward) => {
                     let _ = tx.send(Err(forward.into()));
@@ -288,11 +330,21 @@ where
             };
 
             let read_log_id = lh.get_read_log_id();
+            let lease_valid = read_policy == ReadPolicy::LeaseRead && lh.is_lease_valid();
 
             // TODO: this applied is a little stale when being returned to client.
             //       Fix this when the following heartbeats are replaced with calling RaftNetwork.
             let applied = self.engine.state.io_applied().copied();
 
+            if lease_valid {
+                tracing::debug!(
+                    read_log_id = display(read_log_id.display()),
+                    "serve read with leader lease"
+                );
+                let _ = tx.send(Ok((read_log_id, applied)));
+                return;
+            }
+
             (read_log_id, applied)
         };
 
@@ -429,6 +481,137 @@ where
         let _ = C::AsyncRuntime::spawn(waiting_fu.instrument(tracing::debug_span!("spawn_is_leader_waiting")));
     }
 
+    /// Get a read index for a linearizable read.
+    ///
+    /// A leader confirms its leadership with a quorum, as [`Self::handle_check_is_leader_request`]
+    /// does. A non-leader node forwards the request to the leader it knows.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(super) async fn handle_read_index_request(
+        &mut self,
+        tx: ResultSender<C, Option<LogIdOf<C>>, ReadIndexError<C>>,
+    ) {
+        if self.engine.leader_handler().is_ok() {
+            let (leader_tx, leader_rx) = C::AsyncRuntime::oneshot();
+            self.handle_check_is_leader_request(ReadPolicy::ReadIndex, leader_tx).await;
+
+            // False positive lint warning(`non-binding `let` on a future`): https://github.com/rust-lang/rust-clippy/issues/9932
+            #[allow(clippy::let_underscore_future)]
+            let _ = C::AsyncRuntime::spawn(async move {
+                if let Ok(res) = leader_rx.await {
+                    let _ = tx.send(res.map(|(read_log_id, _applied)| read_log_id).map_err(ReadIndexError::from));
+                }
+            });
+            return;
+        }
+
+        let vote = *self.engine.state.vote_ref();
+        let leader = if vote.is_committed() {
+            vote.leader_id().voted_for()
+        } else {
+            None
+        };
+        let leader_node = leader.and_then(|id| self.engine.state.membership_state.effective().get_node(&id).cloned());
+
+        let (Some(leader), Some(leader_node)) = (leader, leader_node) else {
+            let _ = tx.send(Err(LeaderUnknown { node_id: self.id, vote }.into()));
+            return;
+        };
+
+        let mut client = self.network.new_client(leader, &leader_node).await;
+
+        let ttl = Duration::from_millis(self.config.election_timeout_min);
+        let option = RPCOption::new(ttl);
+        let req = ReadIndexRequest::new(self.id);
+        let id = self.id;
+
+        let fu = async move {
+            let res = match C::AsyncRuntime::timeout(ttl, client.read_index(req, option)).await {
+                Ok(res) => res,
+                Err(_timeout) => Err(RPCError::Timeout(Timeout {
+                    action: RPCTypes::ReadIndex,
+                    id,
+                    target: leader,
+                    timeout: ttl,
+                })),
+            };
+
+            let res = match res {
+                Ok(resp) => Ok(resp.read_log_id),
+                Err(RPCError::RemoteError(remote_err)) => match remote_err.source {
+                    RaftError::APIError(e) => Err(ReadIndexError::from(e)),
+                    RaftError::Fatal(e) => Err(NetworkError::new(&e).into()),
+                },
+                Err(e) => Err(NetworkError::new(&e).into()),
+            };
+
+            if let Err(e) = &res {
+                tracing::info!(error = display(e), "failed to get read index from leader-{}", leader);
+            }
+
+            let _ = tx.send(res);
+        };
+
+        // False positive lint warning(`non-binding `let` on a future`): https://github.com/rust-lang/rust-clippy/issues/9932
+        #[allow(clippy::let_underscore_future)]
+        let _ = C::AsyncRuntime::spawn(fu.instrument(tracing::debug_span!("read_index", leader = display(leader))));
+    }
+
+    /// Send a `HandoffLeader` request to the leader `target`, reporting the election priority of
+    /// this node.
+    pub(crate) async fn send_handoff_leader(&mut self, target: C::NodeId, req: HandoffLeaderRequest<C>) {
+        let Some(target_node) = self.engine.state.membership_state.effective().get_node(&target).cloned() else {
+            tracing::warn!("leader-{} is not in membership, can not send HandoffLeader", target);
+            return;
+        };
+
+        let mut client = self.network.new_client(target, &target_node).await;
+
+        let ttl = Duration::from_millis(self.config.election_timeout_min);
+        let option = RPCOption::new(ttl);
+
+        tracing::info!(req = display(&req), "ask leader-{} to hand off leadership", target);
+
+        let fu = async move {
+            let res = C::AsyncRuntime::timeout(ttl, client.handoff_leader(req, option)).await;
+            match res {
+                Ok(Ok(resp)) => {
+                    tracing::info!(resp = display(&resp), "HandoffLeader response from leader-{}", target);
+                }
+                Ok(Err(err)) => {
+                    tracing::warn!(error = display(&err), "HandoffLeader to leader-{} failed", target);
+                }
+                Err(_timeout) => {
+                    tracing::warn!("HandoffLeader to leader-{} timeout after {:?}", target, ttl);
+                }
+            }
+        };
+
+        // False positive lint warning(`non-binding `let` on a future`): https://github.com/rust-lang/rust-clippy/issues/9932
+        #[allow(clippy::let_underscore_future)]
+        let _ = C::AsyncRuntime::spawn(fu.instrument(tracing::debug_span!("handoff_leader", leader = display(target))));
+    }
+
+    /// Start to transfer leadership to node `to`.
+    ///
+    /// The last log id `to` has to catch up with is sent back through `tx`.
+    #[tracing::instrument(level = "debug", skip(self, tx))]
+    pub(super) fn handle_transfer_leader_request(
+        &mut self,
+        to: C::NodeId,
+        tx: ResultSender<C, Option<LogIdOf<C>>, TransferLeaderError<C>>,
+    ) {
+        let mut lh = match self.engine.leader_handler() {
+            Ok(lh) => lh,
+            Err(_) => {
+                self.reject_with_forward_to_leader(tx);
+                return;
+            }
+        };
+
+        let res = lh.transfer_leader(to).map_err(TransferLeaderError::from);
+        let _ = tx.send(res);
+    }
+
     /// Submit change-membership by writing a Membership log entry.
     ///
     /// If `retain` is `true`, removed `voter` will becomes `learner`. Otherwise they will
@@ -477,21 +660,69 @@ where
     pub fn write_entry(&mut self, entry: C::Entry, resp_tx: Option<ResponderOf<C>>) -> bool {
         tracing::debug!(payload = display(&entry), "write_entry");
 
-        let (mut lh, tx) = if let Some((lh, tx)) = self.engine.get_leader_handler_or_reject(resp_tx) {
-            (lh, tx)
-        } else {
-            return false;
+        self.write_entries(vec![(entry, resp_tx)])
+    }
+
+    /// Write a batch of log entries to the cluster through raft protocol.
+    ///
+    /// The entries are appended to the log in one `AppendInputEntries` command.
+    /// The result of applying each entry is sent to its responder, if it is not `None`.
+    /// If this node is not a leader, every responder receives a `ForwardToLeader` error.
+    #[tracing::instrument(level = "debug", skip_all, fields(id = display(self.id)))]
+    pub(crate) fn write_entries(&mut self, entries: Vec<(C::Entry, Option<ResponderOf<C>>)>) -> bool {
+        tracing::debug!(n = display(entries.len()), "write_entries");
+
+        let mut lh = match self.engine.leader_handler() {
+            Ok(lh) => lh,
+            Err(forward_err) => {
+                for (_, tx) in entries {
+                    if let Some(tx) = tx {
+                        tx.send(Err(forward_err.clone().into()));
+                    }
+                }
+                return false;
+            }
         };
 
-        let entries = vec![entry];
+        // A leader transferring leadership does not accept new writes.
+        if let Some(to) = lh.leader.transfer_to {
+            tracing::info!(
+                transfer_to = display(to),
+                "reject write: leadership is being transferred"
+            );
+
+            let leader_node = lh.state.membership_state.effective().get_node(&to).cloned();
+            for (_, tx) in entries {
+                if let Some(tx) = tx {
+                    tx.send(Err(ForwardToLeader {
+                        leader_id: Some(to),
+                        leader_node: leader_node.clone(),
+                    }
+                    .into()));
+                }
+            }
+            return false;
+        }
+
+        if entries.is_empty() {
+            return true;
+        }
+
+        let (entries, txs): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let n = entries.len() as u64;
+        let now = InstantOf::<C>::now();
+
         // TODO: it should returns membership config error etc. currently this is done by the
         //       caller.
         lh.leader_append_entries(entries);
-        let index = lh.state.last_log_id().unwrap().index;
+        let last_index = lh.state.last_log_id().unwrap().index;
+        let first_index = last_index + 1 - n;
 
         // Install callback channels.
-        if let Some(tx) = tx {
-            self.client_resp_channels.insert(index, tx);
+        for (index, tx) in (first_index..).zip(txs) {
+            if let Some(tx) = tx {
+                self.client_resp_channels.insert(index, (now, tx));
+            }
         }
 
         true
@@ -523,18 +754,41 @@ where
 
     #[tracing::instrument(level = "debug", skip_all)]
     pub fn flush_metrics(&mut self) {
-        let leader_metrics = if let Some(leader) = self.engine.internal_server_state.leading() {
+        let (replication, replication_targets) = if let Some(leader) = self.engine.internal_server_state.leading() {
             let prog = &leader.progress;
-            Some(prog.iter().map(|(id, p)| (*id, *p.borrow())).collect())
+            let replications = self.leader_data.as_ref().map(|l| &l.replications);
+
+            let targets = prog
+                .iter()
+                .map(|(id, p)| {
+                    let m = match replications.and_then(|r| r.get(id)) {
+                        Some(handle) => handle.metrics(p),
+                        None => ReplicationTargetMetrics {
+                            matching: p.matching,
+                            ..Default::default()
+                        },
+                    };
+                    (*id, m)
+                })
+                .collect();
+
+            (
+                Some(prog.iter().map(|(id, p)| (*id, *p.borrow())).collect()),
+                Some(targets),
+            )
         } else {
-            None
+            (None, None)
         };
-        self.report_metrics(leader_metrics);
+        self.report_metrics(replication, replication_targets);
     }
 
     /// Report a metrics payload on the current state of the Raft node.
     #[tracing::instrument(level = "debug", skip_all)]
-    pub(crate) fn report_metrics(&mut self, replication: Option<ReplicationMetrics<C::NodeId>>) {
+    pub(crate) fn report_metrics(
+        &mut self,
+        replication: Option<ReplicationMetrics<C::NodeId>>,
+        replication_targets: Option<BTreeMap<C::NodeId, ReplicationTargetMetrics<C::NodeId>>>,
+    ) {
         let last_quorum_acked = self.last_quorum_acked_time();
         let millis_since_quorum_ack = last_quorum_acked.map(|t| t.elapsed().as_millis() as u64);
 
@@ -551,6 +805,7 @@ where
             current_term: st.vote_ref().leader_id().get_term(),
             vote: *st.io_state().vote(),
             last_log_index: st.last_log_id().index(),
+            committed: st.committed().copied(),
             last_applied: st.io_applied().copied(),
             snapshot: st.io_snapshot_last_log_id().copied(),
             purged: st.io_purged().copied(),
@@ -563,6 +818,8 @@ where
 
             // --- replication ---
             replication: replication.clone(),
+            replication_targets,
+            snapshot_streams: self.snapshot_throttle.metrics(),
         };
 
         let data_metrics = RaftDataMetrics {
@@ -572,6 +829,7 @@ where
             purged: st.io_purged().copied(),
             millis_since_quorum_ack,
             replication,
+            snapshot_streams: self.snapshot_throttle.metrics(),
         };
 
         let server_metrics = RaftServerMetrics {
@@ -603,6 +861,14 @@ where
             false
         });
 
+        self.tx_perf_metrics.send_if_modified(|metrix| {
+            if self.perf_metrics.ne(metrix) {
+                *metrix = self.perf_metrics.clone();
+                return true;
+            }
+            false
+        });
+
         tracing::debug!("report_metrics: {}", m);
         let res = self.tx_metrics.send(m);
 
@@ -724,10 +990,12 @@ where
 
         let callback = LogFlushed::new(log_io_id, tx);
 
+        let start = InstantOf::<C>::now();
         self.log_store.append(entries, callback).await?;
         rx.await
             .map_err(|e| StorageIOError::write_logs(AnyError::error(e)))?
             .map_err(|e| StorageIOError::write_logs(AnyError::error(e)))?;
+        self.perf_metrics.log_flush.record(start.elapsed());
         Ok(())
     }
 
@@ -778,8 +1046,18 @@ where
 
         for log_index in res.since..res.end {
             let ent = applying_entries.next().unwrap();
+
+            if let Some(membership) = &ent.membership {
+                self.events.send(RaftEvent::MembershipCommitted {
+                    membership: StoredMembership::new(Some(ent.log_id), membership.clone()),
+                });
+            }
+
             let apply_res = results.next().unwrap();
-            let tx = self.client_resp_channels.remove(&log_index);
+            let tx = self.client_resp_channels.remove(&log_index).map(|(received, tx)| {
+                self.perf_metrics.client_write_apply.record(received.elapsed());
+                tx
+            });
 
             Self::send_response(ent, apply_res, tx);
         }
@@ -818,19 +1096,31 @@ where
         let target_node = self.engine.state.membership_state.effective().get_node(&target).unwrap();
 
         let membership_log_id = self.engine.state.membership_state.effective().log_id();
+        let witness = self.engine.state.membership_state.effective().is_witness(&target);
         let network = self.network.new_client(target, target_node).await;
+
+        let mut pipeline_networks = vec![];
+        if self.config.max_inflight_append_entries > 1 {
+            for _ in 0..self.config.max_inflight_append_entries {
+                pipeline_networks.push(self.network.new_client(target, target_node).await);
+            }
+        }
+
         let snapshot_network = self.network.new_client(target, target_node).await;
 
         let session_id = ReplicationSessionId::new(*self.engine.state.vote_ref(), *membership_log_id);
 
         ReplicationCore::<C, N, LS>::spawn(
             target,
+            witness,
             session_id,
             self.config.clone(),
             self.engine.state.committed().copied(),
             progress_entry.matching,
             network,
+            pipeline_networks,
             snapshot_network,
+            self.snapshot_throttle.clone(),
             self.log_store.get_log_reader().await,
             self.sm_handle.new_snapshot_reader(),
             self.tx_notify.clone(),
@@ -1044,8 +1334,10 @@ where
     }
 
     /// Spawn parallel vote requests to all cluster members.
+    ///
+    /// If `pre_vote` is true, PreVote requests are sent instead.
     #[tracing::instrument(level = "trace", skip_all)]
-    async fn spawn_parallel_vote_requests(&mut self, vote_req: &VoteRequest<C>) {
+    async fn spawn_parallel_vote_requests(&mut self, vote_req: &VoteRequest<C>, pre_vote: bool) {
         let members = self.engine.state.membership_state.effective().voter_ids();
 
         let vote = vote_req.vote;
@@ -1071,13 +1363,23 @@ where
             #[allow(clippy::let_underscore_future)]
             let _ = C::AsyncRuntime::spawn(
                 async move {
-                    let tm_res = C::AsyncRuntime::timeout(ttl, client.vote(req, option)).await;
+                    let (action, tm_res) = if pre_vote {
+                        (
+                            RPCTypes::PreVote,
+                            C::AsyncRuntime::timeout(ttl, client.pre_vote(req, option)).await,
+                        )
+                    } else {
+                        (
+                            RPCTypes::Vote,
+                            C::AsyncRuntime::timeout(ttl, client.vote(req, option)).await,
+                        )
+                    };
                     let res = match tm_res {
                         Ok(res) => res,
 
                         Err(_timeout) => {
                             let timeout_err = Timeout::<C> {
-                                action: RPCTypes::Vote,
+                                action,
                                 id,
                                 target,
                                 timeout: ttl,
@@ -1089,11 +1391,20 @@ where
 
                     match res {
                         Ok(resp) => {
-                            let _ = tx.send(Notify::VoteResponse {
-                                target,
-                                resp,
-                                sender_vote: vote,
-                            });
+                            let notify = if pre_vote {
+                                Notify::PreVoteResponse {
+                                    target,
+                                    resp,
+                                    sender_vote: vote,
+                                }
+                            } else {
+                                Notify::VoteResponse {
+                                    target,
+                                    resp,
+                                    sender_vote: vote,
+                                }
+                            };
+                            let _ = tx.send(notify);
                         }
                         Err(err) => tracing::error!({error=%err, target=display(target)}, "while requesting vote"),
                     }
@@ -1107,6 +1418,76 @@ where
         }
     }
 
+    /// Send a `TransferLeader` request to all other voters.
+    ///
+    /// The request is sent to the next leader after every other voter has received it.
+    /// Otherwise the vote request from the next leader may be rejected by a voter that is still
+    /// holding the lease of the current leader.
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn broadcast_transfer_leader(&mut self, req: TransferLeaderRequest<C>) {
+        let to = *req.to_node_id();
+        let ttl = Duration::from_millis(self.config.election_timeout_min);
+        let id = self.id;
+
+        let mut others = vec![];
+        let mut next_leader = None;
+
+        let members = self.engine.state.membership_state.effective().voter_ids();
+
+        for target in members {
+            if target == self.id {
+                continue;
+            }
+
+            // Safe unwrap(): target must be in membership
+            let target_node = self.engine.state.membership_state.effective().get_node(&target).unwrap().clone();
+            let client = self.network.new_client(target, &target_node).await;
+
+            if target == to {
+                next_leader = Some(client);
+            } else {
+                others.push((target, client));
+            }
+        }
+
+        let send = move |target: C::NodeId, mut client: N::Network, req: TransferLeaderRequest<C>| async move {
+            let option = RPCOption::new(ttl);
+            let tm_res = C::AsyncRuntime::timeout(ttl, client.transfer_leader(req, option)).await;
+            match tm_res {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    tracing::error!({error=%err, target=display(target)}, "while sending TransferLeader");
+                }
+                Err(_timeout) => {
+                    let timeout_err = Timeout::<C> {
+                        action: RPCTypes::TransferLeader,
+                        id,
+                        target,
+                        timeout: ttl,
+                    };
+                    tracing::error!({error = %timeout_err, target = display(target)}, "timeout");
+                }
+            }
+        };
+
+        let fu = async move {
+            let sending = others.into_iter().map(|(target, client)| send(target, client, req.clone()));
+            futures::future::join_all(sending).await;
+
+            if let Some(client) = next_leader {
+                send(to, client, req).await;
+            }
+        };
+
+        // False positive lint warning(`non-binding `let` on a future`): https://github.com/rust-lang/rust-clippy/issues/9932
+        #[allow(clippy::let_underscore_future)]
+        let _ = C::AsyncRuntime::spawn(fu.instrument(tracing::debug_span!(
+            parent: &Span::current(),
+            "broadcast_transfer_leader",
+            to = display(to)
+        )));
+    }
+
     #[tracing::instrument(level = "debug", skip_all)]
     pub(super) fn handle_vote_request(&mut self, req: VoteRequest<C>, tx: VoteTx<C>) {
         tracing::info!(req = display(&req), func = func_name!());
@@ -1118,6 +1499,17 @@ where
         });
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(super) fn handle_pre_vote_request(&mut self, req: VoteRequest<C>, tx: VoteTx<C>) {
+        tracing::info!(req = display(&req), func = func_name!());
+
+        let resp = self.engine.vote_handler().handle_pre_vote_req(&req);
+        self.engine.output.push_command(Command::Respond {
+            when: None,
+            resp: Respond::new(Ok(resp), tx),
+        });
+    }
+
     #[tracing::instrument(level = "debug", skip_all)]
     pub(super) fn handle_append_entries_request(&mut self, req: AppendEntriesRequest<C>, tx: AppendEntriesTx<C>) {
         tracing::debug!(req = display(&req), func = func_name!());
@@ -1149,18 +1541,42 @@ where
 
                 self.handle_vote_request(rpc, tx);
             }
+            RaftMsg::RequestPreVote { rpc, tx } => {
+                tracing::info!(
+                    pre_vote_request = display(&rpc),
+                    "received RaftMsg::RequestPreVote: {}",
+                    func_name!()
+                );
+
+                self.handle_pre_vote_request(rpc, tx);
+            }
             RaftMsg::BeginReceivingSnapshot { tx } => {
                 self.engine.handle_begin_receiving_snapshot(tx);
             }
             RaftMsg::InstallFullSnapshot { vote, snapshot, tx } => {
                 self.engine.handle_install_full_snapshot(vote, snapshot, tx);
             }
-            RaftMsg::CheckIsLeaderRequest { tx } => {
-                self.handle_check_is_leader_request(tx).await;
+            RaftMsg::InstallSnapshotDelta {
+                vote,
+                base,
+                snapshot,
+                tx,
+            } => {
+                self.engine.handle_install_snapshot_delta(vote, base, snapshot, tx);
+            }
+            RaftMsg::CheckIsLeaderRequest { read_policy, tx } => {
+                self.handle_check_is_leader_request(read_policy, tx).await;
+            }
+            RaftMsg::ReadIndexRequest { tx } => {
+                self.handle_read_index_request(tx).await;
             }
             RaftMsg::ClientWriteRequest { app_data, tx } => {
                 self.write_entry(C::Entry::from_app_data(app_data), Some(tx));
             }
+            RaftMsg::ClientWriteManyRequest { app_data } => {
+                let entries = app_data.into_iter().map(|(d, tx)| (C::Entry::from_app_data(d), Some(tx))).collect();
+                self.write_entries(entries);
+            }
             RaftMsg::Initialize { members, tx } => {
                 tracing::info!(
                     members = debug(&members),
@@ -1180,6 +1596,29 @@ where
 
                 self.change_membership(changes, retain, tx);
             }
+            RaftMsg::TransferLeader { to, tx } => {
+                tracing::info!(to = display(to), "received RaftMsg::TransferLeader: {}", func_name!());
+
+                self.handle_transfer_leader_request(to, tx);
+            }
+            RaftMsg::CancelTransferLeader { to } => {
+                tracing::info!(
+                    to = display(to),
+                    "received RaftMsg::CancelTransferLeader: {}",
+                    func_name!()
+                );
+
+                if let Ok(mut lh) = self.engine.leader_handler() {
+                    lh.cancel_transfer_leader(to);
+                }
+            }
+            RaftMsg::HandleTransferLeader { req } => {
+                self.engine.handle_transfer_leader(&req);
+            }
+            RaftMsg::HandoffLeader { req, tx } => {
+                let resp = self.engine.handle_handoff_leader(&req);
+                let _ = tx.send(Ok(resp));
+            }
             RaftMsg::ExternalCoreRequest { req } => {
                 req(&self.engine.state);
             }
@@ -1188,12 +1627,13 @@ where
 
                 match cmd {
                     ExternalCommand::Elect => {
-                        if self.engine.state.membership_state.effective().is_voter(&self.id) {
+                        let effective = self.engine.state.membership_state.effective();
+                        if effective.is_voter(&self.id) && !effective.is_witness(&self.id) {
                             // TODO: reject if it is already a leader?
                             self.engine.elect();
                             tracing::debug!("ExternalCommand: triggered election");
                         } else {
-                            // Node is switched to learner.
+                            // Node is switched to learner, or it is a witness.
                         }
                     }
                     ExternalCommand::Heartbeat => {
@@ -1240,6 +1680,20 @@ where
                 }
             }
 
+            Notify::PreVoteResponse {
+                target,
+                resp,
+                sender_vote,
+            } => {
+                tracing::info!(
+                    resp = display(&resp),
+                    "received Notify::PreVoteResponse: {}",
+                    func_name!()
+                );
+
+                self.engine.handle_pre_vote_resp(target, &sender_vote, resp);
+            }
+
             Notify::HigherVote {
                 target,
                 higher,
@@ -1266,6 +1720,7 @@ where
                 tracing::debug!("received tick: {}, now: {:?}", i, now);
 
                 self.handle_tick_election();
+                self.engine.try_handoff_leader();
 
                 // TODO: test: fixture: make isolated_nodes a single-way isolating.
 
@@ -1351,10 +1806,15 @@ where
                 }
             }
 
+            Notify::SnapshotStreams => {
+                // Nothing to do: the metrics are reported in every loop of RaftCore.
+            }
+
             Notify::StateMachine { command_result } => {
                 tracing::debug!("sm::StateMachine command result: {:?}", command_result);
 
                 let seq = command_result.command_seq;
+                let elapsed = command_result.elapsed;
                 let res = command_result.result?;
 
                 match res {
@@ -1383,6 +1843,9 @@ where
                         // Update in-memory state first, then the io state.
                         // In-memory state should always be ahead or equal to the io state.
 
+                        self.perf_metrics.snapshot_build.record(elapsed);
+                        self.events.send(RaftEvent::SnapshotBuilt { meta: meta.clone() });
+
                         let last_log_id = meta.last_log_id;
                         self.engine.finish_building_snapshot(meta);
 
@@ -1396,13 +1859,20 @@ where
                             func_name!()
                         );
 
+                        self.perf_metrics.snapshot_install.record(elapsed);
+
                         if let Some(meta) = meta {
                             let st = self.engine.state.io_state_mut();
                             st.update_applied(meta.last_log_id);
                             st.update_snapshot(meta.last_log_id);
+
+                            self.events.send(RaftEvent::SnapshotInstalled { meta });
                         }
                     }
                     sm::Response::Apply(res) => {
+                        self.perf_metrics.apply_batch.record(elapsed);
+                        self.perf_metrics.applied_entries += res.end - res.since;
+
                         self.engine.state.io_state_mut().update_applied(Some(res.last_applied));
 
                         self.handle_apply_result(res);
@@ -1431,6 +1901,11 @@ where
             return;
         }
 
+        if self.engine.state.membership_state.effective().is_witness(&self.id) {
+            tracing::debug!("this node is a witness, which never becomes a leader");
+            return;
+        }
+
         if !self.runtime_config.enable_elect.load(Ordering::Relaxed) {
             tracing::debug!("election is disabled");
             return;
@@ -1469,14 +1944,27 @@ where
                 return;
             }
 
+            // The vote is not changed by PreVote, give the last PreVote round a full election timeout.
+            if let Some(pre_voting) = &self.engine.pre_voting {
+                if pre_voting.starting_time() > now - election_timeout {
+                    tracing::debug!("PreVote is in progress, election timeout has not yet passed");
+                    return;
+                }
+            }
+
             tracing::info!("election timeout passed, check if it is a voter for election");
         }
 
         // Every time elect, reset this flag.
         self.engine.reset_greater_log();
 
-        tracing::info!("do trigger election");
-        self.engine.elect();
+        if self.config.enable_pre_vote {
+            tracing::info!("do trigger PreVote");
+            self.engine.pre_elect();
+        } else {
+            tracing::info!("do trigger election");
+            self.engine.elect();
+        }
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
@@ -1503,6 +1991,46 @@ where
             }
         }
 
+        if let Some(l) = &mut self.leader_data {
+            if let Some(handle) = l.replications.get_mut(&target) {
+                let now = InstantOf::<C>::now();
+                match &result {
+                    Ok(res) => {
+                        handle.last_success = Some(now);
+                        handle.consecutive_failures = 0;
+                        if request_id == RequestId::HeartBeat {
+                            handle.heartbeat_rtt = Some(res.sending_time.elapsed());
+                        }
+                    }
+                    Err(_) => {
+                        handle.last_failure = Some(now);
+                        handle.consecutive_failures += 1;
+                    }
+                }
+            }
+
+            match &result {
+                Ok(_) => {
+                    if l.unreachable.remove(&target) {
+                        self.events.send(RaftEvent::FollowerRecovered { target });
+                    }
+                }
+                Err(error) => {
+                    if l.unreachable.insert(target) {
+                        self.events.send(RaftEvent::FollowerUnreachable {
+                            target,
+                            error: error.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let (RequestId::HeartBeat | RequestId::AppendEntries { .. }, Ok(res)) = (request_id, &result) {
+            let rtt = res.sending_time.elapsed();
+            self.perf_metrics.append_entries_rtt.entry(target).or_default().record(rtt);
+        }
+
         // A leader may have stepped down.
         if self.engine.internal_server_state.is_leading() {
             self.engine.replication_handler().update_progress(target, request_id, result);
@@ -1591,15 +2119,22 @@ where
         match cmd {
             Command::BecomeLeader => {
                 debug_assert!(self.leader_data.is_none(), "can not become leader twice");
-                self.leader_data = Some(LeaderData::new());
+                let vote = *self.engine.state.vote_ref();
+                self.leader_data = Some(LeaderData::new(vote));
+                self.perf_metrics.elections_won += 1;
+                self.events.send(RaftEvent::BecameLeader { vote });
             }
             Command::QuitLeader => {
-                self.leader_data = None;
+                if let Some(l) = self.leader_data.take() {
+                    self.events.send(RaftEvent::QuitLeader { vote: l.vote });
+                }
             }
             Command::AppendInputEntries { vote, entries } => {
                 let last_log_id = *entries.last().unwrap().get_log_id();
                 tracing::debug!("AppendInputEntries: {}", DisplaySlice::<_>(&entries),);
 
+                self.perf_metrics.appended_entries += entries.len() as u64;
+
                 self.append_to_log(entries, vote, last_log_id).await?;
 
                 // The leader may have changed.
@@ -1609,12 +2144,25 @@ where
                 }
             }
             Command::SaveVote { vote } => {
+                // Only an election saves a non-committed vote for this node.
+                if !vote.is_committed() && vote.leader_id().voted_for() == Some(self.id) {
+                    self.perf_metrics.elections += 1;
+                }
+
                 self.log_store.save_vote(&vote).await?;
+
+                let from = self.engine.state.io_state().vote().leader_id().get_term();
+                let to = vote.leader_id().get_term();
                 self.engine.state.io_state_mut().update_vote(vote);
+
+                if to > from {
+                    self.events.send(RaftEvent::TermChanged { from, to });
+                }
             }
             Command::PurgeLog { upto } => {
                 self.log_store.purge(upto).await?;
                 self.engine.state.io_state_mut().update_purged(Some(upto));
+                self.events.send(RaftEvent::LogPurged { upto });
             }
             Command::DeleteConflictLog { since } => {
                 self.log_store.truncate(since).await?;
@@ -1628,7 +2176,7 @@ where
                     // False positive lint warning(`non-binding `let` on a future`): https://github.com/rust-lang/rust-clippy/issues/9932
                     #[allow(clippy::let_underscore_future)]
                     let _ = AsyncRuntimeOf::<C>::spawn(async move {
-                        for (log_index, tx) in removed.into_iter() {
+                        for (log_index, (_, tx)) in removed.into_iter() {
                             tx.send(Err(ClientWriteError::ForwardToLeader(ForwardToLeader {
                                 leader_id,
                                 leader_node: leader_node.clone(),
@@ -1640,7 +2188,17 @@ where
                 }
             }
             Command::SendVote { vote_req } => {
-                self.spawn_parallel_vote_requests(&vote_req).await;
+                self.spawn_parallel_vote_requests(&vote_req, false).await;
+            }
+            Command::SendPreVote { vote_req } => {
+                self.perf_metrics.pre_votes += 1;
+                self.spawn_parallel_vote_requests(&vote_req, true).await;
+            }
+            Command::BroadcastTransferLeader { req } => {
+                self.broadcast_transfer_leader(req).await;
+            }
+            Command::SendHandoffLeader { target, req } => {
+                self.send_handoff_leader(target, req).await;
             }
             Command::ReplicateCommitted { committed } => {
                 if let Some(l) = &self.leader_data {
@@ -1656,6 +2214,14 @@ where
                 ref already_committed,
                 ref upto,
             } => {
+                // Client writes committed by this command.
+                let since = already_committed.next_index();
+                if since <= upto.index {
+                    for (received, _) in self.client_resp_channels.range(since..=upto.index).map(|(_, v)| v) {
+                        self.perf_metrics.client_write_commit.record(received.elapsed());
+                    }
+                }
+
                 self.log_store.save_committed(Some(*upto)).await?;
                 self.apply_to_state_machine(seq, already_committed.next_index(), upto.index).await?;
             }
@@ -5,12 +5,20 @@ use crate::core::raft_msg::external_command::ExternalCommand;
 use crate::error::CheckIsLeaderError;
 use crate::error::Infallible;
 use crate::error::InitializeError;
+use crate::error::ReadIndexError;
+use crate::error::SnapshotBaseMismatch;
+use crate::error::TransferLeaderError;
 use crate::raft::AppendEntriesRequest;
 use crate::raft::AppendEntriesResponse;
 use crate::raft::BoxCoreFn;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
+use crate::raft::ReadPolicy;
 use crate::raft::SnapshotResponse;
+use crate::raft::TransferLeaderRequest;
 use crate::raft::VoteRequest;
 use crate::raft::VoteResponse;
+use crate::storage::SnapshotSignature;
 use crate::type_config::alias::LogIdOf;
 use crate::type_config::alias::OneshotSenderOf;
 use crate::type_config::alias::ResponderOf;
@@ -50,12 +58,25 @@ where C: RaftTypeConfig
         tx: VoteTx<C>,
     },
 
+    RequestPreVote {
+        rpc: VoteRequest<C>,
+        tx: VoteTx<C>,
+    },
+
     InstallFullSnapshot {
         vote: Vote<C::NodeId>,
         snapshot: Snapshot<C>,
         tx: ResultSender<C, SnapshotResponse<C>>,
     },
 
+    /// Install a completely received snapshot delta upon the current snapshot `base`.
+    InstallSnapshotDelta {
+        vote: Vote<C::NodeId>,
+        base: SnapshotSignature<C::NodeId>,
+        snapshot: Snapshot<C>,
+        tx: ResultSender<C, SnapshotResponse<C>, SnapshotBaseMismatch<C>>,
+    },
+
     /// Begin receiving a snapshot from the leader.
     ///
     /// Returns a snapshot data handle for receiving data.
@@ -71,10 +92,23 @@ where C: RaftTypeConfig
         tx: ResponderOf<C>,
     },
 
+    /// Write a batch of app data, each with its own responder.
+    ///
+    /// The batch is appended to the log in one go.
+    ClientWriteManyRequest {
+        app_data: Vec<(C::D, ResponderOf<C>)>,
+    },
+
     CheckIsLeaderRequest {
+        read_policy: ReadPolicy,
         tx: ClientReadTx<C>,
     },
 
+    /// Get a read index from the leader, on any node.
+    ReadIndexRequest {
+        tx: ResultSender<C, Option<LogIdOf<C>>, ReadIndexError<C>>,
+    },
+
     Initialize {
         members: BTreeMap<C::NodeId, C::Node>,
         tx: ResultSender<C, (), InitializeError<C>>,
@@ -90,6 +124,30 @@ where C: RaftTypeConfig
         tx: ResponderOf<C>,
     },
 
+    /// Start to transfer leadership to node `to`.
+    ///
+    /// Returns the last log id `to` has to catch up with.
+    TransferLeader {
+        to: C::NodeId,
+        tx: ResultSender<C, Option<LogIdOf<C>>, TransferLeaderError<C>>,
+    },
+
+    /// Cancel an unfinished leadership transfer to node `to`, and resume accepting writes.
+    CancelTransferLeader {
+        to: C::NodeId,
+    },
+
+    /// A `TransferLeader` request received from the leader.
+    HandleTransferLeader {
+        req: TransferLeaderRequest<C>,
+    },
+
+    /// A `HandoffLeader` request received from a follower, reporting its election priority.
+    HandoffLeader {
+        req: HandoffLeaderRequest<C>,
+        tx: ResultSender<C, HandoffLeaderResponse>,
+    },
+
     ExternalCoreRequest {
         req: BoxCoreFn<C>,
     },
@@ -111,14 +169,32 @@ where C: RaftTypeConfig
             RaftMsg::RequestVote { rpc, .. } => {
                 write!(f, "RequestVote: {}", rpc)
             }
+            RaftMsg::RequestPreVote { rpc, .. } => {
+                write!(f, "RequestPreVote: {}", rpc)
+            }
             RaftMsg::BeginReceivingSnapshot { .. } => {
                 write!(f, "BeginReceivingSnapshot")
             }
             RaftMsg::InstallFullSnapshot { vote, snapshot, .. } => {
                 write!(f, "InstallFullSnapshot: vote: {}, snapshot: {}", vote, snapshot)
             }
+            RaftMsg::InstallSnapshotDelta {
+                vote, base, snapshot, ..
+            } => {
+                write!(
+                    f,
+                    "InstallSnapshotDelta: vote: {}, base: {:?}, snapshot: {}",
+                    vote, base, snapshot
+                )
+            }
             RaftMsg::ClientWriteRequest { .. } => write!(f, "ClientWriteRequest"),
-            RaftMsg::CheckIsLeaderRequest { .. } => write!(f, "CheckIsLeaderRequest"),
+            RaftMsg::ClientWriteManyRequest { app_data } => {
+                write!(f, "ClientWriteManyRequest: n: {}", app_data.len())
+            }
+            RaftMsg::CheckIsLeaderRequest { read_policy, .. } => {
+                write!(f, "CheckIsLeaderRequest: read_policy: {:?}", read_policy)
+            }
+            RaftMsg::ReadIndexRequest { .. } => write!(f, "ReadIndexRequest"),
             RaftMsg::Initialize { members, .. } => {
                 // TODO: avoid using Debug
                 write!(f, "Initialize: {:?}", members)
@@ -127,6 +203,10 @@ where C: RaftTypeConfig
                 // TODO: avoid using Debug
                 write!(f, "ChangeMembership: members: {:?}, retain: {}", changes, retain,)
             }
+            RaftMsg::TransferLeader { to, .. } => write!(f, "TransferLeader: to: {}", to),
+            RaftMsg::CancelTransferLeader { to } => write!(f, "CancelTransferLeader: to: {}", to),
+            RaftMsg::HandleTransferLeader { req } => write!(f, "HandleTransferLeader: {}", req),
+            RaftMsg::HandoffLeader { req, .. } => write!(f, "HandoffLeader: {}", req),
             RaftMsg::ExternalCoreRequest { .. } => write!(f, "External Request"),
             RaftMsg::ExternalCommand { cmd } => {
                 write!(f, "ExternalCommand: {}", cmd)
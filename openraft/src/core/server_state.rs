@@ -8,6 +8,10 @@ pub enum ServerState {
     Learner,
     /// The node is replicating logs from the leader.
     Follower,
+    /// The node is a voter that replicates only log ids and membership from the leader.
+    ///
+    /// A witness votes but never becomes a candidate or leader.
+    Witness,
     /// The node is campaigning to become the cluster leader.
     Candidate,
     /// The node is the Raft cluster leader.
@@ -27,6 +31,11 @@ impl ServerState {
         matches!(self, Self::Follower)
     }
 
+    /// Check if currently in witness state.
+    pub fn is_witness(&self) -> bool {
+        matches!(self, Self::Witness)
+    }
+
     /// Check if currently in candidate state.
     pub fn is_candidate(&self) -> bool {
         matches!(self, Self::Candidate)
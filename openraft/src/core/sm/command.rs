@@ -5,6 +5,7 @@ use crate::core::raft_msg::ResultSender;
 use crate::display_ext::DisplaySlice;
 use crate::error::Infallible;
 use crate::log_id::RaftLogId;
+use crate::storage::SnapshotSignature;
 use crate::type_config::alias::SnapshotDataOf;
 use crate::RaftTypeConfig;
 use crate::Snapshot;
@@ -59,6 +60,14 @@ where C: RaftTypeConfig
         Command::new(payload)
     }
 
+    pub(crate) fn get_snapshot_delta(
+        base: SnapshotSignature<C::NodeId>,
+        tx: ResultSender<C, Option<Snapshot<C>>>,
+    ) -> Self {
+        let payload = CommandPayload::GetSnapshotDelta { base, tx };
+        Command::new(payload)
+    }
+
     pub(crate) fn begin_receiving_snapshot(tx: ResultSender<C, Box<SnapshotDataOf<C>>, Infallible>) -> Self {
         let payload = CommandPayload::BeginReceivingSnapshot { tx };
         Command::new(payload)
@@ -69,6 +78,11 @@ where C: RaftTypeConfig
         Command::new(payload)
     }
 
+    pub(crate) fn install_snapshot_delta(base: SnapshotSignature<C::NodeId>, snapshot: Snapshot<C>) -> Self {
+        let payload = CommandPayload::InstallSnapshotDelta { base, snapshot };
+        Command::new(payload)
+    }
+
     pub(crate) fn apply(entries: Vec<C::Entry>) -> Self {
         let payload = CommandPayload::Apply { entries };
         Command::new(payload)
@@ -94,6 +108,12 @@ where C: RaftTypeConfig
         tx: ResultSender<C, Option<Snapshot<C>>>,
     },
 
+    /// Get a delta from the snapshot `base` to the latest built snapshot.
+    GetSnapshotDelta {
+        base: SnapshotSignature<C::NodeId>,
+        tx: ResultSender<C, Option<Snapshot<C>>>,
+    },
+
     BeginReceivingSnapshot {
         tx: ResultSender<C, Box<SnapshotDataOf<C>>, Infallible>,
     },
@@ -102,6 +122,12 @@ where C: RaftTypeConfig
         snapshot: Snapshot<C>,
     },
 
+    /// Install a snapshot delta upon the current snapshot `base`.
+    InstallSnapshotDelta {
+        base: SnapshotSignature<C::NodeId>,
+        snapshot: Snapshot<C>,
+    },
+
     /// Apply the log entries to the state machine.
     Apply {
         entries: Vec<C::Entry>,
@@ -115,9 +141,13 @@ where C: RaftTypeConfig
         match self {
             CommandPayload::BuildSnapshot => write!(f, "BuildSnapshot"),
             CommandPayload::GetSnapshot { .. } => write!(f, "GetSnapshot"),
+            CommandPayload::GetSnapshotDelta { base, .. } => write!(f, "GetSnapshotDelta: base: {:?}", base),
             CommandPayload::InstallFullSnapshot { snapshot } => {
                 write!(f, "InstallFullSnapshot: meta: {:?}", snapshot.meta)
             }
+            CommandPayload::InstallSnapshotDelta { base, snapshot } => {
+                write!(f, "InstallSnapshotDelta: base: {:?}, meta: {:?}", base, snapshot.meta)
+            }
             CommandPayload::BeginReceivingSnapshot { .. } => {
                 write!(f, "BeginReceivingSnapshot")
             }
@@ -139,6 +169,10 @@ where C: RaftTypeConfig
                 CommandPayload::InstallFullSnapshot { snapshot: s1 },
                 CommandPayload::InstallFullSnapshot { snapshot: s2 },
             ) => s1.meta == s2.meta,
+            (
+                CommandPayload::InstallSnapshotDelta { base: b1, snapshot: s1 },
+                CommandPayload::InstallSnapshotDelta { base: b2, snapshot: s2 },
+            ) => b1 == b2 && s1.meta == s2.meta,
             (CommandPayload::Apply { entries: entries1 }, CommandPayload::Apply { entries: entries2 }) => {
                 // Entry may not be `Eq`, we just compare log id.
                 // This would be enough for testing.
@@ -3,9 +3,14 @@
 use tokio::sync::mpsc;
 
 use crate::core::sm;
+use crate::error::Infallible;
+use crate::storage::SnapshotSignature;
 use crate::type_config::alias::AsyncRuntimeOf;
 use crate::type_config::alias::JoinHandleOf;
+use crate::type_config::alias::OneshotReceiverOf;
+use crate::type_config::alias::SnapshotDataOf;
 use crate::AsyncRuntime;
+use crate::OptionalSend;
 use crate::RaftTypeConfig;
 use crate::Snapshot;
 
@@ -58,6 +63,42 @@ where C: RaftTypeConfig
         let (tx, rx) = AsyncRuntimeOf::<C>::oneshot();
 
         let cmd = sm::Command::get_snapshot(tx);
+        self.call(cmd, rx).await
+    }
+
+    /// Get a delta from the snapshot `base` to the current snapshot from the state machine.
+    ///
+    /// If the state machine worker has shutdown, it will return an error.
+    /// If there is not delta available, it will return `Ok(None)`.
+    pub(crate) async fn get_snapshot_delta(
+        &self,
+        base: SnapshotSignature<C::NodeId>,
+    ) -> Result<Option<Snapshot<C>>, &'static str> {
+        let (tx, rx) = AsyncRuntimeOf::<C>::oneshot();
+
+        let cmd = sm::Command::get_snapshot_delta(base, tx);
+        self.call(cmd, rx).await
+    }
+
+    /// Get an empty snapshot data from the state machine.
+    ///
+    /// It is used to send a snapshot without application data to a witness.
+    /// If the state machine worker has shutdown, it will return an error.
+    pub(crate) async fn get_empty_snapshot_data(&self) -> Result<Box<SnapshotDataOf<C>>, &'static str> {
+        let (tx, rx) = AsyncRuntimeOf::<C>::oneshot();
+
+        let cmd = sm::Command::begin_receiving_snapshot(tx);
+        self.call(cmd, rx).await
+    }
+
+    async fn call<T>(
+        &self,
+        cmd: sm::Command<C>,
+        rx: OneshotReceiverOf<C, Result<T, Infallible>>,
+    ) -> Result<T, &'static str>
+    where
+        T: OptionalSend,
+    {
         tracing::debug!("SnapshotReader sending command to sm::Worker: {:?}", cmd);
 
         let Some(cmd_tx) = self.cmd_tx.upgrade() else {
@@ -77,8 +118,8 @@ where C: RaftTypeConfig
         };
 
         // Safe unwrap(): error is Infallible.
-        let snapshot = got.unwrap();
+        let res = got.unwrap();
 
-        Ok(snapshot)
+        Ok(res)
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::core::sm::command::CommandSeq;
 use crate::core::ApplyResult;
 use crate::RaftTypeConfig;
@@ -29,12 +31,24 @@ where C: RaftTypeConfig
     #[allow(dead_code)]
     pub(crate) command_seq: CommandSeq,
     pub(crate) result: Result<Response<C>, StorageError<C::NodeId>>,
+
+    /// The time spent on running the command by the state machine.
+    pub(crate) elapsed: Duration,
 }
 
 impl<C> CommandResult<C>
 where C: RaftTypeConfig
 {
     pub(crate) fn new(command_seq: CommandSeq, result: Result<Response<C>, StorageError<C::NodeId>>) -> Self {
-        Self { command_seq, result }
+        Self {
+            command_seq,
+            result,
+            elapsed: Duration::default(),
+        }
+    }
+
+    pub(crate) fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = elapsed;
+        self
     }
 }
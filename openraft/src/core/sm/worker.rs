@@ -14,8 +14,10 @@ use crate::core::ApplyingEntry;
 use crate::display_ext::DisplayOptionExt;
 use crate::entry::RaftPayload;
 use crate::storage::RaftStateMachine;
+use crate::type_config::alias::InstantOf;
 use crate::type_config::alias::JoinHandleOf;
 use crate::AsyncRuntime;
+use crate::Instant;
 use crate::RaftLogId;
 use crate::RaftSnapshotBuilder;
 use crate::RaftTypeConfig;
@@ -62,10 +64,7 @@ where
                 tracing::error!("{} while execute state machine command", err,);
 
                 let _ = self.resp_tx.send(Notify::StateMachine {
-                    command_result: CommandResult {
-                        command_seq: 0,
-                        result: Err(err),
-                    },
+                    command_result: CommandResult::new(0, Err(err)),
                 });
             }
         })
@@ -85,6 +84,8 @@ where
 
             tracing::debug!("{}: received command: {:?}", func_name!(), cmd);
 
+            let start = InstantOf::<C>::now();
+
             match cmd.payload {
                 CommandPayload::BuildSnapshot => {
                     tracing::info!("{}: build snapshot", func_name!());
@@ -98,6 +99,13 @@ where
                     self.get_snapshot(tx).await?;
                     // GetSnapshot does not respond to RaftCore
                 }
+                CommandPayload::GetSnapshotDelta { base, tx } => {
+                    tracing::info!("{}: get snapshot delta", func_name!());
+
+                    let delta = self.state_machine.get_snapshot_delta(&base).await?;
+                    let _ = tx.send(Ok(delta));
+                    // GetSnapshotDelta does not respond to RaftCore
+                }
                 CommandPayload::InstallFullSnapshot { snapshot } => {
                     tracing::info!("{}: install complete snapshot", func_name!());
 
@@ -106,7 +114,20 @@ where
 
                     tracing::info!("Done install complete snapshot, meta: {}", meta);
 
-                    let res = CommandResult::new(cmd.seq, Ok(Response::InstallSnapshot(Some(meta))));
+                    let res = CommandResult::new(cmd.seq, Ok(Response::InstallSnapshot(Some(meta))))
+                        .with_elapsed(start.elapsed());
+                    let _ = self.resp_tx.send(Notify::sm(res));
+                }
+                CommandPayload::InstallSnapshotDelta { base, snapshot } => {
+                    tracing::info!("{}: install snapshot delta", func_name!());
+
+                    let meta = snapshot.meta.clone();
+                    self.state_machine.install_snapshot_delta(&base, &meta, snapshot.snapshot).await?;
+
+                    tracing::info!("Done install snapshot delta, base: {:?}, meta: {}", base, meta);
+
+                    let res = CommandResult::new(cmd.seq, Ok(Response::InstallSnapshot(Some(meta))))
+                        .with_elapsed(start.elapsed());
                     let _ = self.resp_tx.send(Notify::sm(res));
                 }
                 CommandPayload::BeginReceivingSnapshot { tx } => {
@@ -119,7 +140,7 @@ where
                 }
                 CommandPayload::Apply { entries } => {
                     let resp = self.apply(entries).await?;
-                    let res = CommandResult::new(cmd.seq, Ok(Response::Apply(resp)));
+                    let res = CommandResult::new(cmd.seq, Ok(Response::Apply(resp))).with_elapsed(start.elapsed());
                     let _ = self.resp_tx.send(Notify::sm(res));
                 }
             };
@@ -184,9 +205,10 @@ where
         let mut builder = self.state_machine.get_snapshot_builder().await;
 
         let _handle = C::AsyncRuntime::spawn(async move {
+            let start = InstantOf::<C>::now();
             let res = builder.build_snapshot().await;
             let res = res.map(|snap| Response::BuildSnapshot(snap.meta));
-            let cmd_res = CommandResult::new(seq, res);
+            let cmd_res = CommandResult::new(seq, res).with_elapsed(start.elapsed());
             let _ = resp_tx.send(Notify::sm(cmd_res));
         });
         tracing::info!("{} returning; spawned building snapshot task", func_name!());
@@ -6,11 +6,14 @@ use crate::engine::CommandKind;
 use crate::error::Infallible;
 use crate::error::InitializeError;
 use crate::error::InstallSnapshotError;
+use crate::error::SnapshotBaseMismatch;
 use crate::progress::entry::ProgressEntry;
 use crate::progress::Inflight;
 use crate::raft::AppendEntriesResponse;
+use crate::raft::HandoffLeaderRequest;
 use crate::raft::InstallSnapshotResponse;
 use crate::raft::SnapshotResponse;
+use crate::raft::TransferLeaderRequest;
 use crate::raft::VoteRequest;
 use crate::raft::VoteResponse;
 use crate::type_config::alias::OneshotSenderOf;
@@ -91,6 +94,18 @@ where C: RaftTypeConfig
     /// Send vote to all other members
     SendVote { vote_req: VoteRequest<C> },
 
+    /// Send PreVote to all other voters
+    SendPreVote { vote_req: VoteRequest<C> },
+
+    /// Send a `TransferLeader` request to all other voters.
+    BroadcastTransferLeader { req: TransferLeaderRequest<C> },
+
+    /// Send a `HandoffLeader` request to the leader, asking it to hand off leadership to this node.
+    SendHandoffLeader {
+        target: C::NodeId,
+        req: HandoffLeaderRequest<C>,
+    },
+
     /// Purge log from the beginning to `upto`, inclusive.
     PurgeLog { upto: LogId<C::NodeId> },
 
@@ -138,6 +153,9 @@ where
             (Command::RebuildReplicationStreams { targets },   Command::RebuildReplicationStreams { targets: b }, )                            => targets == b,
             (Command::SaveVote { vote },                       Command::SaveVote { vote: b })                                                  => vote == b,
             (Command::SendVote { vote_req },                   Command::SendVote { vote_req: b }, )                                            => vote_req == b,
+            (Command::SendPreVote { vote_req },                Command::SendPreVote { vote_req: b }, )                                         => vote_req == b,
+            (Command::BroadcastTransferLeader { req },         Command::BroadcastTransferLeader { req: b }, )                                  => req == b,
+            (Command::SendHandoffLeader { target, req },       Command::SendHandoffLeader { target: b_target, req: b }, )                      => target == b_target && req == b,
             (Command::PurgeLog { upto },                       Command::PurgeLog { upto: b })                                                  => upto == b,
             (Command::DeleteConflictLog { since },             Command::DeleteConflictLog { since: b }, )                                      => since == b,
             (Command::Respond { when, resp: send },            Command::Respond { when: b_when, resp: b })                                     => send == b && when == b_when,
@@ -167,6 +185,9 @@ where C: RaftTypeConfig
             Command::ReplicateCommitted { .. }        => CommandKind::Network,
             Command::Replicate { .. }                 => CommandKind::Network,
             Command::SendVote { .. }                  => CommandKind::Network,
+            Command::SendPreVote { .. }               => CommandKind::Network,
+            Command::BroadcastTransferLeader { .. }   => CommandKind::Network,
+            Command::SendHandoffLeader { .. }         => CommandKind::Network,
 
             Command::StateMachine { .. }              => CommandKind::StateMachine,
             // Apply is firstly handled by RaftCore, then forwarded to state machine worker.
@@ -190,6 +211,9 @@ where C: RaftTypeConfig
             Command::RebuildReplicationStreams { .. } => None,
             Command::SaveVote { .. }                  => None,
             Command::SendVote { .. }                  => None,
+            Command::SendPreVote { .. }               => None,
+            Command::BroadcastTransferLeader { .. }   => None,
+            Command::SendHandoffLeader { .. }         => None,
             Command::PurgeLog { .. }                  => None,
             Command::DeleteConflictLog { .. }         => None,
             Command::Respond { when, .. }             => when.as_ref(),
@@ -235,6 +259,7 @@ where C: RaftTypeConfig
     ReceiveSnapshotChunk(ValueSender<C, Result<(), InstallSnapshotError>>),
     InstallSnapshot(ValueSender<C, Result<InstallSnapshotResponse<C>, InstallSnapshotError>>),
     InstallFullSnapshot(ValueSender<C, Result<SnapshotResponse<C>, Infallible>>),
+    InstallSnapshotDelta(ValueSender<C, Result<SnapshotResponse<C>, SnapshotBaseMismatch<C>>>),
     Initialize(ValueSender<C, Result<(), InitializeError<C>>>),
 }
 
@@ -256,6 +281,7 @@ where C: RaftTypeConfig
             Respond::ReceiveSnapshotChunk(x) => x.send(),
             Respond::InstallSnapshot(x) => x.send(),
             Respond::InstallFullSnapshot(x) => x.send(),
+            Respond::InstallSnapshotDelta(x) => x.send(),
             Respond::Initialize(x) => x.send(),
         }
     }
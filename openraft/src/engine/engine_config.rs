@@ -25,6 +25,12 @@ pub(crate) struct EngineConfig<C: RaftTypeConfig> {
     /// The maximum number of entries per payload allowed to be transmitted during replication
     pub(crate) max_payload_entries: u64,
 
+    /// The maximum number of AppendEntries RPCs in flight to a single target.
+    pub(crate) max_inflight_append_entries: u64,
+
+    /// The election priority of this node. See [`Config::election_priority`].
+    pub(crate) election_priority: u64,
+
     pub(crate) timer_config: time_state::Config,
 }
 
@@ -39,10 +45,14 @@ where C: RaftTypeConfig
             max_in_snapshot_log_to_keep: config.max_in_snapshot_log_to_keep,
             purge_batch_size: config.purge_batch_size,
             max_payload_entries: config.max_payload_entries,
+            max_inflight_append_entries: config.max_inflight_append_entries,
+            election_priority: config.election_priority,
             timer_config: time_state::Config {
                 election_timeout,
                 smaller_log_timeout: Duration::from_millis(config.election_timeout_max * 2),
                 leader_lease: Duration::from_millis(config.election_timeout_max),
+                lease_read_clock_drift: Duration::from_millis(config.lease_read_clock_drift),
+                handoff_leader_interval: config.transfer_leader_timeout(),
             },
         }
     }
@@ -55,6 +65,8 @@ where C: RaftTypeConfig
             max_in_snapshot_log_to_keep: 1000,
             purge_batch_size: 256,
             max_payload_entries: 300,
+            max_inflight_append_entries: 1,
+            election_priority: 0,
             timer_config: time_state::Config::default(),
         }
     }
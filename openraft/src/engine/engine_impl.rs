@@ -28,14 +28,21 @@ use crate::error::InitializeError;
 use crate::error::NotAllowed;
 use crate::error::NotInMembers;
 use crate::error::RejectAppendEntries;
+use crate::error::SnapshotBaseMismatch;
 use crate::internal_server_state::InternalServerState;
+use crate::internal_server_state::LeaderQuorumSet;
+use crate::leader::voting::Voting;
 use crate::raft::responder::Responder;
 use crate::raft::AppendEntriesResponse;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
 use crate::raft::SnapshotResponse;
+use crate::raft::TransferLeaderRequest;
 use crate::raft::VoteRequest;
 use crate::raft::VoteResponse;
 use crate::raft_state::LogStateReader;
 use crate::raft_state::RaftState;
+use crate::storage::SnapshotSignature;
 use crate::type_config::alias::InstantOf;
 use crate::type_config::alias::ResponderOf;
 use crate::type_config::alias::SnapshotDataOf;
@@ -74,6 +81,14 @@ where C: RaftTypeConfig
     /// should be greater.
     pub(crate) seen_greater_log: bool,
 
+    /// The PreVote round in progress.
+    ///
+    /// It is `None` if no PreVote round is started, or the last round has finished.
+    pub(crate) pre_voting: Option<Voting<C, LeaderQuorumSet<C::NodeId>>>,
+
+    /// The leader vote and the time this node sent the last `HandoffLeader` request to.
+    pub(crate) handoff_leader_sent: Option<(Vote<C::NodeId>, InstantOf<C>)>,
+
     /// The internal server state used by Engine.
     pub(crate) internal_server_state: InternalServerState<C>,
 
@@ -89,6 +104,8 @@ where C: RaftTypeConfig
             config,
             state: Valid::new(init_state),
             seen_greater_log: false,
+            pre_voting: None,
+            handoff_leader_sent: None,
             internal_server_state: InternalServerState::default(),
             output: EngineOutput::new(4096),
         }
@@ -104,6 +121,9 @@ where C: RaftTypeConfig
 
     #[tracing::instrument(level = "debug", skip_all)]
     pub(crate) fn startup(&mut self) {
+        // Time based snapshot policies count from when this node starts up.
+        self.state.snapshot_time = Some(InstantOf::<C>::now());
+
         // Allows starting up as a leader.
 
         tracing::info!(
@@ -128,7 +148,10 @@ where C: RaftTypeConfig
             return;
         }
 
-        let server_state = if self.state.membership_state.effective().is_voter(&self.config.id) {
+        let effective = self.state.membership_state.effective();
+        let server_state = if effective.is_witness(&self.config.id) {
+            ServerState::Witness
+        } else if effective.is_voter(&self.config.id) {
             ServerState::Follower
         } else {
             ServerState::Learner
@@ -176,6 +199,8 @@ where C: RaftTypeConfig
     /// Start to elect this node as leader
     #[tracing::instrument(level = "debug", skip(self))]
     pub(crate) fn elect(&mut self) {
+        self.pre_voting = None;
+
         let v = Vote::new(self.state.vote_ref().leader_id().term + 1, self.config.id);
         tracing::info!(vote = display(&v), "{}", func_name!());
 
@@ -208,6 +233,94 @@ where C: RaftTypeConfig
         self.server_state_handler().update_server_state_if_changed();
     }
 
+    /// Place the leadership on the voter with the highest election priority.
+    ///
+    /// On a leader, it transfers leadership to a caught-up voter with a higher priority, see
+    /// [`LeaderHandler::try_handoff_leader()`].
+    ///
+    /// On a follower with a non-zero election priority, it reports the priority to the leader with
+    /// a `HandoffLeader` request. The request is sent only if the follower has heard from the
+    /// leader within the leader lease, and at most once in every `handoff_leader_interval` to the
+    /// same leader, so that a leader that lost the report, e.g., a newly elected one, learns it
+    /// again.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn try_handoff_leader(&mut self) {
+        if let Ok(mut lh) = self.leader_handler() {
+            lh.try_handoff_leader();
+            return;
+        }
+
+        let priority = self.config.election_priority;
+        if priority == 0 {
+            return;
+        }
+
+        if self.state.server_state != ServerState::Follower {
+            return;
+        }
+
+        let vote = *self.state.vote_ref();
+        if !vote.is_committed() {
+            return;
+        }
+
+        // Safe unwrap: voted_for() is always non-None in Openraft
+        let leader = vote.leader_id().voted_for().unwrap();
+        if leader == self.config.id {
+            return;
+        }
+
+        let now = InstantOf::<C>::now();
+        let timer_config = &self.config.timer_config;
+
+        let Some(utime) = self.state.vote_last_modified() else {
+            return;
+        };
+        if now > utime + timer_config.leader_lease {
+            tracing::debug!("leader lease expired, do not ask leader-{} for leadership", leader);
+            return;
+        }
+
+        if let Some((sent_vote, sent_at)) = &self.handoff_leader_sent {
+            if *sent_vote == vote && now < *sent_at + timer_config.handoff_leader_interval {
+                return;
+            }
+        }
+
+        self.handoff_leader_sent = Some((vote, now));
+
+        self.output.push_command(Command::SendHandoffLeader {
+            target: leader,
+            req: HandoffLeaderRequest::new(vote, self.config.id, priority),
+        });
+    }
+
+    /// Start a PreVote round to find out if this node would win an election, without changing the
+    /// local vote.
+    ///
+    /// The election is started with [`Self::elect`] only when a quorum grants the PreVote.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn pre_elect(&mut self) {
+        let v = Vote::new(self.state.vote_ref().leader_id().term + 1, self.config.id);
+        let last_log_id = self.state.last_log_id().copied();
+        tracing::info!(vote = display(&v), "{}", func_name!());
+
+        let quorum_set = self.state.membership_state.effective().membership().to_quorum_set();
+        let mut voting = Voting::new(InstantOf::<C>::now(), v, last_log_id, quorum_set);
+
+        // Fast-path: if there is only one voter in the cluster.
+        if voting.grant_by(&self.config.id) {
+            self.elect();
+            return;
+        }
+
+        self.pre_voting = Some(voting);
+
+        self.output.push_command(Command::SendPreVote {
+            vote_req: VoteRequest::new(v, last_log_id),
+        });
+    }
+
     /// Get a LeaderHandler for handling leader's operation. If it is not a leader, it send back a
     /// ForwardToLeader error through the tx.
     ///
@@ -257,7 +370,8 @@ where C: RaftTypeConfig
             vote_utime + lease - now
         );
 
-        if vote.is_committed() {
+        // The lease is disabled if the current leader is transferring leadership to the candidate.
+        if vote.is_committed() && !self.state.is_lease_disabled() {
             // Current leader lease has not yet expired, reject voting request
             if now <= vote_utime + lease {
                 tracing::info!(
@@ -368,6 +482,67 @@ where C: RaftTypeConfig
         }
     }
 
+    /// Handle the response to a PreVote request sent with `sender_vote`.
+    ///
+    /// When a quorum grants the PreVote, this node starts an election.
+    #[tracing::instrument(level = "debug", skip(self, resp))]
+    pub(crate) fn handle_pre_vote_resp(
+        &mut self,
+        target: C::NodeId,
+        sender_vote: &Vote<C::NodeId>,
+        resp: VoteResponse<C>,
+    ) {
+        tracing::info!(
+            resp = display(&resp),
+            target = display(target),
+            my_vote = display(self.state.vote_ref()),
+            "{}",
+            func_name!()
+        );
+
+        let Some(voting) = self.pre_voting.as_mut() else {
+            tracing::debug!("no PreVote in progress, ignore");
+            return;
+        };
+
+        if voting.vote_ref() != sender_vote {
+            tracing::debug!(voting = display(&voting), "PreVote round changed, ignore");
+            return;
+        }
+
+        // The local vote is changed or refreshed by a leader after the round started.
+        if self.state.vote_last_modified() > Some(voting.starting_time()) {
+            tracing::info!("local vote is updated since PreVote started, abort PreVote");
+            self.pre_voting = None;
+            return;
+        }
+
+        if resp.vote_granted {
+            if voting.grant_by(&target) {
+                tracing::info!("a quorum granted my PreVote, start election");
+                self.elect();
+            }
+            return;
+        }
+
+        // PreVote is rejected:
+
+        // A greater vote is seen. Refreshing the local vote with an equal one would extend the
+        // leader lease without hearing from the leader, thus only a greater vote is accepted.
+        if &resp.vote > self.state.vote_ref() {
+            let _ = self.vote_handler().update_vote(&resp.vote);
+        }
+
+        if resp.last_log_id.as_ref() > self.state.last_log_id() {
+            tracing::info!(
+                greater_log_id = display(resp.last_log_id.display()),
+                "seen a greater log id when {}",
+                func_name!()
+            );
+            self.set_greater_log();
+        }
+    }
+
     /// Append entries to follower/learner.
     ///
     /// Also clean conflicting entries and update membership state.
@@ -457,16 +632,79 @@ where C: RaftTypeConfig
         // The condition to satisfy before running other command that depends on the snapshot.
         // In this case, the response can only be sent when the snapshot is installed.
         let cond = fh.install_full_snapshot(snapshot);
-        let res = Ok(SnapshotResponse {
-            vote: *self.state.vote_ref(),
+        let res = Ok(SnapshotResponse::new(*self.state.vote_ref()).with_snapshot(self.current_snapshot()));
+
+        self.output.push_command(Command::Respond {
+            when: cond,
+            resp: Respond::new(res, tx),
+        });
+    }
+
+    /// Install a completely received snapshot delta on a follower.
+    ///
+    /// The delta is rejected with [`SnapshotBaseMismatch`] if `base` is not the current snapshot.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn handle_install_snapshot_delta(
+        &mut self,
+        vote: Vote<C::NodeId>,
+        base: SnapshotSignature<C::NodeId>,
+        snapshot: Snapshot<C>,
+        tx: ResultSender<C, SnapshotResponse<C>, SnapshotBaseMismatch<C>>,
+    ) {
+        tracing::info!(
+            vote = display(vote),
+            base = debug(&base),
+            snapshot = display(&snapshot),
+            "{}",
+            func_name!()
+        );
+
+        let vote_res = self.vote_handler().accept_vote(&vote, tx, |state, _rejected| {
+            Ok(SnapshotResponse::new(*state.vote_ref()))
         });
 
+        let Some(tx) = vote_res else {
+            return;
+        };
+
+        let current = self.state.snapshot_meta.signature();
+        if current != base {
+            tracing::info!(
+                "snapshot delta base mismatch; current snapshot: {:?}, base: {:?}",
+                current,
+                base
+            );
+
+            self.output.push_command(Command::Respond {
+                when: None,
+                resp: Respond::new(
+                    Err(SnapshotBaseMismatch {
+                        expect: current,
+                        got: base,
+                    }),
+                    tx,
+                ),
+            });
+            return;
+        }
+
+        let mut fh = self.following_handler();
+
+        let cond = fh.install_snapshot_delta(base, snapshot);
+        let res = Ok(SnapshotResponse::new(*self.state.vote_ref()).with_snapshot(self.current_snapshot()));
+
         self.output.push_command(Command::Respond {
             when: cond,
             resp: Respond::new(res, tx),
         });
     }
 
+    /// The signature of the snapshot this node has, or `None` if there is no snapshot yet.
+    fn current_snapshot(&self) -> Option<SnapshotSignature<C::NodeId>> {
+        self.state.snapshot_meta.last_log_id?;
+        Some(self.state.snapshot_meta.signature())
+    }
+
     /// Install a completely received snapshot on a follower.
     #[tracing::instrument(level = "debug", skip_all)]
     pub(crate) fn handle_begin_receiving_snapshot(&mut self, tx: ResultSender<C, Box<SnapshotDataOf<C>>, Infallible>) {
@@ -474,6 +712,72 @@ where C: RaftTypeConfig
         self.output.push_command(Command::from(sm::Command::begin_receiving_snapshot(tx)));
     }
 
+    /// Handle a `HandoffLeader` request sent by a follower, reporting its election priority.
+    ///
+    /// A node that is not the leader rejects it.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn handle_handoff_leader(&mut self, req: &HandoffLeaderRequest<C>) -> HandoffLeaderResponse {
+        let priority = self.config.election_priority;
+
+        match self.leader_handler() {
+            Ok(mut lh) => lh.handle_handoff_leader(req),
+            Err(_) => {
+                tracing::info!(req = display(req), "reject HandoffLeader: this node is not a leader");
+                HandoffLeaderResponse {
+                    priority,
+                    accepted: false,
+                }
+            }
+        }
+    }
+
+    /// Handle a `TransferLeader` request sent by the current leader.
+    ///
+    /// The leader lease of the current leader is disabled so that this node is able to grant a
+    /// vote to the next leader at once. If this node is the next leader, it starts to elect.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn handle_transfer_leader(&mut self, req: &TransferLeaderRequest<C>) {
+        tracing::info!(
+            req = display(req),
+            my_vote = display(self.state.vote_ref()),
+            my_last_log_id = display(self.state.last_log_id().display()),
+            "{}",
+            func_name!()
+        );
+
+        if self.state.vote_ref() != req.from_leader() {
+            tracing::info!("ignore TransferLeader: it is not sent by the current leader");
+            return;
+        }
+
+        self.state.disable_lease(*req.from_leader());
+
+        if req.to_node_id() != &self.config.id {
+            return;
+        }
+
+        if self.state.last_log_id() < req.last_log_id() {
+            tracing::warn!(
+                "can not become leader: local last log id({}) < required({})",
+                self.state.last_log_id().display(),
+                req.last_log_id().display(),
+            );
+            return;
+        }
+
+        if !self.state.membership_state.effective().is_voter(&self.config.id) {
+            tracing::warn!("can not become leader: this node is not a voter");
+            return;
+        }
+
+        if self.state.membership_state.effective().is_witness(&self.config.id) {
+            tracing::warn!("can not become leader: this node is a witness");
+            return;
+        }
+
+        self.elect();
+    }
+
     /// Leader steps down(convert to learner) once the membership not containing it is committed.
     ///
     /// This is only called by leader.
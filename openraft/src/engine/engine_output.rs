@@ -54,6 +54,9 @@ where C: RaftTypeConfig
             Command::RebuildReplicationStreams { .. } => {}
             Command::SaveVote { .. } => {}
             Command::SendVote { .. } => {}
+            Command::SendPreVote { .. } => {}
+            Command::BroadcastTransferLeader { .. } => {}
+            Command::SendHandoffLeader { .. } => {}
             Command::PurgeLog { .. } => {}
             Command::DeleteConflictLog { .. } => {}
             Command::Respond { .. } => {}
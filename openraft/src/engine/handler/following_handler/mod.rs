@@ -14,6 +14,7 @@ use crate::engine::EngineOutput;
 use crate::entry::RaftPayload;
 use crate::error::RejectAppendEntries;
 use crate::raft_state::LogStateReader;
+use crate::storage::SnapshotSignature;
 use crate::EffectiveMembership;
 use crate::LogId;
 use crate::LogIdOptionExt;
@@ -134,6 +135,7 @@ where C: RaftTypeConfig
         debug_assert!(Some(entries[0].get_log_id()) > self.state.log_ids.last());
 
         self.state.extend_log_ids(&entries);
+        self.state.add_log_bytes(&entries);
         self.append_membership(entries.iter());
 
         self.output.push_command(Command::AppendInputEntries {
@@ -166,7 +168,7 @@ where C: RaftTypeConfig
                 upto: committed.unwrap(),
             });
 
-            if self.config.snapshot_policy.should_snapshot(&self.state) {
+            if self.config.snapshot_policy.should_snapshot(self.state, &self.state.logs_since_snapshot()) {
                 self.snapshot_handler().trigger_snapshot();
             }
         }
@@ -191,6 +193,7 @@ where C: RaftTypeConfig
         };
 
         self.state.log_ids.truncate(since);
+        self.state.log_bytes.truncate(since);
         self.output.push_command(Command::DeleteConflictLog { since: since_log_id });
 
         let changed = self.state.membership_state.truncate(since);
@@ -262,8 +265,30 @@ where C: RaftTypeConfig
     ///   current state).
     #[tracing::instrument(level = "debug", skip_all)]
     pub(crate) fn install_full_snapshot(&mut self, snapshot: Snapshot<C>) -> Option<Condition<C::NodeId>> {
+        self.install_snapshot(None, snapshot)
+    }
+
+    /// Install a snapshot delta upon the current snapshot `base`.
+    ///
+    /// The caller must ensure `base` is the current snapshot.
+    /// The returned condition is the same as [`Self::install_full_snapshot`].
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn install_snapshot_delta(
+        &mut self,
+        base: SnapshotSignature<C::NodeId>,
+        snapshot: Snapshot<C>,
+    ) -> Option<Condition<C::NodeId>> {
+        self.install_snapshot(Some(base), snapshot)
+    }
+
+    /// Install a full snapshot if `base` is `None`, otherwise a snapshot delta upon `base`.
+    fn install_snapshot(
+        &mut self,
+        base: Option<SnapshotSignature<C::NodeId>>,
+        snapshot: Snapshot<C>,
+    ) -> Option<Condition<C::NodeId>> {
         let meta = &snapshot.meta;
-        tracing::info!("install_full_snapshot: meta:{:?}", meta);
+        tracing::info!("install_snapshot: base: {:?}, meta:{:?}", base, meta);
 
         let snap_last_log_id = meta.last_log_id;
 
@@ -303,7 +328,11 @@ where C: RaftTypeConfig
             meta.last_membership.clone(),
         ));
 
-        self.output.push_command(Command::from(sm::Command::install_full_snapshot(snapshot)));
+        let cmd = match base {
+            None => sm::Command::install_full_snapshot(snapshot),
+            Some(base) => sm::Command::install_snapshot_delta(base, snapshot),
+        };
+        self.output.push_command(Command::from(cmd));
         let last_sm_seq = self.output.last_sm_seq();
 
         self.state.purge_upto = Some(snap_last_log_id);
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use maplit::btreemap;
+use maplit::btreeset;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+#[allow(unused_imports)] use pretty_assertions::assert_ne;
+#[allow(unused_imports)] use pretty_assertions::assert_str_eq;
+
+use crate::engine::testing::UTConfig;
+use crate::engine::Command;
+use crate::engine::Engine;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
+use crate::raft::TransferLeaderRequest;
+use crate::testing::log_id;
+use crate::utime::UTime;
+use crate::EffectiveMembership;
+use crate::Membership;
+use crate::MembershipState;
+use crate::TokioInstant;
+use crate::Vote;
+
+fn m012() -> Membership<UTConfig> {
+    Membership::<UTConfig>::new(vec![btreeset! {0,1,2}], btreeset! {3})
+}
+
+/// Node 1 with priority 5 is the leader.
+fn eng() -> Engine<UTConfig> {
+    let mut eng = Engine::testing_default(0);
+    eng.state.enable_validation(false); // Disable validation for incomplete state
+
+    eng.config.id = 1;
+    eng.config.election_priority = 5;
+    eng.state.committed = Some(log_id(1, 1, 1));
+    eng.state.vote = UTime::new(TokioInstant::now(), Vote::new_committed(2, 1));
+    eng.state.log_ids.append(log_id(1, 1, 1));
+    eng.state.log_ids.append(log_id(2, 1, 3));
+    eng.state.membership_state = MembershipState::new(
+        Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m012())),
+        Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m012())),
+    );
+    eng.state.server_state = eng.calc_server_state();
+    eng.vote_handler().become_leading();
+    eng.leader_handler().unwrap().send_heartbeat();
+    eng.output.clear_commands();
+
+    eng
+}
+
+fn req(from: u64, priority: u64) -> HandoffLeaderRequest<UTConfig> {
+    HandoffLeaderRequest::new(Vote::new_committed(2, 1), from, priority)
+}
+
+fn transfer_cmd(to: u64) -> Command<UTConfig> {
+    Command::BroadcastTransferLeader {
+        req: TransferLeaderRequest::new(Vote::new_committed(2, 1), to, Some(log_id(2, 1, 3))),
+    }
+}
+
+#[test]
+fn test_handle_handoff_leader() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    tracing::info!("--- not sent to this leader");
+    {
+        let resp = eng.handle_handoff_leader(&HandoffLeaderRequest::new(Vote::new_committed(1, 0), 2, 9));
+        assert_eq!(
+            HandoffLeaderResponse {
+                priority: 5,
+                accepted: false
+            },
+            resp
+        );
+    }
+
+    tracing::info!("--- lower priority is recorded but not accepted");
+    {
+        let resp = eng.handle_handoff_leader(&req(0, 3));
+        assert_eq!(
+            HandoffLeaderResponse {
+                priority: 5,
+                accepted: false
+            },
+            resp
+        );
+    }
+
+    tracing::info!("--- higher priority is accepted, without transferring at once");
+    {
+        let resp = eng.handle_handoff_leader(&req(2, 9));
+        assert_eq!(
+            HandoffLeaderResponse {
+                priority: 5,
+                accepted: true
+            },
+            resp
+        );
+    }
+
+    assert_eq!(btreemap! {0=>3, 2=>9}, eng.leader_handler()?.leader.priorities);
+    assert_eq!(None, eng.leader_handler()?.leader.transfer_to);
+    assert!(eng.output.take_commands().is_empty());
+
+    tracing::info!("--- a follower rejects it");
+    {
+        let mut eng = Engine::<UTConfig>::testing_default(0);
+        eng.config.election_priority = 5;
+
+        let resp = eng.handle_handoff_leader(&req(2, 9));
+        assert_eq!(
+            HandoffLeaderResponse {
+                priority: 5,
+                accepted: false
+            },
+            resp
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_try_handoff_leader_to_highest_priority() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.handle_handoff_leader(&req(0, 7));
+    eng.handle_handoff_leader(&req(2, 9));
+    // A learner is never a candidate.
+    eng.handle_handoff_leader(&req(3, 10));
+
+    eng.replication_handler().update_matching(0, 1, Some(log_id(2, 1, 3)));
+    eng.output.clear_commands();
+
+    tracing::info!("--- do not fall back to a lower priority voter when the highest is lagging");
+    {
+        eng.try_handoff_leader();
+        assert_eq!(None, eng.leader_handler()?.leader.handoff);
+        assert!(eng.output.take_commands().is_empty());
+    }
+
+    tracing::info!("--- the highest priority voter catches up");
+    {
+        eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+        eng.output.clear_commands();
+
+        eng.try_handoff_leader();
+        assert_eq!(Some(2), eng.leader_handler()?.leader.transfer_to);
+        assert_eq!(Some(2), eng.leader_handler()?.leader.handoff.map(|(to, _)| to));
+        assert_eq!(vec![transfer_cmd(2)], eng.output.take_commands());
+    }
+
+    tracing::info!("--- in progress");
+    {
+        eng.try_handoff_leader();
+        assert_eq!(Some(2), eng.leader_handler()?.leader.transfer_to);
+        assert!(eng.output.take_commands().is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_try_handoff_leader_tie_on_smaller_id() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.handle_handoff_leader(&req(2, 9));
+    eng.handle_handoff_leader(&req(0, 9));
+
+    eng.replication_handler().update_matching(0, 1, Some(log_id(2, 1, 3)));
+    eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+    eng.output.clear_commands();
+
+    eng.try_handoff_leader();
+    assert_eq!(Some(0), eng.leader_handler()?.leader.transfer_to);
+    assert_eq!(vec![transfer_cmd(0)], eng.output.take_commands());
+
+    Ok(())
+}
+
+#[test]
+fn test_try_handoff_leader_not_started() -> anyhow::Result<()> {
+    tracing::info!("--- no higher priority reported");
+    {
+        let mut eng = eng();
+        eng.handle_handoff_leader(&req(2, 5));
+        eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+        eng.output.clear_commands();
+
+        eng.try_handoff_leader();
+        assert_eq!(None, eng.leader_handler()?.leader.transfer_to);
+        assert!(eng.output.take_commands().is_empty());
+    }
+
+    tracing::info!("--- a transfer requested by the application is in progress");
+    {
+        let mut eng = eng();
+        eng.handle_handoff_leader(&req(2, 9));
+        eng.leader_handler()?.transfer_leader(0)?;
+        eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+        eng.output.clear_commands();
+
+        eng.try_handoff_leader();
+        assert_eq!(Some(0), eng.leader_handler()?.leader.transfer_to);
+        assert_eq!(None, eng.leader_handler()?.leader.handoff);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_try_handoff_leader_timeout() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.handle_handoff_leader(&req(2, 9));
+    eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+    eng.try_handoff_leader();
+    eng.output.clear_commands();
+
+    let interval = eng.config.timer_config.handoff_leader_interval;
+    {
+        let lh = eng.leader_handler()?;
+        let (to, started_at) = lh.leader.handoff.unwrap();
+        lh.leader.handoff = Some((to, started_at - interval - Duration::from_millis(1)));
+    }
+
+    tracing::info!("--- cancel the timed out handoff and forget the priority");
+    {
+        eng.try_handoff_leader();
+
+        let lh = eng.leader_handler()?;
+        assert_eq!(None, lh.leader.handoff);
+        assert_eq!(None, lh.leader.transfer_to);
+        assert!(lh.leader.priorities.is_empty());
+    }
+
+    tracing::info!("--- not retried until the priority is reported again");
+    {
+        eng.try_handoff_leader();
+        assert_eq!(None, eng.leader_handler()?.leader.transfer_to);
+
+        eng.handle_handoff_leader(&req(2, 9));
+        eng.try_handoff_leader();
+        assert_eq!(Some(2), eng.leader_handler()?.leader.transfer_to);
+    }
+
+    Ok(())
+}
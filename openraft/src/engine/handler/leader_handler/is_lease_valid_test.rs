@@ -0,0 +1,108 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use maplit::btreeset;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+#[allow(unused_imports)] use pretty_assertions::assert_ne;
+#[allow(unused_imports)] use pretty_assertions::assert_str_eq;
+
+use crate::engine::testing::UTConfig;
+use crate::engine::Engine;
+use crate::progress::Progress;
+use crate::testing::log_id;
+use crate::utime::UTime;
+use crate::EffectiveMembership;
+use crate::Membership;
+use crate::MembershipState;
+use crate::TokioInstant;
+use crate::Vote;
+
+fn m123() -> Membership<UTConfig> {
+    Membership::<UTConfig>::new(vec![btreeset! {1,2,3}], None)
+}
+
+fn eng() -> Engine<UTConfig> {
+    let mut eng = Engine::testing_default(0);
+    eng.state.enable_validation(false); // Disable validation for incomplete state
+
+    eng.config.id = 1;
+    eng.config.timer_config.leader_lease = Duration::from_millis(1000);
+    eng.config.timer_config.lease_read_clock_drift = Duration::from_millis(200);
+    eng.state.committed = Some(log_id(1, 1, 1));
+    eng.state.vote = UTime::new(TokioInstant::now(), Vote::new_committed(3, 1));
+    eng.state.log_ids.append(log_id(1, 1, 1));
+    eng.state.membership_state = MembershipState::new(
+        Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m123())),
+        Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m123())),
+    );
+    eng.state.server_state = eng.calc_server_state();
+    eng.vote_handler().become_leading();
+
+    eng
+}
+
+/// Set the time node 2 acknowledged to `ago` before now.
+fn ack(eng: &mut Engine<UTConfig>, ago: Duration) {
+    let leading = eng.internal_server_state.leading_mut().unwrap();
+    let _ = leading.clock_progress.increase_to(&2, Some(TokioInstant::now() - ago));
+}
+
+#[test]
+fn test_is_lease_valid() -> anyhow::Result<()> {
+    tracing::info!("--- no quorum acked time");
+    {
+        let mut eng = eng();
+        assert!(!eng.leader_handler()?.is_lease_valid());
+    }
+
+    tracing::info!("--- acked within the lease");
+    {
+        let mut eng = eng();
+        ack(&mut eng, Duration::from_millis(100));
+        assert!(eng.leader_handler()?.is_lease_valid());
+    }
+
+    tracing::info!("--- acked within the lease, but not within the clock drift margin");
+    {
+        let mut eng = eng();
+        ack(&mut eng, Duration::from_millis(900));
+        assert!(!eng.leader_handler()?.is_lease_valid());
+
+        eng.config.timer_config.lease_read_clock_drift = Duration::from_millis(0);
+        assert!(eng.leader_handler()?.is_lease_valid());
+    }
+
+    tracing::info!("--- lease expired");
+    {
+        let mut eng = eng();
+        ack(&mut eng, Duration::from_millis(1100));
+        assert!(!eng.leader_handler()?.is_lease_valid());
+    }
+
+    tracing::info!("--- transferring leadership");
+    {
+        let mut eng = eng();
+        ack(&mut eng, Duration::from_millis(100));
+        eng.internal_server_state.leading_mut().unwrap().transfer_to = Some(2);
+        assert!(!eng.leader_handler()?.is_lease_valid());
+    }
+
+    tracing::info!("--- leadership transfer is sent then cancelled");
+    {
+        let mut eng = eng();
+        eng.leader_handler()?.send_heartbeat();
+        eng.replication_handler().update_matching(2, 1, Some(log_id(1, 1, 1)));
+
+        eng.leader_handler()?.transfer_leader(2)?;
+        assert!(eng.state.is_lease_disabled());
+
+        eng.leader_handler()?.cancel_transfer_leader(2);
+        ack(&mut eng, Duration::from_millis(100));
+        assert!(
+            !eng.leader_handler()?.is_lease_valid(),
+            "voters may have disabled the lease of this leader"
+        );
+    }
+
+    Ok(())
+}
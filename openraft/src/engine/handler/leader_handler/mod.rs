@@ -1,20 +1,30 @@
+use crate::display_ext::DisplayOptionExt;
 use crate::engine::handler::replication_handler::ReplicationHandler;
 use crate::engine::handler::replication_handler::SendNone;
 use crate::engine::Command;
 use crate::engine::EngineConfig;
 use crate::engine::EngineOutput;
 use crate::entry::RaftPayload;
+use crate::error::NotInMembers;
 use crate::internal_server_state::LeaderQuorumSet;
 use crate::leader::Leading;
+use crate::progress::Progress;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
 use crate::raft_state::LogStateReader;
+use crate::type_config::alias::InstantOf;
 use crate::type_config::alias::LogIdOf;
+use crate::Instant;
 use crate::RaftLogId;
 use crate::RaftState;
 use crate::RaftTypeConfig;
 
 #[cfg(test)] mod append_entries_test;
 #[cfg(test)] mod get_read_log_id_test;
+#[cfg(test)] mod handoff_leader_test;
+#[cfg(test)] mod is_lease_valid_test;
 #[cfg(test)] mod send_heartbeat_test;
+#[cfg(test)] mod transfer_leader_test;
 
 /// Handle leader operations.
 ///
@@ -53,6 +63,7 @@ where C: RaftTypeConfig
         self.leader.assign_log_ids(&mut entries).unwrap();
 
         self.state.extend_log_ids_from_same_leader(&entries);
+        self.state.add_log_bytes(&entries);
 
         let mut membership_entry = None;
         for entry in entries.iter() {
@@ -104,11 +115,151 @@ where C: RaftTypeConfig
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
-    pub(crate) fn send_heartbeat(&mut self) -> () {
+    pub(crate) fn send_heartbeat(&mut self) {
         let mut rh = self.replication_handler();
         rh.initiate_replication(SendNone::True);
     }
 
+    /// Start to transfer leadership to node `to`.
+    ///
+    /// This leader stops accepting new writes, and sends a `TransferLeader` request to the cluster
+    /// once `to` has replicated all logs of this leader.
+    ///
+    /// It returns the last log id `to` has to catch up with.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) fn transfer_leader(&mut self, to: C::NodeId) -> Result<Option<LogIdOf<C>>, NotInMembers<C>> {
+        let membership = self.state.membership_state.effective();
+        if !membership.is_voter(&to) || membership.is_witness(&to) {
+            return Err(NotInMembers {
+                node_id: to,
+                membership: membership.membership().clone(),
+            });
+        }
+
+        let last_log_id = self.leader.last_log_id().copied();
+
+        if to == self.config.id {
+            tracing::info!("transfer leader to self, nothing to do");
+            return Ok(last_log_id);
+        }
+
+        if self.leader.transfer_to != Some(to) {
+            self.leader.transfer_to = Some(to);
+            self.leader.transfer_sent = false;
+        }
+
+        let mut rh = self.replication_handler();
+        rh.try_transfer_leader();
+
+        Ok(last_log_id)
+    }
+
+    /// Cancel an unfinished leadership transfer to node `to`, and resume accepting new writes.
+    ///
+    /// The leader lease is not restored if the `TransferLeader` request has been sent,
+    /// because other voters may have already disabled it.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) fn cancel_transfer_leader(&mut self, to: C::NodeId) {
+        if self.leader.transfer_to == Some(to) {
+            self.leader.transfer_to = None;
+            self.leader.transfer_sent = false;
+        }
+    }
+
+    /// Record the election priority a voter reports with a `HandoffLeader` request.
+    ///
+    /// The response tells whether the reported priority is higher than this leader's. The
+    /// leadership is not transferred here but by [`Self::try_handoff_leader()`] on a later tick,
+    /// after the leader has compared all reported priorities.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) fn handle_handoff_leader(&mut self, req: &HandoffLeaderRequest<C>) -> HandoffLeaderResponse {
+        let priority = self.config.election_priority;
+
+        if req.vote != self.leader.vote {
+            tracing::info!(
+                my_vote = display(&self.leader.vote),
+                "reject HandoffLeader: it is not sent to this leader"
+            );
+            return HandoffLeaderResponse {
+                priority,
+                accepted: false,
+            };
+        }
+
+        self.leader.priorities.insert(req.from, req.priority);
+
+        HandoffLeaderResponse {
+            priority,
+            accepted: req.priority > priority,
+        }
+    }
+
+    /// Transfer leadership to the voter with the highest election priority, if its priority is
+    /// higher than this leader's and it has replicated all logs of this leader.
+    ///
+    /// Priorities tie on the smaller node id. A handoff that does not finish within
+    /// `handoff_leader_interval` is cancelled, and the priority of its target is forgotten until
+    /// the target reports it again.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) fn try_handoff_leader(&mut self) {
+        let now = InstantOf::<C>::now();
+
+        if let Some((to, started_at)) = self.leader.handoff {
+            if now < started_at + self.config.timer_config.handoff_leader_interval {
+                return;
+            }
+
+            tracing::info!(to = display(to), "handoff leader timeout, cancel it");
+
+            self.leader.handoff = None;
+            self.leader.priorities.remove(&to);
+            self.cancel_transfer_leader(to);
+            return;
+        }
+
+        if self.leader.transfer_to.is_some() {
+            return;
+        }
+
+        let my_priority = self.config.election_priority;
+        let membership = self.state.membership_state.effective();
+
+        let mut highest: Option<(C::NodeId, u64)> = None;
+        for (id, priority) in self.leader.priorities.iter() {
+            if *priority <= my_priority || *id == self.config.id {
+                continue;
+            }
+            if !membership.is_voter(id) || membership.is_witness(id) {
+                continue;
+            }
+            if highest.map_or(true, |(_, p)| *priority > p) {
+                highest = Some((*id, *priority));
+            }
+        }
+
+        let Some((to, priority)) = highest else {
+            return;
+        };
+
+        let last_log_id = self.leader.last_log_id().copied();
+        let matching = self.leader.progress.try_get(&to).and_then(|p| p.matching);
+        if matching < last_log_id {
+            tracing::debug!(
+                to = display(to),
+                matching = display(matching.display()),
+                last_log_id = display(last_log_id.display()),
+                "handoff leader: target is lagging"
+            );
+            return;
+        }
+
+        tracing::info!(to = display(to), priority, my_priority, "hand off leadership");
+
+        if self.transfer_leader(to).is_ok() {
+            self.leader.handoff = Some((to, now));
+        }
+    }
+
     /// Get the log id for a linearizable read.
     ///
     /// See: [Read Operation](crate::docs::protocol::read)
@@ -118,6 +269,23 @@ where C: RaftTypeConfig
         std::cmp::max(self.leader.noop_log_id, committed)
     }
 
+    /// Return `true` if a read can be served with the leader lease acknowledged by a quorum.
+    ///
+    /// The lease is shortened by `lease_read_clock_drift` to tolerate the clock drift between
+    /// nodes.
+    ///
+    /// Once a `TransferLeader` request is sent, the lease is disabled for the current vote even if
+    /// the transfer is cancelled, because other voters no longer honor it.
+    pub(crate) fn is_lease_valid(&mut self) -> bool {
+        if self.state.is_lease_disabled() {
+            return false;
+        }
+
+        let timer_config = &self.config.timer_config;
+        let lease = timer_config.leader_lease.saturating_sub(timer_config.lease_read_clock_drift);
+        self.leader.has_valid_lease(lease)
+    }
+
     pub(crate) fn replication_handler(&mut self) -> ReplicationHandler<C> {
         ReplicationHandler {
             config: self.config,
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use maplit::btreemap;
+use maplit::btreeset;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+#[allow(unused_imports)] use pretty_assertions::assert_ne;
+#[allow(unused_imports)] use pretty_assertions::assert_str_eq;
+
+use crate::engine::testing::UTConfig;
+use crate::engine::Command;
+use crate::engine::Engine;
+use crate::error::NotInMembers;
+use crate::raft::TransferLeaderRequest;
+use crate::testing::log_id;
+use crate::utime::UTime;
+use crate::ChangeMembers;
+use crate::EffectiveMembership;
+use crate::Membership;
+use crate::MembershipState;
+use crate::TokioInstant;
+use crate::Vote;
+
+fn m012() -> Membership<UTConfig> {
+    Membership::<UTConfig>::new(vec![btreeset! {0,1,2}], btreeset! {3})
+}
+
+fn eng() -> Engine<UTConfig> {
+    let mut eng = Engine::testing_default(0);
+    eng.state.enable_validation(false); // Disable validation for incomplete state
+
+    eng.config.id = 1;
+    eng.state.committed = Some(log_id(1, 1, 1));
+    eng.state.vote = UTime::new(TokioInstant::now(), Vote::new_committed(2, 1));
+    eng.state.log_ids.append(log_id(1, 1, 1));
+    eng.state.log_ids.append(log_id(2, 1, 3));
+    eng.state.membership_state = MembershipState::new(
+        Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m012())),
+        Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m012())),
+    );
+    eng.state.server_state = eng.calc_server_state();
+    eng.vote_handler().become_leading();
+    eng.leader_handler().unwrap().send_heartbeat();
+    eng.output.clear_commands();
+
+    eng
+}
+
+#[test]
+fn test_transfer_leader_not_a_voter() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let res = eng.leader_handler()?.transfer_leader(3);
+    assert_eq!(
+        Err(NotInMembers {
+            node_id: 3,
+            membership: m012(),
+        }),
+        res
+    );
+
+    assert_eq!(None, eng.leader_handler()?.leader.transfer_to);
+    assert!(eng.output.take_commands().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_leader_to_witness() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let m = m012().change(ChangeMembers::AddWitnesses(btreemap! {4=>()}), false)?;
+    eng.state.membership_state = MembershipState::new(
+        Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m012())),
+        Arc::new(EffectiveMembership::new(Some(log_id(2, 1, 3)), m.clone())),
+    );
+
+    let res = eng.leader_handler()?.transfer_leader(4);
+    assert_eq!(
+        Err(NotInMembers {
+            node_id: 4,
+            membership: m,
+        }),
+        res
+    );
+
+    assert_eq!(None, eng.leader_handler()?.leader.transfer_to);
+    assert!(eng.output.take_commands().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_leader_to_self() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let res = eng.leader_handler()?.transfer_leader(1);
+    assert_eq!(Ok(Some(log_id(2, 1, 3))), res);
+
+    assert_eq!(None, eng.leader_handler()?.leader.transfer_to);
+    assert!(!eng.state.is_lease_disabled());
+    assert!(eng.output.take_commands().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_leader_wait_for_target_to_catch_up() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let res = eng.leader_handler()?.transfer_leader(2);
+    assert_eq!(Ok(Some(log_id(2, 1, 3))), res);
+
+    // Target is lagging, no TransferLeader is sent.
+    assert_eq!(Some(2), eng.leader_handler()?.leader.transfer_to);
+    assert!(!eng.state.is_lease_disabled());
+    assert!(eng.output.take_commands().is_empty());
+
+    // Target catches up.
+    eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+
+    assert!(eng.state.is_lease_disabled());
+    assert!(eng.leader_handler()?.leader.transfer_sent);
+    assert!(eng.output.take_commands().contains(&Command::BroadcastTransferLeader {
+        req: TransferLeaderRequest::new(Vote::new_committed(2, 1), 2, Some(log_id(2, 1, 3))),
+    }));
+
+    // TransferLeader is sent only once.
+    eng.replication_handler().try_transfer_leader();
+    assert!(!eng.output.take_commands().iter().any(|c| matches!(c, Command::BroadcastTransferLeader { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_leader_target_caught_up() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+    eng.output.clear_commands();
+
+    let res = eng.leader_handler()?.transfer_leader(2);
+    assert_eq!(Ok(Some(log_id(2, 1, 3))), res);
+
+    assert!(eng.state.is_lease_disabled());
+    assert_eq!(
+        vec![Command::BroadcastTransferLeader {
+            req: TransferLeaderRequest::new(Vote::new_committed(2, 1), 2, Some(log_id(2, 1, 3))),
+        }],
+        eng.output.take_commands()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_cancel_transfer_leader() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.leader_handler()?.transfer_leader(2)?;
+
+    // Cancel a transfer to another node does nothing.
+    eng.leader_handler()?.cancel_transfer_leader(0);
+    assert_eq!(Some(2), eng.leader_handler()?.leader.transfer_to);
+
+    eng.leader_handler()?.cancel_transfer_leader(2);
+    assert_eq!(None, eng.leader_handler()?.leader.transfer_to);
+
+    // Target catches up after cancel: nothing is sent.
+    eng.replication_handler().update_matching(2, 1, Some(log_id(2, 1, 3)));
+    assert!(!eng.state.is_lease_disabled());
+    assert!(!eng.output.take_commands().iter().any(|c| matches!(c, Command::BroadcastTransferLeader { .. })));
+
+    Ok(())
+}
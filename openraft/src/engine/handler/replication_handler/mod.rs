@@ -11,6 +11,7 @@ use crate::leader::Leading;
 use crate::progress::entry::ProgressEntry;
 use crate::progress::Inflight;
 use crate::progress::Progress;
+use crate::raft::TransferLeaderRequest;
 use crate::raft_state::LogStateReader;
 use crate::replication::request_id::RequestId;
 use crate::replication::response::ReplicationResult;
@@ -206,6 +207,48 @@ where C: RaftTypeConfig
         );
 
         self.try_commit_quorum_accepted(quorum_accepted);
+
+        if self.leader.transfer_to == Some(node_id) {
+            self.try_transfer_leader();
+        }
+    }
+
+    /// Send a `TransferLeader` request to the cluster, if leadership is being transferred and the
+    /// target has caught up with all logs of this leader.
+    ///
+    /// Since then, the leader lease of this leader is disabled,
+    /// so that the vote request from the target won't be rejected by this node.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn try_transfer_leader(&mut self) {
+        let Some(to) = self.leader.transfer_to else {
+            return;
+        };
+
+        if self.leader.transfer_sent {
+            return;
+        }
+
+        let last_log_id = self.leader.last_log_id().copied();
+        let matching = self.leader.progress.try_get(&to).and_then(|p| p.matching);
+
+        tracing::debug!(
+            to = display(to),
+            matching = display(matching.display()),
+            last_log_id = display(last_log_id.display()),
+            "{}",
+            func_name!()
+        );
+
+        if matching < last_log_id {
+            return;
+        }
+
+        self.leader.transfer_sent = true;
+        self.state.disable_lease(self.leader.vote);
+
+        self.output.push_command(Command::BroadcastTransferLeader {
+            req: TransferLeaderRequest::new(self.leader.vote, to, last_log_id),
+        });
     }
 
     /// Commit the log id that is granted(accepted) by a quorum of voters.
@@ -232,7 +275,7 @@ where C: RaftTypeConfig
                 upto: self.state.committed().copied().unwrap(),
             });
 
-            if self.config.snapshot_policy.should_snapshot(&self.state) {
+            if self.config.snapshot_policy.should_snapshot(self.state, &self.state.logs_since_snapshot()) {
                 self.snapshot_handler().trigger_snapshot();
             }
         }
@@ -312,7 +355,9 @@ where C: RaftTypeConfig
         {
             let p = self.leader.progress.get_mut(&target).unwrap();
 
-            let r = p.next_send(self.state.deref(), self.config.max_payload_entries);
+            let max_entries =
+                p.max_sending_entries(self.config.max_payload_entries, self.config.max_inflight_append_entries);
+            let r = p.next_send(self.state.deref(), max_entries);
             tracing::debug!(next_send_res = debug(&r), "next_send");
 
             if let Ok(inflight) = r {
@@ -354,7 +399,9 @@ where C: RaftTypeConfig
                 continue;
             }
 
-            let t = prog_entry.next_send(self.state, self.config.max_payload_entries);
+            let max_entries = prog_entry
+                .max_sending_entries(self.config.max_payload_entries, self.config.max_inflight_append_entries);
+            let t = prog_entry.next_send(self.state, max_entries);
             tracing::debug!(target = display(*id), send = debug(&t), "next send");
 
             match t {
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use maplit::btreemap;
 use maplit::btreeset;
 use pretty_assertions::assert_eq;
 
@@ -8,6 +9,7 @@ use crate::engine::Command;
 use crate::engine::Engine;
 use crate::testing::log_id;
 use crate::utime::UTime;
+use crate::ChangeMembers;
 use crate::EffectiveMembership;
 use crate::Membership;
 use crate::MembershipState;
@@ -66,3 +68,26 @@ fn test_update_server_state_if_changed() -> anyhow::Result<()> {
     //          A leader keeps working after it is removed from the voters.
     Ok(())
 }
+
+#[test]
+fn test_update_server_state_witness() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let m = m123().change(ChangeMembers::AddWitnesses(btreemap! {4=>()}), false)?;
+
+    eng.config.id = 4;
+    eng.state.vote = UTime::new(TokioInstant::now(), Vote::new_committed(2, 1));
+    eng.state.membership_state = MembershipState::new(
+        Arc::new(EffectiveMembership::new(Some(log_id(2, 1, 3)), m123())),
+        Arc::new(EffectiveMembership::new(Some(log_id(2, 1, 4)), m)),
+    );
+    eng.state.server_state = ServerState::Follower;
+
+    let mut ssh = eng.server_state_handler();
+    ssh.update_server_state_if_changed();
+
+    assert_eq!(ServerState::Witness, ssh.state.server_state);
+    assert!(ssh.output.take_commands().is_empty());
+
+    Ok(())
+}
@@ -5,6 +5,9 @@ use crate::display_ext::DisplayOptionExt;
 use crate::engine::Command;
 use crate::engine::EngineOutput;
 use crate::raft_state::LogStateReader;
+use crate::type_config::alias::InstantOf;
+use crate::Instant;
+use crate::LogIdOptionExt;
 use crate::RaftState;
 use crate::RaftTypeConfig;
 use crate::SnapshotMeta;
@@ -60,7 +63,10 @@ where C: RaftTypeConfig
             return false;
         }
 
+        // Logs appended while the snapshot is built are not included in it.
+        self.state.log_bytes.forget_before(meta.last_log_id.next_index());
         self.state.snapshot_meta = meta;
+        self.state.snapshot_time = Some(InstantOf::<C>::now());
 
         true
     }
@@ -81,3 +81,22 @@ fn test_update_snapshot_updated() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_update_snapshot_keep_log_bytes_after_snapshot() -> anyhow::Result<()> {
+    // Logs appended while the snapshot is built are still counted.
+    let mut eng = eng();
+
+    eng.state.log_bytes.append(3, 5, 20);
+    eng.state.log_bytes.append(5, 8, 30);
+
+    eng.snapshot_handler().update_snapshot(SnapshotMeta {
+        last_log_id: Some(log_id(2, 1, 4)),
+        last_membership: StoredMembership::new(Some(log_id(2, 1, 2)), m1234()),
+        snapshot_id: "1-2-3-4".to_string(),
+    });
+
+    assert_eq!(30, eng.state.logs_since_snapshot().bytes);
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use maplit::btreeset;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+
+use crate::engine::testing::UTConfig;
+use crate::engine::Engine;
+use crate::engine::LogIdList;
+use crate::raft::VoteRequest;
+use crate::raft::VoteResponse;
+use crate::testing::log_id;
+use crate::utime::UTime;
+use crate::EffectiveMembership;
+use crate::Membership;
+use crate::TokioInstant;
+use crate::Vote;
+
+fn m012() -> Membership<UTConfig> {
+    Membership::<UTConfig>::new(vec![btreeset! {0,1,2}], None)
+}
+
+/// Node 0 is a follower of leader 1, and the leader lease has expired.
+fn eng() -> Engine<UTConfig> {
+    let mut eng = Engine::testing_default(0);
+    eng.state.enable_validation(false); // Disable validation for incomplete state
+
+    let expired = TokioInstant::now() - eng.config.timer_config.leader_lease - Duration::from_millis(1);
+
+    eng.state.vote = UTime::new(expired, Vote::new_committed(2, 1));
+    eng.state.log_ids = LogIdList::new(vec![log_id(2, 1, 3)]);
+    eng.state
+        .membership_state
+        .set_effective(Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m012())));
+    eng.vote_handler().become_following();
+    eng.output.clear_commands();
+
+    eng
+}
+
+fn resp(granted: bool) -> VoteResponse<UTConfig> {
+    VoteResponse {
+        vote: Vote::new_committed(2, 1),
+        vote_granted: granted,
+        last_log_id: Some(log_id(2, 1, 3)),
+    }
+}
+
+#[test]
+fn test_handle_pre_vote_req_granted() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let got = eng.vote_handler().handle_pre_vote_req(&VoteRequest::new(Vote::new(3, 2), Some(log_id(2, 1, 3))));
+    assert_eq!(resp(true), got);
+
+    // PreVote does not change any state
+    assert_eq!(Vote::new_committed(2, 1), *eng.state.vote_ref());
+    assert_eq!(0, eng.output.take_commands().len());
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_pre_vote_req_reject_by_lease() -> anyhow::Result<()> {
+    let mut eng = eng();
+    eng.state.vote.touch(TokioInstant::now());
+
+    let got = eng.vote_handler().handle_pre_vote_req(&VoteRequest::new(Vote::new(3, 2), Some(log_id(2, 1, 3))));
+    assert_eq!(resp(false), got);
+
+    tracing::info!("--- lease is disabled by leader transfer");
+    {
+        eng.state.disable_lease(Vote::new_committed(2, 1));
+        let got = eng.vote_handler().handle_pre_vote_req(&VoteRequest::new(Vote::new(3, 2), Some(log_id(2, 1, 3))));
+        assert_eq!(resp(true), got);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_pre_vote_req_reject_by_leader() -> anyhow::Result<()> {
+    let mut eng = eng();
+    eng.config.id = 1;
+
+    let got = eng.vote_handler().handle_pre_vote_req(&VoteRequest::new(Vote::new(3, 2), Some(log_id(2, 1, 3))));
+    assert_eq!(resp(false), got);
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_pre_vote_req_reject_by_last_log_id() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let got = eng.vote_handler().handle_pre_vote_req(&VoteRequest::new(Vote::new(3, 2), Some(log_id(2, 1, 2))));
+    assert_eq!(resp(false), got);
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_pre_vote_req_reject_by_vote() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    let got = eng.vote_handler().handle_pre_vote_req(&VoteRequest::new(Vote::new(1, 2), Some(log_id(2, 1, 3))));
+    assert_eq!(resp(false), got);
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
 use crate::core::raft_msg::ResultSender;
+use crate::display_ext::DisplayOptionExt;
 use crate::engine::handler::server_state_handler::ServerStateHandler;
 use crate::engine::Command;
 use crate::engine::EngineConfig;
@@ -10,6 +11,8 @@ use crate::engine::ValueSender;
 use crate::error::RejectVoteRequest;
 use crate::internal_server_state::InternalServerState;
 use crate::leader::Leading;
+use crate::raft::VoteRequest;
+use crate::raft::VoteResponse;
 use crate::raft_state::LogStateReader;
 use crate::type_config::alias::InstantOf;
 use crate::Instant;
@@ -20,6 +23,7 @@ use crate::Vote;
 
 #[cfg(test)] mod accept_vote_test;
 #[cfg(test)] mod handle_message_vote_test;
+#[cfg(test)] mod handle_pre_vote_req_test;
 
 /// Handle raft vote related operations
 ///
@@ -115,6 +119,61 @@ where C: RaftTypeConfig
         Ok(())
     }
 
+    /// Check if this node would grant the vote in a PreVote request, without changing any state.
+    ///
+    /// A PreVote is granted only if:
+    /// - This node is not the leader, and the leader lease it knows has expired;
+    /// - The candidate's last log id is greater than or equal to the local one;
+    /// - The proposed vote is greater than or equal to the local vote.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn handle_pre_vote_req(&self, req: &VoteRequest<C>) -> VoteResponse<C> {
+        tracing::info!(
+            req = display(req),
+            my_vote = display(self.state.vote_ref()),
+            my_last_log_id = display(self.state.last_log_id().display()),
+            "{}",
+            func_name!()
+        );
+
+        let vote_granted = if self.state.is_leader(&self.config.id) {
+            tracing::info!("reject PreVote: this node is the leader");
+            false
+        } else if self.is_leader_lease_valid() {
+            tracing::info!("reject PreVote: leader lease has not yet expired");
+            false
+        } else if req.last_log_id.as_ref() < self.state.last_log_id() {
+            tracing::info!("reject PreVote: candidate's last log id is smaller");
+            false
+        } else if &req.vote >= self.state.vote_ref() {
+            true
+        } else {
+            tracing::info!("reject PreVote: proposed vote is not greater than or equal to the local vote");
+            false
+        };
+
+        VoteResponse {
+            vote: *self.state.vote_ref(),
+            vote_granted,
+            last_log_id: self.state.last_log_id().copied(),
+        }
+    }
+
+    /// Return `true` if the vote is committed, i.e., there is a leader, and this node received a
+    /// message from the leader within the leader lease.
+    ///
+    /// The lease is disabled if the leader is transferring its leadership.
+    fn is_leader_lease_valid(&self) -> bool {
+        if !self.state.vote_ref().is_committed() || self.state.is_lease_disabled() {
+            return false;
+        }
+
+        let Some(utime) = self.state.vote_last_modified() else {
+            return false;
+        };
+
+        InstantOf::<C>::now() <= utime + self.config.timer_config.leader_lease
+    }
+
     /// Enter leading or following state by checking `vote`.
     pub(crate) fn update_internal_server_state(&mut self) {
         if self.state.is_leading(&self.config.id) {
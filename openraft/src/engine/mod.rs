@@ -45,9 +45,12 @@ mod tests {
     mod handle_vote_resp_test;
     mod initialize_test;
     mod install_full_snapshot_test;
+    mod install_snapshot_delta_test;
     mod log_id_list_test;
+    mod pre_elect_test;
     mod startup_test;
     mod trigger_purge_log_test;
+    mod try_handoff_leader_test;
 }
 #[cfg(test)] pub(crate) mod testing;
 
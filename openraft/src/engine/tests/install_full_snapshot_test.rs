@@ -10,6 +10,7 @@ use crate::engine::Condition;
 use crate::engine::Engine;
 use crate::engine::LogIdList;
 use crate::engine::Respond;
+use crate::error::Infallible;
 use crate::raft::SnapshotResponse;
 use crate::testing::log_id;
 use crate::type_config::alias::AsyncRuntimeOf;
@@ -93,7 +94,12 @@ fn test_handle_install_full_snapshot_lt_last_snapshot() -> anyhow::Result<()> {
             //
             Command::Respond {
                 when: None,
-                resp: Respond::new(Ok(SnapshotResponse::new(curr_vote)), dummy_tx),
+                resp: Respond::new(
+                    Ok::<_, Infallible>(
+                        SnapshotResponse::new(curr_vote).with_snapshot(Some(eng.state.snapshot_meta.signature()))
+                    ),
+                    dummy_tx
+                ),
             },
         ],
         eng.output.take_commands()
@@ -153,7 +159,12 @@ fn test_handle_install_full_snapshot_no_conflict() -> anyhow::Result<()> {
             Command::PurgeLog { upto: log_id(4, 1, 6) },
             Command::Respond {
                 when: Some(Condition::StateMachineCommand { command_seq: 1 }),
-                resp: Respond::new(Ok(SnapshotResponse::new(curr_vote)), dummy_tx),
+                resp: Respond::new(
+                    Ok::<_, Infallible>(
+                        SnapshotResponse::new(curr_vote).with_snapshot(Some(eng.state.snapshot_meta.signature()))
+                    ),
+                    dummy_tx
+                ),
             },
         ],
         eng.output.take_commands()
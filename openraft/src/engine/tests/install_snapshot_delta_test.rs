@@ -0,0 +1,148 @@
+use std::io::Cursor;
+
+use maplit::btreeset;
+use pretty_assertions::assert_eq;
+
+use crate::core::sm;
+use crate::engine::testing::UTConfig;
+use crate::engine::Command;
+use crate::engine::Condition;
+use crate::engine::Engine;
+use crate::engine::LogIdList;
+use crate::engine::Respond;
+use crate::error::SnapshotBaseMismatch;
+use crate::raft::SnapshotResponse;
+use crate::testing::log_id;
+use crate::type_config::alias::AsyncRuntimeOf;
+use crate::AsyncRuntime;
+use crate::Membership;
+use crate::Snapshot;
+use crate::SnapshotMeta;
+use crate::StoredMembership;
+use crate::TokioInstant;
+use crate::Vote;
+
+fn m12() -> Membership<UTConfig> {
+    Membership::<UTConfig>::new(vec![btreeset! {1,2}], None)
+}
+
+fn m1234() -> Membership<UTConfig> {
+    Membership::<UTConfig>::new(vec![btreeset! {1,2,3,4}], None)
+}
+
+fn snapshot_meta() -> SnapshotMeta<UTConfig> {
+    SnapshotMeta {
+        last_log_id: Some(log_id(2, 1, 2)),
+        last_membership: StoredMembership::new(Some(log_id(1, 1, 1)), m12()),
+        snapshot_id: "1-2-3-4".to_string(),
+    }
+}
+
+fn delta() -> Snapshot<UTConfig> {
+    Snapshot {
+        meta: SnapshotMeta {
+            last_log_id: Some(log_id(4, 1, 6)),
+            last_membership: StoredMembership::new(Some(log_id(1, 1, 1)), m1234()),
+            snapshot_id: "1-2-3-5".to_string(),
+        },
+        snapshot: Box::new(Cursor::new(vec![0u8])),
+    }
+}
+
+fn eng() -> Engine<UTConfig> {
+    let mut eng = Engine::testing_default(0);
+    eng.state.enable_validation(false); // Disable validation for incomplete state
+
+    eng.state.vote.update(TokioInstant::now(), Vote::new_committed(2, 1));
+    eng.state.committed = Some(log_id(4, 1, 5));
+    eng.state.log_ids = LogIdList::new(vec![
+        //
+        log_id(2, 1, 2),
+        log_id(3, 1, 5),
+        log_id(4, 1, 6),
+        log_id(4, 1, 8),
+    ]);
+    eng.state.snapshot_meta = snapshot_meta();
+    eng.state.server_state = eng.calc_server_state();
+
+    eng
+}
+
+#[test]
+fn test_handle_install_snapshot_delta_base_mismatch() -> anyhow::Result<()> {
+    // The delta is rejected because its base is not the current snapshot.
+    // It should respond at once.
+
+    let mut eng = eng();
+
+    let curr_vote = *eng.state.vote_ref();
+
+    let base = SnapshotMeta {
+        snapshot_id: "1-2-3-3".to_string(),
+        ..snapshot_meta()
+    }
+    .signature();
+
+    let (tx, _rx) = AsyncRuntimeOf::<UTConfig>::oneshot();
+
+    eng.handle_install_snapshot_delta(curr_vote, base.clone(), delta(), tx);
+
+    assert_eq!(snapshot_meta(), eng.state.snapshot_meta);
+
+    let (dummy_tx, _rx) = AsyncRuntimeOf::<UTConfig>::oneshot();
+    assert_eq!(
+        vec![
+            //
+            Command::Respond {
+                when: None,
+                resp: Respond::new(
+                    Err(SnapshotBaseMismatch {
+                        expect: snapshot_meta().signature(),
+                        got: base,
+                    }),
+                    dummy_tx
+                ),
+            },
+        ],
+        eng.output.take_commands()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_install_snapshot_delta() -> anyhow::Result<()> {
+    // The delta is installed upon the current snapshot.
+    // The response should be sent after the delta is installed.
+
+    let mut eng = eng();
+
+    let curr_vote = *eng.state.vote_ref();
+
+    let (tx, _rx) = AsyncRuntimeOf::<UTConfig>::oneshot();
+
+    eng.handle_install_snapshot_delta(curr_vote, snapshot_meta().signature(), delta(), tx);
+
+    assert_eq!(delta().meta, eng.state.snapshot_meta);
+
+    let (dummy_tx, _rx) = AsyncRuntimeOf::<UTConfig>::oneshot();
+    assert_eq!(
+        vec![
+            //
+            Command::from(sm::Command::install_snapshot_delta(snapshot_meta().signature(), delta()).with_seq(1)),
+            Command::PurgeLog { upto: log_id(4, 1, 6) },
+            Command::Respond {
+                when: Some(Condition::StateMachineCommand { command_seq: 1 }),
+                resp: Respond::new(
+                    Ok::<_, SnapshotBaseMismatch<UTConfig>>(
+                        SnapshotResponse::new(curr_vote).with_snapshot(Some(delta().meta.signature()))
+                    ),
+                    dummy_tx
+                ),
+            },
+        ],
+        eng.output.take_commands()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use maplit::btreeset;
+use pretty_assertions::assert_eq;
+
+use crate::core::ServerState;
+use crate::engine::testing::UTConfig;
+use crate::engine::Command;
+use crate::engine::Engine;
+use crate::engine::LogIdList;
+use crate::raft::VoteRequest;
+use crate::raft::VoteResponse;
+use crate::testing::log_id;
+use crate::utime::UTime;
+use crate::EffectiveMembership;
+use crate::Membership;
+use crate::TokioInstant;
+use crate::Vote;
+
+fn m1() -> Membership<UTConfig> {
+    Membership::new(vec![btreeset! {1}], None)
+}
+
+fn m123() -> Membership<UTConfig> {
+    Membership::new(vec![btreeset! {1,2,3}], None)
+}
+
+/// Node 1 is a follower of leader 2 at term 2, and has not heard from the leader for a while.
+fn eng() -> Engine<UTConfig> {
+    let mut eng = Engine::testing_default(0);
+    eng.state.enable_validation(false); // Disable validation for incomplete state
+
+    eng.config.id = 1;
+    eng.state.log_ids = LogIdList::new([log_id(2, 2, 3)]);
+    eng.state.vote = UTime::new(
+        TokioInstant::now() - Duration::from_millis(1000),
+        Vote::new_committed(2, 2),
+    );
+    eng.state
+        .membership_state
+        .set_effective(Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m123())));
+    eng.vote_handler().become_following();
+    eng.output.clear_commands();
+    eng
+}
+
+fn pre_vote_resp(granted: bool, vote: Vote<u64>) -> VoteResponse<UTConfig> {
+    VoteResponse {
+        vote,
+        vote_granted: granted,
+        last_log_id: Some(log_id(2, 2, 3)),
+    }
+}
+
+#[test]
+fn test_pre_elect_single_voter() -> anyhow::Result<()> {
+    let mut eng = eng();
+    eng.state
+        .membership_state
+        .set_effective(Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m1())));
+
+    eng.pre_elect();
+
+    assert!(eng.pre_voting.is_none());
+    assert_eq!(Vote::new_committed(3, 1), *eng.state.vote_ref());
+    assert_eq!(ServerState::Leader, eng.state.server_state);
+
+    Ok(())
+}
+
+#[test]
+fn test_pre_elect_does_not_change_vote() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.pre_elect();
+
+    assert_eq!(Vote::new_committed(2, 2), *eng.state.vote_ref());
+    assert!(eng.internal_server_state.is_following());
+    assert_eq!(Some(&Vote::new(3, 1)), eng.pre_voting.as_ref().map(|v| v.vote_ref()));
+
+    assert_eq!(
+        vec![Command::SendPreVote {
+            vote_req: VoteRequest::new(Vote::new(3, 1), Some(log_id(2, 2, 3))),
+        }],
+        eng.output.take_commands()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_pre_vote_resp_granted_by_quorum() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.pre_elect();
+    eng.output.clear_commands();
+
+    eng.handle_pre_vote_resp(2, &Vote::new(3, 1), pre_vote_resp(true, Vote::new_committed(2, 2)));
+
+    assert!(eng.pre_voting.is_none());
+    assert_eq!(Vote::new(3, 1), *eng.state.vote_ref());
+    assert_eq!(ServerState::Candidate, eng.state.server_state);
+    assert_eq!(
+        vec![Command::SaveVote { vote: Vote::new(3, 1) }, Command::SendVote {
+            vote_req: VoteRequest::new(Vote::new(3, 1), Some(log_id(2, 2, 3))),
+        },],
+        eng.output.take_commands()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_pre_vote_resp_rejected() -> anyhow::Result<()> {
+    tracing::info!("--- rejected with the same vote: nothing changes");
+    {
+        let mut eng = eng();
+
+        eng.pre_elect();
+        eng.output.clear_commands();
+
+        let utime = eng.state.vote_last_modified();
+        eng.handle_pre_vote_resp(2, &Vote::new(3, 1), pre_vote_resp(false, Vote::new_committed(2, 2)));
+
+        assert!(eng.pre_voting.is_some());
+        assert_eq!(Vote::new_committed(2, 2), *eng.state.vote_ref());
+        assert_eq!(utime, eng.state.vote_last_modified(), "leader lease is not extended");
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    tracing::info!("--- rejected with a greater vote: update local vote");
+    {
+        let mut eng = eng();
+
+        eng.pre_elect();
+        eng.output.clear_commands();
+
+        eng.handle_pre_vote_resp(2, &Vote::new(3, 1), pre_vote_resp(false, Vote::new_committed(4, 3)));
+
+        assert_eq!(Vote::new_committed(4, 3), *eng.state.vote_ref());
+        assert_eq!(
+            vec![Command::SaveVote {
+                vote: Vote::new_committed(4, 3)
+            }],
+            eng.output.take_commands()
+        );
+
+        // The local vote is updated, the PreVote round is aborted.
+        eng.handle_pre_vote_resp(3, &Vote::new(3, 1), pre_vote_resp(true, Vote::new_committed(2, 2)));
+        assert!(eng.pre_voting.is_none());
+        assert_eq!(Vote::new_committed(4, 3), *eng.state.vote_ref());
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_pre_vote_resp_stale() -> anyhow::Result<()> {
+    tracing::info!("--- response to another round is ignored");
+    {
+        let mut eng = eng();
+
+        eng.pre_elect();
+        eng.output.clear_commands();
+
+        eng.handle_pre_vote_resp(2, &Vote::new(2, 1), pre_vote_resp(true, Vote::new_committed(2, 2)));
+
+        assert!(eng.pre_voting.is_some());
+        assert_eq!(Vote::new_committed(2, 2), *eng.state.vote_ref());
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    tracing::info!("--- heard from the leader after PreVote started: abort");
+    {
+        let mut eng = eng();
+
+        eng.pre_elect();
+        eng.output.clear_commands();
+
+        // The leader is heard from after the PreVote round started.
+        let started = eng.pre_voting.as_ref().unwrap().starting_time();
+        eng.state.vote.touch(started + Duration::from_millis(1));
+
+        eng.handle_pre_vote_resp(2, &Vote::new(3, 1), pre_vote_resp(true, Vote::new_committed(2, 2)));
+
+        assert!(eng.pre_voting.is_none());
+        assert_eq!(Vote::new_committed(2, 2), *eng.state.vote_ref());
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    Ok(())
+}
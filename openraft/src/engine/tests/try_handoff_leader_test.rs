@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use maplit::btreeset;
+use pretty_assertions::assert_eq;
+
+use crate::engine::testing::UTConfig;
+use crate::engine::Command;
+use crate::engine::Engine;
+use crate::engine::LogIdList;
+use crate::raft::HandoffLeaderRequest;
+use crate::testing::log_id;
+use crate::utime::UTime;
+use crate::EffectiveMembership;
+use crate::Membership;
+use crate::TokioInstant;
+use crate::Vote;
+
+fn m123() -> Membership<UTConfig> {
+    Membership::new(vec![btreeset! {1,2,3}], None)
+}
+
+/// Node 1 with priority 5 is a follower of leader 2, and has just heard from the leader.
+fn eng() -> Engine<UTConfig> {
+    let mut eng = Engine::testing_default(0);
+    eng.state.enable_validation(false); // Disable validation for incomplete state
+
+    eng.config.id = 1;
+    eng.config.election_priority = 5;
+    eng.state.log_ids = LogIdList::new([log_id(2, 2, 3)]);
+    eng.state.vote = UTime::new(TokioInstant::now(), Vote::new_committed(2, 2));
+    eng.state
+        .membership_state
+        .set_effective(Arc::new(EffectiveMembership::new(Some(log_id(1, 1, 1)), m123())));
+    eng.vote_handler().become_following();
+    eng.state.server_state = eng.calc_server_state();
+    eng.output.clear_commands();
+    eng
+}
+
+fn handoff_cmd() -> Command<UTConfig> {
+    Command::SendHandoffLeader {
+        target: 2,
+        req: HandoffLeaderRequest::new(Vote::new_committed(2, 2), 1, 5),
+    }
+}
+
+#[test]
+fn test_try_handoff_leader() -> anyhow::Result<()> {
+    let mut eng = eng();
+
+    eng.try_handoff_leader();
+    assert_eq!(vec![handoff_cmd()], eng.output.take_commands());
+
+    tracing::info!("--- do not send again within handoff_leader_interval");
+    {
+        eng.try_handoff_leader();
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    tracing::info!("--- send again after handoff_leader_interval");
+    {
+        let interval = eng.config.timer_config.handoff_leader_interval;
+        let (vote, sent_at) = eng.handoff_leader_sent.unwrap();
+        eng.handoff_leader_sent = Some((vote, sent_at - interval - Duration::from_millis(1)));
+
+        eng.try_handoff_leader();
+        assert_eq!(vec![handoff_cmd()], eng.output.take_commands());
+    }
+
+    tracing::info!("--- send at once to a new leader");
+    {
+        eng.state.vote.update(TokioInstant::now(), Vote::new_committed(3, 3));
+
+        eng.try_handoff_leader();
+        assert_eq!(
+            vec![Command::SendHandoffLeader {
+                target: 3,
+                req: HandoffLeaderRequest::new(Vote::new_committed(3, 3), 1, 5),
+            }],
+            eng.output.take_commands()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_try_handoff_leader_not_sent() -> anyhow::Result<()> {
+    tracing::info!("--- priority is 0");
+    {
+        let mut eng = eng();
+        eng.config.election_priority = 0;
+
+        eng.try_handoff_leader();
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    tracing::info!("--- leader is unknown");
+    {
+        let mut eng = eng();
+        eng.state.vote.update(TokioInstant::now(), Vote::new(2, 2));
+
+        eng.try_handoff_leader();
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    tracing::info!("--- leader lease expired");
+    {
+        let mut eng = eng();
+        let lease = eng.config.timer_config.leader_lease;
+        eng.state.vote = UTime::new(
+            TokioInstant::now() - lease - Duration::from_millis(1),
+            Vote::new_committed(2, 2),
+        );
+
+        eng.try_handoff_leader();
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    tracing::info!("--- not a voter");
+    {
+        let mut eng = eng();
+        eng.state.membership_state.set_effective(Arc::new(EffectiveMembership::new(
+            Some(log_id(1, 1, 1)),
+            Membership::new(vec![btreeset! {2,3}], btreeset! {1}),
+        )));
+        eng.state.server_state = eng.calc_server_state();
+
+        eng.try_handoff_leader();
+        assert_eq!(0, eng.output.take_commands().len());
+    }
+
+    Ok(())
+}
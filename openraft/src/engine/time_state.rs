@@ -20,6 +20,14 @@ pub(crate) struct Config {
     /// When a follower or learner perceives an active leader, such as by receiving an AppendEntries
     /// message, it should not grant another candidate to become the leader during this period.
     pub(crate) leader_lease: Duration,
+
+    /// The margin subtracted from `leader_lease` when a leader serves a lease read.
+    ///
+    /// It tolerates the clock drift between the leader and the followers.
+    pub(crate) lease_read_clock_drift: Duration,
+
+    /// The minimal interval between two `HandoffLeader` requests sent to the same leader.
+    pub(crate) handoff_leader_interval: Duration,
 }
 
 impl Default for Config {
@@ -28,6 +36,8 @@ impl Default for Config {
             election_timeout: Duration::from_millis(150),
             smaller_log_timeout: Duration::from_millis(200),
             leader_lease: Duration::from_millis(150),
+            lease_read_clock_drift: Duration::from_millis(20),
+            handoff_leader_interval: Duration::from_millis(3000),
         }
     }
 }
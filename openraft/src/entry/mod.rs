@@ -125,6 +125,19 @@ where C: RaftTypeConfig
             payload: EntryPayload::Membership(m),
         }
     }
+
+    fn approx_size(&self) -> u64 {
+        let payload_size = match &self.payload {
+            EntryPayload::Blank => 0,
+            EntryPayload::Normal(data) => C::approx_data_size(data),
+            EntryPayload::Membership(m) => {
+                let node_size = std::mem::size_of::<(C::NodeId, C::Node)>() as u64;
+                std::mem::size_of_val(m) as u64 + node_size * m.nodes().count() as u64
+            }
+        };
+
+        std::mem::size_of::<LogId<C::NodeId>>() as u64 + payload_size
+    }
 }
 
 impl<C> FromAppData<C::D> for Entry<C>
@@ -35,6 +35,20 @@ where
     ///
     /// The returned instance must return `Some()` for `Self::get_membership()`.
     fn new_membership(log_id: LogId<C::NodeId>, m: Membership<C>) -> Self;
+
+    /// Return the approximate size in bytes of this entry.
+    ///
+    /// It is used by size based policies, such as
+    /// [`SnapshotPolicy::BytesSinceLast`](`crate::SnapshotPolicy::BytesSinceLast`), and to limit
+    /// the size of an AppendEntries RPC, see
+    /// [`Config::max_payload_bytes`](`crate::Config::max_payload_bytes`).
+    /// The default implementation returns the in-memory size of `Self`, which does not include
+    /// heap allocated data. An application should override it if the payload holds heap data.
+    /// [`Entry`](`crate::Entry`) counts the size of its application data with
+    /// [`RaftTypeConfig::approx_data_size()`](`crate::RaftTypeConfig::approx_data_size`).
+    fn approx_size(&self) -> u64 {
+        std::mem::size_of_val(self) as u64
+    }
 }
 
 /// Build a raft log entry from app data.
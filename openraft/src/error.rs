@@ -16,6 +16,7 @@ pub use self::streaming_error::StreamingError;
 use crate::network::RPCTypes;
 use crate::raft::AppendEntriesResponse;
 use crate::raft_types::SnapshotSegmentId;
+use crate::storage::SnapshotSignature;
 use crate::try_as_ref::TryAsRef;
 use crate::LogId;
 use crate::Membership;
@@ -203,6 +204,12 @@ pub enum ChangeMembershipError<C: RaftTypeConfig> {
 
     #[error(transparent)]
     LearnerNotFound(#[from] LearnerNotFound<C>),
+
+    #[error(transparent)]
+    InvalidWitness(#[from] InvalidWitness<C>),
+
+    #[error(transparent)]
+    LearnerLagging(#[from] LearnerLagging<C>),
 }
 
 /// The set of errors which may take place when initializing a pristine Raft node.
@@ -218,6 +225,85 @@ where C: RaftTypeConfig
     NotInMembers(#[from] NotInMembers<C>),
 }
 
+/// The set of errors which may take place when transferring leadership to another node.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, derive_more::TryInto)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub enum TransferLeaderError<C>
+where C: RaftTypeConfig
+{
+    #[error(transparent)]
+    ForwardToLeader(#[from] ForwardToLeader<C>),
+
+    /// The target node is not a voter, or is a witness, and can not become a leader.
+    #[error(transparent)]
+    NotInMembers(#[from] NotInMembers<C>),
+
+    /// The target node did not catch up with the leader's logs in time.
+    #[error(transparent)]
+    TargetLagging(#[from] TargetLagging<C>),
+
+    /// The target node caught up but did not become the leader in time.
+    #[error(transparent)]
+    Timeout(#[from] Timeout<C>),
+}
+
+impl<C> TryAsRef<ForwardToLeader<C>> for TransferLeaderError<C>
+where C: RaftTypeConfig
+{
+    fn try_as_ref(&self) -> Option<&ForwardToLeader<C>> {
+        match self {
+            Self::ForwardToLeader(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
+/// The set of errors which may take place when a non-leader node gets a read index from the
+/// leader.
+#[derive(Debug, Clone, thiserror::Error, derive_more::TryInto)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub enum ReadIndexError<C>
+where C: RaftTypeConfig
+{
+    /// This node does not know which node is the leader.
+    #[error(transparent)]
+    LeaderUnknown(#[from] LeaderUnknown<C>),
+
+    /// The node believed to be the leader is no longer the leader.
+    #[error(transparent)]
+    ForwardToLeader(#[from] ForwardToLeader<C>),
+
+    /// The leader failed to confirm its leadership with a quorum.
+    #[error(transparent)]
+    QuorumNotEnough(#[from] QuorumNotEnough<C>),
+
+    /// Failed to send the request to the leader or to receive the response.
+    #[error(transparent)]
+    Network(#[from] NetworkError),
+}
+
+impl<C> From<CheckIsLeaderError<C>> for ReadIndexError<C>
+where C: RaftTypeConfig
+{
+    fn from(e: CheckIsLeaderError<C>) -> Self {
+        match e {
+            CheckIsLeaderError::ForwardToLeader(e) => e.into(),
+            CheckIsLeaderError::QuorumNotEnough(e) => e.into(),
+        }
+    }
+}
+
+impl<C> TryAsRef<ForwardToLeader<C>> for ReadIndexError<C>
+where C: RaftTypeConfig
+{
+    fn try_as_ref(&self) -> Option<&ForwardToLeader<C>> {
+        match self {
+            Self::ForwardToLeader(f) => Some(f),
+            _ => None,
+        }
+    }
+}
+
 /// Error variants related to the Replication.
 #[derive(Debug, thiserror::Error)]
 #[allow(clippy::large_enum_variant)]
@@ -447,15 +533,18 @@ impl fmt::Display for PayloadTooLarge {
 
         write!(f, " hint:(")?;
         match self.action {
-            RPCTypes::Vote => {
+            RPCTypes::Vote | RPCTypes::PreVote => {
                 unreachable!("vote rpc should not have payload")
             }
             RPCTypes::AppendEntries => {
                 write!(f, "entries:{}", self.entries_hint)?;
             }
-            RPCTypes::InstallSnapshot => {
+            RPCTypes::InstallSnapshot | RPCTypes::SnapshotDelta => {
                 write!(f, "bytes:{}", self.bytes_hint)?;
             }
+            RPCTypes::TransferLeader | RPCTypes::ReadIndex | RPCTypes::HandoffLeader => {
+                unreachable!("{} rpc should not have payload", self.action)
+            }
         }
         write!(f, ")")?;
 
@@ -562,6 +651,26 @@ pub struct SnapshotMismatch {
     pub got: SnapshotSegmentId,
 }
 
+/// A snapshot delta is rejected because its base is not the current snapshot of the receiver.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+#[error("snapshot delta base mismatch, expect: {expect:?}, got: {got:?}")]
+pub struct SnapshotBaseMismatch<C: RaftTypeConfig> {
+    /// The current snapshot of the receiver.
+    pub expect: SnapshotSignature<C::NodeId>,
+
+    /// The base snapshot the delta is built upon.
+    pub got: SnapshotSignature<C::NodeId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+#[error("node {node_id} does not know the leader, vote: {vote}")]
+pub struct LeaderUnknown<C: RaftTypeConfig> {
+    pub node_id: C::NodeId,
+    pub vote: Vote<C::NodeId>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
 #[error("not enough for a quorum, cluster: {cluster}, got: {got:?}")]
@@ -585,6 +694,24 @@ pub struct LearnerNotFound<C: RaftTypeConfig> {
     pub node_id: C::NodeId,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+#[error("learner {node_id} did not catch up in {timeout:?}: matching: {matching:?}, leader last log index: {last_log_index:?}")]
+pub struct LearnerLagging<C: RaftTypeConfig> {
+    pub node_id: C::NodeId,
+    pub matching: Option<LogId<C::NodeId>>,
+    pub last_log_index: Option<u64>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+#[error("invalid witness {node_id}: {reason}")]
+pub struct InvalidWitness<C: RaftTypeConfig> {
+    pub node_id: C::NodeId,
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
 #[error("not allowed to initialize due to current raft state: last_log_id: {last_log_id:?} vote: {vote}")]
@@ -603,6 +730,15 @@ where C: RaftTypeConfig
     pub membership: Membership<C>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+#[error("transfer leader target {target} is lagging: matching: {matching:?}, leader last log id: {last_log_id:?}")]
+pub struct TargetLagging<C: RaftTypeConfig> {
+    pub target: C::NodeId,
+    pub matching: Option<LogId<C::NodeId>>,
+    pub last_log_id: Option<LogId<C::NodeId>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[error("new membership can not be empty")]
@@ -70,3 +70,19 @@ where
         }
     }
 }
+
+/// An RPC error of a network that sends a snapshot in a single RPC.
+///
+/// [`RPCError::PayloadTooLarge`] is converted to a [`NetworkError`], because a snapshot can not be
+/// split by the caller.
+impl<C: RaftTypeConfig, E: Error> From<RPCError<C, E>> for StreamingError<C, E> {
+    fn from(e: RPCError<C, E>) -> Self {
+        match e {
+            RPCError::Timeout(e) => StreamingError::Timeout(e),
+            RPCError::Unreachable(e) => StreamingError::Unreachable(e),
+            RPCError::PayloadTooLarge(e) => StreamingError::Network(NetworkError::new(&e)),
+            RPCError::Network(e) => StreamingError::Network(e),
+            RPCError::RemoteError(e) => StreamingError::RemoteError(e),
+        }
+    }
+}
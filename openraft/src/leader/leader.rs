@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::fmt;
+use std::time::Duration;
 
 use crate::leader::voting::Voting;
 use crate::progress::entry::ProgressEntry;
@@ -58,6 +60,24 @@ where C: RaftTypeConfig
     ///
     /// [`docs::leader_lease`]: `crate::docs::protocol::replication::leader_lease`
     pub(crate) clock_progress: VecProgress<C::NodeId, Option<InstantOf<C>>, Option<InstantOf<C>>, QS>,
+
+    /// The node this leader is transferring leadership to.
+    ///
+    /// When it is set, this leader stops accepting new writes.
+    pub(crate) transfer_to: Option<C::NodeId>,
+
+    /// Whether the `TransferLeader` request has been sent for the current `transfer_to`.
+    pub(crate) transfer_sent: bool,
+
+    /// The election priorities of other voters, reported with `HandoffLeader` requests.
+    pub(crate) priorities: BTreeMap<C::NodeId, u64>,
+
+    /// The voter this leader is handing off leadership to because of its higher election
+    /// priority, and when the handoff started.
+    ///
+    /// Unlike a transfer requested by the application, a handoff is cancelled by the leader
+    /// itself if it does not finish in time.
+    pub(crate) handoff: Option<(C::NodeId, InstantOf<C>)>,
 }
 
 impl<C, QS> Leading<C, QS>
@@ -85,6 +105,10 @@ where
                 ProgressEntry::empty(last_log_id.next_index()),
             ),
             clock_progress: VecProgress::new(quorum_set, learner_ids, None),
+            transfer_to: None,
+            transfer_sent: false,
+            priorities: BTreeMap::new(),
+            handoff: None,
         }
     }
 
@@ -188,10 +212,32 @@ where
             Err(x) => *x,
         }
     }
+
+    /// Return `true` if the leadership is still guaranteed by the time acknowledged by a quorum.
+    ///
+    /// The lease is valid if `now < last_quorum_acked_time + lease`.
+    /// No other node can be elected before the lease expires, thus a read can be served without
+    /// another round of heartbeats.
+    ///
+    /// A leader that is transferring its leadership does not have a valid lease,
+    /// because the target node may elect itself without waiting for the lease to expire.
+    pub(crate) fn has_valid_lease(&mut self, lease: Duration) -> bool {
+        if self.transfer_to.is_some() {
+            return false;
+        }
+
+        let Some(acked) = self.last_quorum_acked_time() else {
+            return false;
+        };
+
+        InstantOf::<C>::now() < acked + lease
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::engine::testing::UTConfig;
     use crate::entry::RaftEntry;
     use crate::leader::Leading;
@@ -299,4 +345,24 @@ mod tests {
         let t = leading.last_quorum_acked_time();
         assert_eq!(Some(t2), t, "n2 and n3 acked");
     }
+
+    #[test]
+    fn test_leading_has_valid_lease() {
+        let mut leading = Leading::<UTConfig, Vec<u64>>::new(Vote::new_committed(2, 1), vec![1, 2, 3], [4], None);
+
+        let lease = Duration::from_millis(1000);
+        assert!(!leading.has_valid_lease(lease), "no remote node acked");
+
+        let t2 = InstantOf::<UTConfig>::now();
+        let _ = leading.clock_progress.increase_to(&2, Some(t2));
+        assert!(leading.has_valid_lease(lease), "n1(leader) and n2 acked");
+
+        let _ = leading.clock_progress.increase_to(&2, Some(t2 - Duration::from_millis(2000)));
+        assert!(leading.has_valid_lease(lease), "acked time does not go backward");
+
+        assert!(!leading.has_valid_lease(Duration::from_millis(0)), "lease expired");
+
+        leading.transfer_to = Some(2);
+        assert!(!leading.has_valid_lease(lease), "no lease when transferring leadership");
+    }
 }
@@ -69,6 +69,10 @@ where
         &self.vote
     }
 
+    pub(crate) fn starting_time(&self) -> InstantOf<C> {
+        self.starting_time
+    }
+
     pub(crate) fn progress(&self) -> &VecProgress<C::NodeId, bool, bool, QS> {
         &self.progress
     }
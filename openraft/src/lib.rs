@@ -75,7 +75,9 @@ pub use crate::async_runtime::TokioRuntime;
 pub use crate::change_members::ChangeMembers;
 pub use crate::config::Config;
 pub use crate::config::ConfigError;
+pub use crate::config::LogsSinceSnapshot;
 pub use crate::config::SnapshotPolicy;
+pub use crate::config::SnapshotPredicate;
 pub use crate::core::ServerState;
 pub use crate::entry::Entry;
 pub use crate::entry::EntryPayload;
@@ -113,6 +113,11 @@ where C: RaftTypeConfig
         self.membership().is_voter(nid)
     }
 
+    /// Check if the given node is a witness voter, which never becomes a leader.
+    pub(crate) fn is_witness(&self, nid: &C::NodeId) -> bool {
+        self.membership().is_witness(nid)
+    }
+
     /// Returns an Iterator of all voter node ids. Learners are not included.
     pub fn voter_ids(&self) -> impl Iterator<Item = C::NodeId> + '_ {
         self.voter_ids.iter().copied()
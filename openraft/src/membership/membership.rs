@@ -4,6 +4,7 @@ use std::collections::BTreeSet;
 
 use crate::error::ChangeMembershipError;
 use crate::error::EmptyMembership;
+use crate::error::InvalidWitness;
 use crate::error::LearnerNotFound;
 use crate::membership::IntoNodes;
 use crate::quorum::AsJoint;
@@ -31,6 +32,14 @@ where C: RaftTypeConfig
     ///
     /// A node-id key that is in `nodes` but is not in `configs` is a **learner**.
     nodes: BTreeMap<C::NodeId, C::Node>,
+
+    /// Voters that are **witness**.
+    ///
+    /// A witness votes and counts toward the quorum, but stores only log ids and membership,
+    /// without application data, and never becomes a leader.
+    /// Every witness must be a voter.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeSet::is_empty"))]
+    witnesses: BTreeSet<C::NodeId>,
 }
 
 impl<C> From<BTreeMap<C::NodeId, C::Node>> for Membership<C>
@@ -87,7 +96,20 @@ where C: RaftTypeConfig
                 write!(f, "None")?;
             }
         }
-        write!(f, "]}}")?;
+        write!(f, "]")?;
+
+        if !self.witnesses.is_empty() {
+            write!(f, ", witnesses:[")?;
+            for (i, node_id) in self.witnesses.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{node_id}")?;
+            }
+            write!(f, "]")?;
+        }
+
+        write!(f, "}}")?;
         Ok(())
     }
 }
@@ -113,7 +135,11 @@ where C: RaftTypeConfig
         let voter_ids = config.as_joint().ids().collect::<BTreeSet<_>>();
         let nodes = Self::extend_nodes(nodes.into_nodes(), &voter_ids.into_nodes());
 
-        Membership { configs: config, nodes }
+        Membership {
+            configs: config,
+            nodes,
+            witnesses: BTreeSet::new(),
+        }
     }
 
     /// Returns reference to the joint config.
@@ -146,6 +172,23 @@ where C: RaftTypeConfig
     pub fn learner_ids(&self) -> impl Iterator<Item = C::NodeId> + '_ {
         self.nodes.keys().filter(|x| !self.is_voter(x)).copied()
     }
+
+    /// Returns an Iterator of all witness node ids.
+    ///
+    /// A witness is also a voter, thus it is included in [`Self::voter_ids()`] too.
+    pub fn witness_ids(&self) -> impl Iterator<Item = C::NodeId> + '_ {
+        self.witnesses.iter().copied()
+    }
+
+    /// Mark the given voters as witnesses, e.g., when rebuilding a membership decoded from the
+    /// wire.
+    ///
+    /// An id that is not a voter is ignored.
+    pub fn with_witnesses(mut self, witnesses: impl IntoIterator<Item = C::NodeId>) -> Self {
+        let witnesses = witnesses.into_iter().filter(|id| self.is_voter(id)).collect::<Vec<_>>();
+        self.witnesses.extend(witnesses);
+        self
+    }
 }
 
 impl<C> Membership<C>
@@ -166,12 +209,21 @@ where C: RaftTypeConfig
         false
     }
 
+    /// Check if the given `NodeId` is a witness voter.
+    pub(crate) fn is_witness(&self, node_id: &C::NodeId) -> bool {
+        self.witnesses.contains(node_id)
+    }
+
     /// Create a new Membership the same as [`Self::new()`], but does not add default value
     /// `Node::default()` if a voter id is not in `nodes`. Thus it may create an invalid instance.
     pub(crate) fn new_unchecked<T>(configs: Vec<BTreeSet<C::NodeId>>, nodes: T) -> Self
     where T: IntoNodes<C::NodeId, C::Node> {
         let nodes = nodes.into_nodes();
-        Membership { configs, nodes }
+        Membership {
+            configs,
+            nodes,
+            witnesses: BTreeSet::new(),
+        }
     }
 
     /// Extends nodes btreemap with another.
@@ -197,9 +249,37 @@ where C: RaftTypeConfig
     /// Ensure the membership config is valid:
     /// - No empty sub-config in it.
     /// - Every voter has a corresponding Node.
+    /// - Every witness is a voter, and every sub-config has at least one voter that is not a
+    ///   witness.
     pub(crate) fn ensure_valid(&self) -> Result<(), ChangeMembershipError<C>> {
         self.ensure_non_empty_config()?;
         self.ensure_voter_nodes().map_err(|nid| LearnerNotFound { node_id: nid })?;
+        self.ensure_witnesses()?;
+        Ok(())
+    }
+
+    /// Ensures that every witness is a voter and a leader can be elected in every sub-config.
+    pub(crate) fn ensure_witnesses(&self) -> Result<(), InvalidWitness<C>> {
+        for witness_id in self.witnesses.iter() {
+            if !self.is_voter(witness_id) {
+                return Err(InvalidWitness {
+                    node_id: *witness_id,
+                    reason: "a witness has to be a voter".to_string(),
+                });
+            }
+        }
+
+        for c in self.configs.iter() {
+            if c.iter().all(|id| self.is_witness(id)) {
+                // Safe unwrap(): non-empty config is checked before
+                let witness_id = *c.iter().next().unwrap();
+                return Err(InvalidWitness {
+                    node_id: witness_id,
+                    reason: "every config has to contain at least one non-witness voter".to_string(),
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -251,21 +331,30 @@ where C: RaftTypeConfig
     ///     curr = next;
     /// }
     /// ```
+    ///
+    /// A removed witness is never retained as a learner, because it does not store application
+    /// data.
     pub(crate) fn next_coherent(&self, goal: BTreeSet<C::NodeId>, retain: bool) -> Self {
         let config = Joint::from(self.configs.clone()).find_coherent(goal).children().clone();
 
         let mut nodes = self.nodes.clone();
 
-        if !retain {
-            let old_voter_ids = self.configs.as_joint().ids().collect::<BTreeSet<_>>();
-            let new_voter_ids = config.as_joint().ids().collect::<BTreeSet<_>>();
+        let old_voter_ids = self.configs.as_joint().ids().collect::<BTreeSet<_>>();
+        let new_voter_ids = config.as_joint().ids().collect::<BTreeSet<_>>();
 
-            for node_id in old_voter_ids.difference(&new_voter_ids) {
+        for node_id in old_voter_ids.difference(&new_voter_ids) {
+            if !retain || self.is_witness(node_id) {
                 nodes.remove(node_id);
             }
-        };
+        }
+
+        let witnesses = self.witnesses.intersection(&new_voter_ids).copied().collect();
 
-        Membership::new_unchecked(config, nodes)
+        Membership {
+            configs: config,
+            nodes,
+            witnesses,
+        }
     }
 
     /// Apply a change-membership request and return a new instance.
@@ -301,6 +390,33 @@ where C: RaftTypeConfig
                 self.next_coherent(new_voter_ids, retain)
             }
             ChangeMembers::ReplaceAllVoters(all_voter_ids) => self.next_coherent(all_voter_ids, retain),
+            ChangeMembers::AddWitnesses(add_witnesses) => {
+                // A witness has to be a new node: an existing voter or learner stores application
+                // data and can not be turned into a witness.
+                for node_id in add_witnesses.keys() {
+                    if self.nodes.contains_key(node_id) && !self.is_witness(node_id) {
+                        return Err(InvalidWitness {
+                            node_id: *node_id,
+                            reason: "an existing voter or learner can not be added as a witness".to_string(),
+                        }
+                        .into());
+                    }
+                }
+
+                self.nodes = Self::extend_nodes(self.nodes, &add_witnesses);
+
+                let add_witness_ids = add_witnesses.keys().copied().collect::<BTreeSet<_>>();
+                self.witnesses.extend(add_witness_ids.iter().copied());
+
+                let new_voter_ids = last.union(&add_witness_ids).copied().collect::<BTreeSet<_>>();
+                self.next_coherent(new_voter_ids, retain)
+            }
+            ChangeMembers::RemoveWitnesses(remove_witness_ids) => {
+                let remove_witness_ids =
+                    remove_witness_ids.into_iter().filter(|id| self.is_witness(id)).collect::<BTreeSet<_>>();
+                let new_voter_ids = last.difference(&remove_witness_ids).copied().collect::<BTreeSet<_>>();
+                self.next_coherent(new_voter_ids, retain)
+            }
             ChangeMembers::AddNodes(add_nodes) => {
                 // When adding nodes, do not override existing node
                 for (node_id, node) in add_nodes.into_iter() {
@@ -351,6 +467,7 @@ mod tests {
     use crate::engine::testing::UTConfig;
     use crate::error::ChangeMembershipError;
     use crate::error::EmptyMembership;
+    use crate::error::InvalidWitness;
     use crate::error::LearnerNotFound;
     use crate::ChangeMembers;
     use crate::Membership;
@@ -360,6 +477,7 @@ mod tests {
         let m = Membership::<UTConfig> {
             configs: vec![btreeset! {1,2}],
             nodes: btreemap! {1=>()},
+            witnesses: btreeset! {},
         };
         assert_eq!(Err(2), m.ensure_voter_nodes());
         Ok(())
@@ -370,6 +488,7 @@ mod tests {
         let m = || Membership::<UTConfig> {
             configs: vec![btreeset! {1,2}],
             nodes: btreemap! {1=>(),2=>(),3=>()},
+            witnesses: btreeset! {},
         };
 
         // Add: no such learner
@@ -387,7 +506,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}, btreeset! {1,2,3}],
-                    nodes: btreemap! {1=>(),2=>(),3=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -399,7 +519,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}, btreeset! {1,2,5}],
-                    nodes: btreemap! {1=>(),2=>(),3=>(),5=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>(),5=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -411,7 +532,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}],
-                    nodes: btreemap! {1=>(),2=>(),3=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -429,7 +551,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}, btreeset! {2}],
-                    nodes: btreemap! {1=>(),2=>(),3=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -441,7 +564,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}, btreeset! {2}],
-                    nodes: btreemap! {1=>(),2=>(),3=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -452,12 +576,14 @@ mod tests {
             let mem = Membership::<UTConfig> {
                 configs: vec![btreeset! {1,2}, btreeset! {2}],
                 nodes: btreemap! {1=>(),2=>(),3=>()},
+                witnesses: btreeset! {},
             };
             let res = mem.change(ChangeMembers::RemoveVoters(btreeset! {1}), false);
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {2}],
-                    nodes: btreemap! {2=>(),3=>()}
+                    nodes: btreemap! {2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -469,7 +595,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}, btreeset! {2}],
-                    nodes: btreemap! {1=>(),2=>(),3=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -481,7 +608,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}],
-                    nodes: btreemap! {1=>(),2=>(),3=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -493,7 +621,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}],
-                    nodes: btreemap! {1=>(),2=>(),3=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -505,7 +634,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}],
-                    nodes: btreemap! {1=>(),2=>(),3=>(), 4=>()}
+                    nodes: btreemap! {1=>(),2=>(),3=>(), 4=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -516,13 +646,15 @@ mod tests {
             let m = || Membership::<UTConfig<u64>> {
                 configs: vec![btreeset! {1,2}],
                 nodes: btreemap! {1=>1,2=>2,3=>3},
+                witnesses: btreeset! {},
             };
 
             let res = m().change(ChangeMembers::SetNodes(btreemap! {3=>30, 4=>40}), false);
             assert_eq!(
                 Ok(Membership::<UTConfig<u64>> {
                     configs: vec![btreeset! {1,2}],
-                    nodes: btreemap! {1=>1,2=>2,3=>30, 4=>40}
+                    nodes: btreemap! {1=>1,2=>2,3=>30, 4=>40},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -543,7 +675,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}],
-                    nodes: btreemap! {1=>(),2=>()}
+                    nodes: btreemap! {1=>(),2=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -555,7 +688,8 @@ mod tests {
             assert_eq!(
                 Ok(Membership::<UTConfig> {
                     configs: vec![btreeset! {1,2}],
-                    nodes: btreemap! {1=>(),2=>(),4=>()}
+                    nodes: btreemap! {1=>(),2=>(),4=>()},
+                    witnesses: btreeset! {},
                 }),
                 res
             );
@@ -563,4 +697,94 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_membership_change_witness() -> anyhow::Result<()> {
+        let m = || Membership::<UTConfig> {
+            configs: vec![btreeset! {1,2}],
+            nodes: btreemap! {1=>(),2=>(),3=>()},
+            witnesses: btreeset! {},
+        };
+
+        // AddWitnesses: ok
+        {
+            let res = m().change(ChangeMembers::AddWitnesses(btreemap! {5=>()}), false)?;
+            assert_eq!(
+                Membership::<UTConfig> {
+                    configs: vec![btreeset! {1,2}, btreeset! {1,2,5}],
+                    nodes: btreemap! {1=>(),2=>(),3=>(),5=>()},
+                    witnesses: btreeset! {5},
+                },
+                res
+            );
+            assert!(res.is_voter(&5));
+            assert!(res.is_witness(&5));
+            assert_eq!(vec![5], res.witness_ids().collect::<Vec<_>>());
+            assert_eq!(
+                "{voters:[{1:(),2:()},{1:(),2:(),5:()}], learners:[3:()], witnesses:[5]}",
+                res.to_string()
+            );
+        }
+
+        // AddWitnesses: an existing learner can not be a witness
+        {
+            let res = m().change(ChangeMembers::AddWitnesses(btreemap! {3=>()}), false);
+            assert_eq!(
+                Err(ChangeMembershipError::InvalidWitness(InvalidWitness {
+                    node_id: 3,
+                    reason: "an existing voter or learner can not be added as a witness".to_string(),
+                })),
+                res
+            );
+        }
+
+        let mw = || Membership::<UTConfig> {
+            configs: vec![btreeset! {1,2,5}],
+            nodes: btreemap! {1=>(),2=>(),3=>(),5=>()},
+            witnesses: btreeset! {5},
+        };
+
+        // RemoveWitnesses: the witness is not retained as a learner
+        {
+            let res = mw().change(ChangeMembers::RemoveWitnesses(btreeset! {5}), true)?;
+            assert_eq!(
+                Membership::<UTConfig> {
+                    configs: vec![btreeset! {1,2,5}, btreeset! {1,2}],
+                    nodes: btreemap! {1=>(),2=>(),3=>(),5=>()},
+                    witnesses: btreeset! {5},
+                },
+                res
+            );
+
+            let res = res.change(ChangeMembers::RemoveWitnesses(btreeset! {5}), true)?;
+            assert_eq!(
+                Membership::<UTConfig> {
+                    configs: vec![btreeset! {1,2}],
+                    nodes: btreemap! {1=>(),2=>(),3=>()},
+                    witnesses: btreeset! {},
+                },
+                res
+            );
+        }
+
+        // RemoveWitnesses: non-witness ids are ignored
+        {
+            let res = mw().change(ChangeMembers::RemoveWitnesses(btreeset! {1}), true)?;
+            assert_eq!(mw(), res);
+        }
+
+        // ReplaceAllVoters: can not leave only witnesses in a config
+        {
+            let res = mw().change(ChangeMembers::ReplaceAllVoters(btreeset! {5}), true);
+            assert_eq!(
+                Err(ChangeMembershipError::InvalidWitness(InvalidWitness {
+                    node_id: 5,
+                    reason: "every config has to contain at least one non-witness voter".to_string(),
+                })),
+                res
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -222,6 +222,17 @@ fn test_membership_with_nodes() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_membership_with_witnesses() -> anyhow::Result<()> {
+    let m = Membership::<UTConfig>::new(vec![btreeset! {1,2,3}], Some(btreeset! {4}));
+
+    let m = m.with_witnesses([3, 4, 5]);
+    assert_eq!(vec![3], m.witness_ids().collect::<Vec<_>>());
+    assert_eq!(vec![1, 2, 3], m.voter_ids().collect::<Vec<_>>());
+
+    Ok(())
+}
+
 #[test]
 fn test_membership_next_coherent() -> anyhow::Result<()> {
     let nodes = || btreeset! {1,2,3,4,5,6,7,8,9}.into_nodes();
@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::core::ServerState;
+use crate::display_ext::DisplayOptionExt;
+use crate::error::Fatal;
+use crate::LogId;
+use crate::LogIdOptionExt;
+use crate::LogIndexOptionExt;
+use crate::RaftMetrics;
+use crate::RaftTypeConfig;
+
+/// Thresholds of the checks in a [`Health`] verdict.
+///
+/// The default values are loose enough for a cluster with the default [`Config`]. Liveness and
+/// readiness probes may use different thresholds.
+///
+/// [`Config`]: crate::Config
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct HealthThresholds {
+    /// On a leader, the max milliseconds since a quorum acknowledged it.
+    ///
+    /// A leader that is not acknowledged in time may be partitioned from the cluster.
+    pub max_millis_since_quorum_ack: u64,
+
+    /// The max number of committed log entries not yet applied to the state machine.
+    pub max_apply_lag: u64,
+
+    /// On a leader, the max number of log entries a follower or learner can lag behind the last
+    /// log before it is considered not caught up.
+    pub max_replication_lag: u64,
+
+    /// On a leader, the max milliseconds since a follower or learner last accepted a replication
+    /// RPC, including heartbeats, before it is considered unreachable.
+    pub max_millis_since_last_success: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_millis_since_quorum_ack: 1_000,
+            max_apply_lag: 1_000,
+            max_replication_lag: 1_000,
+            max_millis_since_last_success: 1_000,
+        }
+    }
+}
+
+/// The result of a single check in a [`Health`] verdict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum HealthCheck {
+    Pass,
+    Fail,
+
+    /// The check does not apply to this node, e.g., quorum ack on a follower.
+    Skip,
+}
+
+impl HealthCheck {
+    fn pass_if(ok: bool) -> Self {
+        if ok {
+            HealthCheck::Pass
+        } else {
+            HealthCheck::Fail
+        }
+    }
+
+    /// Returns `false` only if the check failed.
+    pub fn is_ok(&self) -> bool {
+        *self != HealthCheck::Fail
+    }
+}
+
+impl fmt::Display for HealthCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthCheck::Pass => write!(f, "pass"),
+            HealthCheck::Fail => write!(f, "fail"),
+            HealthCheck::Skip => write!(f, "skip"),
+        }
+    }
+}
+
+/// Replication health of a follower or learner, seen by the leader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct FollowerHealth<C: RaftTypeConfig> {
+    /// The last log id replicated to the target.
+    pub matching: Option<LogId<C::NodeId>>,
+
+    /// The number of log entries the target lags behind the leader's last log.
+    pub lag: u64,
+
+    /// Fails if [`Self::lag`] exceeds [`HealthThresholds::max_replication_lag`].
+    pub caught_up: HealthCheck,
+
+    /// The elapsed time in milliseconds since the target last accepted a replication RPC.
+    /// `None` if it has not accepted any yet.
+    pub millis_since_last_success: Option<u64>,
+
+    /// The number of replication RPCs to the target that failed since the last successful one.
+    pub consecutive_failures: u64,
+
+    /// Fails if the target has not accepted a replication RPC within
+    /// [`HealthThresholds::max_millis_since_last_success`], or if it has never accepted one and
+    /// an RPC to it has failed.
+    pub reachable: HealthCheck,
+}
+
+/// A structured health verdict of a Raft node, built from its [`RaftMetrics`].
+///
+/// [`Health::is_live()`] and [`Health::is_ready()`] can be used directly as liveness and readiness
+/// probes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct Health<C: RaftTypeConfig> {
+    /// The ID of the Raft node.
+    pub id: C::NodeId,
+
+    /// The state of the Raft node.
+    pub state: ServerState,
+
+    /// The error that stopped Raft, if any.
+    pub fatal: Option<Fatal<C>>,
+
+    /// Fails if Raft stopped because of a storage error.
+    pub storage: HealthCheck,
+
+    /// The current cluster leader.
+    pub current_leader: Option<C::NodeId>,
+
+    /// Fails if there is no known leader.
+    pub has_leader: HealthCheck,
+
+    /// On a leader, the elapsed time in milliseconds since it is acknowledged by a quorum.
+    pub millis_since_quorum_ack: Option<u64>,
+
+    /// Fails if a leader is not acknowledged by a quorum within
+    /// [`HealthThresholds::max_millis_since_quorum_ack`]. Skipped on other nodes.
+    pub quorum_ack: HealthCheck,
+
+    /// The number of committed log entries not yet applied to the state machine.
+    ///
+    /// On a follower, it is measured against the committed log id the leader replicated to it.
+    pub apply_lag: u64,
+
+    /// Fails if [`Self::apply_lag`] exceeds [`HealthThresholds::max_apply_lag`].
+    pub apply: HealthCheck,
+
+    /// On a leader, the replication health of every other node. Empty on other nodes.
+    pub followers: BTreeMap<C::NodeId, FollowerHealth<C>>,
+}
+
+impl<C> Health<C>
+where C: RaftTypeConfig
+{
+    /// Evaluate every check on a snapshot of [`RaftMetrics`].
+    pub fn new(metrics: &RaftMetrics<C>, thresholds: &HealthThresholds) -> Self {
+        let fatal = metrics.running_state.clone().err();
+        let storage = HealthCheck::pass_if(!matches!(fatal, Some(Fatal::StorageError(_))));
+
+        let has_leader = HealthCheck::pass_if(fatal.is_none() && metrics.current_leader.is_some());
+
+        let quorum_ack = if metrics.state == ServerState::Leader {
+            HealthCheck::pass_if(
+                metrics.millis_since_quorum_ack.map_or(false, |x| x <= thresholds.max_millis_since_quorum_ack),
+            )
+        } else {
+            HealthCheck::Skip
+        };
+
+        let apply_lag = metrics.committed.next_index().saturating_sub(metrics.last_applied.next_index());
+        let apply = HealthCheck::pass_if(apply_lag <= thresholds.max_apply_lag);
+
+        let mut followers = BTreeMap::new();
+        if let Some(targets) = &metrics.replication_targets {
+            let last_log_next = metrics.last_log_index.next_index();
+
+            for (target, m) in targets.iter() {
+                if *target == metrics.id {
+                    continue;
+                }
+
+                let lag = last_log_next.saturating_sub(m.matching.next_index());
+
+                let reachable = match m.millis_since_last_success {
+                    Some(millis) => millis <= thresholds.max_millis_since_last_success,
+                    None => m.consecutive_failures == 0,
+                };
+
+                followers.insert(*target, FollowerHealth {
+                    matching: m.matching,
+                    lag,
+                    caught_up: HealthCheck::pass_if(lag <= thresholds.max_replication_lag),
+                    millis_since_last_success: m.millis_since_last_success,
+                    consecutive_failures: m.consecutive_failures,
+                    reachable: HealthCheck::pass_if(reachable),
+                });
+            }
+        }
+
+        Self {
+            id: metrics.id,
+            state: metrics.state,
+            fatal,
+            storage,
+            current_leader: metrics.current_leader,
+            has_leader,
+            millis_since_quorum_ack: metrics.millis_since_quorum_ack,
+            quorum_ack,
+            apply_lag,
+            apply,
+            followers,
+        }
+    }
+
+    /// Whether Raft is still running.
+    ///
+    /// A node that is not live has to be restarted.
+    pub fn is_live(&self) -> bool {
+        self.fatal.is_none()
+    }
+
+    /// Whether the node is live, knows a leader, has a fresh quorum ack if it is the leader, and
+    /// its state machine is not lagging behind.
+    ///
+    /// Follower health is not included, because an unreachable or lagging follower does not stop
+    /// this node from serving requests.
+    pub fn is_ready(&self) -> bool {
+        self.is_live()
+            && self.storage.is_ok()
+            && self.has_leader.is_ok()
+            && self.quorum_ack.is_ok()
+            && self.apply.is_ok()
+    }
+}
+
+impl<C> fmt::Display for Health<C>
+where C: RaftTypeConfig
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Health{{")?;
+
+        write!(
+            f,
+            "id:{}, {:?}, live:{}, ready:{}, storage:{}, leader:{}({}), quorum_ack:{}({} ms), apply:{}(lag:{})",
+            self.id,
+            self.state,
+            self.is_live(),
+            self.is_ready(),
+            self.storage,
+            self.current_leader.display(),
+            self.has_leader,
+            self.quorum_ack,
+            self.millis_since_quorum_ack.display(),
+            self.apply,
+            self.apply_lag,
+        )?;
+
+        write!(f, ", followers:{{")?;
+        for (i, (target, h)) in self.followers.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(
+                f,
+                "{}:{{reachable:{}(failures:{}), caught_up:{}(lag:{})}}",
+                target, h.reachable, h.consecutive_failures, h.caught_up, h.lag
+            )?;
+        }
+        write!(f, "}}")?;
+
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
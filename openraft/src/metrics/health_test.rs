@@ -0,0 +1,190 @@
+use maplit::btreemap;
+
+use crate::core::ServerState;
+use crate::engine::testing::UTConfig;
+use crate::error::Fatal;
+use crate::metrics::FollowerHealth;
+use crate::metrics::Health;
+use crate::metrics::HealthCheck;
+use crate::metrics::HealthThresholds;
+use crate::metrics::ReplicationTargetMetrics;
+use crate::testing::log_id;
+use crate::RaftMetrics;
+use crate::StorageError;
+use crate::StorageIOError;
+
+fn leader_metrics() -> RaftMetrics<UTConfig> {
+    let mut m = RaftMetrics::new_initial(1);
+    m.current_term = 3;
+    m.last_log_index = Some(10);
+    m.committed = Some(log_id(3, 1, 10));
+    m.last_applied = Some(log_id(3, 1, 9));
+    m.state = ServerState::Leader;
+    m.current_leader = Some(1);
+    m.millis_since_quorum_ack = Some(7);
+    m.replication = Some(btreemap! {
+        1 => Some(log_id(3, 1, 10)),
+        2 => Some(log_id(3, 1, 8)),
+        3 => None,
+    });
+    m.replication_targets = Some(btreemap! {
+        1 => ReplicationTargetMetrics {
+            matching: Some(log_id(3, 1, 10)),
+            ..Default::default()
+        },
+        2 => ReplicationTargetMetrics {
+            matching: Some(log_id(3, 1, 8)),
+            millis_since_last_success: Some(20),
+            ..Default::default()
+        },
+        3 => ReplicationTargetMetrics {
+            matching: None,
+            millis_since_last_success: Some(30),
+            ..Default::default()
+        },
+    });
+    m
+}
+
+fn thresholds() -> HealthThresholds {
+    HealthThresholds {
+        max_millis_since_quorum_ack: 100,
+        max_apply_lag: 5,
+        max_replication_lag: 5,
+        max_millis_since_last_success: 100,
+    }
+}
+
+#[test]
+fn test_health_initial() {
+    let h = Health::new(&RaftMetrics::<UTConfig>::new_initial(1), &thresholds());
+
+    assert!(h.is_live());
+    assert!(!h.is_ready(), "no leader");
+    assert_eq!(HealthCheck::Pass, h.storage);
+    assert_eq!(HealthCheck::Fail, h.has_leader);
+    assert_eq!(HealthCheck::Skip, h.quorum_ack);
+    assert_eq!(0, h.apply_lag);
+    assert_eq!(HealthCheck::Pass, h.apply);
+    assert!(h.followers.is_empty());
+}
+
+#[test]
+fn test_health_leader() {
+    let h = Health::new(&leader_metrics(), &thresholds());
+
+    assert!(h.is_ready());
+    assert_eq!(HealthCheck::Pass, h.quorum_ack);
+    assert_eq!(1, h.apply_lag);
+    assert_eq!(
+        btreemap! {
+            2 => FollowerHealth {
+                matching: Some(log_id(3, 1, 8)),
+                lag: 2,
+                caught_up: HealthCheck::Pass,
+                millis_since_last_success: Some(20),
+                consecutive_failures: 0,
+                reachable: HealthCheck::Pass,
+            },
+            3 => FollowerHealth {
+                matching: None,
+                lag: 11,
+                caught_up: HealthCheck::Fail,
+                millis_since_last_success: Some(30),
+                consecutive_failures: 0,
+                reachable: HealthCheck::Pass,
+            },
+        },
+        h.followers
+    );
+    assert_eq!(
+        "Health{id:1, Leader, live:true, ready:true, storage:pass, leader:1(pass), quorum_ack:pass(7 ms), apply:pass(lag:1), followers:{2:{reachable:pass(failures:0), caught_up:pass(lag:2)},3:{reachable:pass(failures:0), caught_up:fail(lag:11)}}}",
+        h.to_string()
+    );
+}
+
+#[test]
+fn test_health_leader_stale_quorum_ack() {
+    let mut m = leader_metrics();
+
+    m.millis_since_quorum_ack = Some(101);
+    let h = Health::new(&m, &thresholds());
+    assert_eq!(HealthCheck::Fail, h.quorum_ack);
+    assert!(!h.is_ready());
+
+    m.millis_since_quorum_ack = None;
+    let h = Health::new(&m, &thresholds());
+    assert_eq!(HealthCheck::Fail, h.quorum_ack, "not yet acknowledged");
+}
+
+#[test]
+fn test_health_leader_unreachable_follower() {
+    let mut m = leader_metrics();
+
+    // Follower 2 is up to date but has not responded for a while.
+    {
+        let t = m.replication_targets.as_mut().unwrap().get_mut(&2).unwrap();
+        t.millis_since_last_success = Some(101);
+        t.consecutive_failures = 3;
+    }
+
+    let h = Health::new(&m, &thresholds());
+    let f2 = &h.followers[&2];
+    assert_eq!(HealthCheck::Fail, f2.reachable);
+    assert_eq!(HealthCheck::Pass, f2.caught_up);
+    assert_eq!(3, f2.consecutive_failures);
+    assert!(
+        h.is_ready(),
+        "an unreachable follower does not stop the leader from serving"
+    );
+
+    // Follower 3 has never responded.
+    {
+        let t = m.replication_targets.as_mut().unwrap().get_mut(&3).unwrap();
+        t.millis_since_last_success = None;
+        t.consecutive_failures = 0;
+    }
+    let h = Health::new(&m, &thresholds());
+    assert_eq!(HealthCheck::Pass, h.followers[&3].reachable, "not yet contacted");
+
+    m.replication_targets.as_mut().unwrap().get_mut(&3).unwrap().consecutive_failures = 1;
+    let h = Health::new(&m, &thresholds());
+    assert_eq!(HealthCheck::Fail, h.followers[&3].reachable);
+}
+
+#[test]
+fn test_health_follower_apply_lag() {
+    let mut m = RaftMetrics::<UTConfig>::new_initial(2);
+    m.current_leader = Some(1);
+    m.committed = Some(log_id(3, 1, 10));
+    m.last_applied = Some(log_id(3, 1, 4));
+
+    let h = Health::new(&m, &thresholds());
+    assert_eq!(HealthCheck::Skip, h.quorum_ack);
+    assert_eq!(6, h.apply_lag);
+    assert_eq!(HealthCheck::Fail, h.apply);
+    assert!(!h.is_ready());
+
+    m.last_applied = Some(log_id(3, 1, 5));
+    let h = Health::new(&m, &thresholds());
+    assert_eq!(HealthCheck::Pass, h.apply);
+    assert!(h.is_ready());
+}
+
+#[test]
+fn test_health_fatal() {
+    let mut m = leader_metrics();
+
+    m.running_state = Err(Fatal::Stopped);
+    let h = Health::new(&m, &thresholds());
+    assert!(!h.is_live());
+    assert!(!h.is_ready());
+    assert_eq!(HealthCheck::Pass, h.storage);
+    assert_eq!(HealthCheck::Fail, h.has_leader);
+
+    let err = StorageError::from(StorageIOError::write_logs(anyerror::AnyError::error("disk")));
+    m.running_state = Err(Fatal::StorageError(err));
+    let h = Health::new(&m, &thresholds());
+    assert!(!h.is_live());
+    assert_eq!(HealthCheck::Fail, h.storage);
+}
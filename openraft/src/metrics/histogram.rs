@@ -0,0 +1,114 @@
+use std::fmt;
+use std::time::Duration;
+
+/// The upper bound of the first bucket, in microseconds.
+const FIRST_BOUND_MICROS: u64 = 16;
+
+/// The number of buckets, including the last one without an upper bound.
+const N_BUCKETS: usize = 24;
+
+/// A cumulative histogram of durations.
+///
+/// The buckets are exponential: the upper bound of the `i`-th bucket is `16µs * 2^i`, from 16µs
+/// up to about 67s. The last bucket has no upper bound.
+/// Thus a percentile computed from it is accurate to within a factor of 2.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Histogram {
+    count: u64,
+    sum: Duration,
+    max: Duration,
+
+    /// The number of samples in each bucket, not cumulative.
+    buckets: [u64; N_BUCKETS],
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.mean(), self.percentile(0.99)) {
+            (Some(mean), Some(p99)) => write!(
+                f,
+                "{{n:{}, mean:{:?}, p99:{:?}, max:{:?}}}",
+                self.count, mean, p99, self.max
+            ),
+            _ => write!(f, "{{n:0}}"),
+        }
+    }
+}
+
+impl Histogram {
+    /// Add a sample.
+    pub fn record(&mut self, d: Duration) {
+        self.count += 1;
+        self.sum += d;
+        self.max = self.max.max(d);
+        self.buckets[Self::bucket_index(d)] += 1;
+    }
+
+    /// The number of samples.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The sum of all samples.
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// The largest sample.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// The average of all samples, or `None` if there is no sample.
+    pub fn mean(&self) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(Duration::from_nanos((self.sum.as_nanos() / self.count as u128) as u64))
+    }
+
+    /// The `q`-quantile, e.g., `0.99` for p99, or `None` if there is no sample.
+    ///
+    /// It returns the upper bound of the bucket the quantile falls in, capped by the largest
+    /// sample.
+    pub fn percentile(&self, q: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+
+        let mut seen = 0;
+        for (bound, n) in self.buckets() {
+            seen += n;
+            if seen >= rank {
+                return Some(bound.map_or(self.max, |b| b.min(self.max)));
+            }
+        }
+        unreachable!("rank {} <= count {}", rank, self.count)
+    }
+
+    /// Iterate over the buckets as `(upper_bound, count)`, in ascending order.
+    ///
+    /// The count is the number of samples in the bucket, not cumulative.
+    /// The upper bound of the last bucket is `None`.
+    pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+        self.buckets.iter().enumerate().map(|(i, n)| {
+            let bound = if i < N_BUCKETS - 1 {
+                Some(Duration::from_micros(FIRST_BOUND_MICROS << i))
+            } else {
+                None
+            };
+            (bound, *n)
+        })
+    }
+
+    fn bucket_index(d: Duration) -> usize {
+        let micros = d.as_micros().min(u64::MAX as u128) as u64;
+
+        // The smallest `i` such that `micros <= FIRST_BOUND_MICROS << i`.
+        let i = (u64::BITS - (micros.saturating_sub(1) / FIRST_BOUND_MICROS).leading_zeros()) as usize;
+        i.min(N_BUCKETS - 1)
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use crate::metrics::Histogram;
+
+#[test]
+fn test_histogram_empty() {
+    let h = Histogram::default();
+
+    assert_eq!(0, h.count());
+    assert_eq!(None, h.mean());
+    assert_eq!(None, h.percentile(0.99));
+    assert_eq!("{n:0}", h.to_string());
+}
+
+#[test]
+fn test_histogram_buckets() {
+    let mut h = Histogram::default();
+
+    h.record(Duration::from_micros(0));
+    h.record(Duration::from_micros(16));
+    h.record(Duration::from_micros(17));
+    h.record(Duration::from_micros(32));
+    h.record(Duration::from_micros(33));
+    h.record(Duration::from_secs(1000));
+
+    let buckets = h.buckets().collect::<Vec<_>>();
+    assert_eq!(24, buckets.len());
+
+    assert_eq!((Some(Duration::from_micros(16)), 2), buckets[0]);
+    assert_eq!((Some(Duration::from_micros(32)), 2), buckets[1]);
+    assert_eq!((Some(Duration::from_micros(64)), 1), buckets[2]);
+    assert_eq!((Some(Duration::from_micros(16 << 22)), 0), buckets[22]);
+    assert_eq!((None, 1), buckets[23]);
+
+    assert_eq!(6, h.count());
+    assert_eq!(Duration::from_secs(1000), h.max());
+}
+
+#[test]
+fn test_histogram_percentile() {
+    let mut h = Histogram::default();
+
+    for ms in 1..=100 {
+        h.record(Duration::from_millis(ms));
+    }
+
+    assert_eq!(100, h.count());
+    assert_eq!(Duration::from_millis(5050), h.sum());
+    assert_eq!(Some(Duration::from_micros(50_500)), h.mean());
+
+    // 1ms is in the bucket (512µs, 1024µs].
+    assert_eq!(Some(Duration::from_micros(1024)), h.percentile(0.0));
+    assert_eq!(Some(Duration::from_micros(1024)), h.percentile(0.01));
+
+    // The 50th sample, 50ms, is in the bucket (32768µs, 65536µs].
+    assert_eq!(Some(Duration::from_micros(65_536)), h.percentile(0.5));
+
+    // The upper bound of the bucket is capped by the max sample.
+    assert_eq!(Some(Duration::from_millis(100)), h.percentile(0.99));
+    assert_eq!(Some(Duration::from_millis(100)), h.percentile(1.0));
+}
@@ -27,20 +27,39 @@
 //! not every change of the state.
 //! Because internally, `watch::channel()` only stores one last state.
 
+mod health;
+mod histogram;
 mod metric;
+mod perf_metrics;
+#[cfg(feature = "prometheus")] mod prometheus;
 mod raft_metrics;
+mod replication_target_metrics;
+mod snapshot_stream_metrics;
 mod wait;
 
+#[cfg(test)] mod health_test;
+#[cfg(test)] mod histogram_test;
 mod metric_display;
+#[cfg(all(test, feature = "prometheus"))] mod prometheus_test;
 mod wait_condition;
 #[cfg(test)] mod wait_test;
 
 use std::collections::BTreeMap;
 
+pub use health::FollowerHealth;
+pub use health::Health;
+pub use health::HealthCheck;
+pub use health::HealthThresholds;
+pub use histogram::Histogram;
 pub use metric::Metric;
+pub use perf_metrics::PerfMetrics;
+#[cfg(feature = "prometheus")] pub use prometheus::PrometheusExporter;
 pub use raft_metrics::RaftDataMetrics;
 pub use raft_metrics::RaftMetrics;
 pub use raft_metrics::RaftServerMetrics;
+pub use replication_target_metrics::InflightMetrics;
+pub use replication_target_metrics::ReplicationTargetMetrics;
+pub use snapshot_stream_metrics::SnapshotStreamMetrics;
 pub use wait::Wait;
 pub use wait::WaitError;
 pub(crate) use wait_condition::Condition;
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::metrics::Histogram;
+use crate::RaftTypeConfig;
+
+/// Cumulative latency histograms and throughput counters of a Raft node, since it is started.
+///
+/// Unlike [`RaftMetrics`](crate::RaftMetrics), which describes the current state, every field
+/// here only grows. A monitoring system samples it periodically and computes the rates from the
+/// differences, to find out whether the storage, the network or the state machine is slowing down
+/// writes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct PerfMetrics<C: RaftTypeConfig> {
+    // ---
+    // --- client write ---
+    // ---
+    /// The time from when a leader receives a write request, e.g. a
+    /// [`Raft::client_write()`](crate::Raft::client_write), to when its log entry is committed.
+    pub client_write_commit: Histogram,
+
+    /// The time from when a leader receives a write request, to when its log entry is applied and
+    /// the response is sent.
+    pub client_write_apply: Histogram,
+
+    // ---
+    // --- log ---
+    // ---
+    /// The time from calling [`RaftLogStorage::append()`] to the [`LogFlushed`] callback being
+    /// called, for every batch of entries.
+    ///
+    /// [`RaftLogStorage::append()`]: crate::storage::RaftLogStorage::append
+    /// [`LogFlushed`]: crate::storage::LogFlushed
+    pub log_flush: Histogram,
+
+    /// The number of log entries appended to the local log store.
+    pub appended_entries: u64,
+
+    // ---
+    // --- state machine ---
+    // ---
+    /// The time of [`RaftStateMachine::apply()`](crate::storage::RaftStateMachine::apply) for
+    /// every batch of entries.
+    pub apply_batch: Histogram,
+
+    /// The number of log entries applied to the state machine.
+    pub applied_entries: u64,
+
+    /// The time to build a snapshot.
+    pub snapshot_build: Histogram,
+
+    /// The time to install a snapshot received from the leader.
+    pub snapshot_install: Histogram,
+
+    // ---
+    // --- replication ---
+    // ---
+    /// For a leader, the round trip time of `AppendEntries` requests, including heartbeats, that
+    /// are accepted by each target.
+    ///
+    /// A target is kept after it is removed or this node is no longer a leader.
+    pub append_entries_rtt: BTreeMap<C::NodeId, Histogram>,
+
+    // ---
+    // --- election ---
+    // ---
+    /// The number of PreVote rounds started by this node.
+    pub pre_votes: u64,
+
+    /// The number of elections started by this node.
+    pub elections: u64,
+
+    /// The number of elections won by this node, i.e., how many times it becomes a leader.
+    pub elections_won: u64,
+}
+
+impl<C> fmt::Display for PerfMetrics<C>
+where C: RaftTypeConfig
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PerfMetrics{{")?;
+
+        write!(
+            f,
+            "client_write_commit:{}, client_write_apply:{}, log_flush:{}, appended:{}, apply_batch:{}, applied:{}, snapshot_build:{}, snapshot_install:{}",
+            self.client_write_commit,
+            self.client_write_apply,
+            self.log_flush,
+            self.appended_entries,
+            self.apply_batch,
+            self.applied_entries,
+            self.snapshot_build,
+            self.snapshot_install,
+        )?;
+
+        write!(f, ", append_entries_rtt:{{")?;
+        for (i, (target, h)) in self.append_entries_rtt.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}:{}", target, h)?;
+        }
+        write!(f, "}}")?;
+
+        write!(
+            f,
+            ", pre_votes:{}, elections:{}, elections_won:{}",
+            self.pre_votes, self.elections, self.elections_won
+        )?;
+
+        write!(f, "}}")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+//! Export Raft metrics in the Prometheus text exposition format.
+
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use tokio::sync::watch;
+
+use crate::core::ServerState;
+use crate::metrics::RaftDataMetrics;
+use crate::metrics::RaftMetrics;
+use crate::metrics::RaftServerMetrics;
+use crate::metrics::ReplicationMetrics;
+use crate::RaftTypeConfig;
+
+const SERVER_STATES: [(ServerState, &str); 6] = [
+    (ServerState::Learner, "learner"),
+    (ServerState::Follower, "follower"),
+    (ServerState::Witness, "witness"),
+    (ServerState::Candidate, "candidate"),
+    (ServerState::Leader, "leader"),
+    (ServerState::Shutdown, "shutdown"),
+];
+
+/// The last observed metrics of a Raft node.
+struct State<C>
+where C: RaftTypeConfig
+{
+    running: Option<bool>,
+    term: Option<u64>,
+    last_log_index: Option<u64>,
+    last_applied_index: Option<u64>,
+    snapshot_index: Option<u64>,
+    purged_index: Option<u64>,
+    server_state: Option<ServerState>,
+    current_leader: Option<C::NodeId>,
+    millis_since_quorum_ack: Option<u64>,
+    replication: Option<ReplicationMetrics<C::NodeId>>,
+
+    /// The last leader counted by `leader_changes`, identified by term and node id.
+    last_leader: Option<(u64, C::NodeId)>,
+    leader_changes: u64,
+}
+
+impl<C> State<C>
+where C: RaftTypeConfig
+{
+    /// Count a leader change if the leader of the current term is a new one.
+    fn update_leader(&mut self, current_leader: Option<C::NodeId>) {
+        self.current_leader = current_leader;
+
+        let (Some(term), Some(leader)) = (self.term, current_leader) else {
+            return;
+        };
+        if self.last_leader != Some((term, leader)) {
+            self.last_leader = Some((term, leader));
+            self.leader_changes += 1;
+        }
+    }
+}
+
+/// Maps the metrics of a Raft node to Prometheus gauges and counters, and encodes them in the
+/// [text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+///
+/// It only keeps the last observed values: feed it with [`observe()`](Self::observe) or
+/// [`watch()`](Self::watch) and call [`encode()`](Self::encode) when the metrics are scraped.
+/// Serving the encoded text, e.g., over HTTP, is left to the application.
+///
+/// All samples are labeled with `node_id`. Samples of a value that is unknown, such as
+/// `last_applied_index` before anything is applied, are omitted.
+///
+/// The exported metrics are, with the default namespace `openraft`:
+///
+/// - `openraft_running`: 1 if the node is running, 0 if it stopped on a fatal error.
+/// - `openraft_term`: the current term.
+/// - `openraft_last_log_index`: the index of the last log entry.
+/// - `openraft_last_applied_index`: the index of the last applied log entry.
+/// - `openraft_snapshot_index`: the index of the last log entry included in the snapshot.
+/// - `openraft_purged_index`: the index of the last purged log entry.
+/// - `openraft_server_state{state}`: 1 for the current server state and 0 for the others.
+/// - `openraft_has_leader`: 1 if the node knows a leader.
+/// - `openraft_leader_changes_total`: the number of leaders observed, a leader is identified by
+///   term and node id.
+/// - `openraft_millis_since_quorum_ack`: on a leader, the milliseconds since a quorum acknowledged
+///   it.
+/// - `openraft_replication_matched_index{target}`: on a leader, the last log index replicated to
+///   `target`.
+/// - `openraft_replication_lag{target}`: on a leader, the number of log entries not yet replicated
+///   to `target`.
+///
+/// It is cheap to clone and all clones share the same values.
+pub struct PrometheusExporter<C>
+where C: RaftTypeConfig
+{
+    node_id: C::NodeId,
+    namespace: String,
+    labels: Vec<(String, String)>,
+    state: Arc<Mutex<State<C>>>,
+}
+
+impl<C> Clone for PrometheusExporter<C>
+where C: RaftTypeConfig
+{
+    fn clone(&self) -> Self {
+        Self {
+            node_id: self.node_id,
+            namespace: self.namespace.clone(),
+            labels: self.labels.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<C> PrometheusExporter<C>
+where C: RaftTypeConfig
+{
+    /// Create an exporter for the metrics of node `node_id`.
+    pub fn new(node_id: C::NodeId) -> Self {
+        Self {
+            node_id,
+            namespace: "openraft".to_string(),
+            labels: vec![],
+            state: Arc::new(Mutex::new(State {
+                running: None,
+                term: None,
+                last_log_index: None,
+                last_applied_index: None,
+                snapshot_index: None,
+                purged_index: None,
+                server_state: None,
+                current_leader: None,
+                millis_since_quorum_ack: None,
+                replication: None,
+                last_leader: None,
+                leader_changes: 0,
+            })),
+        }
+    }
+
+    /// Set the prefix of metric names, `openraft` by default.
+    pub fn with_namespace(mut self, namespace: impl ToString) -> Self {
+        self.namespace = namespace.to_string();
+        self
+    }
+
+    /// Add a constant label to every sample, e.g., the name of the cluster.
+    pub fn with_label(mut self, name: impl ToString, value: impl ToString) -> Self {
+        self.labels.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Update the exported values with a [`RaftMetrics`].
+    pub fn observe(&self, m: &RaftMetrics<C>) {
+        let mut st = self.state.lock().unwrap();
+
+        st.running = Some(m.running_state.is_ok());
+        st.term = Some(m.current_term);
+        st.last_log_index = m.last_log_index;
+        st.last_applied_index = m.last_applied.map(|x| x.index);
+        st.snapshot_index = m.snapshot.map(|x| x.index);
+        st.purged_index = m.purged.map(|x| x.index);
+        st.server_state = Some(m.state);
+        st.millis_since_quorum_ack = m.millis_since_quorum_ack;
+        st.replication.clone_from(&m.replication);
+        st.update_leader(m.current_leader);
+    }
+
+    /// Update the exported values with a [`RaftDataMetrics`].
+    pub fn observe_data(&self, m: &RaftDataMetrics<C>) {
+        let mut st = self.state.lock().unwrap();
+
+        st.last_log_index = m.last_log.map(|x| x.index);
+        st.last_applied_index = m.last_applied.map(|x| x.index);
+        st.snapshot_index = m.snapshot.map(|x| x.index);
+        st.purged_index = m.purged.map(|x| x.index);
+        st.millis_since_quorum_ack = m.millis_since_quorum_ack;
+        st.replication.clone_from(&m.replication);
+    }
+
+    /// Update the exported values with a [`RaftServerMetrics`].
+    pub fn observe_server(&self, m: &RaftServerMetrics<C>) {
+        let mut st = self.state.lock().unwrap();
+
+        st.term = Some(m.vote.leader_id().get_term());
+        st.server_state = Some(m.state);
+        st.update_leader(m.current_leader);
+    }
+
+    /// Observe every [`RaftMetrics`] sent by [`Raft::metrics()`](crate::Raft::metrics), until the
+    /// Raft node is dropped.
+    pub async fn watch(&self, mut rx: watch::Receiver<RaftMetrics<C>>) {
+        loop {
+            self.observe(&rx.borrow_and_update());
+
+            if rx.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Encode the last observed values in the Prometheus text exposition format.
+    pub fn encode(&self) -> String {
+        let st = self.state.lock().unwrap();
+        let mut enc = Encoder {
+            namespace: &self.namespace,
+            labels: self.labels(),
+            buf: String::new(),
+        };
+
+        enc.family(
+            "running",
+            "gauge",
+            "Whether the node is running; 0 if it stopped on a fatal error.",
+            st.running.map(|x| ("", x as u64)),
+        );
+        enc.family("term", "gauge", "The current term.", st.term.map(|x| ("", x)));
+        enc.family(
+            "last_log_index",
+            "gauge",
+            "The index of the last log entry.",
+            st.last_log_index.map(|x| ("", x)),
+        );
+        enc.family(
+            "last_applied_index",
+            "gauge",
+            "The index of the last applied log entry.",
+            st.last_applied_index.map(|x| ("", x)),
+        );
+        enc.family(
+            "snapshot_index",
+            "gauge",
+            "The index of the last log entry included in the snapshot.",
+            st.snapshot_index.map(|x| ("", x)),
+        );
+        enc.family(
+            "purged_index",
+            "gauge",
+            "The index of the last purged log entry.",
+            st.purged_index.map(|x| ("", x)),
+        );
+        enc.family(
+            "server_state",
+            "gauge",
+            "The server state of the node, 1 for the current state.",
+            st.server_state.iter().flat_map(|cur| {
+                SERVER_STATES.iter().map(move |(s, name)| (format!("state=\"{}\"", name), (s == cur) as u64))
+            }),
+        );
+        enc.family(
+            "has_leader",
+            "gauge",
+            "Whether the node knows a leader.",
+            st.server_state.map(|_| ("", st.current_leader.is_some() as u64)),
+        );
+        enc.family(
+            "leader_changes_total",
+            "counter",
+            "The number of leaders observed.",
+            Some(("", st.leader_changes)),
+        );
+        enc.family(
+            "millis_since_quorum_ack",
+            "gauge",
+            "On a leader, the milliseconds since a quorum acknowledged it.",
+            st.millis_since_quorum_ack.map(|x| ("", x)),
+        );
+
+        let replication = st.replication.iter().flat_map(|x| x.iter());
+        enc.family(
+            "replication_matched_index",
+            "gauge",
+            "On a leader, the last log index replicated to a target.",
+            replication.clone().filter_map(|(target, matched)| matched.map(|m| (target_label(target), m.index))),
+        );
+
+        let last_log_next = st.last_log_index.map_or(0, |x| x + 1);
+        enc.family(
+            "replication_lag",
+            "gauge",
+            "On a leader, the number of log entries not yet replicated to a target.",
+            replication.map(|(target, matched)| {
+                let matched_next = matched.map_or(0, |m| m.index + 1);
+                (target_label(target), last_log_next.saturating_sub(matched_next))
+            }),
+        );
+
+        enc.buf
+    }
+
+    /// Build the labels shared by every sample.
+    fn labels(&self) -> String {
+        let mut labels = format!("node_id=\"{}\"", escape(&self.node_id.to_string()));
+        for (k, v) in self.labels.iter() {
+            write!(labels, ",{}=\"{}\"", k, escape(v)).unwrap();
+        }
+        labels
+    }
+}
+
+fn target_label(target: &impl ToString) -> String {
+    format!("target=\"{}\"", escape(&target.to_string()))
+}
+
+/// Escape a label value: backslash, double-quote and line feed.
+fn escape(v: &str) -> String {
+    v.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n")
+}
+
+struct Encoder<'a> {
+    namespace: &'a str,
+    labels: String,
+    buf: String,
+}
+
+impl<'a> Encoder<'a> {
+    /// Write a metric family with its samples, each of which is a pair of extra labels and value.
+    ///
+    /// Nothing is written if there is no sample.
+    fn family<L: AsRef<str>>(
+        &mut self,
+        name: &str,
+        typ: &str,
+        help: &str,
+        samples: impl IntoIterator<Item = (L, u64)>,
+    ) {
+        let mut samples = samples.into_iter().peekable();
+        if samples.peek().is_none() {
+            return;
+        }
+
+        let name = if self.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}_{}", self.namespace, name)
+        };
+
+        writeln!(self.buf, "# HELP {} {}", name, help).unwrap();
+        writeln!(self.buf, "# TYPE {} {}", name, typ).unwrap();
+
+        for (labels, value) in samples {
+            let labels = labels.as_ref();
+            if labels.is_empty() {
+                writeln!(self.buf, "{}{{{}}} {}", name, self.labels, value).unwrap();
+            } else {
+                writeln!(self.buf, "{}{{{},{}}} {}", name, self.labels, labels, value).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use maplit::btreemap;
+use tokio::sync::watch;
+
+use crate::core::ServerState;
+use crate::engine::testing::UTConfig;
+use crate::error::Fatal;
+use crate::metrics::PrometheusExporter;
+use crate::metrics::RaftDataMetrics;
+use crate::metrics::RaftServerMetrics;
+use crate::testing::log_id;
+use crate::RaftMetrics;
+use crate::Vote;
+
+fn leader_metrics() -> RaftMetrics<UTConfig> {
+    let mut m = RaftMetrics::new_initial(1);
+    m.current_term = 3;
+    m.vote = Vote::new_committed(3, 1);
+    m.last_log_index = Some(10);
+    m.last_applied = Some(log_id(3, 1, 9));
+    m.snapshot = Some(log_id(2, 1, 5));
+    m.purged = Some(log_id(1, 1, 2));
+    m.state = ServerState::Leader;
+    m.current_leader = Some(1);
+    m.millis_since_quorum_ack = Some(7);
+    m.replication = Some(btreemap! {
+        1 => Some(log_id(3, 1, 10)),
+        2 => Some(log_id(3, 1, 8)),
+        3 => None,
+    });
+    m
+}
+
+#[test]
+fn test_encode_initial() {
+    let exp = PrometheusExporter::<UTConfig>::new(1);
+    assert_eq!(
+        "\
+# HELP openraft_leader_changes_total The number of leaders observed.
+# TYPE openraft_leader_changes_total counter
+openraft_leader_changes_total{node_id=\"1\"} 0
+",
+        exp.encode()
+    );
+
+    exp.observe(&RaftMetrics::new_initial(1));
+    let got = exp.encode();
+
+    assert!(got.contains("openraft_running{node_id=\"1\"} 1\n"), "{}", got);
+    assert!(got.contains("openraft_term{node_id=\"1\"} 0\n"), "{}", got);
+    assert!(got.contains("openraft_has_leader{node_id=\"1\"} 0\n"), "{}", got);
+    assert!(
+        !got.contains("openraft_last_log_index"),
+        "unknown values are omitted: {}",
+        got
+    );
+    assert!(!got.contains("openraft_last_applied_index"), "{}", got);
+    assert!(!got.contains("openraft_millis_since_quorum_ack"), "{}", got);
+    assert!(!got.contains("openraft_replication_lag"), "{}", got);
+}
+
+#[test]
+fn test_encode_leader() {
+    let exp = PrometheusExporter::<UTConfig>::new(1);
+    exp.observe(&leader_metrics());
+
+    let want = "\
+# HELP openraft_running Whether the node is running; 0 if it stopped on a fatal error.
+# TYPE openraft_running gauge
+openraft_running{node_id=\"1\"} 1
+# HELP openraft_term The current term.
+# TYPE openraft_term gauge
+openraft_term{node_id=\"1\"} 3
+# HELP openraft_last_log_index The index of the last log entry.
+# TYPE openraft_last_log_index gauge
+openraft_last_log_index{node_id=\"1\"} 10
+# HELP openraft_last_applied_index The index of the last applied log entry.
+# TYPE openraft_last_applied_index gauge
+openraft_last_applied_index{node_id=\"1\"} 9
+# HELP openraft_snapshot_index The index of the last log entry included in the snapshot.
+# TYPE openraft_snapshot_index gauge
+openraft_snapshot_index{node_id=\"1\"} 5
+# HELP openraft_purged_index The index of the last purged log entry.
+# TYPE openraft_purged_index gauge
+openraft_purged_index{node_id=\"1\"} 2
+# HELP openraft_server_state The server state of the node, 1 for the current state.
+# TYPE openraft_server_state gauge
+openraft_server_state{node_id=\"1\",state=\"learner\"} 0
+openraft_server_state{node_id=\"1\",state=\"follower\"} 0
+openraft_server_state{node_id=\"1\",state=\"witness\"} 0
+openraft_server_state{node_id=\"1\",state=\"candidate\"} 0
+openraft_server_state{node_id=\"1\",state=\"leader\"} 1
+openraft_server_state{node_id=\"1\",state=\"shutdown\"} 0
+# HELP openraft_has_leader Whether the node knows a leader.
+# TYPE openraft_has_leader gauge
+openraft_has_leader{node_id=\"1\"} 1
+# HELP openraft_leader_changes_total The number of leaders observed.
+# TYPE openraft_leader_changes_total counter
+openraft_leader_changes_total{node_id=\"1\"} 1
+# HELP openraft_millis_since_quorum_ack On a leader, the milliseconds since a quorum acknowledged it.
+# TYPE openraft_millis_since_quorum_ack gauge
+openraft_millis_since_quorum_ack{node_id=\"1\"} 7
+# HELP openraft_replication_matched_index On a leader, the last log index replicated to a target.
+# TYPE openraft_replication_matched_index gauge
+openraft_replication_matched_index{node_id=\"1\",target=\"1\"} 10
+openraft_replication_matched_index{node_id=\"1\",target=\"2\"} 8
+# HELP openraft_replication_lag On a leader, the number of log entries not yet replicated to a target.
+# TYPE openraft_replication_lag gauge
+openraft_replication_lag{node_id=\"1\",target=\"1\"} 0
+openraft_replication_lag{node_id=\"1\",target=\"2\"} 2
+openraft_replication_lag{node_id=\"1\",target=\"3\"} 11
+";
+    assert_eq!(want, exp.encode());
+}
+
+#[test]
+fn test_namespace_and_labels() {
+    let exp = PrometheusExporter::<UTConfig>::new(1).with_namespace("kv").with_label("cluster", "a\"b\\c\nd");
+    exp.observe(&leader_metrics());
+
+    let got = exp.encode();
+    assert!(
+        got.contains("kv_term{node_id=\"1\",cluster=\"a\\\"b\\\\c\\nd\"} 3\n"),
+        "{}",
+        got
+    );
+    assert!(!got.contains("openraft_"), "{}", got);
+}
+
+#[test]
+fn test_leader_changes() {
+    let exp = PrometheusExporter::<UTConfig>::new(1);
+    let changes = |exp: &PrometheusExporter<UTConfig>| {
+        let got = exp.encode();
+        let line = got.lines().find(|l| l.starts_with("openraft_leader_changes_total")).unwrap().to_string();
+        line.rsplit(' ').next().unwrap().parse::<u64>().unwrap()
+    };
+
+    let mut m = leader_metrics();
+    exp.observe(&m);
+    exp.observe(&m);
+    assert_eq!(1, changes(&exp));
+
+    // Leader is lost during an election.
+    m.current_term = 4;
+    m.current_leader = None;
+    m.state = ServerState::Candidate;
+    exp.observe(&m);
+    assert_eq!(1, changes(&exp));
+
+    // The same node is elected again in a new term.
+    m.current_leader = Some(1);
+    m.state = ServerState::Leader;
+    exp.observe(&m);
+    assert_eq!(2, changes(&exp));
+
+    // Another node becomes leader, observed via server metrics.
+    exp.observe_server(&RaftServerMetrics {
+        id: 1,
+        vote: Vote::new_committed(5, 2),
+        state: ServerState::Follower,
+        current_leader: Some(2),
+        membership_config: Default::default(),
+    });
+    assert_eq!(3, changes(&exp));
+
+    let got = exp.encode();
+    assert!(got.contains("openraft_term{node_id=\"1\"} 5\n"), "{}", got);
+    assert!(
+        got.contains("openraft_server_state{node_id=\"1\",state=\"follower\"} 1\n"),
+        "{}",
+        got
+    );
+}
+
+#[test]
+fn test_observe_data() {
+    let exp = PrometheusExporter::<UTConfig>::new(1);
+    exp.observe(&leader_metrics());
+
+    exp.observe_data(&RaftDataMetrics {
+        last_log: Some(log_id(3, 1, 20)),
+        last_applied: Some(log_id(3, 1, 20)),
+        replication: None,
+        ..Default::default()
+    });
+
+    let got = exp.encode();
+    assert!(got.contains("openraft_last_log_index{node_id=\"1\"} 20\n"), "{}", got);
+    assert!(
+        got.contains("openraft_last_applied_index{node_id=\"1\"} 20\n"),
+        "{}",
+        got
+    );
+    assert!(!got.contains("openraft_snapshot_index"), "{}", got);
+    assert!(!got.contains("openraft_replication_lag"), "{}", got);
+}
+
+#[tokio::test]
+async fn test_watch() {
+    let exp = PrometheusExporter::<UTConfig>::new(1);
+    let (tx, rx) = watch::channel(RaftMetrics::new_initial(1));
+
+    let h = tokio::spawn({
+        let exp = exp.clone();
+        async move { exp.watch(rx).await }
+    });
+
+    let mut m = leader_metrics();
+    m.running_state = Err(Fatal::Stopped);
+    tx.send(m).unwrap();
+    drop(tx);
+
+    h.await.unwrap();
+    let got = exp.encode();
+    assert!(got.contains("openraft_running{node_id=\"1\"} 0\n"), "{}", got);
+    assert!(got.contains("openraft_term{node_id=\"1\"} 3\n"), "{}", got);
+}
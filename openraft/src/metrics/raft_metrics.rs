@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
 
@@ -6,6 +7,8 @@ use crate::display_ext::DisplayOption;
 use crate::display_ext::DisplayOptionExt;
 use crate::error::Fatal;
 use crate::metrics::ReplicationMetrics;
+use crate::metrics::ReplicationTargetMetrics;
+use crate::metrics::SnapshotStreamMetrics;
 use crate::LogId;
 use crate::RaftTypeConfig;
 use crate::StoredMembership;
@@ -32,6 +35,11 @@ pub struct RaftMetrics<C: RaftTypeConfig> {
     /// The last log index has been appended to this Raft node's log.
     pub last_log_index: Option<u64>,
 
+    /// The last log id known to be committed.
+    ///
+    /// On a follower, it is the committed log id the leader has replicated to it.
+    pub committed: Option<LogId<C::NodeId>>,
+
     /// The last log index has been applied to this Raft node's state machine.
     pub last_applied: Option<LogId<C::NodeId>>,
 
@@ -77,6 +85,13 @@ pub struct RaftMetrics<C: RaftTypeConfig> {
     // ---
     /// The replication states. It is Some() only when this node is leader.
     pub replication: Option<ReplicationMetrics<C::NodeId>>,
+
+    /// The replication details of every target, such as the data in flight, the RPC failures and
+    /// the heartbeat round trip time. It is Some() only when this node is leader.
+    pub replication_targets: Option<BTreeMap<C::NodeId, ReplicationTargetMetrics<C::NodeId>>>,
+
+    /// The snapshot streams being sent or waiting to be sent by this node.
+    pub snapshot_streams: SnapshotStreamMetrics,
 }
 
 impl<C> fmt::Display for RaftMetrics<C>
@@ -87,12 +102,13 @@ where C: RaftTypeConfig
 
         write!(
             f,
-            "id:{}, {:?}, term:{}, vote:{}, last_log:{}, last_applied:{}, leader:{}(since_last_ack:{} ms)",
+            "id:{}, {:?}, term:{}, vote:{}, last_log:{}, committed:{}, last_applied:{}, leader:{}(since_last_ack:{} ms)",
             self.id,
             self.state,
             self.current_term,
             self.vote,
             DisplayOption(&self.last_log_index),
+            DisplayOption(&self.committed),
             DisplayOption(&self.last_applied),
             DisplayOption(&self.current_leader),
             DisplayOption(&self.millis_since_quorum_ack),
@@ -101,7 +117,7 @@ where C: RaftTypeConfig
         write!(f, ", ")?;
         write!(
             f,
-            "membership:{}, snapshot:{}, purged:{}, replication:{{{}}}",
+            "membership:{}, snapshot:{}, purged:{}, replication:{{{}}}, snapshot_streams:{}",
             self.membership_config,
             DisplayOption(&self.snapshot),
             DisplayOption(&self.purged),
@@ -109,6 +125,7 @@ where C: RaftTypeConfig
                 .as_ref()
                 .map(|x| { x.iter().map(|(k, v)| format!("{}:{}", k, DisplayOption(v))).collect::<Vec<_>>().join(",") })
                 .unwrap_or_default(),
+            self.snapshot_streams,
         )?;
 
         write!(f, "}}")?;
@@ -127,6 +144,7 @@ where C: RaftTypeConfig
             current_term: 0,
             vote: Vote::default(),
             last_log_index: None,
+            committed: None,
             last_applied: None,
             snapshot: None,
             purged: None,
@@ -136,6 +154,8 @@ where C: RaftTypeConfig
             millis_since_quorum_ack: None,
             membership_config: Arc::new(StoredMembership::default()),
             replication: None,
+            replication_targets: None,
+            snapshot_streams: SnapshotStreamMetrics::default(),
         }
     }
 }
@@ -165,6 +185,9 @@ pub struct RaftDataMetrics<C: RaftTypeConfig> {
     pub millis_since_quorum_ack: Option<u64>,
 
     pub replication: Option<ReplicationMetrics<C::NodeId>>,
+
+    /// The snapshot streams being sent or waiting to be sent by this node.
+    pub snapshot_streams: SnapshotStreamMetrics,
 }
 
 impl<C> fmt::Display for RaftDataMetrics<C>
@@ -175,7 +198,7 @@ where C: RaftTypeConfig
 
         write!(
             f,
-            "last_log:{}, last_applied:{}, snapshot:{}, purged:{}, quorum_acked(leader):{} ms before, replication:{{{}}}",
+            "last_log:{}, last_applied:{}, snapshot:{}, purged:{}, quorum_acked(leader):{} ms before, replication:{{{}}}, snapshot_streams:{}",
             DisplayOption(&self.last_log),
             DisplayOption(&self.last_applied),
             DisplayOption(&self.snapshot),
@@ -185,6 +208,7 @@ where C: RaftTypeConfig
                 .as_ref()
                 .map(|x| { x.iter().map(|(k, v)| format!("{}:{}", k, DisplayOption(v))).collect::<Vec<_>>().join(",") })
                 .unwrap_or_default(),
+            self.snapshot_streams,
         )?;
 
         write!(f, "}}")?;
@@ -0,0 +1,91 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::display_ext::DisplayOptionExt;
+use crate::LogId;
+use crate::NodeId;
+
+/// The data a leader is sending to a replication target and waiting for the response.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub enum InflightMetrics<NID: NodeId> {
+    /// Nothing is being sent.
+    #[default]
+    None,
+
+    /// Logs in the range `(prev, last]` are being sent.
+    Logs {
+        prev: Option<LogId<NID>>,
+        last: Option<LogId<NID>>,
+    },
+
+    /// A snapshot including logs up to `last_log_id` is being sent.
+    Snapshot { last_log_id: Option<LogId<NID>> },
+}
+
+impl<NID: NodeId> fmt::Display for InflightMetrics<NID> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InflightMetrics::None => write!(f, "None"),
+            InflightMetrics::Logs { prev, last } => write!(f, "Logs({}, {}]", prev.display(), last.display()),
+            InflightMetrics::Snapshot { last_log_id } => write!(f, "Snapshot({})", last_log_id.display()),
+        }
+    }
+}
+
+/// Replication details of a single target, reported by a leader.
+///
+/// It tells whether a lagging follower is slowed down by the network, its disk, or a snapshot
+/// being sent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct ReplicationTargetMetrics<NID: NodeId> {
+    /// The last log id replicated to the target.
+    pub matching: Option<LogId<NID>>,
+
+    /// The data being sent to the target.
+    pub inflight: InflightMetrics<NID>,
+
+    /// The elapsed time in milliseconds since the last replication RPC that is accepted by the
+    /// target, including heartbeats. `None` if there is no such RPC yet.
+    pub millis_since_last_success: Option<u64>,
+
+    /// The elapsed time in milliseconds since the last replication RPC that failed, e.g., because
+    /// of a network error or a timeout. `None` if there is no failure yet.
+    pub millis_since_last_failure: Option<u64>,
+
+    /// The number of replication RPCs that failed since the last successful one.
+    pub consecutive_failures: u64,
+
+    /// The current interval to wait before retrying, if the target is
+    /// [`Unreachable`](crate::error::Unreachable) and the replication is backing off.
+    pub backoff: Option<Duration>,
+
+    /// The bytes of the snapshot being sent that the target has received, if [`Self::inflight`]
+    /// is a snapshot.
+    ///
+    /// It is `None` until the snapshot transport reports the progress with
+    /// [`RPCOption::snapshot_received()`](crate::network::RPCOption::snapshot_received), and
+    /// always `None` with a transport that does not.
+    pub snapshot_sent_bytes: Option<u64>,
+
+    /// The round trip time of the last heartbeat accepted by the target.
+    pub heartbeat_rtt: Option<Duration>,
+}
+
+impl<NID: NodeId> fmt::Display for ReplicationTargetMetrics<NID> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{matching:{}, inflight:{}, since_last_success:{} ms, since_last_failure:{} ms, failures:{}, backoff:{:?}, snapshot_sent:{}, heartbeat_rtt:{:?}}}",
+            self.matching.display(),
+            self.inflight,
+            self.millis_since_last_success.display(),
+            self.millis_since_last_failure.display(),
+            self.consecutive_failures,
+            self.backoff,
+            self.snapshot_sent_bytes.display(),
+            self.heartbeat_rtt,
+        )
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+
+/// Metrics of the snapshot streams sent by a leader.
+///
+/// A stream waits in the queue if there are already
+/// [`Config::max_concurrent_snapshot_streams`] streams being sent.
+///
+/// [`Config::max_concurrent_snapshot_streams`]: `crate::Config::max_concurrent_snapshot_streams`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SnapshotStreamMetrics {
+    /// Number of snapshot streams being sent.
+    pub sending: u64,
+
+    /// Number of snapshot streams waiting to be sent.
+    pub queued: u64,
+}
+
+impl fmt::Display for SnapshotStreamMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{sending:{}, queued:{}}}", self.sending, self.queued)
+    }
+}
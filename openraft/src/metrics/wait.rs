@@ -66,6 +66,9 @@ where C: RaftTypeConfig
             let delay = AsyncRuntimeOf::<C>::sleep(sleep_time);
 
             tokio::select! {
+                // Poll in a fixed order to keep a simulation reproducible.
+                biased;
+
                 _ = delay => {
                 tracing::debug!( "id={} timeout wait {:} latest: {}", latest.id, msg.to_string(), latest );
                     return Err(WaitError::Timeout(self.timeout, format!("{} latest: {}", msg.to_string(), latest)));
@@ -255,6 +255,7 @@ where C: RaftTypeConfig {
         current_term: 0,
         vote: Vote::default(),
         last_log_index: None,
+        committed: None,
         last_applied: None,
         purged: None,
 
@@ -264,6 +265,8 @@ where C: RaftTypeConfig {
 
         snapshot: None,
         replication: None,
+        replication_targets: None,
+        snapshot_streams: Default::default(),
     };
     let (tx, rx) = watch::channel(init.clone());
     let w = Wait {
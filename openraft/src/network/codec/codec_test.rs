@@ -0,0 +1,452 @@
+use std::fmt::Debug;
+use std::io;
+use std::io::Cursor;
+
+use maplit::btreemap;
+use maplit::btreeset;
+
+use crate::declare_raft_types;
+use crate::network::codec::decode_from_slice;
+use crate::network::codec::encode_struct;
+use crate::network::codec::encode_to_vec;
+use crate::network::codec::Decode;
+use crate::network::codec::Encode;
+use crate::raft::AppendEntriesResponse;
+use crate::raft::ClientWriteResponse;
+use crate::raft::HandoffLeaderResponse;
+use crate::raft::InstallSnapshotResponse;
+use crate::raft::ReadIndexRequest;
+use crate::raft::SnapshotResponse;
+use crate::raft::VoteRequest;
+use crate::BasicNode;
+use crate::CommittedLeaderId;
+use crate::Entry;
+use crate::EntryPayload;
+use crate::LogId;
+use crate::Membership;
+use crate::SnapshotMeta;
+use crate::StoredMembership;
+use crate::TokioRuntime;
+use crate::Vote;
+
+declare_raft_types!(
+    TestConfig:
+        D = String,
+        R = String,
+        NodeId = u64,
+        Node = BasicNode,
+        Entry = Entry<TestConfig>,
+        SnapshotData = Cursor<Vec<u8>>,
+        AsyncRuntime = TokioRuntime,
+);
+
+fn log_id(term: u64, node_id: u64, index: u64) -> LogId<u64> {
+    LogId {
+        leader_id: CommittedLeaderId::new(term, node_id),
+        index,
+    }
+}
+
+fn membership() -> Membership<TestConfig> {
+    Membership::new(vec![btreeset! {1,2}], btreemap! {
+        1 => BasicNode::new("a"),
+        2 => BasicNode::new("b"),
+        3 => BasicNode::new("c"),
+    })
+    .with_witnesses([2])
+}
+
+fn snapshot_meta() -> SnapshotMeta<TestConfig> {
+    SnapshotMeta {
+        last_log_id: Some(log_id(2, 1, 5)),
+        last_membership: StoredMembership::new(Some(log_id(1, 1, 3)), membership()),
+        snapshot_id: "s1".to_string(),
+    }
+}
+
+fn from_hex(s: &str) -> Vec<u8> {
+    let s = s.split_whitespace().collect::<String>();
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+}
+
+fn assert_round_trip<T>(v: &T)
+where T: Encode + Decode + PartialEq + Debug {
+    let b = encode_to_vec(v).unwrap();
+    assert_eq!(v, &decode_from_slice::<T>(&b).unwrap());
+}
+
+#[test]
+fn test_primitive() -> anyhow::Result<()> {
+    assert_eq!(from_hex("0000000000000102"), encode_to_vec(&258u64)?);
+    assert_eq!(from_hex("01 00000003 616263"), encode_to_vec(&Some("abc".to_string()))?);
+    assert_eq!(from_hex("00000002 0001 0002"), encode_to_vec(&vec![1u16, 2])?);
+
+    assert_round_trip(&true);
+    assert_round_trip(&Option::<u64>::None);
+    assert_round_trip(&btreeset! {3u64, 1});
+    assert_round_trip(&btreemap! {1u64 => "x".to_string()});
+
+    let res = decode_from_slice::<bool>(&[2]);
+    assert_eq!(io::ErrorKind::InvalidData, res.unwrap_err().kind());
+
+    let res = decode_from_slice::<Option<u64>>(&[2]);
+    assert_eq!(io::ErrorKind::InvalidData, res.unwrap_err().kind());
+
+    let res = decode_from_slice::<String>(&from_hex("00000001 ff"));
+    assert_eq!(io::ErrorKind::InvalidData, res.unwrap_err().kind());
+
+    let res = decode_from_slice::<u64>(&from_hex("0000000000000001 00"));
+    assert_eq!(io::ErrorKind::InvalidData, res.unwrap_err().kind());
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_truncated() -> anyhow::Result<()> {
+    // A corrupted length larger than the input.
+    let res = decode_from_slice::<String>(&from_hex("ffffffff 616263"));
+    assert_eq!(io::ErrorKind::UnexpectedEof, res.unwrap_err().kind());
+
+    let b = encode_to_vec(&VoteRequest::<TestConfig>::new(Vote::new(1, 2), None))?;
+    for i in 0..b.len() {
+        let res = decode_from_slice::<VoteRequest<TestConfig>>(&b[..i]);
+        assert_eq!(
+            io::ErrorKind::UnexpectedEof,
+            res.unwrap_err().kind(),
+            "truncated at {}",
+            i
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_round_trip() -> anyhow::Result<()> {
+    assert_round_trip(&membership());
+    assert_round_trip(&snapshot_meta());
+    assert_round_trip(&EntryPayload::<TestConfig>::Normal("foo".to_string()));
+    assert_round_trip(&EntryPayload::<TestConfig>::Membership(membership()));
+
+    for resp in [
+        AppendEntriesResponse::<TestConfig>::Success,
+        AppendEntriesResponse::PartialSuccess(Some(log_id(3, 1, 6))),
+        AppendEntriesResponse::PartialSuccess(None),
+        AppendEntriesResponse::Conflict,
+        AppendEntriesResponse::HigherVote(Vote::new(5, 2)),
+    ] {
+        assert_round_trip(&resp);
+    }
+
+    assert_round_trip(&InstallSnapshotResponse::<TestConfig> {
+        vote: Vote::new_committed(3, 1),
+    });
+    assert_round_trip(&SnapshotResponse::<TestConfig>::new(Vote::new_committed(3, 1)));
+    assert_round_trip(
+        &SnapshotResponse::<TestConfig>::new(Vote::new_committed(3, 1))
+            .with_snapshot(Some(snapshot_meta().signature())),
+    );
+    assert_round_trip(&ReadIndexRequest::<TestConfig>::new(3));
+    assert_round_trip(&HandoffLeaderResponse {
+        priority: 10,
+        accepted: true,
+    });
+
+    let resp = ClientWriteResponse::<TestConfig> {
+        log_id: log_id(3, 1, 6),
+        data: "ok".to_string(),
+        membership: Some(membership()),
+    };
+    let got = decode_from_slice::<ClientWriteResponse<TestConfig>>(&encode_to_vec(&resp)?)?;
+    assert_eq!(resp.log_id, got.log_id);
+    assert_eq!(resp.data, got.data);
+    assert_eq!(resp.membership, got.membership);
+
+    Ok(())
+}
+
+/// The fixtures are built with the default `LeaderId`, in which a committed leader id has a node
+/// id.
+#[cfg(not(feature = "single-term-leader"))]
+mod fixtures {
+    use std::fmt::Write;
+
+    use super::*;
+    use crate::raft::AppendEntriesRequest;
+    use crate::raft::HandoffLeaderRequest;
+    use crate::raft::InstallSnapshotRequest;
+    use crate::raft::ReadIndexResponse;
+    use crate::raft::SnapshotChunkRequest;
+    use crate::raft::SnapshotChunkResponse;
+    use crate::raft::TransferLeaderRequest;
+    use crate::raft::VoteResponse;
+
+    fn to_hex(b: &[u8]) -> String {
+        b.iter().fold(String::new(), |mut s, x| {
+            write!(s, "{:02x}", x).unwrap();
+            s
+        })
+    }
+
+    /// Assert `v` is encoded as `fixture` and `fixture` is decoded as `v`.
+    fn assert_fixture<T>(v: &T, fixture: &str)
+    where T: Encode + Decode + PartialEq + Debug {
+        let fixture = from_hex(fixture);
+        assert_eq!(to_hex(&fixture), to_hex(&encode_to_vec(v).unwrap()), "encode {:?}", v);
+        assert_eq!(v, &decode_from_slice::<T>(&fixture).unwrap());
+    }
+
+    /// The frozen encoding of every message.
+    ///
+    /// **Do not change a fixture**: a changed fixture means a peer of an older version can not
+    /// decode the message any more. Add a new field with a version bump instead.
+    #[test]
+    fn test_fixtures() -> anyhow::Result<()> {
+        assert_fixture(
+            &Vote::<u64>::new_committed(3, 1),
+            "000000000000000301000000000000000101",
+        );
+        assert_fixture(&log_id(2, 1, 5), "00000000000000020100000000000000010000000000000005");
+        assert_fixture(&BasicNode::new("a"), "01000000050000000161");
+        assert_fixture(
+            &membership(),
+            "020000005e000000010000000200000000000000010000000000000002000000\
+             0300000000000000010100000005000000016100000000000000020100000005\
+             0000000162000000000000000301000000050000000163000000010000000000\
+             000002",
+        );
+        assert_fixture(
+            &snapshot_meta(),
+            "01000000a2010000000000000002010000000000000001000000000000000501\
+             0000007d01000000000000000101000000000000000100000000000000030200\
+             00005e0000000100000002000000000000000100000000000000020000000300\
+             0000000000000101000000050000000161000000000000000201000000050000\
+             0001620000000000000003010000000500000001630000000100000000000000\
+             02000000027331",
+        );
+        assert_fixture(
+            &Entry::<TestConfig> {
+                log_id: log_id(3, 1, 5),
+                payload: EntryPayload::Normal("foo".to_string()),
+            },
+            "0100000021000000000000000301000000000000000100000000000000050100\
+             000003666f6f",
+        );
+
+        let req = AppendEntriesRequest::<TestConfig> {
+            vote: Vote::new_committed(3, 1),
+            prev_log_id: Some(log_id(2, 1, 4)),
+            entries: vec![
+                Entry {
+                    log_id: log_id(3, 1, 5),
+                    payload: EntryPayload::Blank,
+                },
+                Entry {
+                    log_id: log_id(3, 1, 6),
+                    payload: EntryPayload::Normal("foo".to_string()),
+                },
+            ],
+            leader_commit: Some(log_id(2, 1, 4)),
+        };
+        let fixture = from_hex(
+            "010000008f000000000000000301000000000000000101010000000000000002\
+             010000000000000001000000000000000400000002010000001a000000000000\
+             0003010000000000000001000000000000000500010000002100000000000000\
+             0301000000000000000100000000000000060100000003666f6f010000000000\
+             0000020100000000000000010000000000000004",
+        );
+        assert_eq!(to_hex(&fixture), to_hex(&encode_to_vec(&req)?));
+        let got = decode_from_slice::<AppendEntriesRequest<TestConfig>>(&fixture)?;
+        assert_eq!(req.vote, got.vote);
+        assert_eq!(req.prev_log_id, got.prev_log_id);
+        assert_eq!(req.entries, got.entries);
+        assert_eq!(req.leader_commit, got.leader_commit);
+
+        assert_fixture(&AppendEntriesResponse::<TestConfig>::Success, "00");
+        assert_fixture(
+            &AppendEntriesResponse::<TestConfig>::PartialSuccess(Some(log_id(3, 1, 6))),
+            "010100000000000000030100000000000000010000000000000006",
+        );
+        assert_fixture(&AppendEntriesResponse::<TestConfig>::Conflict, "02");
+        assert_fixture(
+            &AppendEntriesResponse::<TestConfig>::HigherVote(Vote::new(5, 2)),
+            "03000000000000000501000000000000000200",
+        );
+
+        assert_fixture(
+            &VoteRequest::<TestConfig>::new(Vote::new(5, 2), Some(log_id(3, 1, 6))),
+            "010000002c000000000000000501000000000000000200010000000000000003\
+             0100000000000000010000000000000006",
+        );
+        assert_fixture(
+            &VoteResponse::<TestConfig> {
+                vote: Vote::new(5, 2),
+                vote_granted: true,
+                last_log_id: None,
+            },
+            "01000000140000000000000005010000000000000002000100",
+        );
+
+        assert_fixture(
+            &InstallSnapshotRequest::<TestConfig> {
+                vote: Vote::new_committed(3, 1),
+                meta: snapshot_meta(),
+                offset: 1024,
+                data: b"xy".to_vec(),
+                done: true,
+            },
+            "01000000c800000000000000030100000000000000010101000000a201000000\
+             00000000020100000000000000010000000000000005010000007d0100000000\
+             000000010100000000000000010000000000000003020000005e000000010000\
+             0002000000000000000100000000000000020000000300000000000000010100\
+             0000050000000161000000000000000201000000050000000162000000000000\
+             0003010000000500000001630000000100000000000000020000000273310000\
+             00000000040000000002787901",
+        );
+        assert_fixture(
+            &InstallSnapshotResponse::<TestConfig> {
+                vote: Vote::new_committed(3, 1),
+            },
+            "0100000012000000000000000301000000000000000101",
+        );
+        assert_fixture(
+            &SnapshotResponse::<TestConfig>::new(Vote::new_committed(3, 1)),
+            "020000001300000000000000030100000000000000010100",
+        );
+        // Version 1, without `snapshot`.
+        assert_eq!(
+            SnapshotResponse::<TestConfig>::new(Vote::new_committed(3, 1)),
+            decode_from_slice(&from_hex("0100000012000000000000000301000000000000000101"))?
+        );
+
+        assert_fixture(
+            &SnapshotChunkRequest::<TestConfig>::new(
+                Vote::new_committed(3, 1),
+                snapshot_meta(),
+                1024,
+                b"xy".to_vec(),
+                false,
+            ),
+            "01000000cc00000000000000030100000000000000010101000000a201000000\
+             00000000020100000000000000010000000000000005010000007d0100000000\
+             000000010100000000000000010000000000000003020000005e000000010000\
+             0002000000000000000100000000000000020000000300000000000000010100\
+             0000050000000161000000000000000201000000050000000162000000000000\
+             0003010000000500000001630000000100000000000000020000000273310000\
+             0000000004000000000278798fe6289900",
+        );
+        assert_fixture(
+            &SnapshotChunkResponse::<TestConfig> {
+                vote: Vote::new_committed(3, 1),
+                offset: 1026,
+            },
+            "010000001a0000000000000003010000000000000001010000000000000402",
+        );
+
+        assert_fixture(&ReadIndexRequest::<TestConfig>::new(3), "01000000080000000000000003");
+        assert_fixture(
+            &ReadIndexResponse::<TestConfig> {
+                read_log_id: Some(log_id(3, 1, 6)),
+            },
+            "010000001a0100000000000000030100000000000000010000000000000006",
+        );
+
+        assert_fixture(
+            &HandoffLeaderRequest::<TestConfig>::new(Vote::new_committed(3, 1), 2, 10),
+            "0100000022000000000000000301000000000000000101000000000000000200\
+             0000000000000a",
+        );
+        assert_fixture(
+            &HandoffLeaderResponse {
+                priority: 10,
+                accepted: true,
+            },
+            "0100000009000000000000000a01",
+        );
+
+        assert_fixture(
+            &TransferLeaderRequest::<TestConfig>::new(Vote::new_committed(3, 1), 2, Some(log_id(3, 1, 6))),
+            "0100000034000000000000000301000000000000000101000000000000000201\
+             00000000000000030100000000000000010000000000000006",
+        );
+
+        let resp = ClientWriteResponse::<TestConfig> {
+            log_id: log_id(3, 1, 6),
+            data: "ok".to_string(),
+            membership: None,
+        };
+        let fixture = from_hex(
+            "0100000020000000000000000301000000000000000100000000000000060000\
+             00026f6b00",
+        );
+        assert_eq!(to_hex(&fixture), to_hex(&encode_to_vec(&resp)?));
+        let got = decode_from_slice::<ClientWriteResponse<TestConfig>>(&fixture)?;
+        assert_eq!(resp.log_id, got.log_id);
+        assert_eq!(resp.data, got.data);
+        assert_eq!(resp.membership, got.membership);
+
+        Ok(())
+    }
+}
+
+/// A message from a newer version with an unknown field appended can be decoded.
+#[test]
+fn test_decode_newer_version() -> anyhow::Result<()> {
+    let req = VoteRequest::<TestConfig>::new(Vote::new(5, 2), Some(log_id(3, 1, 6)));
+
+    let mut b = Vec::new();
+    encode_struct(&mut b, 9, |b| {
+        req.vote.encode(b)?;
+        req.last_log_id.encode(b)?;
+        // A field added in version 9
+        "new field".to_string().encode(b)
+    })?;
+
+    let got = decode_from_slice::<VoteRequest<TestConfig>>(&b)?;
+    assert_eq!(req, got);
+
+    Ok(())
+}
+
+/// A message from an older version without the newer fields can be decoded.
+#[test]
+fn test_decode_older_version() -> anyhow::Result<()> {
+    // Membership version 1 has no witnesses.
+    let m = Membership::<TestConfig>::new(vec![btreeset! {1,2}], btreemap! {
+        1 => BasicNode::new("a"),
+        2 => BasicNode::new("b"),
+    });
+
+    let mut b = Vec::new();
+    encode_struct(&mut b, 1, |b| {
+        m.get_joint_config().encode(b)?;
+        btreemap! {
+            1u64 => BasicNode::new("a"),
+            2u64 => BasicNode::new("b"),
+        }
+        .encode(b)
+    })?;
+
+    let got = decode_from_slice::<Membership<TestConfig>>(&b)?;
+    assert_eq!(m, got);
+    assert_eq!(0, got.witness_ids().count());
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_unknown_tag() -> anyhow::Result<()> {
+    let res = decode_from_slice::<AppendEntriesResponse<TestConfig>>(&[4]);
+    let err = res.unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    assert_eq!("unknown tag of AppendEntriesResponse: 4", err.to_string());
+
+    let res = decode_from_slice::<EntryPayload<TestConfig>>(&[3]);
+    assert_eq!(io::ErrorKind::InvalidData, res.unwrap_err().kind());
+
+    // Version 0 is never used.
+    let res = decode_from_slice::<BasicNode>(&from_hex("00 00000000"));
+    assert_eq!(io::ErrorKind::InvalidData, res.unwrap_err().kind());
+
+    Ok(())
+}
@@ -0,0 +1,466 @@
+use std::io;
+
+use crate::network::codec::decode_bytes;
+use crate::network::codec::decode_struct;
+use crate::network::codec::encode_bytes;
+use crate::network::codec::encode_struct;
+use crate::network::codec::unknown_tag;
+use crate::network::codec::Decode;
+use crate::network::codec::Encode;
+use crate::raft::AppendEntriesRequest;
+use crate::raft::AppendEntriesResponse;
+use crate::raft::ClientWriteResponse;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
+use crate::raft::InstallSnapshotRequest;
+use crate::raft::InstallSnapshotResponse;
+use crate::raft::ReadIndexRequest;
+use crate::raft::ReadIndexResponse;
+use crate::raft::SnapshotChunkRequest;
+use crate::raft::SnapshotChunkResponse;
+use crate::raft::SnapshotResponse;
+use crate::raft::TransferLeaderRequest;
+use crate::raft::VoteRequest;
+use crate::raft::VoteResponse;
+use crate::Membership;
+use crate::RaftTypeConfig;
+use crate::SnapshotMeta;
+use crate::Vote;
+
+impl<C> Encode for AppendEntriesRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Entry: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.vote.encode(b)?;
+            self.prev_log_id.encode(b)?;
+            self.entries.encode(b)?;
+            self.leader_commit.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for AppendEntriesRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Entry: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(AppendEntriesRequest {
+                vote: Vote::decode(b)?,
+                prev_log_id: Option::decode(b)?,
+                entries: Vec::decode(b)?,
+                leader_commit: Option::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for AppendEntriesResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            AppendEntriesResponse::Success => 0u8.encode(w),
+            AppendEntriesResponse::PartialSuccess(matching) => {
+                1u8.encode(w)?;
+                matching.encode(w)
+            }
+            AppendEntriesResponse::Conflict => 2u8.encode(w),
+            AppendEntriesResponse::HigherVote(vote) => {
+                3u8.encode(w)?;
+                vote.encode(w)
+            }
+        }
+    }
+}
+
+impl<C> Decode for AppendEntriesResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        match u8::decode(r)? {
+            0 => Ok(AppendEntriesResponse::Success),
+            1 => Ok(AppendEntriesResponse::PartialSuccess(Option::decode(r)?)),
+            2 => Ok(AppendEntriesResponse::Conflict),
+            3 => Ok(AppendEntriesResponse::HigherVote(Vote::decode(r)?)),
+            x => Err(unknown_tag("AppendEntriesResponse", x)),
+        }
+    }
+}
+
+impl<C> Encode for VoteRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.vote.encode(b)?;
+            self.last_log_id.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for VoteRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(VoteRequest {
+                vote: Vote::decode(b)?,
+                last_log_id: Option::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for VoteResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.vote.encode(b)?;
+            self.vote_granted.encode(b)?;
+            self.last_log_id.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for VoteResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(VoteResponse {
+                vote: Vote::decode(b)?,
+                vote_granted: bool::decode(b)?,
+                last_log_id: Option::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for InstallSnapshotRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.vote.encode(b)?;
+            self.meta.encode(b)?;
+            self.offset.encode(b)?;
+            encode_bytes(&self.data, b)?;
+            self.done.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for InstallSnapshotRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(InstallSnapshotRequest {
+                vote: Vote::decode(b)?,
+                meta: SnapshotMeta::decode(b)?,
+                offset: u64::decode(b)?,
+                data: decode_bytes(b)?,
+                done: bool::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for InstallSnapshotResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| self.vote.encode(b))
+    }
+}
+
+impl<C> Decode for InstallSnapshotResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| Ok(InstallSnapshotResponse { vote: Vote::decode(b)? }))
+    }
+}
+
+impl<C> Encode for SnapshotResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        // Version 2 adds `snapshot`.
+        encode_struct(w, 2, |b| {
+            self.vote.encode(b)?;
+            self.snapshot.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for SnapshotResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |version, b| {
+            let vote = Vote::decode(b)?;
+            let snapshot = if version >= 2 { Option::decode(b)? } else { None };
+            Ok(SnapshotResponse { vote, snapshot })
+        })
+    }
+}
+
+impl<C> Encode for SnapshotChunkRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.vote.encode(b)?;
+            self.meta.encode(b)?;
+            self.offset.encode(b)?;
+            encode_bytes(&self.data, b)?;
+            self.checksum.encode(b)?;
+            self.done.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for SnapshotChunkRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+{
+    /// The checksum is decoded as is, it is up to the receiver to verify it.
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(SnapshotChunkRequest {
+                vote: Vote::decode(b)?,
+                meta: SnapshotMeta::decode(b)?,
+                offset: u64::decode(b)?,
+                data: decode_bytes(b)?,
+                checksum: u32::decode(b)?,
+                done: bool::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for SnapshotChunkResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.vote.encode(b)?;
+            self.offset.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for SnapshotChunkResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(SnapshotChunkResponse {
+                vote: Vote::decode(b)?,
+                offset: u64::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for ReadIndexRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| self.from.encode(b))
+    }
+}
+
+impl<C> Decode for ReadIndexRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| Ok(ReadIndexRequest::new(C::NodeId::decode(b)?)))
+    }
+}
+
+impl<C> Encode for ReadIndexResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| self.read_log_id.encode(b))
+    }
+}
+
+impl<C> Decode for ReadIndexResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(ReadIndexResponse {
+                read_log_id: Option::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for HandoffLeaderRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.vote.encode(b)?;
+            self.from.encode(b)?;
+            self.priority.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for HandoffLeaderRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(HandoffLeaderRequest::new(
+                Vote::decode(b)?,
+                C::NodeId::decode(b)?,
+                u64::decode(b)?,
+            ))
+        })
+    }
+}
+
+impl Encode for HandoffLeaderResponse {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.priority.encode(b)?;
+            self.accepted.encode(b)
+        })
+    }
+}
+
+impl Decode for HandoffLeaderResponse {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(HandoffLeaderResponse {
+                priority: u64::decode(b)?,
+                accepted: bool::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for TransferLeaderRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.from_leader.encode(b)?;
+            self.to_node_id.encode(b)?;
+            self.last_log_id.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for TransferLeaderRequest<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(TransferLeaderRequest::new(
+                Vote::decode(b)?,
+                C::NodeId::decode(b)?,
+                Option::decode(b)?,
+            ))
+        })
+    }
+}
+
+impl<C> Encode for ClientWriteResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+    C::R: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.log_id.encode(b)?;
+            self.data.encode(b)?;
+            self.membership.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for ClientWriteResponse<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+    C::R: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(ClientWriteResponse {
+                log_id: Decode::decode(b)?,
+                data: C::R::decode(b)?,
+                membership: Option::<Membership<C>>::decode(b)?,
+            })
+        })
+    }
+}
@@ -0,0 +1,123 @@
+//! A stable, versioned binary encoding of the Raft messages.
+//!
+//! Unlike the `serde` feature, which leaves the format to the application, this encoding is
+//! defined by openraft and is kept compatible across openraft versions, so that nodes of different
+//! versions in a cluster can talk to each other during a rolling upgrade.
+//!
+//! An application implements [`Encode`] and [`Decode`] for its own types used in the messages:
+//! `C::NodeId`, `C::Node`, `C::D` and `C::R`. They are already implemented for the integer types,
+//! `()`, `String`, [`BasicNode`] and [`EmptyNode`].
+//!
+//! ## Wire format
+//!
+//! - An integer is encoded in fixed size big-endian.
+//! - A `bool` is a byte `0` or `1`.
+//! - A `String` or a byte slice is a `u32` length followed by the bytes.
+//! - An `Option` is a byte `0` for `None`, or `1` followed by the value.
+//! - A `Vec`, `BTreeSet` or `BTreeMap` is a `u32` count followed by the items.
+//! - An enum is a `u8` tag followed by the fields of the variant.
+//! - A struct is a `u8` version, a `u32` body length, then the body with the fields in order. See
+//!   [`encode_struct()`] and [`decode_struct()`].
+//!
+//! [`LeaderId`], [`CommittedLeaderId`], [`LogId`] and [`Vote`] are encoded without a version,
+//! because they appear in almost every message and are not expected to change. A `LeaderId` is
+//! always a term followed by an optional node id, no matter whether feature
+//! `single-term-leader` is enabled.
+//!
+//! ## Compatibility rules
+//!
+//! - A new field is only appended to the end of a struct, along with a version bump.
+//! - A decoder ignores the bytes after the fields it knows, i.e., the fields added by a newer
+//!   version.
+//! - A decoder fills the fields that do not exist in an older version with a default value.
+//! - A variant of an enum must not be removed or be re-tagged. A decoder fails with
+//!   [`io::ErrorKind::InvalidData`] on an unknown tag.
+//!
+//! [`BasicNode`]: crate::BasicNode
+//! [`EmptyNode`]: crate::EmptyNode
+//! [`LeaderId`]: crate::LeaderId
+//! [`CommittedLeaderId`]: crate::CommittedLeaderId
+//! [`LogId`]: crate::LogId
+//! [`Vote`]: crate::Vote
+
+mod message;
+mod primitive;
+mod types;
+
+#[cfg(test)] mod codec_test;
+
+use std::fmt;
+use std::io;
+
+pub use primitive::decode_bytes;
+pub use primitive::encode_bytes;
+
+/// Encode a value into the versioned binary format.
+pub trait Encode {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()>;
+}
+
+/// Decode a value from the versioned binary format.
+pub trait Decode: Sized {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self>;
+}
+
+/// Encode a value into a new `Vec<u8>`.
+pub fn encode_to_vec<T: Encode + ?Sized>(v: &T) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    v.encode(&mut buf)?;
+    Ok(buf)
+}
+
+/// Decode a value from `buf`, which must contain exactly one encoded value.
+pub fn decode_from_slice<T: Decode>(mut buf: &[u8]) -> io::Result<T> {
+    let v = T::decode(&mut buf)?;
+    if !buf.is_empty() {
+        return Err(invalid_data(format_args!(
+            "{} trailing bytes after the value",
+            buf.len()
+        )));
+    }
+    Ok(v)
+}
+
+/// Encode a struct of `version`: `[version: u8][body length: u32][body]`.
+///
+/// `f` writes the fields of the body.
+pub fn encode_struct<W, F>(w: &mut W, version: u8, f: F) -> io::Result<()>
+where
+    W: io::Write,
+    F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+{
+    let mut body = Vec::new();
+    f(&mut body)?;
+
+    version.encode(w)?;
+    encode_bytes(&body, w)
+}
+
+/// Decode a struct encoded by [`encode_struct()`].
+///
+/// `f` reads the fields from the body, given the version the struct is encoded with. The body
+/// bytes left unread by `f` are fields added by a newer version and are ignored.
+pub fn decode_struct<R, T, F>(r: &mut R, f: F) -> io::Result<T>
+where
+    R: io::Read,
+    F: FnOnce(u8, &mut &[u8]) -> io::Result<T>,
+{
+    let version = u8::decode(r)?;
+    if version == 0 {
+        return Err(invalid_data("struct version 0"));
+    }
+
+    let body = decode_bytes(r)?;
+    f(version, &mut body.as_slice())
+}
+
+pub(crate) fn invalid_data(msg: impl fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+pub(crate) fn unknown_tag(type_name: &str, tag: u8) -> io::Error {
+    invalid_data(format_args!("unknown tag of {}: {}", type_name, tag))
+}
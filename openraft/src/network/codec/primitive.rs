@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io;
+use std::io::Read;
+
+use crate::network::codec::invalid_data;
+use crate::network::codec::unknown_tag;
+use crate::network::codec::Decode;
+use crate::network::codec::Encode;
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+                    w.write_all(&self.to_be_bytes())
+                }
+            }
+
+            impl Decode for $t {
+                fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+                    let mut b = [0u8; std::mem::size_of::<$t>()];
+                    r.read_exact(&mut b)?;
+                    Ok(<$t>::from_be_bytes(b))
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+pub(crate) fn encode_len<W: io::Write>(len: usize, w: &mut W) -> io::Result<()> {
+    let len = u32::try_from(len)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, format!("length {} exceeds u32::MAX", len)))?;
+    len.encode(w)
+}
+
+/// Encode a byte slice as `[length: u32][bytes]`.
+///
+/// It is the same as encoding a `Vec<u8>` but writes the bytes at once.
+pub fn encode_bytes<W: io::Write>(b: &[u8], w: &mut W) -> io::Result<()> {
+    encode_len(b.len(), w)?;
+    w.write_all(b)
+}
+
+/// Decode bytes encoded by [`encode_bytes()`].
+///
+/// The buffer grows with the bytes actually read, thus a corrupted length does not allocate a
+/// huge buffer.
+pub fn decode_bytes<R: Read>(r: &mut R) -> io::Result<Vec<u8>> {
+    let len = u32::decode(r)? as u64;
+
+    let mut buf = Vec::new();
+    r.take(len).read_to_end(&mut buf)?;
+
+    if buf.len() as u64 != len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("expect {} bytes, got {}", len, buf.len()),
+        ));
+    }
+    Ok(buf)
+}
+
+impl Encode for () {
+    fn encode<W: io::Write>(&self, _w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Decode for () {
+    fn decode<R: Read>(_r: &mut R) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+impl Encode for bool {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        (*self as u8).encode(w)
+    }
+}
+
+impl Decode for bool {
+    fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        match u8::decode(r)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            x => Err(unknown_tag("bool", x)),
+        }
+    }
+}
+
+impl Encode for String {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_bytes(self.as_bytes(), w)
+    }
+}
+
+impl Decode for String {
+    fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        String::from_utf8(decode_bytes(r)?).map_err(invalid_data)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            None => 0u8.encode(w),
+            Some(v) => {
+                1u8.encode(w)?;
+                v.encode(w)
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        match u8::decode(r)? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(r)?)),
+            x => Err(unknown_tag("Option", x)),
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_len(self.len(), w)?;
+        for v in self {
+            v.encode(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        let n = u32::decode(r)?;
+        // Do not trust `n` for pre-allocation.
+        let mut res = Vec::new();
+        for _ in 0..n {
+            res.push(T::decode(r)?);
+        }
+        Ok(res)
+    }
+}
+
+impl<T: Encode> Encode for BTreeSet<T> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_len(self.len(), w)?;
+        for v in self {
+            v.encode(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Decode + Ord> Decode for BTreeSet<T> {
+    fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        let n = u32::decode(r)?;
+        let mut res = BTreeSet::new();
+        for _ in 0..n {
+            res.insert(T::decode(r)?);
+        }
+        Ok(res)
+    }
+}
+
+impl<K: Encode, V: Encode> Encode for BTreeMap<K, V> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_len(self.len(), w)?;
+        for (k, v) in self {
+            k.encode(w)?;
+            v.encode(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
+    fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        let n = u32::decode(r)?;
+        let mut res = BTreeMap::new();
+        for _ in 0..n {
+            let k = K::decode(r)?;
+            let v = V::decode(r)?;
+            res.insert(k, v);
+        }
+        Ok(res)
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io;
+
+use crate::network::codec::decode_struct;
+use crate::network::codec::encode_struct;
+use crate::network::codec::primitive::encode_len;
+use crate::network::codec::unknown_tag;
+use crate::network::codec::Decode;
+use crate::network::codec::Encode;
+use crate::storage::SnapshotSignature;
+use crate::BasicNode;
+use crate::CommittedLeaderId;
+use crate::EmptyNode;
+use crate::Entry;
+use crate::EntryPayload;
+use crate::LeaderId;
+use crate::LogId;
+use crate::Membership;
+use crate::NodeId;
+use crate::RaftTypeConfig;
+use crate::SnapshotMeta;
+use crate::StoredMembership;
+use crate::Vote;
+
+impl Encode for BasicNode {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| self.addr.encode(b))
+    }
+}
+
+impl Decode for BasicNode {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(BasicNode {
+                addr: String::decode(b)?,
+            })
+        })
+    }
+}
+
+impl Encode for EmptyNode {
+    fn encode<W: io::Write>(&self, _w: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Decode for EmptyNode {
+    fn decode<R: io::Read>(_r: &mut R) -> io::Result<Self> {
+        Ok(EmptyNode {})
+    }
+}
+
+#[cfg(not(feature = "single-term-leader"))]
+impl<NID: NodeId + Encode> Encode for LeaderId<NID> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.term.encode(w)?;
+        Some(self.node_id).encode(w)
+    }
+}
+
+#[cfg(not(feature = "single-term-leader"))]
+impl<NID: NodeId + Decode> Decode for LeaderId<NID> {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let term = u64::decode(r)?;
+        let node_id =
+            Option::<NID>::decode(r)?.ok_or_else(|| crate::network::codec::invalid_data("LeaderId without node_id"))?;
+        Ok(LeaderId { term, node_id })
+    }
+}
+
+#[cfg(feature = "single-term-leader")]
+impl<NID: NodeId + Encode> Encode for LeaderId<NID> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.term.encode(w)?;
+        self.voted_for.encode(w)
+    }
+}
+
+#[cfg(feature = "single-term-leader")]
+impl<NID: NodeId + Decode> Decode for LeaderId<NID> {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let term = u64::decode(r)?;
+        let voted_for = Option::<NID>::decode(r)?;
+        Ok(LeaderId { term, voted_for })
+    }
+}
+
+/// A committed leader id does not have a node id in `single-term-leader` mode, it is encoded as a
+/// `LeaderId` without node id.
+#[cfg(feature = "single-term-leader")]
+impl<NID: NodeId + Encode> Encode for CommittedLeaderId<NID> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.term.encode(w)?;
+        Option::<NID>::None.encode(w)
+    }
+}
+
+#[cfg(feature = "single-term-leader")]
+impl<NID: NodeId + Decode> Decode for CommittedLeaderId<NID> {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        let term = u64::decode(r)?;
+        let _node_id = Option::<NID>::decode(r)?;
+
+        let mut c = CommittedLeaderId::default();
+        c.term = term;
+        Ok(c)
+    }
+}
+
+impl<NID: NodeId + Encode> Encode for Vote<NID> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.leader_id.encode(w)?;
+        self.committed.encode(w)
+    }
+}
+
+impl<NID: NodeId + Decode> Decode for Vote<NID> {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Vote {
+            leader_id: LeaderId::decode(r)?,
+            committed: bool::decode(r)?,
+        })
+    }
+}
+
+impl<NID: NodeId + Encode> Encode for LogId<NID> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.leader_id.encode(w)?;
+        self.index.encode(w)
+    }
+}
+
+impl<NID: NodeId + Decode> Decode for LogId<NID> {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        // Do not use `LogId::new()`, which panics on an invalid log id.
+        Ok(LogId {
+            leader_id: CommittedLeaderId::decode(r)?,
+            index: u64::decode(r)?,
+        })
+    }
+}
+
+/// Version 2 adds `witnesses`.
+impl<C> Encode for Membership<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 2, |b| {
+            self.get_joint_config().encode(b)?;
+
+            let nodes = self.nodes().collect::<Vec<_>>();
+            encode_len(nodes.len(), b)?;
+            for (id, node) in nodes {
+                id.encode(b)?;
+                node.encode(b)?;
+            }
+
+            self.witness_ids().collect::<BTreeSet<_>>().encode(b)
+        })
+    }
+}
+
+impl<C> Decode for Membership<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |version, b| {
+            let configs = Vec::<BTreeSet<C::NodeId>>::decode(b)?;
+            let nodes = BTreeMap::<C::NodeId, C::Node>::decode(b)?;
+            let witnesses = if version >= 2 {
+                BTreeSet::<C::NodeId>::decode(b)?
+            } else {
+                BTreeSet::new()
+            };
+
+            Ok(Membership::new_unchecked(configs, nodes).with_witnesses(witnesses))
+        })
+    }
+}
+
+impl<C> Encode for StoredMembership<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.log_id().encode(b)?;
+            self.membership().encode(b)
+        })
+    }
+}
+
+impl<C> Decode for StoredMembership<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            let log_id = Option::<LogId<C::NodeId>>::decode(b)?;
+            let membership = Membership::decode(b)?;
+            Ok(StoredMembership::new(log_id, membership))
+        })
+    }
+}
+
+impl<C> Encode for SnapshotMeta<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.last_log_id.encode(b)?;
+            self.last_membership.encode(b)?;
+            self.snapshot_id.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for SnapshotMeta<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(SnapshotMeta {
+                last_log_id: Option::decode(b)?,
+                last_membership: StoredMembership::decode(b)?,
+                snapshot_id: String::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<NID: NodeId + Encode> Encode for SnapshotSignature<NID> {
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.last_log_id.encode(b)?;
+            self.last_membership_log_id.encode(b)?;
+            self.snapshot_id.encode(b)
+        })
+    }
+}
+
+impl<NID: NodeId + Decode> Decode for SnapshotSignature<NID> {
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(SnapshotSignature {
+                last_log_id: Option::decode(b)?,
+                last_membership_log_id: Option::decode(b)?,
+                snapshot_id: String::decode(b)?,
+            })
+        })
+    }
+}
+
+impl<C> Encode for EntryPayload<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+    C::D: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            EntryPayload::Blank => 0u8.encode(w),
+            EntryPayload::Normal(d) => {
+                1u8.encode(w)?;
+                d.encode(w)
+            }
+            EntryPayload::Membership(m) => {
+                2u8.encode(w)?;
+                m.encode(w)
+            }
+        }
+    }
+}
+
+impl<C> Decode for EntryPayload<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+    C::D: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        match u8::decode(r)? {
+            0 => Ok(EntryPayload::Blank),
+            1 => Ok(EntryPayload::Normal(C::D::decode(r)?)),
+            2 => Ok(EntryPayload::Membership(Membership::decode(r)?)),
+            x => Err(unknown_tag("EntryPayload", x)),
+        }
+    }
+}
+
+impl<C> Encode for Entry<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Encode,
+    C::Node: Encode,
+    C::D: Encode,
+{
+    fn encode<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        encode_struct(w, 1, |b| {
+            self.log_id.encode(b)?;
+            self.payload.encode(b)
+        })
+    }
+}
+
+impl<C> Decode for Entry<C>
+where
+    C: RaftTypeConfig,
+    C::NodeId: Decode,
+    C::Node: Decode,
+    C::D: Decode,
+{
+    fn decode<R: io::Read>(r: &mut R) -> io::Result<Self> {
+        decode_struct(r, |_version, b| {
+            Ok(Entry {
+                log_id: LogId::decode(b)?,
+                payload: EntryPayload::decode(b)?,
+            })
+        })
+    }
+}
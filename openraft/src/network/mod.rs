@@ -1,12 +1,14 @@
 //! The Raft network interface.
 
 mod backoff;
+pub(crate) mod rate_limiter;
 mod rpc_option;
 mod rpc_type;
 
 pub mod v1;
 pub mod v2;
 
+pub mod codec;
 pub mod snapshot_transport;
 
 pub use backoff::Backoff;
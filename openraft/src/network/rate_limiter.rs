@@ -0,0 +1,86 @@
+//! Limits the rate of sending data, such as snapshot data, shared by several senders.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::type_config::alias::InstantOf;
+use crate::Instant;
+use crate::OptionalSend;
+use crate::OptionalSync;
+use crate::RaftTypeConfig;
+
+/// Limits the total bytes per second sent by all of its users.
+///
+/// Every user reserves the bytes it is going to send with [`RateLimiter::reserve`], and waits for
+/// the returned duration before sending them.
+#[derive(Debug)]
+pub(crate) struct RateLimiter<C: RaftTypeConfig> {
+    bytes_per_sec: u64,
+
+    /// The time when the reserved bytes are all sent, at the limited rate.
+    reserved_until: Mutex<Option<InstantOf<C>>>,
+}
+
+impl<C: RaftTypeConfig> RateLimiter<C> {
+    pub(crate) fn new(bytes_per_sec: u64) -> Self {
+        debug_assert!(bytes_per_sec > 0, "bytes_per_sec should be greater than 0");
+
+        Self {
+            bytes_per_sec,
+            reserved_until: Mutex::new(None),
+        }
+    }
+
+    fn reserve_at(&self, bytes: u64, now: InstantOf<C>) -> Duration {
+        let cost = Duration::from_secs_f64(bytes as f64 / self.bytes_per_sec as f64);
+
+        let mut reserved_until = self.reserved_until.lock().unwrap();
+
+        let start = match *reserved_until {
+            Some(t) if t > now => t,
+            _ => now,
+        };
+
+        *reserved_until = Some(start + cost);
+        start - now
+    }
+}
+
+/// Reserves bandwidth from a [`RateLimiter`], with the type config erased so that it can be held
+/// by [`RPCOption`](crate::network::RPCOption).
+pub(crate) trait Reserve: fmt::Debug + OptionalSend + OptionalSync {
+    /// Reserve `bytes` to send, and return the duration to wait before sending them.
+    fn reserve(&self, bytes: u64) -> Duration;
+}
+
+impl<C: RaftTypeConfig> Reserve for RateLimiter<C> {
+    fn reserve(&self, bytes: u64) -> Duration {
+        self.reserve_at(bytes, InstantOf::<C>::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::engine::testing::UTConfig;
+    use crate::network::rate_limiter::RateLimiter;
+    use crate::TokioInstant;
+
+    #[test]
+    fn test_reserve() {
+        let r = RateLimiter::<UTConfig>::new(1000);
+        let now = TokioInstant::now();
+
+        assert_eq!(Duration::ZERO, r.reserve_at(500, now));
+        assert_eq!(Duration::from_millis(500), r.reserve_at(1000, now));
+        assert_eq!(
+            Duration::from_millis(1000),
+            r.reserve_at(1000, now + Duration::from_millis(500))
+        );
+
+        // All reserved bytes are sent: no wait.
+        assert_eq!(Duration::ZERO, r.reserve_at(1000, now + Duration::from_millis(3000)));
+    }
+}
@@ -1,5 +1,9 @@
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::network::rate_limiter::Reserve;
+use crate::replication::replication_status::SnapshotProgress;
+
 /// An additional argument to the [`RaftNetwork`] methods to allow applications to customize
 /// networking behaviors.
 ///
@@ -13,6 +17,12 @@ pub struct RPCOption {
 
     /// The size of the snapshot chunk.
     pub(crate) snapshot_chunk_size: Option<usize>,
+
+    /// Limits the bandwidth of all of the snapshots sent by this node.
+    pub(crate) snapshot_rate_limiter: Option<Arc<dyn Reserve>>,
+
+    /// Receives the progress of the snapshot being sent, for reporting it in metrics.
+    pub(crate) snapshot_progress: Option<Arc<SnapshotProgress>>,
 }
 
 impl RPCOption {
@@ -20,6 +30,8 @@ impl RPCOption {
         Self {
             hard_ttl,
             snapshot_chunk_size: None,
+            snapshot_rate_limiter: None,
+            snapshot_progress: None,
         }
     }
 
@@ -51,4 +63,34 @@ impl RPCOption {
     pub fn snapshot_chunk_size(&self) -> Option<usize> {
         self.snapshot_chunk_size
     }
+
+    /// Reserve `bytes` of snapshot data to send, and return the duration to wait before sending
+    /// them.
+    ///
+    /// A snapshot transport should call it for every chunk, including a resent one, to keep the
+    /// total bandwidth of the snapshots sent by this node within
+    /// [`Config::snapshot_max_bytes_per_sec`].
+    /// It returns zero if the bandwidth is not limited.
+    ///
+    /// [`Config::snapshot_max_bytes_per_sec`]: `crate::Config::snapshot_max_bytes_per_sec`
+    pub fn snapshot_throttle(&self, bytes: u64) -> Duration {
+        match &self.snapshot_rate_limiter {
+            Some(limiter) => limiter.reserve(bytes),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Report that the target has received the first `received` bytes of the snapshot being sent.
+    ///
+    /// A snapshot transport should call it when the target acknowledges a chunk, to report the
+    /// progress in [`ReplicationTargetMetrics::snapshot_sent_bytes`]. `received` is the total,
+    /// not an increment, so a resent chunk is not counted twice, and it may go back, e.g., to 0
+    /// if the target starts over.
+    ///
+    /// [`ReplicationTargetMetrics::snapshot_sent_bytes`]: `crate::metrics::ReplicationTargetMetrics::snapshot_sent_bytes`
+    pub fn snapshot_received(&self, received: u64) {
+        if let Some(progress) = &self.snapshot_progress {
+            progress.set_received(received);
+        }
+    }
 }
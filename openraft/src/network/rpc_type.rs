@@ -6,8 +6,13 @@ use std::fmt;
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum RPCTypes {
     Vote,
+    PreVote,
     AppendEntries,
     InstallSnapshot,
+    TransferLeader,
+    ReadIndex,
+    HandoffLeader,
+    SnapshotDelta,
 }
 
 impl fmt::Display for RPCTypes {
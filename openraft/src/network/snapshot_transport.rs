@@ -1,11 +1,20 @@
 //! Provide a default chunked snapshot transport implementation for SnapshotData that implements
 //! AsyncWrite + AsyncRead + AsyncSeek + Unpin.
+//!
+//! - [`Chunked`] sends a snapshot by chunks with `RaftNetwork::install_snapshot()`, and restarts
+//!   from offset 0 if the target reports a mismatch.
+//! - [`Resumable`] sends a snapshot by checksummed chunks with
+//!   [`SnapshotChunkNetwork::snapshot_chunk()`], and continues an interrupted transfer from the
+//!   offset the target has received.
 
 use std::future::Future;
+use std::io;
 use std::io::SeekFrom;
 use std::time::Duration;
 
 use futures::FutureExt;
+use futures::Stream;
+use futures::StreamExt;
 use openraft_macros::add_async_trait;
 use tokio::io::AsyncReadExt;
 use tokio::io::AsyncSeekExt;
@@ -15,16 +24,21 @@ use crate::error::Fatal;
 use crate::error::InstallSnapshotError;
 use crate::error::RPCError;
 use crate::error::RaftError;
+use crate::error::RemoteError;
 use crate::error::ReplicationClosed;
 use crate::error::StreamingError;
+use crate::network::Backoff;
 use crate::network::RPCOption;
 use crate::raft::InstallSnapshotRequest;
+use crate::raft::SnapshotChunkRequest;
+use crate::raft::SnapshotChunkResponse;
 use crate::raft::SnapshotResponse;
 use crate::type_config::alias::AsyncRuntimeOf;
 use crate::AsyncRuntime;
 use crate::ErrorSubject;
 use crate::ErrorVerb;
 use crate::OptionalSend;
+use crate::OptionalSync;
 use crate::Raft;
 use crate::RaftNetwork;
 use crate::RaftTypeConfig;
@@ -159,6 +173,12 @@ where C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io:
                 "sending snapshot chunk"
             );
 
+            // Keep the total snapshot bandwidth of this node under the limit.
+            let delay = option.snapshot_throttle(n_read as u64);
+            if !delay.is_zero() {
+                AsyncRuntimeOf::<C>::sleep(delay).await;
+            }
+
             #[allow(deprecated)]
             let res = AsyncRuntimeOf::<C>::timeout(option.hard_ttl(), net.install_snapshot(req, option.clone())).await;
 
@@ -210,8 +230,13 @@ where C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io:
                 return Ok(SnapshotResponse::new(resp.vote));
             }
 
+            option.snapshot_received(offset + n_read as u64);
+
             if done {
-                return Ok(SnapshotResponse::new(resp.vote));
+                // The chunk response does not carry the snapshot of the target. Assume it is the one
+                // just sent; if not, the target rejects a delta upon it and reports its own.
+                let sig = snapshot.meta.signature();
+                return Ok(SnapshotResponse::new(resp.vote).with_snapshot(Some(sig)));
             }
 
             offset += n_read as u64;
@@ -279,6 +304,316 @@ where C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io:
     }
 }
 
+/// The network API to send a chunk of snapshot for [`Resumable`] snapshot transport.
+///
+/// An application implements [`RaftNetworkV2::full_snapshot()`] by calling
+/// [`Resumable::send_snapshot()`] with a network that implements this trait. The target node
+/// passes every received [`SnapshotChunkRequest`] to [`Raft::receive_snapshot_chunk()`].
+///
+/// [`RaftNetworkV2::full_snapshot()`]: crate::network::v2::RaftNetworkV2::full_snapshot
+#[add_async_trait]
+pub trait SnapshotChunkNetwork<C>: OptionalSend + OptionalSync
+where C: RaftTypeConfig
+{
+    /// Send a chunk of snapshot to the target.
+    async fn snapshot_chunk(
+        &mut self,
+        rpc: SnapshotChunkRequest<C>,
+        option: RPCOption,
+    ) -> Result<SnapshotChunkResponse<C>, RPCError<C, RaftError<C>>>;
+
+    /// Build a backoff instance to sleep before resuming a transfer interrupted by an error.
+    ///
+    /// By default it returns a constant backoff of 500 ms.
+    fn backoff(&self) -> Backoff {
+        Backoff::new(std::iter::repeat(Duration::from_millis(500)))
+    }
+}
+
+/// A chunk of snapshot data returned by [`snapshot_chunks()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotChunk {
+    /// The byte offset of this chunk in the snapshot data.
+    pub offset: u64,
+
+    /// The raw bytes of this chunk.
+    pub data: Vec<u8>,
+
+    /// Will be `true` if this is the last chunk in the snapshot data.
+    pub done: bool,
+}
+
+/// Read snapshot data as a stream of chunks of at most `chunk_size` bytes, starting at `offset`.
+///
+/// The stream yields at least one chunk: if `offset` is at the end of the data, the only chunk
+/// is an empty one with `done` set.
+pub fn snapshot_chunks<R>(
+    reader: &mut R,
+    offset: u64,
+    chunk_size: usize,
+) -> impl Stream<Item = Result<SnapshotChunk, io::Error>> + '_
+where
+    R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin + ?Sized,
+{
+    // The state is `None` once the last chunk is returned or an error occurs.
+    // The end of the data is unknown until the first chunk is read.
+    let state = Some((reader, offset, None));
+
+    futures::stream::unfold(state, move |state| async move {
+        let (reader, offset, end) = state?;
+
+        let read = async {
+            let end = match end {
+                Some(end) => end,
+                None => {
+                    let end = reader.seek(SeekFrom::End(0)).await?;
+                    reader.seek(SeekFrom::Start(offset)).await?;
+                    end
+                }
+            };
+
+            let mut data = Vec::with_capacity(chunk_size);
+            (&mut *reader).take(chunk_size as u64).read_to_end(&mut data).await?;
+            Ok::<_, io::Error>((end, data))
+        };
+
+        match read.await {
+            Ok((end, data)) => {
+                let next = offset + data.len() as u64;
+
+                // An empty read before `end` means the data is truncated: stop here.
+                let done = data.is_empty() || next >= end;
+
+                let chunk = SnapshotChunk { offset, data, done };
+                let state = if done { None } else { Some((reader, next, Some(end))) };
+                Some((Ok(chunk), state))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+}
+
+/// Send and receive snapshot by chunks with checksums, and resume an interrupted transfer.
+///
+/// Every response from the target carries the offset up to which the snapshot data is received.
+/// After a network error or a timeout, the sender continues from the last offset the target
+/// responded, instead of from offset 0. A chunk that is corrupted, or that does not start at the
+/// received offset, is not written by the target, and the sender resends from the offset in the
+/// response after a backoff.
+///
+/// The received offset is kept in memory by the target, in the receiving state [`Streaming`],
+/// along with the snapshot data being written. It is not persisted: if the target restarts, the
+/// receiving state is lost and the transfer starts over from offset 0.
+///
+/// This implementation requires `SnapshotData` to be `AsyncRead + AsyncSeek` on the sending side
+/// and `AsyncWrite + AsyncSeek` on the receiving side.
+pub struct Resumable {}
+
+impl Resumable {
+    /// Send a snapshot to a target node via `Net`.
+    ///
+    /// It returns when the snapshot is completely received and installed by the target, or when
+    /// the target responds a higher vote, or returns a [`Fatal`] error. Other errors are retried
+    /// with the backoff returned by [`SnapshotChunkNetwork::backoff()`] until `cancel` becomes
+    /// `Ready`.
+    pub async fn send_snapshot<C, Net>(
+        net: &mut Net,
+        vote: Vote<C::NodeId>,
+        mut snapshot: Snapshot<C>,
+        cancel: impl Future<Output = ReplicationClosed> + OptionalSend + 'static,
+        option: RPCOption,
+    ) -> Result<SnapshotResponse<C>, StreamingError<C, Fatal<C>>>
+    where
+        C: RaftTypeConfig,
+        C::SnapshotData: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+        Net: SnapshotChunkNetwork<C> + ?Sized,
+    {
+        let meta = snapshot.meta.clone();
+        let subject_verb = || (ErrorSubject::Snapshot(Some(meta.signature())), ErrorVerb::Read);
+
+        // Safe unwrap(): `full_snapshot()` is always called with a chunk size.
+        let chunk_size = option.snapshot_chunk_size().unwrap();
+
+        // The offset up to which the target has received.
+        let mut offset = 0;
+        let mut backoff: Option<Backoff> = None;
+
+        let mut c = std::pin::pin!(cancel);
+
+        'transfer: loop {
+            if let Some(b) = &mut backoff {
+                let duration = b.next().unwrap_or(Duration::from_millis(500));
+                AsyncRuntimeOf::<C>::sleep(duration).await;
+            }
+
+            let mut chunks = std::pin::pin!(snapshot_chunks(&mut *snapshot.snapshot, offset, chunk_size));
+
+            while let Some(chunk) = chunks.next().await {
+                // If canceled, return at once
+                if let Some(err) = c.as_mut().now_or_never() {
+                    return Err(err.into());
+                }
+
+                let chunk = chunk.sto_res(subject_verb)?;
+
+                let req = SnapshotChunkRequest::new(vote, meta.clone(), chunk.offset, chunk.data, chunk.done);
+                let end = req.end();
+                let done = req.done;
+
+                tracing::debug!(req = display(&req), "sending snapshot chunk");
+
+                // Keep the total snapshot bandwidth of this node under the limit.
+                let delay = option.snapshot_throttle(req.data.len() as u64);
+                if !delay.is_zero() {
+                    AsyncRuntimeOf::<C>::sleep(delay).await;
+                }
+
+                let res =
+                    AsyncRuntimeOf::<C>::timeout(option.hard_ttl(), net.snapshot_chunk(req, option.clone())).await;
+
+                let resp = match res {
+                    Ok(Ok(resp)) => resp,
+                    Ok(Err(RPCError::RemoteError(RemoteError {
+                        target,
+                        target_node,
+                        source: RaftError::Fatal(fatal),
+                    }))) => {
+                        tracing::error!(error=%fatal, offset, "target is shut down, stop sending snapshot");
+                        return Err(StreamingError::RemoteError(RemoteError {
+                            target,
+                            target_node,
+                            source: fatal,
+                        }));
+                    }
+                    Ok(Err(err)) => {
+                        tracing::warn!(error=%err, offset, "error sending snapshot chunk to target, resume later");
+                        backoff.get_or_insert_with(|| net.backoff());
+                        continue 'transfer;
+                    }
+                    Err(err) => {
+                        tracing::warn!(error=%err, offset, "timeout sending snapshot chunk to target, resume later");
+                        backoff.get_or_insert_with(|| net.backoff());
+                        continue 'transfer;
+                    }
+                };
+
+                if resp.vote > vote {
+                    // Unfinished, return a response with a higher vote.
+                    // The caller checks the vote and return a HigherVote error.
+                    return Ok(SnapshotResponse::new(resp.vote));
+                }
+
+                offset = resp.offset;
+                option.snapshot_received(offset);
+
+                if offset != end {
+                    tracing::warn!(
+                        expect = end,
+                        received = offset,
+                        "target did not receive snapshot chunk, resume from the received offset later"
+                    );
+                    // Do not resend at once: a chunk that is always discarded by the target, e.g.,
+                    // corrupted by a faulty link, would be resent in a busy loop.
+                    backoff.get_or_insert_with(|| net.backoff());
+                    continue 'transfer;
+                }
+
+                backoff = None;
+
+                if done {
+                    // See the comment in `Chunked::send_snapshot()`.
+                    return Ok(SnapshotResponse::new(resp.vote).with_snapshot(Some(meta.signature())));
+                }
+            }
+        }
+    }
+
+    /// Receive a chunk of snapshot sent by [`Resumable::send_snapshot()`].
+    ///
+    /// The receiving state `streaming` is maintained by the caller, and a new one is created with
+    /// [`Raft::begin_receiving_snapshot()`] when a chunk of another snapshot is received at offset
+    /// 0. When the last chunk is received, the snapshot is installed with
+    /// [`Raft::install_full_snapshot()`].
+    ///
+    /// The returned response carries the offset up to which the snapshot data is received, which
+    /// is kept only in `streaming`.
+    ///
+    /// [`Raft::receive_snapshot_chunk()`] provides a ready-to-use receiving state.
+    pub async fn receive_chunk<C>(
+        streaming: &mut Option<Streaming<C>>,
+        raft: &Raft<C>,
+        req: SnapshotChunkRequest<C>,
+    ) -> Result<SnapshotChunkResponse<C>, RaftError<C>>
+    where
+        C: RaftTypeConfig,
+        C::SnapshotData: tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+    {
+        let snapshot_id = &req.meta.snapshot_id;
+        let vote = req.vote;
+
+        tracing::debug!(req = display(&req), "{}", func_name!());
+
+        let curr_id = streaming.as_ref().map(|s| s.snapshot_id());
+
+        if curr_id != Some(snapshot_id) {
+            if req.offset != 0 {
+                let installed_id = raft.with_raft_state(|st| st.snapshot_meta.snapshot_id.clone()).await?;
+
+                // The last chunk is received but the response is lost.
+                let offset = if req.done && &installed_id == snapshot_id {
+                    req.end()
+                } else {
+                    0
+                };
+
+                tracing::info!(
+                    req = display(&req),
+                    offset,
+                    "no receiving state for snapshot chunk, respond offset"
+                );
+                return Ok(SnapshotChunkResponse { vote, offset });
+            }
+
+            // Changed to another stream. re-init snapshot state.
+            let snapshot_data = raft.begin_receiving_snapshot().await.map_err(|e| {
+                // Safe unwrap: `RaftError<Infallible>` is always a Fatal.
+                RaftError::Fatal(e.into_fatal().unwrap())
+            })?;
+
+            *streaming = Some(Streaming::new(snapshot_id.clone(), snapshot_data));
+        }
+
+        let done = req.done;
+        let end = req.end();
+        let snapshot_meta = req.meta.clone();
+
+        let offset = {
+            let s = streaming.as_mut().unwrap();
+            s.receive_chunk(req).await?
+        };
+
+        if done && offset == end {
+            let streaming = streaming.take().unwrap();
+            let mut data = streaming.into_snapshot_data();
+
+            data.as_mut().shutdown().await.map_err(|e| {
+                let io_err = StorageIOError::write_snapshot(Some(snapshot_meta.signature()), &e);
+                StorageError::from(io_err)
+            })?;
+
+            tracing::info!("finished streaming snapshot: {:?}", snapshot_meta);
+
+            let resp = raft.install_full_snapshot(vote, Snapshot::new(snapshot_meta, data)).await?;
+            return Ok(SnapshotChunkResponse {
+                vote: resp.vote,
+                offset,
+            });
+        }
+
+        Ok(SnapshotChunkResponse { vote, offset })
+    }
+}
+
 /// The Raft node is streaming in a snapshot from the leader.
 pub struct Streaming<C>
 where C: RaftTypeConfig
@@ -347,27 +682,92 @@ where
         self.offset += req.data.len() as u64;
         Ok(req.done)
     }
+
+    /// Receive a chunk of snapshot data with checksum, and return the offset up to which the
+    /// data is received, i.e., written and flushed to the snapshot data.
+    ///
+    /// The chunk is not written if its checksum does not match, or if it does not start at or
+    /// before the received offset. A chunk entirely before the received offset is skipped.
+    pub async fn receive_chunk(&mut self, req: SnapshotChunkRequest<C>) -> Result<u64, StorageError<C::NodeId>> {
+        if !req.verify_checksum() {
+            tracing::warn!(req = display(&req), "snapshot chunk checksum mismatch, discard it");
+            return Ok(self.offset);
+        }
+
+        if req.offset > self.offset || (req.offset < self.offset && req.end() <= self.offset) {
+            tracing::info!(
+                req = display(&req),
+                received = self.offset,
+                "snapshot chunk does not continue received data, discard it"
+            );
+            return Ok(self.offset);
+        }
+
+        let subject = || ErrorSubject::Snapshot(Some(req.meta.signature()));
+
+        if req.offset != self.offset {
+            self.snapshot_data
+                .as_mut()
+                .seek(SeekFrom::Start(req.offset))
+                .await
+                .map_err(|e| StorageError::from_io_error(subject(), ErrorVerb::Seek, e))?;
+        }
+
+        self.snapshot_data
+            .as_mut()
+            .write_all(&req.data)
+            .await
+            .map_err(|e| StorageError::from_io_error(subject(), ErrorVerb::Write, e))?;
+
+        self.snapshot_data
+            .as_mut()
+            .flush()
+            .await
+            .map_err(|e| StorageError::from_io_error(subject(), ErrorVerb::Write, e))?;
+
+        self.offset = req.end();
+        Ok(self.offset)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
     use std::time::Duration;
 
+    use anyerror::AnyError;
+    use futures::StreamExt;
+
     use crate::engine::testing::UTConfig;
+    use crate::error::Fatal;
     use crate::error::InstallSnapshotError;
     use crate::error::RPCError;
     use crate::error::RaftError;
+    use crate::error::RemoteError;
     use crate::error::SnapshotMismatch;
+    use crate::error::StreamingError;
+    use crate::error::Unreachable;
+    use crate::network::snapshot_transport::snapshot_chunks;
     use crate::network::snapshot_transport::Chunked;
+    use crate::network::snapshot_transport::Resumable;
+    use crate::network::snapshot_transport::SnapshotChunk;
+    use crate::network::snapshot_transport::SnapshotChunkNetwork;
     use crate::network::snapshot_transport::SnapshotTransport;
+    use crate::network::snapshot_transport::Streaming;
+    use crate::network::Backoff;
     use crate::network::RPCOption;
     use crate::raft::AppendEntriesRequest;
     use crate::raft::AppendEntriesResponse;
     use crate::raft::InstallSnapshotRequest;
     use crate::raft::InstallSnapshotResponse;
+    use crate::raft::SnapshotChunkRequest;
+    use crate::raft::SnapshotChunkResponse;
     use crate::raft::VoteRequest;
     use crate::raft::VoteResponse;
+    use crate::replication::replication_status::SnapshotProgress;
     use crate::RaftNetwork;
     use crate::RaftTypeConfig;
     use crate::Snapshot;
@@ -423,7 +823,7 @@ mod tests {
                     },
                 };
                 let err = RaftError::APIError(InstallSnapshotError::SnapshotMismatch(mismatch));
-                Err(RPCError::RemoteError(crate::error::RemoteError::new(0, err)))
+                Err(RPCError::RemoteError(RemoteError::new(0, err)))
             } else {
                 Ok(InstallSnapshotResponse { vote: rpc.vote })
             }
@@ -441,8 +841,11 @@ mod tests {
             match_cnt: 4,
         };
 
+        let progress = Arc::new(SnapshotProgress::default());
+
         let mut opt = RPCOption::new(Duration::from_millis(100));
         opt.snapshot_chunk_size = Some(1);
+        opt.snapshot_progress = Some(progress.clone());
         let cancel = futures::future::pending();
 
         Chunked::send_snapshot(
@@ -463,5 +866,167 @@ mod tests {
         .unwrap();
 
         assert_eq!(net.received_offset, vec![0, 1, 2, 0, 1, 2]);
+        assert_eq!(Some(3), progress.received(), "resent chunks are not counted twice");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_chunks() -> anyhow::Result<()> {
+        let mut data = Cursor::new(vec![1, 2, 3, 4, 5]);
+        let chunks = snapshot_chunks(&mut data, 0, 2).collect::<Vec<_>>().await;
+        let chunks = chunks.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(chunks, vec![
+            SnapshotChunk {
+                offset: 0,
+                data: vec![1, 2],
+                done: false
+            },
+            SnapshotChunk {
+                offset: 2,
+                data: vec![3, 4],
+                done: false
+            },
+            SnapshotChunk {
+                offset: 4,
+                data: vec![5],
+                done: true
+            },
+        ]);
+
+        // Start from the end: a single empty chunk
+        let chunks = snapshot_chunks(&mut data, 5, 2).collect::<Vec<_>>().await;
+        let chunks = chunks.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(chunks, vec![SnapshotChunk {
+            offset: 5,
+            data: vec![],
+            done: true
+        }]);
+
+        Ok(())
+    }
+
+    /// A fake target that writes chunks into a [`Streaming`], and fails on some of the chunks.
+    struct ChunkNetwork {
+        streaming: Streaming<UTConfig>,
+        sent: Vec<u64>,
+
+        /// The index of the chunk that is received but the response is lost.
+        lose_response_at: usize,
+
+        /// The index of the chunk that is corrupted on the way.
+        corrupt_at: usize,
+
+        /// The index of the chunk upon which the target returns a [`Fatal`] error.
+        fatal_at: usize,
+
+        /// The number of backoffs built by the sender.
+        backoffs: AtomicUsize,
+    }
+
+    impl ChunkNetwork {
+        fn new(lose_response_at: usize, corrupt_at: usize, fatal_at: usize) -> Self {
+            Self {
+                streaming: Streaming::new("1-1-1-1".to_string(), Box::new(Cursor::new(vec![]))),
+                sent: vec![],
+                lose_response_at,
+                corrupt_at,
+                fatal_at,
+                backoffs: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl SnapshotChunkNetwork<UTConfig> for ChunkNetwork {
+        async fn snapshot_chunk(
+            &mut self,
+            mut rpc: SnapshotChunkRequest<UTConfig>,
+            _option: RPCOption,
+        ) -> Result<SnapshotChunkResponse<UTConfig>, RPCError<UTConfig, RaftError<UTConfig>>> {
+            let i = self.sent.len();
+            self.sent.push(rpc.offset);
+
+            if i == self.corrupt_at {
+                rpc.data[0] ^= 0xff;
+            }
+
+            if i == self.fatal_at {
+                return Err(RPCError::RemoteError(RemoteError::new(
+                    0,
+                    RaftError::Fatal(Fatal::Stopped),
+                )));
+            }
+
+            let vote = rpc.vote;
+            let offset = self.streaming.receive_chunk(rpc).await.unwrap();
+
+            if i == self.lose_response_at {
+                return Err(RPCError::Unreachable(Unreachable::new(&AnyError::error("lost"))));
+            }
+
+            Ok(SnapshotChunkResponse { vote, offset })
+        }
+
+        fn backoff(&self) -> Backoff {
+            self.backoffs.fetch_add(1, Ordering::Relaxed);
+            Backoff::new(std::iter::repeat(Duration::from_millis(1)))
+        }
+    }
+
+    fn snapshot(data: Vec<u8>) -> Snapshot<UTConfig> {
+        Snapshot::<UTConfig>::new(
+            SnapshotMeta {
+                last_log_id: None,
+                last_membership: StoredMembership::default(),
+                snapshot_id: "1-1-1-1".to_string(),
+            },
+            Box::new(Cursor::new(data)),
+        )
+    }
+
+    /// Test that `Resumable` continues from the offset the target has received after an error,
+    /// and resends a corrupted chunk.
+    #[tokio::test]
+    async fn test_resumable_resume_from_received_offset() {
+        let data = (0..10).collect::<Vec<u8>>();
+
+        let mut net = ChunkNetwork::new(3, 5, usize::MAX);
+        let progress = Arc::new(SnapshotProgress::default());
+
+        let mut opt = RPCOption::new(Duration::from_millis(100));
+        opt.snapshot_chunk_size = Some(2);
+        opt.snapshot_progress = Some(progress.clone());
+        let cancel = futures::future::pending();
+
+        Resumable::send_snapshot(&mut net, Vote::new(1, 0), snapshot(data.clone()), cancel, opt)
+            .await
+            .unwrap();
+
+        // - The response of chunk at offset 6 is lost, resend from 6, which is skipped by the target.
+        // - The chunk at offset 8 is corrupted, resend it.
+        assert_eq!(net.sent, vec![0, 2, 4, 6, 6, 8, 8]);
+        // Both resends are after a backoff.
+        assert_eq!(net.backoffs.load(Ordering::Relaxed), 2);
+        assert_eq!(net.streaming.into_snapshot_data().into_inner(), data);
+        assert_eq!(Some(10), progress.received(), "resent chunks are not counted twice");
+    }
+
+    /// Test that `Resumable` stops at once if the target returns a [`Fatal`] error.
+    #[tokio::test]
+    async fn test_resumable_stop_on_remote_fatal() {
+        let mut net = ChunkNetwork::new(usize::MAX, usize::MAX, 1);
+
+        let mut opt = RPCOption::new(Duration::from_millis(100));
+        opt.snapshot_chunk_size = Some(2);
+        let cancel = futures::future::pending();
+
+        let res = Resumable::send_snapshot(&mut net, Vote::new(1, 0), snapshot((0..10).collect()), cancel, opt).await;
+
+        assert_eq!(
+            Err(StreamingError::RemoteError(RemoteError::new(0, Fatal::Stopped))),
+            res.map(|_| ())
+        );
+        assert_eq!(net.sent, vec![0, 2]);
+        assert_eq!(net.backoffs.load(Ordering::Relaxed), 0);
     }
 }
@@ -1,18 +1,26 @@
 use std::future::Future;
 
+use crate::error::CheckIsLeaderError;
 use crate::error::Fatal;
 use crate::error::RPCError;
 use crate::error::RaftError;
 use crate::error::ReplicationClosed;
+use crate::error::SnapshotBaseMismatch;
 use crate::error::StreamingError;
 use crate::network::v2::RaftNetworkV2;
 use crate::network::Backoff;
 use crate::network::RPCOption;
 use crate::raft::AppendEntriesRequest;
 use crate::raft::AppendEntriesResponse;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
+use crate::raft::ReadIndexRequest;
+use crate::raft::ReadIndexResponse;
 use crate::raft::SnapshotResponse;
+use crate::raft::TransferLeaderRequest;
 use crate::raft::VoteRequest;
 use crate::raft::VoteResponse;
+use crate::storage::SnapshotSignature;
 use crate::OptionalSend;
 use crate::RaftNetwork;
 use crate::RaftTypeConfig;
@@ -41,6 +49,14 @@ where
         RaftNetwork::<C>::vote(self, rpc, option).await
     }
 
+    async fn pre_vote(
+        &mut self,
+        rpc: VoteRequest<C>,
+        option: RPCOption,
+    ) -> Result<VoteResponse<C>, RPCError<C, RaftError<C>>> {
+        RaftNetwork::<C>::pre_vote(self, rpc, option).await
+    }
+
     async fn full_snapshot(
         &mut self,
         vote: Vote<C::NodeId>,
@@ -55,6 +71,41 @@ where
         Ok(resp)
     }
 
+    async fn snapshot_delta(
+        &mut self,
+        vote: Vote<C::NodeId>,
+        base: SnapshotSignature<C::NodeId>,
+        delta: Snapshot<C>,
+        cancel: impl Future<Output = ReplicationClosed> + OptionalSend + 'static,
+        option: RPCOption,
+    ) -> Result<SnapshotResponse<C>, StreamingError<C, RaftError<C, SnapshotBaseMismatch<C>>>> {
+        RaftNetwork::<C>::snapshot_delta(self, vote, base, delta, cancel, option).await
+    }
+
+    async fn read_index(
+        &mut self,
+        rpc: ReadIndexRequest<C>,
+        option: RPCOption,
+    ) -> Result<ReadIndexResponse<C>, RPCError<C, RaftError<C, CheckIsLeaderError<C>>>> {
+        RaftNetwork::<C>::read_index(self, rpc, option).await
+    }
+
+    async fn handoff_leader(
+        &mut self,
+        rpc: HandoffLeaderRequest<C>,
+        option: RPCOption,
+    ) -> Result<HandoffLeaderResponse, RPCError<C, RaftError<C>>> {
+        RaftNetwork::<C>::handoff_leader(self, rpc, option).await
+    }
+
+    async fn transfer_leader(
+        &mut self,
+        req: TransferLeaderRequest<C>,
+        option: RPCOption,
+    ) -> Result<(), RPCError<C, RaftError<C>>> {
+        RaftNetwork::<C>::transfer_leader(self, req, option).await
+    }
+
     fn backoff(&self) -> Backoff {
         RaftNetwork::<C>::backoff(self)
     }
@@ -1,20 +1,30 @@
 use std::future::Future;
 use std::time::Duration;
 
+use anyerror::AnyError;
 use openraft_macros::add_async_trait;
 
+use crate::error::CheckIsLeaderError;
 use crate::error::Fatal;
 use crate::error::RPCError;
 use crate::error::RaftError;
 use crate::error::ReplicationClosed;
+use crate::error::SnapshotBaseMismatch;
 use crate::error::StreamingError;
+use crate::error::Unreachable;
 use crate::network::Backoff;
 use crate::network::RPCOption;
 use crate::raft::AppendEntriesRequest;
 use crate::raft::AppendEntriesResponse;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
+use crate::raft::ReadIndexRequest;
+use crate::raft::ReadIndexResponse;
 use crate::raft::SnapshotResponse;
+use crate::raft::TransferLeaderRequest;
 use crate::raft::VoteRequest;
 use crate::raft::VoteResponse;
+use crate::storage::SnapshotSignature;
 use crate::OptionalSend;
 use crate::OptionalSync;
 use crate::RaftTypeConfig;
@@ -54,6 +64,27 @@ where C: RaftTypeConfig
         option: RPCOption,
     ) -> Result<VoteResponse<C>, RPCError<C, RaftError<C>>>;
 
+    /// Send a PreVote RPC to the target.
+    ///
+    /// It is sent by a node before it starts an election, if [`Config::enable_pre_vote`] is
+    /// enabled. The receiving node should pass it to [`Raft::pre_vote()`].
+    ///
+    /// The default implementation returns [`Unreachable`]; a node can not be elected with
+    /// `enable_pre_vote` on unless this method is implemented.
+    ///
+    /// [`Config::enable_pre_vote`]: crate::Config::enable_pre_vote
+    /// [`Raft::pre_vote()`]: crate::Raft::pre_vote
+    /// [`Unreachable`]: crate::error::Unreachable
+    async fn pre_vote(
+        &mut self,
+        _rpc: VoteRequest<C>,
+        _option: RPCOption,
+    ) -> Result<VoteResponse<C>, RPCError<C, RaftError<C>>> {
+        Err(RPCError::Unreachable(Unreachable::new(&AnyError::error(
+            "pre_vote is not implemented",
+        ))))
+    }
+
     /// Send a complete Snapshot to the target.
     ///
     /// This method is responsible to fragment the snapshot and send it to the target node.
@@ -77,6 +108,107 @@ where C: RaftTypeConfig
         option: RPCOption,
     ) -> Result<SnapshotResponse<C>, StreamingError<C, Fatal<C>>>;
 
+    /// Send a snapshot delta to the target.
+    ///
+    /// It is called instead of [`Self::full_snapshot`] if [`Config::enable_snapshot_delta`] is
+    /// enabled and the target reported having the snapshot `base` in its last
+    /// [`SnapshotResponse`]. The `delta`
+    /// is built by [`RaftStateMachine::get_snapshot_delta()`] and contains only the changes since
+    /// `base`.
+    ///
+    /// Like [`Self::full_snapshot`], the delta should be completely transmitted before returning.
+    /// When the follower finished receiving it, it calls [`Raft::install_snapshot_delta()`] with
+    /// `vote` and `base`, and returns the [`SnapshotBaseMismatch`] error, if any, as a
+    /// [`RemoteError`]. The leader then resends at once, a delta upon the snapshot the target has,
+    /// or a full snapshot.
+    ///
+    /// The default implementation returns [`Unreachable`], upon which the leader sends a full
+    /// snapshot at once; snapshot deltas do not work unless this method is implemented.
+    ///
+    /// [`Config::enable_snapshot_delta`]: crate::Config::enable_snapshot_delta
+    /// [`RaftStateMachine::get_snapshot_delta()`]: crate::storage::RaftStateMachine::get_snapshot_delta
+    /// [`Raft::install_snapshot_delta()`]: crate::Raft::install_snapshot_delta
+    /// [`SnapshotBaseMismatch`]: crate::error::SnapshotBaseMismatch
+    /// [`RemoteError`]: crate::error::RemoteError
+    /// [`Unreachable`]: crate::error::Unreachable
+    async fn snapshot_delta(
+        &mut self,
+        _vote: Vote<C::NodeId>,
+        _base: SnapshotSignature<C::NodeId>,
+        _delta: Snapshot<C>,
+        _cancel: impl Future<Output = ReplicationClosed> + OptionalSend + 'static,
+        _option: RPCOption,
+    ) -> Result<SnapshotResponse<C>, StreamingError<C, RaftError<C, SnapshotBaseMismatch<C>>>> {
+        Err(StreamingError::Unreachable(Unreachable::new(&AnyError::error(
+            "snapshot_delta is not implemented",
+        ))))
+    }
+
+    /// Send a ReadIndex RPC to the leader.
+    ///
+    /// It is sent by a non-leader node when [`Raft::read_index()`] is called on it.
+    /// The receiving leader should pass it to [`Raft::handle_read_index()`].
+    ///
+    /// The default implementation returns [`Unreachable`]; reading on a non-leader node does not
+    /// work unless this method is implemented.
+    ///
+    /// [`Raft::read_index()`]: crate::Raft::read_index
+    /// [`Raft::handle_read_index()`]: crate::Raft::handle_read_index
+    /// [`Unreachable`]: crate::error::Unreachable
+    async fn read_index(
+        &mut self,
+        _rpc: ReadIndexRequest<C>,
+        _option: RPCOption,
+    ) -> Result<ReadIndexResponse<C>, RPCError<C, RaftError<C, CheckIsLeaderError<C>>>> {
+        Err(RPCError::Unreachable(Unreachable::new(&AnyError::error(
+            "read_index is not implemented",
+        ))))
+    }
+
+    /// Send a HandoffLeader RPC to the leader.
+    ///
+    /// It is sent by a follower with a non-zero [`Config::election_priority`], reporting the
+    /// priority to the leader. The receiving leader should pass it to
+    /// [`Raft::handle_handoff_leader()`].
+    ///
+    /// The default implementation returns [`Unreachable`]; priority based leader placement does
+    /// not work unless this method is implemented.
+    ///
+    /// [`Config::election_priority`]: crate::Config::election_priority
+    /// [`Raft::handle_handoff_leader()`]: crate::Raft::handle_handoff_leader
+    /// [`Unreachable`]: crate::error::Unreachable
+    async fn handoff_leader(
+        &mut self,
+        _rpc: HandoffLeaderRequest<C>,
+        _option: RPCOption,
+    ) -> Result<HandoffLeaderResponse, RPCError<C, RaftError<C>>> {
+        Err(RPCError::Unreachable(Unreachable::new(&AnyError::error(
+            "handoff_leader is not implemented",
+        ))))
+    }
+
+    /// Send a TransferLeader message to the target.
+    ///
+    /// It is sent by the Leader to every voter when it hands off leadership with
+    /// [`Raft::transfer_leader()`]. The receiving node should pass it to
+    /// [`Raft::handle_transfer_leader()`].
+    ///
+    /// The default implementation returns [`Unreachable`]; leadership transfer does not work
+    /// unless this method is implemented.
+    ///
+    /// [`Raft::transfer_leader()`]: crate::Raft::transfer_leader
+    /// [`Raft::handle_transfer_leader()`]: crate::Raft::handle_transfer_leader
+    /// [`Unreachable`]: crate::error::Unreachable
+    async fn transfer_leader(
+        &mut self,
+        _req: TransferLeaderRequest<C>,
+        _option: RPCOption,
+    ) -> Result<(), RPCError<C, RaftError<C>>> {
+        Err(RPCError::Unreachable(Unreachable::new(&AnyError::error(
+            "transfer_leader is not implemented",
+        ))))
+    }
+
     /// Build a backoff instance if the target node is temporarily(or permanently) unreachable.
     ///
     /// When a [`Unreachable`](`crate::error::Unreachable`) error is returned from the `Network`
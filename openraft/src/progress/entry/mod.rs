@@ -222,6 +222,20 @@ impl<NID: NodeId> ProgressEntry<NID> {
         Ok(&self.inflight)
     }
 
+    /// Return the max number of entries to send in the next [`Inflight`].
+    ///
+    /// Once the last matching log id is found, the logs to send are pipelined in up to `window`
+    /// AppendEntries RPCs, each of which has at most `max_payload_entries` entries.
+    /// While searching for the matching log id, a conflict is expected, thus only one RPC is sent
+    /// at a time.
+    pub(crate) fn max_sending_entries(&self, max_payload_entries: u64, window: u64) -> u64 {
+        if self.matching.next_index() < self.searching_end {
+            max_payload_entries
+        } else {
+            max_payload_entries * window
+        }
+    }
+
     /// Return the index range(`[start,end]`) of the first log in the next AppendEntries.
     ///
     /// The returned range is left close and right close.
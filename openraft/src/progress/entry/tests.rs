@@ -320,3 +320,23 @@ fn test_next_send() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_max_sending_entries() -> anyhow::Result<()> {
+    // Searching for the matching log id: one AppendEntries at a time.
+    {
+        let mut pe = ProgressEntry::empty(20);
+        pe.matching = Some(log_id(7));
+        assert_eq!(5, pe.max_sending_entries(5, 3));
+    }
+
+    // The matching log id is found: pipeline `window` AppendEntries.
+    {
+        let mut pe = ProgressEntry::empty(8);
+        pe.matching = Some(log_id(7));
+        assert_eq!(15, pe.max_sending_entries(5, 3));
+        assert_eq!(5, pe.max_sending_entries(5, 1));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Lifecycle events of a Raft node.
+
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+
+use futures::Stream;
+use tokio::sync::mpsc;
+
+use crate::error::Fatal;
+use crate::LogId;
+use crate::RaftTypeConfig;
+use crate::SnapshotMeta;
+use crate::StoredMembership;
+use crate::Vote;
+
+/// A lifecycle transition of a Raft node, received from [`Raft::subscribe_events()`].
+///
+/// Unlike [`RaftMetrics`], which only keeps the latest state, every transition is delivered in
+/// the order it happens.
+///
+/// [`Raft::subscribe_events()`]: crate::Raft::subscribe_events
+/// [`RaftMetrics`]: crate::RaftMetrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub enum RaftEvent<C>
+where C: RaftTypeConfig
+{
+    /// This node became a leader, with a committed `vote`.
+    BecameLeader { vote: Vote<C::NodeId> },
+
+    /// This node quit the leadership it established with `vote`.
+    QuitLeader { vote: Vote<C::NodeId> },
+
+    /// The term of this node increased, i.e., a vote of a greater term is persisted.
+    TermChanged { from: u64, to: u64 },
+
+    /// A membership config log is committed.
+    ///
+    /// It is sent when the membership log is applied, thus a membership that is committed but
+    /// replaced by a snapshot before being applied is not sent.
+    MembershipCommitted { membership: StoredMembership<C> },
+
+    /// This node built a snapshot.
+    SnapshotBuilt { meta: SnapshotMeta<C> },
+
+    /// This node installed a snapshot received from the leader.
+    SnapshotInstalled { meta: SnapshotMeta<C> },
+
+    /// The logs up to `upto`, inclusive, are purged from the log store.
+    LogPurged { upto: LogId<C::NodeId> },
+
+    /// For a leader, a replication RPC to a follower or learner failed, after the previous one
+    /// succeeded.
+    FollowerUnreachable { target: C::NodeId, error: String },
+
+    /// For a leader, a replication RPC to a follower or learner succeeded, after the previous one
+    /// failed.
+    FollowerRecovered { target: C::NodeId },
+
+    /// A leadership transfer started with [`Raft::transfer_leader()`] completed: `to` is seen as
+    /// the leader by this node.
+    ///
+    /// [`Raft::transfer_leader()`]: crate::Raft::transfer_leader
+    LeaderTransferred { to: C::NodeId },
+
+    /// A leadership transfer to `to` did not complete in time and is cancelled. This node resumes
+    /// accepting writes if it is still the leader.
+    LeaderTransferCancelled { to: C::NodeId, error: String },
+
+    /// Raft stopped, with [`Fatal::Stopped`] if it is shut down normally.
+    ///
+    /// It is the last event; the event stream ends after it.
+    Fatal { error: Fatal<C> },
+}
+
+impl<C> fmt::Display for RaftEvent<C>
+where C: RaftTypeConfig
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaftEvent::BecameLeader { vote } => write!(f, "BecameLeader: vote: {}", vote),
+            RaftEvent::QuitLeader { vote } => write!(f, "QuitLeader: vote: {}", vote),
+            RaftEvent::TermChanged { from, to } => write!(f, "TermChanged: {} -> {}", from, to),
+            RaftEvent::MembershipCommitted { membership } => write!(f, "MembershipCommitted: {}", membership),
+            RaftEvent::SnapshotBuilt { meta } => write!(f, "SnapshotBuilt: {}", meta),
+            RaftEvent::SnapshotInstalled { meta } => write!(f, "SnapshotInstalled: {}", meta),
+            RaftEvent::LogPurged { upto } => write!(f, "LogPurged: upto: {}", upto),
+            RaftEvent::FollowerUnreachable { target, error } => {
+                write!(f, "FollowerUnreachable: target: {}, error: {}", target, error)
+            }
+            RaftEvent::FollowerRecovered { target } => write!(f, "FollowerRecovered: target: {}", target),
+            RaftEvent::LeaderTransferred { to } => write!(f, "LeaderTransferred: to: {}", to),
+            RaftEvent::LeaderTransferCancelled { to, error } => {
+                write!(f, "LeaderTransferCancelled: to: {}, error: {}", to, error)
+            }
+            RaftEvent::Fatal { error } => write!(f, "Fatal: {}", error),
+        }
+    }
+}
+
+/// A stream of [`RaftEvent`], returned by [`Raft::subscribe_events()`].
+///
+/// It ends after the [`RaftEvent::Fatal`] event, when Raft stops.
+/// Events are buffered without a limit until they are received, thus a subscriber should keep
+/// consuming it, or drop it.
+///
+/// [`Raft::subscribe_events()`]: crate::Raft::subscribe_events
+pub struct EventStream<C>
+where C: RaftTypeConfig
+{
+    rx: mpsc::UnboundedReceiver<RaftEvent<C>>,
+}
+
+impl<C> EventStream<C>
+where C: RaftTypeConfig
+{
+    /// Receive the next event, or `None` if Raft has stopped and all events are received.
+    pub async fn recv(&mut self) -> Option<RaftEvent<C>> {
+        self.rx.recv().await
+    }
+}
+
+impl<C> Stream for EventStream<C>
+where C: RaftTypeConfig
+{
+    type Item = RaftEvent<C>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Sends events to every [`EventStream`].
+///
+/// It is cheap to clone and all clones share the same subscribers.
+pub(crate) struct EventSender<C>
+where C: RaftTypeConfig
+{
+    /// `None` once Raft stopped and no more event will be sent.
+    subscribers: Arc<Mutex<Option<Vec<mpsc::UnboundedSender<RaftEvent<C>>>>>>,
+}
+
+impl<C> Clone for EventSender<C>
+where C: RaftTypeConfig
+{
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<C> EventSender<C>
+where C: RaftTypeConfig
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            subscribers: Arc::new(Mutex::new(Some(vec![]))),
+        }
+    }
+
+    /// Create a stream that receives the events sent from now on.
+    ///
+    /// If Raft has stopped, the stream ends at once.
+    pub(crate) fn subscribe(&self) -> EventStream<C> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        if let Some(subscribers) = self.subscribers.lock().unwrap().as_mut() {
+            subscribers.push(tx);
+        }
+        EventStream { rx }
+    }
+
+    /// Send an event to every subscriber, and forget the ones that are dropped.
+    pub(crate) fn send(&self, event: RaftEvent<C>) {
+        tracing::debug!("send event: {}", event);
+
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if let Some(subscribers) = subscribers.as_mut() {
+            subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+        }
+    }
+
+    /// Drop all subscribers, to end their streams. Subscribers added after this end at once.
+    pub(crate) fn close(&self) {
+        self.subscribers.lock().unwrap().take();
+    }
+}
@@ -2,11 +2,16 @@
 //! Blocking mode write API blocks until the write operation is completed,
 //! where [`RaftTypeConfig::Responder`] is a [`OneshotResponder`].
 
+use std::time::Duration;
+
 use maplit::btreemap;
+use maplit::btreeset;
 
 use crate::core::raft_msg::RaftMsg;
 use crate::error::ClientWriteError;
+use crate::error::LearnerLagging;
 use crate::error::RaftError;
+use crate::metrics::WaitError;
 use crate::raft::message::ClientWriteResult;
 use crate::raft::responder::OneshotResponder;
 use crate::raft::ClientWriteResponse;
@@ -166,6 +171,65 @@ where C: RaftTypeConfig<Responder = OneshotResponder<C>>
 
         Ok(resp)
     }
+
+    /// Promote a learner to a voter once it catches up with the leader.
+    ///
+    /// This method must be called on the leader. It keeps the learner `id` non-voting until the
+    /// distance between its matching log id and the leader's last log index is no more than
+    /// [`Config::replication_lag_threshold`], then it changes the membership to add `id` as a
+    /// voter, through a **joint** config, the same as [`Raft::change_membership()`] does.
+    /// Removed voters are retained as learners.
+    ///
+    /// The returned future resolves when the uniform config containing `id` as a voter is
+    /// committed.
+    /// If the learner does not catch up within `timeout`, it returns [`LearnerLagging`] and the
+    /// membership is not changed.
+    ///
+    /// [`Config::replication_lag_threshold`]: crate::Config::replication_lag_threshold
+    /// [`LearnerLagging`]: crate::error::LearnerLagging
+    #[tracing::instrument(level = "debug", skip(self, id), fields(target=display(id)))]
+    pub async fn promote_learner(
+        &self,
+        id: C::NodeId,
+        timeout: Duration,
+    ) -> Result<ClientWriteResponse<C>, RaftError<C, ClientWriteError<C>>> {
+        let wait_res = self
+            .wait(Some(timeout))
+            .metrics(
+                |metrics| self.check_replication_upto_date(metrics, id, None).is_ok(),
+                "wait learner to catch up",
+            )
+            .await;
+
+        match wait_res {
+            Ok(_) => {}
+            Err(WaitError::ShuttingDown) => {
+                let fatal =
+                    self.inner.get_core_stopped_error("waiting for learner to catch up", None::<&'static str>).await;
+                return Err(RaftError::Fatal(fatal));
+            }
+            Err(WaitError::Timeout(_, _)) => {
+                let metrics = self.metrics().borrow().clone();
+                let matching = metrics.replication.as_ref().and_then(|r| r.get(&id).copied().flatten());
+
+                let err = LearnerLagging {
+                    node_id: id,
+                    matching,
+                    last_log_index: metrics.last_log_index,
+                    timeout,
+                };
+                tracing::info!(error = display(&err), "learner did not catch up, do not promote it");
+
+                return Err(RaftError::APIError(ClientWriteError::ChangeMembershipError(err.into())));
+            }
+        }
+
+        tracing::info!("learner caught up, promote it to voter");
+
+        // If this node is no longer the leader, or `id` is not a learner,
+        // change_membership() returns the corresponding error.
+        self.change_membership(ChangeMembers::AddVoterIds(btreeset! {id}), true).await
+    }
 }
 
 fn oneshot_channel<C>() -> (OneshotResponder<C>, OneshotReceiverOf<C, ClientWriteResult<C>>)
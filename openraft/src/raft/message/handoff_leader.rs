@@ -0,0 +1,63 @@
+use std::fmt;
+
+use crate::RaftTypeConfig;
+use crate::Vote;
+
+/// A request sent by a follower to the leader, reporting its election priority.
+///
+/// It is sent periodically by a voter with a non-zero [`Config::election_priority`].
+/// The leader hands off leadership to the voter with the highest reported priority, if the
+/// priority is higher than its own and the voter has replicated all logs of the leader.
+///
+/// [`Config::election_priority`]: crate::Config::election_priority
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct HandoffLeaderRequest<C: RaftTypeConfig> {
+    /// The vote of the leader, as seen by the sender.
+    pub vote: Vote<C::NodeId>,
+
+    /// The node reporting its priority.
+    pub from: C::NodeId,
+
+    /// The election priority of the node `from`.
+    pub priority: u64,
+}
+
+impl<C> fmt::Display for HandoffLeaderRequest<C>
+where C: RaftTypeConfig
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{vote:{}, from:{}, priority:{}}}",
+            self.vote, self.from, self.priority
+        )
+    }
+}
+
+impl<C> HandoffLeaderRequest<C>
+where C: RaftTypeConfig
+{
+    pub fn new(vote: Vote<C::NodeId>, from: C::NodeId, priority: u64) -> Self {
+        Self { vote, from, priority }
+    }
+}
+
+/// The response to a [`HandoffLeaderRequest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct HandoffLeaderResponse {
+    /// The election priority of the leader.
+    pub priority: u64,
+
+    /// Whether the requesting node has a higher priority than the leader, i.e., the leader will
+    /// hand off leadership to it once it catches up, unless another voter has an even higher
+    /// priority.
+    pub accepted: bool,
+}
+
+impl fmt::Display for HandoffLeaderResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{priority:{}, accepted:{}}}", self.priority, self.accepted)
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use crate::storage::SnapshotSignature;
 use crate::RaftTypeConfig;
 use crate::SnapshotMeta;
 use crate::Vote;
@@ -51,15 +52,28 @@ pub struct InstallSnapshotResponse<C: RaftTypeConfig> {
 #[derive(Debug)]
 #[derive(PartialEq, Eq)]
 #[derive(derive_more::Display)]
-#[display(fmt = "SnapshotResponse{{vote:{}}}", vote)]
+#[display(fmt = "SnapshotResponse{{vote:{}, snapshot:{:?}}}", vote, snapshot)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
 pub struct SnapshotResponse<C: RaftTypeConfig> {
     pub vote: Vote<C::NodeId>,
+
+    /// The current snapshot of the receiver after handling the request, if it has one.
+    ///
+    /// The leader builds the next snapshot delta upon it, see
+    /// [`Config::enable_snapshot_delta`](crate::Config::enable_snapshot_delta).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub snapshot: Option<SnapshotSignature<C::NodeId>>,
 }
 
 impl<C: RaftTypeConfig> SnapshotResponse<C> {
     pub fn new(vote: Vote<C::NodeId>) -> Self {
-        Self { vote }
+        Self { vote, snapshot: None }
+    }
+
+    /// Set the current snapshot of the receiver.
+    pub fn with_snapshot(mut self, snapshot: Option<SnapshotSignature<C::NodeId>>) -> Self {
+        self.snapshot = snapshot;
+        self
     }
 }
 
@@ -4,7 +4,11 @@
 //! and are also used by network layer to talk to other Raft nodes.
 
 mod append_entries;
+mod handoff_leader;
 mod install_snapshot;
+mod read_index;
+mod snapshot_chunk;
+mod transfer_leader;
 mod vote;
 
 mod client_write;
@@ -13,8 +17,15 @@ pub use append_entries::AppendEntriesRequest;
 pub use append_entries::AppendEntriesResponse;
 pub use client_write::ClientWriteResponse;
 pub use client_write::ClientWriteResult;
+pub use handoff_leader::HandoffLeaderRequest;
+pub use handoff_leader::HandoffLeaderResponse;
 pub use install_snapshot::InstallSnapshotRequest;
 pub use install_snapshot::InstallSnapshotResponse;
 pub use install_snapshot::SnapshotResponse;
+pub use read_index::ReadIndexRequest;
+pub use read_index::ReadIndexResponse;
+pub use snapshot_chunk::SnapshotChunkRequest;
+pub use snapshot_chunk::SnapshotChunkResponse;
+pub use transfer_leader::TransferLeaderRequest;
 pub use vote::VoteRequest;
 pub use vote::VoteResponse;
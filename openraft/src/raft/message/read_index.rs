@@ -0,0 +1,47 @@
+use std::fmt;
+
+use crate::display_ext::DisplayOptionExt;
+use crate::LogId;
+use crate::RaftTypeConfig;
+
+/// An RPC sent by a non-leader node to the leader to get a read index for a linearizable read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct ReadIndexRequest<C: RaftTypeConfig> {
+    /// The node that requests the read index.
+    pub from: C::NodeId,
+}
+
+impl<C> fmt::Display for ReadIndexRequest<C>
+where C: RaftTypeConfig
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{from:{}}}", self.from)
+    }
+}
+
+impl<C> ReadIndexRequest<C>
+where C: RaftTypeConfig
+{
+    pub fn new(from: C::NodeId) -> Self {
+        Self { from }
+    }
+}
+
+/// The response to a `ReadIndexRequest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct ReadIndexResponse<C: RaftTypeConfig> {
+    /// The log id up to which the state machine has to apply to serve a linearizable read.
+    ///
+    /// The leadership of the leader is confirmed by a quorum when this is returned.
+    pub read_log_id: Option<LogId<C::NodeId>>,
+}
+
+impl<C> fmt::Display for ReadIndexResponse<C>
+where C: RaftTypeConfig
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{read_log_id:{}}}", self.read_log_id.display())
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use crate::RaftTypeConfig;
+use crate::SnapshotMeta;
+use crate::Vote;
+
+/// An RPC sent by the Raft leader to send a chunk of a snapshot to a follower, with
+/// [`Resumable`] snapshot transport.
+///
+/// Unlike [`InstallSnapshotRequest`], every chunk carries a checksum of its data, so that a
+/// corrupted chunk is detected and sent again.
+///
+/// [`Resumable`]: crate::network::snapshot_transport::Resumable
+/// [`InstallSnapshotRequest`]: crate::raft::InstallSnapshotRequest
+#[derive(Clone, Debug)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct SnapshotChunkRequest<C: RaftTypeConfig> {
+    pub vote: Vote<C::NodeId>,
+
+    /// Metadata of a snapshot: snapshot_id, last_log_ed membership etc.
+    pub meta: SnapshotMeta<C>,
+
+    /// The byte offset where this chunk of data is positioned in the snapshot file.
+    pub offset: u64,
+
+    /// The raw bytes of the snapshot chunk, starting at `offset`.
+    pub data: Vec<u8>,
+
+    /// The CRC32 checksum of `data`.
+    pub checksum: u32,
+
+    /// Will be `true` if this is the last chunk in the snapshot.
+    pub done: bool,
+}
+
+impl<C: RaftTypeConfig> SnapshotChunkRequest<C> {
+    /// Create a request of a chunk of snapshot and calculate the checksum of `data`.
+    pub fn new(vote: Vote<C::NodeId>, meta: SnapshotMeta<C>, offset: u64, data: Vec<u8>, done: bool) -> Self {
+        let checksum = crc32fast::hash(&data);
+        Self {
+            vote,
+            meta,
+            offset,
+            data,
+            checksum,
+            done,
+        }
+    }
+
+    /// Return `true` if `data` matches the `checksum`.
+    pub fn verify_checksum(&self) -> bool {
+        crc32fast::hash(&self.data) == self.checksum
+    }
+
+    /// The byte offset right after the end of this chunk.
+    pub fn end(&self) -> u64 {
+        self.offset + self.data.len() as u64
+    }
+}
+
+impl<C: RaftTypeConfig> fmt::Display for SnapshotChunkRequest<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SnapshotChunkRequest {{ vote:{}, meta:{}, offset:{}, len:{}, checksum:{:08x}, done:{} }}",
+            self.vote,
+            self.meta,
+            self.offset,
+            self.data.len(),
+            self.checksum,
+            self.done
+        )
+    }
+}
+
+/// The response to a [`SnapshotChunkRequest`].
+#[derive(Debug)]
+#[derive(PartialEq, Eq)]
+#[derive(derive_more::Display)]
+#[display(fmt = "{{vote:{}, offset:{}}}", vote, offset)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct SnapshotChunkResponse<C: RaftTypeConfig> {
+    pub vote: Vote<C::NodeId>,
+
+    /// The offset up to which the snapshot data is received by the follower.
+    ///
+    /// The leader continues sending from this offset.
+    pub offset: u64,
+}
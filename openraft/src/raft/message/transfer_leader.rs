@@ -0,0 +1,65 @@
+use std::fmt;
+
+use crate::display_ext::DisplayOptionExt;
+use crate::LogId;
+use crate::RaftTypeConfig;
+use crate::Vote;
+
+/// A request sent by the Leader to every voter to hand off leadership to another node.
+///
+/// Upon receiving this request, a node disables the leader lease of `from_leader`, so that it can
+/// grant a vote to the next leader at once.
+/// The node `to_node_id` starts an election at once, if its last log id is at least `last_log_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(bound = ""))]
+pub struct TransferLeaderRequest<C: RaftTypeConfig> {
+    /// The vote of the Leader that is transferring the leadership.
+    pub(crate) from_leader: Vote<C::NodeId>,
+
+    /// The node to become the next Leader.
+    pub(crate) to_node_id: C::NodeId,
+
+    /// The last log id the `to_node_id` node has to have to become the next Leader.
+    pub(crate) last_log_id: Option<LogId<C::NodeId>>,
+}
+
+impl<C> fmt::Display for TransferLeaderRequest<C>
+where C: RaftTypeConfig
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{from_leader:{}, to:{}, last_log:{}}}",
+            self.from_leader,
+            self.to_node_id,
+            self.last_log_id.display(),
+        )
+    }
+}
+
+impl<C> TransferLeaderRequest<C>
+where C: RaftTypeConfig
+{
+    pub fn new(from_leader: Vote<C::NodeId>, to_node_id: C::NodeId, last_log_id: Option<LogId<C::NodeId>>) -> Self {
+        Self {
+            from_leader,
+            to_node_id,
+            last_log_id,
+        }
+    }
+
+    /// The vote of the Leader that is transferring the leadership.
+    pub fn from_leader(&self) -> &Vote<C::NodeId> {
+        &self.from_leader
+    }
+
+    /// The node to become the next Leader.
+    pub fn to_node_id(&self) -> &C::NodeId {
+        &self.to_node_id
+    }
+
+    /// The last log id the `to_node_id` node has to have to become the next Leader.
+    pub fn last_log_id(&self) -> Option<&LogId<C::NodeId>> {
+        self.last_log_id.as_ref()
+    }
+}
@@ -9,10 +9,12 @@
 //! to efficiently share access.
 
 #[cfg(test)] mod declare_raft_types_test;
+pub(crate) mod event;
 mod external_request;
 mod impl_raft_blocking_write;
 pub(crate) mod message;
 mod raft_inner;
+mod read_policy;
 pub mod responder;
 mod runtime_config_handle;
 pub mod trigger;
@@ -30,15 +32,25 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use core_state::CoreState;
+pub use event::EventStream;
+pub use event::RaftEvent;
 pub use message::AppendEntriesRequest;
 pub use message::AppendEntriesResponse;
 pub use message::ClientWriteResponse;
 pub use message::ClientWriteResult;
+pub use message::HandoffLeaderRequest;
+pub use message::HandoffLeaderResponse;
 pub use message::InstallSnapshotRequest;
 pub use message::InstallSnapshotResponse;
+pub use message::ReadIndexRequest;
+pub use message::ReadIndexResponse;
+pub use message::SnapshotChunkRequest;
+pub use message::SnapshotChunkResponse;
 pub use message::SnapshotResponse;
+pub use message::TransferLeaderRequest;
 pub use message::VoteRequest;
 pub use message::VoteResponse;
+pub use read_policy::ReadPolicy;
 use tokio::sync::mpsc;
 use tokio::sync::watch;
 use tokio::sync::Mutex;
@@ -64,18 +76,29 @@ use crate::error::Fatal;
 use crate::error::Infallible;
 use crate::error::InitializeError;
 use crate::error::RaftError;
+use crate::error::ReadIndexError;
+use crate::error::SnapshotBaseMismatch;
+use crate::error::TargetLagging;
+use crate::error::TransferLeaderError;
 use crate::membership::IntoNodes;
+use crate::metrics::Health;
+use crate::metrics::HealthThresholds;
+use crate::metrics::PerfMetrics;
 use crate::metrics::RaftDataMetrics;
 use crate::metrics::RaftMetrics;
 use crate::metrics::RaftServerMetrics;
 use crate::metrics::Wait;
 use crate::metrics::WaitError;
+use crate::network::RPCTypes;
+use crate::raft::event::EventSender;
 use crate::raft::raft_inner::RaftInner;
 use crate::raft::responder::Responder;
 pub use crate::raft::runtime_config_handle::RuntimeConfigHandle;
 use crate::raft::trigger::Trigger;
+use crate::replication::snapshot_throttle::SnapshotThrottle;
 use crate::storage::RaftLogStorage;
 use crate::storage::RaftStateMachine;
+use crate::storage::SnapshotSignature;
 use crate::type_config::alias::AsyncRuntimeOf;
 use crate::type_config::alias::JoinErrorOf;
 use crate::type_config::alias::ResponderOf;
@@ -244,6 +267,8 @@ where C: RaftTypeConfig
         let (tx_metrics, rx_metrics) = watch::channel(RaftMetrics::new_initial(id));
         let (tx_data_metrics, rx_data_metrics) = watch::channel(RaftDataMetrics::default());
         let (tx_server_metrics, rx_server_metrics) = watch::channel(RaftServerMetrics::default());
+        let (tx_perf_metrics, rx_perf_metrics) = watch::channel(PerfMetrics::default());
+        let events = EventSender::new();
         let (tx_shutdown, rx_shutdown) = C::AsyncRuntime::oneshot();
 
         let tick_handle = Tick::spawn(
@@ -266,7 +291,11 @@ where C: RaftTypeConfig
 
         let state = {
             let mut helper = StorageHelper::new(&mut log_store, &mut state_machine);
-            helper.get_initial_state().await?
+            let mut state = helper.get_initial_state().await?;
+            if config.snapshot_policy.uses_log_bytes() {
+                helper.load_log_bytes(&mut state).await?;
+            }
+            state
         };
 
         let engine = Engine::new(state, eng_config);
@@ -287,6 +316,8 @@ where C: RaftTypeConfig
 
             leader_data: None,
 
+            snapshot_throttle: Arc::new(SnapshotThrottle::new(&config, tx_notify.clone())),
+
             tx_api: tx_api.clone(),
             rx_api,
 
@@ -296,6 +327,9 @@ where C: RaftTypeConfig
             tx_metrics,
             tx_data_metrics,
             tx_server_metrics,
+            tx_perf_metrics,
+            perf_metrics: PerfMetrics::default(),
+            events: events.clone(),
 
             command_state: CommandState::default(),
             span: core_span,
@@ -314,6 +348,8 @@ where C: RaftTypeConfig
             rx_metrics,
             rx_data_metrics,
             rx_server_metrics,
+            rx_perf_metrics,
+            events,
             tx_shutdown: Mutex::new(Some(tx_shutdown)),
             core_state: Mutex::new(CoreState::Running(core_handle)),
 
@@ -378,6 +414,19 @@ where C: RaftTypeConfig
         self.inner.call_core(RaftMsg::RequestVote { rpc, tx }, rx).await
     }
 
+    /// Submit a PreVote RPC to this Raft node.
+    ///
+    /// A PreVote request is sent by a node before it starts an election, if
+    /// [`Config::enable_pre_vote`] is enabled.
+    /// This node replies whether it would grant the vote, without changing its own state.
+    #[tracing::instrument(level = "debug", skip(self, rpc))]
+    pub async fn pre_vote(&self, rpc: VoteRequest<C>) -> Result<VoteResponse<C>, RaftError<C>> {
+        tracing::info!(rpc = display(&rpc), "Raft::pre_vote()");
+
+        let (tx, rx) = C::AsyncRuntime::oneshot();
+        self.inner.call_core(RaftMsg::RequestPreVote { rpc, tx }, rx).await
+    }
+
     /// Get the latest snapshot from the state machine.
     ///
     /// It returns error only when `RaftCore` fails to serve the request, e.g., Encountering a
@@ -425,6 +474,38 @@ where C: RaftTypeConfig
         }
     }
 
+    /// Install a completely received snapshot delta to the state machine.
+    ///
+    /// This method is used on the follower side of [`RaftNetworkV2::snapshot_delta()`].
+    /// The delta is built by the leader upon the snapshot `base`.
+    ///
+    /// It returns [`SnapshotBaseMismatch`] if `base` is not the current snapshot of this node,
+    /// and the leader should send a full snapshot instead.
+    ///
+    /// [`RaftNetworkV2::snapshot_delta()`]: crate::network::v2::RaftNetworkV2::snapshot_delta
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn install_snapshot_delta(
+        &self,
+        vote: Vote<C::NodeId>,
+        base: SnapshotSignature<C::NodeId>,
+        snapshot: Snapshot<C>,
+    ) -> Result<SnapshotResponse<C>, RaftError<C, SnapshotBaseMismatch<C>>> {
+        tracing::info!("Raft::install_snapshot_delta()");
+
+        let (tx, rx) = C::AsyncRuntime::oneshot();
+        self.inner
+            .call_core(
+                RaftMsg::InstallSnapshotDelta {
+                    vote,
+                    base,
+                    snapshot,
+                    tx,
+                },
+                rx,
+            )
+            .await
+    }
+
     /// Receive an `InstallSnapshotRequest`.
     ///
     /// These RPCs are sent by the cluster leader in order to bring a new node or a slow node
@@ -473,6 +554,57 @@ where C: RaftTypeConfig
         Ok(resp)
     }
 
+    /// Receive a `SnapshotChunkRequest` sent by [`Resumable::send_snapshot()`].
+    ///
+    /// The response carries the offset up to which the snapshot data is received, from which the
+    /// leader continues sending. The receiving state is kept in memory: after a restart, the
+    /// leader sends from offset 0 again.
+    /// If the last chunk is received, it installs the snapshot to the state machine.
+    ///
+    /// [`Resumable::send_snapshot()`]: crate::network::snapshot_transport::Resumable::send_snapshot
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn receive_snapshot_chunk(
+        &self,
+        req: SnapshotChunkRequest<C>,
+    ) -> Result<SnapshotChunkResponse<C>, RaftError<C>>
+    where
+        C::SnapshotData: tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin,
+    {
+        tracing::debug!(req = display(&req), "Raft::receive_snapshot_chunk()");
+
+        let my_vote = self.with_raft_state(|state| *state.vote_ref()).await?;
+
+        // Check vote to prevent unnecessary snapshot transfer early.
+        if req.vote < my_vote {
+            tracing::info!("vote {} is rejected by local vote: {}", req.vote, my_vote);
+            return Ok(SnapshotChunkResponse {
+                vote: my_vote,
+                offset: 0,
+            });
+        }
+
+        use crate::network::snapshot_transport::Resumable;
+
+        let mut streaming = self.inner.snapshot.lock().await;
+        Resumable::receive_chunk(&mut *streaming, self, req).await
+    }
+
+    /// Receive a `TransferLeaderRequest` sent by the leader with
+    /// [`RaftNetworkV2::transfer_leader`].
+    ///
+    /// This node disables the leader lease of the sending leader,
+    /// and starts an election at once if it is the assigned next leader.
+    ///
+    /// It returns at once without waiting for the request to be handled.
+    ///
+    /// [`RaftNetworkV2::transfer_leader`]: crate::network::v2::RaftNetworkV2::transfer_leader
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn handle_transfer_leader(&self, req: TransferLeaderRequest<C>) -> Result<(), Fatal<C>> {
+        tracing::info!(req = display(&req), "Raft::handle_transfer_leader()");
+
+        self.inner.send_msg(RaftMsg::HandleTransferLeader { req }).await
+    }
+
     /// Get the ID of the current leader from this Raft node.
     ///
     /// This method is based on the Raft metrics system which does a good job at staying
@@ -492,7 +624,16 @@ where C: RaftTypeConfig
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn is_leader(&self) -> Result<(), RaftError<C, CheckIsLeaderError<C>>> {
         let (tx, rx) = C::AsyncRuntime::oneshot();
-        let _ = self.inner.call_core(RaftMsg::CheckIsLeaderRequest { tx }, rx).await?;
+        let _ = self
+            .inner
+            .call_core(
+                RaftMsg::CheckIsLeaderRequest {
+                    read_policy: ReadPolicy::ReadIndex,
+                    tx,
+                },
+                rx,
+            )
+            .await?;
         Ok(())
     }
 
@@ -521,7 +662,27 @@ where C: RaftTypeConfig
     /// Read more about how it works: [Read Operation](crate::docs::protocol::read)
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn ensure_linearizable(&self) -> Result<Option<LogId<C::NodeId>>, RaftError<C, CheckIsLeaderError<C>>> {
-        let (read_log_id, applied) = self.get_read_log_id().await?;
+        self.ensure_linearizable_with(ReadPolicy::ReadIndex).await
+    }
+
+    /// Ensures a read operation performed following this method are linearizable across the
+    /// cluster, with the specified [`ReadPolicy`] to confirm the leadership.
+    ///
+    /// With [`ReadPolicy::LeaseRead`], the leadership is confirmed with the leader lease if it is
+    /// still valid, which saves a round of heartbeats.
+    /// Otherwise it works the same way as [`ensure_linearizable()`](Raft::ensure_linearizable).
+    ///
+    /// # Examples
+    /// ```ignore
+    /// my_raft.ensure_linearizable_with(ReadPolicy::LeaseRead).await?;
+    /// // Proceed with the state machine read
+    /// ```
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn ensure_linearizable_with(
+        &self,
+        read_policy: ReadPolicy,
+    ) -> Result<Option<LogId<C::NodeId>>, RaftError<C, CheckIsLeaderError<C>>> {
+        let (read_log_id, applied) = self.get_read_log_id_with(read_policy).await?;
 
         if read_log_id.index() > applied.index() {
             self.wait(None)
@@ -569,12 +730,105 @@ where C: RaftTypeConfig
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn get_read_log_id(
         &self,
+    ) -> Result<(Option<LogId<C::NodeId>>, Option<LogId<C::NodeId>>), RaftError<C, CheckIsLeaderError<C>>> {
+        self.get_read_log_id_with(ReadPolicy::ReadIndex).await
+    }
+
+    /// Ensures this node is leader with the specified [`ReadPolicy`] and returns the log id up to
+    /// which the state machine should apply to ensure a read can be linearizable across the
+    /// cluster.
+    ///
+    /// With [`ReadPolicy::LeaseRead`], no heartbeat is sent if the leader lease acknowledged by a
+    /// quorum is still valid. It falls back to [`ReadPolicy::ReadIndex`] when the lease has
+    /// expired.
+    ///
+    /// See: [`get_read_log_id()`](Raft::get_read_log_id)
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn get_read_log_id_with(
+        &self,
+        read_policy: ReadPolicy,
     ) -> Result<(Option<LogId<C::NodeId>>, Option<LogId<C::NodeId>>), RaftError<C, CheckIsLeaderError<C>>> {
         let (tx, rx) = C::AsyncRuntime::oneshot();
-        let (read_log_id, applied) = self.inner.call_core(RaftMsg::CheckIsLeaderRequest { tx }, rx).await?;
+        let (read_log_id, applied) =
+            self.inner.call_core(RaftMsg::CheckIsLeaderRequest { read_policy, tx }, rx).await?;
         Ok((read_log_id, applied))
     }
 
+    /// Ensures a read operation performed following this method are linearizable across the
+    /// cluster, on any node, including followers and learners.
+    ///
+    /// If this node is the leader, it works the same way as [`ensure_linearizable()`].
+    /// Otherwise, this node sends a [`ReadIndexRequest`] to the leader it knows with
+    /// [`RaftNetworkV2::read_index()`]. The leader confirms its leadership with a quorum and
+    /// replies the read log id. This node then waits for its state machine to apply up to the read
+    /// log id.
+    ///
+    /// Returns:
+    /// - `Ok(read_log_id)` when the local state machine has applied up to `read_log_id`.
+    /// - `Err(RaftError<ReadIndexError>)` if the leader is unknown, the leader fails to confirm its
+    ///   leadership, or it fails to communicate with the leader.
+    ///
+    /// [`ensure_linearizable()`]: Raft::ensure_linearizable
+    /// [`RaftNetworkV2::read_index()`]: crate::network::v2::RaftNetworkV2::read_index
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn read_index(&self) -> Result<Option<LogId<C::NodeId>>, RaftError<C, ReadIndexError<C>>> {
+        let (tx, rx) = C::AsyncRuntime::oneshot();
+        let read_log_id = self.inner.call_core(RaftMsg::ReadIndexRequest { tx }, rx).await?;
+
+        self.wait(None)
+            .applied_index_at_least(read_log_id.index(), "read_index")
+            .await
+            .map_err(|e| match e {
+                WaitError::Timeout(_, _) => {
+                    unreachable!("did not specify timeout")
+                }
+                WaitError::ShuttingDown => Fatal::Stopped,
+            })?;
+
+        Ok(read_log_id)
+    }
+
+    /// Handle a [`ReadIndexRequest`] sent by a non-leader node with
+    /// [`RaftNetworkV2::read_index()`].
+    ///
+    /// This node confirms its leadership with a quorum and returns the read log id, as
+    /// [`get_read_log_id()`] does.
+    ///
+    /// [`get_read_log_id()`]: Raft::get_read_log_id
+    /// [`RaftNetworkV2::read_index()`]: crate::network::v2::RaftNetworkV2::read_index
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn handle_read_index(
+        &self,
+        req: ReadIndexRequest<C>,
+    ) -> Result<ReadIndexResponse<C>, RaftError<C, CheckIsLeaderError<C>>> {
+        tracing::debug!(req = display(&req), "Raft::handle_read_index()");
+
+        let (read_log_id, _applied) = self.get_read_log_id().await?;
+        Ok(ReadIndexResponse { read_log_id })
+    }
+
+    /// Handle a [`HandoffLeaderRequest`] sent by a follower with
+    /// [`RaftNetworkV2::handoff_leader()`].
+    ///
+    /// The leader records the [`Config::election_priority`] the follower reports, and returns at
+    /// once without waiting for any leadership transfer. On a later tick the leader hands off
+    /// leadership to the voter with the highest priority, if that priority is higher than its own
+    /// and the voter has replicated all logs of the leader.
+    ///
+    /// A node that is not the leader of [`HandoffLeaderRequest::vote`] rejects the request.
+    ///
+    /// [`RaftNetworkV2::handoff_leader()`]: crate::network::v2::RaftNetworkV2::handoff_leader
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn handle_handoff_leader(
+        &self,
+        req: HandoffLeaderRequest<C>,
+    ) -> Result<HandoffLeaderResponse, RaftError<C>> {
+        tracing::info!(req = display(&req), "Raft::handle_handoff_leader()");
+
+        let (tx, rx) = C::AsyncRuntime::oneshot();
+        self.inner.call_core(RaftMsg::HandoffLeader { req, tx }, rx).await
+    }
+
     /// Submit a mutating client request to Raft to update the state of the system (§5.1).
     ///
     /// It will be appended to the log, committed to the cluster, and then applied to the
@@ -625,6 +879,123 @@ where C: RaftTypeConfig
         Ok(rx)
     }
 
+    /// Submit a batch of mutating client requests to Raft, and wait for all of them to be applied.
+    ///
+    /// The batch is sent to `RaftCore` in one message and is appended to the log in one go, which
+    /// saves the per-request channel and core-loop overhead of calling [`Raft::client_write`] for
+    /// every request.
+    ///
+    /// It returns one result for every request, in the same order as `app_data`.
+    /// A request that is not applied, e.g., because this node is not a leader, gets an `Err`.
+    #[tracing::instrument(level = "debug", skip(self, app_data))]
+    pub async fn client_write_many<E>(&self, app_data: Vec<C::D>) -> Result<Vec<ClientWriteResult<C>>, Fatal<C>>
+    where
+        ResponderReceiverOf<C>: Future<Output = Result<ClientWriteResult<C>, E>>,
+        E: Error + OptionalSend,
+    {
+        let receivers = self.client_write_many_ff(app_data).await?;
+
+        let mut results = Vec::with_capacity(receivers.len());
+        for rx in receivers {
+            let res: ClientWriteResult<C> = self.inner.recv_msg(rx).await?;
+            results.push(res);
+        }
+
+        Ok(results)
+    }
+
+    /// Submit a batch of mutating client requests to Raft, returns one application defined
+    /// response receiver [`Responder::Receiver`] for every request.
+    ///
+    /// It is same as [`Raft::client_write_many`] but does not wait for the responses.
+    #[tracing::instrument(level = "debug", skip(self, app_data))]
+    pub async fn client_write_many_ff(&self, app_data: Vec<C::D>) -> Result<Vec<ResponderReceiverOf<C>>, Fatal<C>> {
+        let mut batch = Vec::with_capacity(app_data.len());
+        let mut receivers = Vec::with_capacity(app_data.len());
+
+        for d in app_data {
+            let (d, tx, rx) = ResponderOf::<C>::from_app_data(d);
+            batch.push((d, tx));
+            receivers.push(rx);
+        }
+
+        self.inner.send_msg(RaftMsg::ClientWriteManyRequest { app_data: batch }).await?;
+
+        Ok(receivers)
+    }
+
+    /// Transfer leadership to the voter `to`.
+    ///
+    /// This method must be called on the leader. The leader stops accepting new writes at once:
+    /// a write is rejected with a [`ForwardToLeader`] error pointing to `to`.
+    /// Then the leader waits for `to` to catch up through the replication streams.
+    /// Once `to` has all logs of the leader, the leader sends a [`TransferLeaderRequest`] to every
+    /// voter, so that they disable the leader lease and `to` starts an election at once.
+    ///
+    /// It returns `Ok(())` when `to` is seen as the new leader by this node, which is also
+    /// observable with `Raft::wait().current_leader()`.
+    ///
+    /// If the transfer does not finish within [`Config::transfer_leader_timeout`], it returns
+    /// [`TargetLagging`] if `to` did not catch up, or [`Timeout`] otherwise.
+    /// In either case this node resumes accepting writes if it is still the leader.
+    ///
+    /// The outcome is also sent to [`Raft::subscribe_events()`], as
+    /// [`RaftEvent::LeaderTransferred`] or [`RaftEvent::LeaderTransferCancelled`].
+    ///
+    /// [`ForwardToLeader`]: crate::error::ForwardToLeader
+    /// [`TargetLagging`]: crate::error::TargetLagging
+    /// [`Timeout`]: crate::error::Timeout
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn transfer_leader(&self, to: C::NodeId) -> Result<(), RaftError<C, TransferLeaderError<C>>> {
+        let (tx, rx) = C::AsyncRuntime::oneshot();
+        let last_log_id = self.inner.call_core(RaftMsg::TransferLeader { to, tx }, rx).await?;
+
+        let timeout = self.inner.config.transfer_leader_timeout();
+
+        let wait_res = self.wait(Some(timeout)).metrics(|m| m.current_leader == Some(to), "transfer leader").await;
+
+        let err = match wait_res {
+            Ok(_) => {
+                tracing::info!(to = display(to), "leadership is transferred");
+                self.inner.events.send(RaftEvent::LeaderTransferred { to });
+                return Ok(());
+            }
+            Err(WaitError::ShuttingDown) => {
+                let fatal =
+                    self.inner.get_core_stopped_error("waiting for transfer leader", None::<&'static str>).await;
+                return Err(RaftError::Fatal(fatal));
+            }
+            Err(WaitError::Timeout(_, _)) => {
+                let metrics = self.metrics().borrow().clone();
+                let matching = metrics.replication.as_ref().and_then(|r| r.get(&to).copied().flatten());
+
+                if matching < last_log_id {
+                    TransferLeaderError::from(TargetLagging {
+                        target: to,
+                        matching,
+                        last_log_id,
+                    })
+                } else {
+                    TransferLeaderError::from(crate::error::Timeout {
+                        action: RPCTypes::TransferLeader,
+                        id: self.inner.id,
+                        target: to,
+                        timeout,
+                    })
+                }
+            }
+        };
+
+        tracing::info!(error = display(&err), "transfer leader failed, cancel it");
+        self.inner.send_msg(RaftMsg::CancelTransferLeader { to }).await?;
+        self.inner.events.send(RaftEvent::LeaderTransferCancelled {
+            to,
+            error: err.to_string(),
+        });
+
+        Err(RaftError::APIError(err))
+    }
+
     /// Return `true` if this node is already initialized and can not be initialized again with
     /// [`Raft::initialize`]
     pub async fn is_initialized(&self) -> Result<bool, Fatal<C>> {
@@ -793,6 +1164,36 @@ where C: RaftTypeConfig
         self.inner.rx_server_metrics.clone()
     }
 
+    /// Get a handle to the performance metrics channel.
+    ///
+    /// [`PerfMetrics`] holds cumulative latency histograms and throughput counters, such as the
+    /// latency of client writes, log flushes and applying logs, to find out which part slows down
+    /// the writes.
+    pub fn perf_metrics(&self) -> watch::Receiver<PerfMetrics<C>> {
+        self.inner.rx_perf_metrics.clone()
+    }
+
+    /// Evaluate the health of this node on the latest [`RaftMetrics`].
+    ///
+    /// The returned [`Health`] reports whether there is a known leader, whether a leader has a
+    /// fresh quorum ack, how far the state machine lags behind the committed log, whether storage
+    /// failed and, on a leader, the reachability of every follower, with the given `thresholds`.
+    /// [`Health::is_live()`] and [`Health::is_ready()`] are meant for liveness and readiness
+    /// probes.
+    pub fn health(&self, thresholds: &HealthThresholds) -> Health<C> {
+        Health::new(&self.inner.rx_metrics.borrow(), thresholds)
+    }
+
+    /// Subscribe to the lifecycle events of this node, such as becoming a leader or installing a
+    /// snapshot.
+    ///
+    /// Unlike [`Raft::metrics()`], which only keeps the latest state, the returned stream
+    /// receives every [`RaftEvent`] sent after this call, in order. It ends after
+    /// [`RaftEvent::Fatal`] when Raft stops.
+    pub fn subscribe_events(&self) -> EventStream<C> {
+        self.inner.events.subscribe()
+    }
+
     /// Get a handle to wait for the metrics to satisfy some condition.
     ///
     /// If `timeout` is `None`, then it will wait forever(10 years).
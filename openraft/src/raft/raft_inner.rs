@@ -14,9 +14,11 @@ use crate::core::raft_msg::RaftMsg;
 use crate::core::TickHandle;
 use crate::error::Fatal;
 use crate::error::RaftError;
+use crate::metrics::PerfMetrics;
 use crate::metrics::RaftDataMetrics;
 use crate::metrics::RaftServerMetrics;
 use crate::raft::core_state::CoreState;
+use crate::raft::event::EventSender;
 use crate::type_config::alias::OneshotReceiverOf;
 use crate::type_config::alias::OneshotSenderOf;
 use crate::AsyncRuntime;
@@ -38,6 +40,8 @@ where C: RaftTypeConfig
     pub(in crate::raft) rx_metrics: watch::Receiver<RaftMetrics<C>>,
     pub(in crate::raft) rx_data_metrics: watch::Receiver<RaftDataMetrics<C>>,
     pub(in crate::raft) rx_server_metrics: watch::Receiver<RaftServerMetrics<C>>,
+    pub(in crate::raft) rx_perf_metrics: watch::Receiver<PerfMetrics<C>>,
+    pub(in crate::raft) events: EventSender<C>,
 
     // TODO(xp): it does not need to be a async mutex.
     #[allow(clippy::type_complexity)]
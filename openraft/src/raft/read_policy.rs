@@ -0,0 +1,30 @@
+//! Defines how a leader confirms its leadership for a linearizable read.
+
+/// The policy a leader uses to confirm its leadership before serving a linearizable read.
+///
+/// It is used by [`Raft::ensure_linearizable_with()`] and [`Raft::get_read_log_id_with()`].
+///
+/// [`Raft::ensure_linearizable_with()`]: crate::Raft::ensure_linearizable_with
+/// [`Raft::get_read_log_id_with()`]: crate::Raft::get_read_log_id_with
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ReadPolicy {
+    /// Confirm the leadership by sending a round of heartbeats to a quorum.
+    ///
+    /// It does not depend on the clock, at the cost of a network round trip per read.
+    #[default]
+    ReadIndex,
+
+    /// Confirm the leadership with the leader lease acknowledged by a quorum.
+    ///
+    /// If the time acknowledged by a quorum is within `election_timeout_max -
+    /// lease_read_clock_drift`, no other node can have been elected, and the read is served
+    /// without any network round trip. Otherwise it falls back to [`ReadPolicy::ReadIndex`].
+    ///
+    /// It is correct only if the clock drift between nodes is less than
+    /// [`Config::lease_read_clock_drift`].
+    ///
+    /// [`Config::lease_read_clock_drift`]: crate::Config::lease_read_clock_drift
+    LeaseRead,
+}
@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+/// The approximate sizes of the logs held by a Raft node, to find out the size of the logs after
+/// any given index.
+///
+/// It records the accumulated size of the logs at the end of every appended batch of entries.
+/// The size of the logs after an index that is not at a batch boundary is an over-estimate, by
+/// at most the size of the batch containing it.
+#[derive(Debug, Clone)]
+#[derive(Default)]
+#[derive(PartialEq, Eq)]
+pub(crate) struct LogBytes {
+    /// A list of `(index, bytes)` sorted by `index`: `bytes` is the accumulated size of the
+    /// logs before `index`.
+    ///
+    /// The first point is the base, from which the sizes are accumulated.
+    points: VecDeque<(u64, u64)>,
+}
+
+impl LogBytes {
+    /// The max number of points to keep.
+    ///
+    /// When exceeded, every other point is removed, which halves the resolution.
+    const MAX_POINTS: usize = 1024;
+
+    /// Record the size of the logs in range `[start, end)`, which are appended after the last
+    /// recorded log.
+    pub(crate) fn append(&mut self, start: u64, end: u64, bytes: u64) {
+        let last = self.last();
+
+        match last {
+            Some((index, _)) if index == start => {}
+            _ => {
+                let total = last.map(|(_, total)| total).unwrap_or_default();
+                self.points.push_back((start, total));
+            }
+        }
+
+        let total = self.last().map(|(_, total)| total).unwrap_or_default();
+        self.points.push_back((end, total.saturating_add(bytes)));
+
+        if self.points.len() > Self::MAX_POINTS {
+            self.compact();
+        }
+    }
+
+    /// Forget the sizes of the logs since `since`, inclusive, when these logs are truncated.
+    pub(crate) fn truncate(&mut self, since: u64) {
+        while let Some((index, _)) = self.last() {
+            if index <= since {
+                break;
+            }
+            self.points.pop_back();
+        }
+    }
+
+    /// Forget the sizes of the logs before `index`, e.g., logs that are included in a snapshot.
+    pub(crate) fn forget_before(&mut self, index: u64) {
+        while self.points.len() > 1 && self.points[1].0 <= index {
+            self.points.pop_front();
+        }
+    }
+
+    /// Return the approximate size of the logs since `index`, inclusive.
+    pub(crate) fn bytes_since(&self, index: u64) -> u64 {
+        let (Some((_, first_total)), Some((_, last_total))) = (self.points.front(), self.last()) else {
+            return 0;
+        };
+
+        let base = self.points.iter().rev().find(|(i, _)| *i <= index).map(|(_, total)| *total);
+        let base = base.unwrap_or(*first_total);
+
+        last_total.saturating_sub(base)
+    }
+
+    fn last(&self) -> Option<(u64, u64)> {
+        self.points.back().copied()
+    }
+
+    /// Remove every other point except the first and the last one.
+    fn compact(&mut self) {
+        let n = self.points.len();
+        let mut i = 0;
+        self.points.retain(|_| {
+            let keep = i == 0 || i == n - 1 || i % 2 == 0;
+            i += 1;
+            keep
+        });
+    }
+}
@@ -3,10 +3,13 @@ use std::ops::Deref;
 
 use validit::Validate;
 
+use crate::config::LogsSinceSnapshot;
 use crate::engine::LogIdList;
+use crate::entry::RaftEntry;
 use crate::error::ForwardToLeader;
 use crate::log_id::RaftLogId;
 use crate::utime::UTime;
+use crate::Instant;
 use crate::LogId;
 use crate::LogIdOptionExt;
 use crate::RaftTypeConfig;
@@ -16,6 +19,7 @@ use crate::Vote;
 
 mod accepted;
 pub(crate) mod io_state;
+mod log_bytes;
 mod log_state_reader;
 mod membership_state;
 pub(crate) mod snapshot_streaming;
@@ -29,11 +33,13 @@ mod tests {
     mod accepted_test;
     mod forward_to_leader_test;
     mod is_initialized_test;
+    mod log_bytes_test;
     mod log_state_reader_test;
     mod validate_test;
 }
 
 pub(crate) use accepted::Accepted;
+pub(crate) use log_bytes::LogBytes;
 pub(crate) use log_state_reader::LogStateReader;
 pub use membership_state::MembershipState;
 pub(crate) use vote_state_reader::VoteStateReader;
@@ -87,6 +93,18 @@ where C: RaftTypeConfig
     /// If a log is in use by a replication task, the purge is postponed and is stored in this
     /// field.
     pub(crate) purge_upto: Option<LogId<C::NodeId>>,
+
+    /// The vote whose leader lease is disabled.
+    ///
+    /// It is set when the leader of this vote hands off leadership to another node.
+    /// As long as the local vote is this vote, a vote request won't be rejected by the lease.
+    pub(crate) lease_disabled_vote: Option<Vote<C::NodeId>>,
+
+    /// The approximate sizes of the logs that are not yet included in the last snapshot.
+    pub(crate) log_bytes: LogBytes,
+
+    /// The time when the last snapshot is built or installed, or when this node starts up.
+    pub(crate) snapshot_time: Option<InstantOf<C>>,
 }
 
 impl<C> Default for RaftState<C>
@@ -105,6 +123,9 @@ where C: RaftTypeConfig
             io_state: IOState::default(),
             snapshot_streaming: None,
             purge_upto: None,
+            lease_disabled_vote: None,
+            log_bytes: LogBytes::default(),
+            snapshot_time: None,
         }
     }
 }
@@ -201,6 +222,43 @@ where C: RaftTypeConfig
         self.vote.utime()
     }
 
+    /// Disable the leader lease of the given vote.
+    ///
+    /// The lease will not be restored until the local vote changes.
+    pub(crate) fn disable_lease(&mut self, vote: Vote<C::NodeId>) {
+        self.lease_disabled_vote = Some(vote);
+    }
+
+    /// Return `true` if the leader lease of the current vote is disabled.
+    pub(crate) fn is_lease_disabled(&self) -> bool {
+        self.lease_disabled_vote.as_ref() == Some(self.vote_ref())
+    }
+
+    /// Record the size of the appended log entries.
+    pub(crate) fn add_log_bytes(&mut self, entries: &[C::Entry]) {
+        let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+            return;
+        };
+
+        let bytes = entries.iter().map(|ent| ent.approx_size()).sum::<u64>();
+        self.log_bytes.append(first.get_log_id().index, last.get_log_id().index + 1, bytes);
+    }
+
+    /// Return the logs since the last snapshot, for a [`SnapshotPolicy`] to decide whether to
+    /// build a snapshot.
+    ///
+    /// [`SnapshotPolicy`]: crate::SnapshotPolicy
+    pub(crate) fn logs_since_snapshot(&self) -> LogsSinceSnapshot {
+        let committed_logs = self.committed().next_index().saturating_sub(self.snapshot_last_log_id().next_index());
+        let elapsed = self.snapshot_time.map(|t| InstantOf::<C>::now() - t).unwrap_or_default();
+
+        LogsSinceSnapshot {
+            committed_logs,
+            bytes: self.log_bytes.bytes_since(self.snapshot_last_log_id().next_index()),
+            elapsed,
+        }
+    }
+
     pub(crate) fn is_initialized(&self) -> bool {
         // initialize() writes a membership config log entry.
         // If there are logs, it is already initialized.
@@ -338,7 +396,9 @@ where C: RaftTypeConfig
         } else if self.is_leading(id) {
             ServerState::Candidate
         } else {
-            if self.is_voter(id) {
+            if self.membership_state.effective().is_witness(id) {
+                ServerState::Witness
+            } else if self.is_voter(id) {
                 ServerState::Follower
             } else {
                 ServerState::Learner
@@ -0,0 +1,82 @@
+use crate::raft_state::LogBytes;
+
+#[test]
+fn test_log_bytes_append() -> anyhow::Result<()> {
+    let mut lb = LogBytes::default();
+    assert_eq!(0, lb.bytes_since(0));
+
+    lb.append(1, 3, 20);
+    lb.append(3, 6, 30);
+    lb.append(6, 7, 5);
+
+    assert_eq!(55, lb.bytes_since(0));
+    assert_eq!(55, lb.bytes_since(1));
+    assert_eq!(35, lb.bytes_since(3));
+    assert_eq!(35, lb.bytes_since(4), "over-estimated inside a batch");
+    assert_eq!(5, lb.bytes_since(6));
+    assert_eq!(0, lb.bytes_since(7));
+    assert_eq!(0, lb.bytes_since(10));
+
+    Ok(())
+}
+
+#[test]
+fn test_log_bytes_forget_before() -> anyhow::Result<()> {
+    let mut lb = LogBytes::default();
+
+    lb.append(1, 3, 20);
+    lb.append(3, 6, 30);
+    lb.append(6, 7, 5);
+
+    // A snapshot includes logs before 6, while logs in [6, 7) are appended during building it.
+    lb.forget_before(6);
+    assert_eq!(5, lb.bytes_since(6));
+    assert_eq!(5, lb.bytes_since(0));
+
+    // A snapshot includes all logs.
+    lb.forget_before(10);
+    assert_eq!(0, lb.bytes_since(10));
+
+    lb.append(10, 12, 8);
+    assert_eq!(8, lb.bytes_since(10));
+
+    Ok(())
+}
+
+#[test]
+fn test_log_bytes_truncate() -> anyhow::Result<()> {
+    let mut lb = LogBytes::default();
+
+    lb.append(1, 3, 20);
+    lb.append(3, 6, 30);
+
+    lb.truncate(3);
+    assert_eq!(20, lb.bytes_since(1));
+
+    lb.append(3, 5, 7);
+    assert_eq!(27, lb.bytes_since(1));
+
+    // Truncate inside a batch: the rest of the batch is forgotten.
+    lb.truncate(4);
+    assert_eq!(20, lb.bytes_since(1));
+
+    lb.append(4, 5, 1);
+    assert_eq!(21, lb.bytes_since(1));
+
+    Ok(())
+}
+
+#[test]
+fn test_log_bytes_compact() -> anyhow::Result<()> {
+    let mut lb = LogBytes::default();
+
+    for i in 0..10_000 {
+        lb.append(i, i + 1, 1);
+    }
+
+    assert_eq!(10_000, lb.bytes_since(0));
+    assert_eq!(1, lb.bytes_since(9_999));
+    assert!(lb.bytes_since(5_000) >= 5_000);
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use core::fmt;
 use crate::error::Fatal;
 use crate::error::StreamingError;
 use crate::raft::SnapshotResponse;
+use crate::storage::SnapshotSignature;
 use crate::type_config::alias::InstantOf;
 use crate::RaftTypeConfig;
 use crate::SnapshotMeta;
@@ -24,6 +25,22 @@ pub(crate) struct SnapshotCallback<C: RaftTypeConfig> {
 
     /// The result of the snapshot replication.
     pub(crate) result: Result<SnapshotResponse<C>, StreamingError<C, Fatal<C>>>,
+
+    /// Set if a snapshot delta is sent but not installed by the target, in which case `result` is
+    /// the error.
+    pub(crate) delta_rejected: Option<DeltaRejected<C>>,
+}
+
+/// A snapshot delta is not installed by the target.
+///
+/// The leader should send a delta upon `current`, or a full snapshot if `current` is `None`.
+#[derive(Debug)]
+pub(crate) struct DeltaRejected<C: RaftTypeConfig> {
+    /// The snapshot the target has, as it reported.
+    ///
+    /// It is `None` if the target has no snapshot or did not report one, e.g., the network does
+    /// not implement sending snapshot deltas.
+    pub(crate) current: Option<SnapshotSignature<C::NodeId>>,
 }
 
 impl<C: RaftTypeConfig> SnapshotCallback<C> {
@@ -31,11 +48,13 @@ impl<C: RaftTypeConfig> SnapshotCallback<C> {
         start_time: InstantOf<C>,
         snapshot_meta: SnapshotMeta<C>,
         result: Result<SnapshotResponse<C>, StreamingError<C, Fatal<C>>>,
+        delta_rejected: Option<DeltaRejected<C>>,
     ) -> Self {
         Self {
             start_time,
             snapshot_meta,
             result,
+            delta_rejected,
         }
     }
 }
@@ -53,6 +72,10 @@ impl<C: RaftTypeConfig> fmt::Display for SnapshotCallback<C> {
             Err(e) => write!(f, " Err({})", e)?,
         };
 
+        if let Some(rejected) = &self.delta_rejected {
+            write!(f, ", delta_rejected: {{ current: {:?} }}", rejected.current)?;
+        }
+
         write!(f, " }}",)
     }
 }
@@ -3,21 +3,27 @@
 pub(crate) mod callbacks;
 pub(crate) mod hint;
 mod replication_session_id;
+pub(crate) mod replication_status;
 pub(crate) mod request;
 pub(crate) mod request_id;
 pub(crate) mod response;
+pub(crate) mod snapshot_throttle;
 
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyerror::AnyError;
 use futures::future::FutureExt;
+use futures::stream::FuturesOrdered;
+use futures::StreamExt;
 pub(crate) use replication_session_id::ReplicationSessionId;
+use replication_status::ReplicationStatus;
 use request::Data;
 use request::DataWithId;
 use request::Replicate;
 use response::ReplicationResult;
 pub(crate) use response::Response;
+use snapshot_throttle::SnapshotThrottle;
 use tokio::select;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
@@ -28,27 +34,41 @@ use crate::config::Config;
 use crate::core::notify::Notify;
 use crate::core::sm::handle::SnapshotReader;
 use crate::display_ext::DisplayOptionExt;
+use crate::entry::RaftEntry;
+use crate::entry::RaftPayload;
+use crate::error::Fatal;
 use crate::error::HigherVote;
+use crate::error::NetworkError;
 use crate::error::PayloadTooLarge;
 use crate::error::RPCError;
 use crate::error::RaftError;
+use crate::error::RemoteError;
 use crate::error::ReplicationClosed;
 use crate::error::ReplicationError;
+use crate::error::SnapshotBaseMismatch;
+use crate::error::StreamingError;
 use crate::error::Timeout;
 use crate::log_id::LogIdOptionExt;
 use crate::log_id_range::LogIdRange;
+use crate::metrics::InflightMetrics;
+use crate::metrics::ReplicationTargetMetrics;
 use crate::network::v2::RaftNetworkV2;
 use crate::network::Backoff;
 use crate::network::RPCOption;
 use crate::network::RPCTypes;
+use crate::progress::entry::ProgressEntry;
+use crate::progress::inflight::Inflight;
 use crate::raft::AppendEntriesRequest;
 use crate::raft::AppendEntriesResponse;
+use crate::raft::SnapshotResponse;
+use crate::replication::callbacks::DeltaRejected;
 use crate::replication::callbacks::SnapshotCallback;
 use crate::replication::hint::ReplicationHint;
 use crate::replication::request_id::RequestId;
 use crate::storage::RaftLogReader;
 use crate::storage::RaftLogStorage;
 use crate::storage::Snapshot;
+use crate::storage::SnapshotSignature;
 use crate::type_config::alias::AsyncRuntimeOf;
 use crate::type_config::alias::InstantOf;
 use crate::type_config::alias::JoinHandleOf;
@@ -72,6 +92,48 @@ where C: RaftTypeConfig
 
     /// The channel used for communicating with the replication task.
     pub(crate) tx_repl: mpsc::UnboundedSender<Replicate<C>>,
+
+    /// The state of the replication task that is reported in metrics.
+    pub(crate) status: Arc<ReplicationStatus>,
+
+    /// The time when the last replication RPC is accepted by the target.
+    pub(crate) last_success: Option<InstantOf<C>>,
+
+    /// The time when the last replication RPC failed.
+    pub(crate) last_failure: Option<InstantOf<C>>,
+
+    /// The number of replication RPCs that failed since the last successful one.
+    pub(crate) consecutive_failures: u64,
+
+    /// The round trip time of the last heartbeat accepted by the target.
+    pub(crate) heartbeat_rtt: Option<Duration>,
+}
+
+impl<C> ReplicationHandle<C>
+where C: RaftTypeConfig
+{
+    /// Build the metrics of this replication target, with the progress tracked by the leader.
+    pub(crate) fn metrics(&self, progress: &ProgressEntry<C::NodeId>) -> ReplicationTargetMetrics<C::NodeId> {
+        let inflight = match progress.inflight {
+            Inflight::None => InflightMetrics::None,
+            Inflight::Logs { log_id_range, .. } => InflightMetrics::Logs {
+                prev: log_id_range.prev,
+                last: log_id_range.last,
+            },
+            Inflight::Snapshot { last_log_id, .. } => InflightMetrics::Snapshot { last_log_id },
+        };
+
+        ReplicationTargetMetrics {
+            matching: progress.matching,
+            inflight,
+            millis_since_last_success: self.last_success.map(|t| t.elapsed().as_millis() as u64),
+            millis_since_last_failure: self.last_failure.map(|t| t.elapsed().as_millis() as u64),
+            consecutive_failures: self.consecutive_failures,
+            backoff: self.status.backoff(),
+            snapshot_sent_bytes: self.status.snapshot_sent_bytes(),
+            heartbeat_rtt: self.heartbeat_rtt,
+        }
+    }
 }
 
 /// A task responsible for sending replication events to a target follower in the Raft cluster.
@@ -88,6 +150,9 @@ where
     /// The ID of the target Raft node which replication events are to be sent to.
     target: C::NodeId,
 
+    /// Whether the target is a witness, which receives entries without application data.
+    witness: bool,
+
     /// Identifies which session this replication belongs to.
     session_id: ReplicationSessionId<C::NodeId>,
 
@@ -108,11 +173,21 @@ where
     /// The `RaftNetwork` interface for replicating logs and heartbeat.
     network: N::Network,
 
+    /// Additional `RaftNetwork` clients for pipelining AppendEntries RPCs.
+    ///
+    /// There are [`Config::max_inflight_append_entries`] of them if pipelining is enabled, one
+    /// for each RPC in flight. Every client is moved into the RPC it sends, and is put back when
+    /// the RPC is responded.
+    pipeline_networks: Vec<N::Network>,
+
     /// Another `RaftNetwork` specific for snapshot replication.
     ///
     /// Snapshot transmitting is a long running task, and is processed in a separate task.
     snapshot_network: Arc<Mutex<N::Network>>,
 
+    /// Limits the bandwidth and the number of concurrent snapshots sent by this node.
+    snapshot_throttle: Arc<SnapshotThrottle<C>>,
+
     /// The current snapshot replication state.
     ///
     /// It includes a cancel signaler and the join handle of the snapshot replication task.
@@ -120,10 +195,20 @@ where
     /// to quit.
     snapshot_state: Option<(oneshot::Sender<()>, JoinHandleOf<C, ()>)>,
 
+    /// The signature of the snapshot the target has, as it reported in the last snapshot response.
+    ///
+    /// It is the base of the next snapshot delta if [`Config::enable_snapshot_delta`] is enabled.
+    /// It is kept if sending a snapshot fails: if it is no longer the snapshot of the target, the
+    /// target rejects the delta and reports its actual snapshot.
+    snapshot_base: Option<SnapshotSignature<C::NodeId>>,
+
     /// The backoff policy if an [`Unreachable`](`crate::error::Unreachable`) error is returned.
     /// It will be reset to `None` when an successful response is received.
     backoff: Option<Backoff>,
 
+    /// The state of this replication that is reported in metrics, shared with `RaftCore`.
+    status: Arc<ReplicationStatus>,
+
     /// The [`RaftLogStorage::LogReader`] interface.
     log_reader: LS::LogReader,
 
@@ -159,12 +244,15 @@ where
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn spawn(
         target: C::NodeId,
+        witness: bool,
         session_id: ReplicationSessionId<C::NodeId>,
         config: Arc<Config>,
         committed: Option<LogId<C::NodeId>>,
         matching: Option<LogId<C::NodeId>>,
         network: N::Network,
+        pipeline_networks: Vec<N::Network>,
         snapshot_network: N::Network,
+        snapshot_throttle: Arc<SnapshotThrottle<C>>,
         log_reader: LS::LogReader,
         snapshot_reader: SnapshotReader<C>,
         tx_raft_core: mpsc::UnboundedSender<Notify<C>>,
@@ -181,13 +269,20 @@ where
         // other component to ReplicationStream
         let (tx_event, rx_event) = mpsc::unbounded_channel();
 
+        let status = Arc::new(ReplicationStatus::default());
+
         let this = Self {
             target,
+            witness,
             session_id,
             network,
+            pipeline_networks,
             snapshot_network: Arc::new(Mutex::new(snapshot_network)),
+            snapshot_throttle,
             snapshot_state: None,
+            snapshot_base: None,
             backoff: None,
+            status: status.clone(),
             log_reader,
             snapshot_reader,
             config,
@@ -205,6 +300,11 @@ where
         ReplicationHandle {
             join_handle,
             tx_repl: tx_event,
+            status,
+            last_success: None,
+            last_failure: None,
+            consecutive_failures: 0,
+            heartbeat_rtt: None,
         }
     }
 
@@ -248,6 +348,7 @@ where
                 Ok(next) => {
                     // reset backoff at once if replication succeeds
                     self.backoff = None;
+                    self.status.set_backoff(None);
 
                     // If the RPC was successful but not finished, continue.
                     if let Some(next) = next {
@@ -325,6 +426,7 @@ where
                 tracing::warn!("backoff exhausted, using default");
                 Duration::from_millis(500)
             });
+            self.status.set_backoff(Some(duration));
 
             self.backoff_drain_events(InstantOf::<C>::now() + duration).await?;
         }
@@ -338,16 +440,19 @@ where
         const DEFAULT_ENTRIES_HINT_TTL: u64 = 10;
 
         match too_large.action() {
-            RPCTypes::Vote => {
+            RPCTypes::Vote | RPCTypes::PreVote => {
                 unreachable!("Vote RPC should not be too large")
             }
             RPCTypes::AppendEntries => {
                 self.entries_hint = ReplicationHint::new(too_large.entries_hint(), DEFAULT_ENTRIES_HINT_TTL);
                 tracing::debug!(entries_hint = debug(&self.entries_hint), "updated entries hint");
             }
-            RPCTypes::InstallSnapshot => {
+            RPCTypes::InstallSnapshot | RPCTypes::SnapshotDelta => {
                 // TODO: handle too large
-                tracing::error!("InstallSnapshot RPC is too large, but it is not supported yet");
+                tracing::error!("{} RPC is too large, but it is not supported yet", too_large.action());
+            }
+            RPCTypes::TransferLeader | RPCTypes::ReadIndex | RPCTypes::HandoffLeader => {
+                unreachable!("{} RPC should not be too large", too_large.action())
             }
         }
     }
@@ -371,6 +476,10 @@ where
             "send_log_entries",
         );
 
+        if !self.pipeline_networks.is_empty() && log_ids.data().len() > self.config.max_payload_entries {
+            return self.send_log_entries_pipelined(log_ids).await;
+        }
+
         // Series of logs to send, and the last log id to send
         let (logs, sending_range) = {
             let rng = log_ids.data();
@@ -393,17 +502,7 @@ where
                 let r = LogIdRange::new(rng.prev, rng.prev);
                 (vec![], r)
             } else {
-                let logs = self.log_reader.try_get_log_entries(start..end).await?;
-                debug_assert_eq!(
-                    logs.len(),
-                    (end - start) as usize,
-                    "expect logs {}..{} but got only {} entries, first: {}, last: {}",
-                    start,
-                    end,
-                    logs.len(),
-                    logs.first().map(|ent| ent.get_log_id()).display(),
-                    logs.last().map(|ent| ent.get_log_id()).display()
-                );
+                let logs = self.read_payload(start, end).await?;
 
                 let last_log_id = logs.last().map(|ent| *ent.get_log_id());
 
@@ -436,15 +535,7 @@ where
 
         tracing::debug!("append_entries res: {:?}", res);
 
-        let append_res = res.map_err(|_e| {
-            let to = Timeout {
-                action: RPCTypes::AppendEntries,
-                id: self.session_id.vote.leader_id().voted_for().unwrap(),
-                target: self.target,
-                timeout: the_timeout,
-            };
-            RPCError::Timeout(to)
-        })?; // return Timeout error
+        let append_res = res.map_err(|_e| self.append_entries_timeout(the_timeout))?; // return Timeout error
 
         let append_resp = append_res?;
 
@@ -491,6 +582,284 @@ where
         }
     }
 
+    /// Send a series of logs in several AppendEntries RPCs in flight at the same time.
+    ///
+    /// The logs are split into RPCs of at most [`Self::max_entries_per_rpc`] entries, each sent
+    /// with its own network client. Up to `window` RPCs are in flight: the responses are handled
+    /// in the order the RPCs are sent, and every time the oldest RPC is acknowledged, the next RPC
+    /// is sent with the freed client, until all of the logs are sent or handling stops at the
+    /// first RPC that does not succeed.
+    ///
+    /// A conflict or an error of the first RPC is handled the same as a single AppendEntries.
+    /// A later RPC may arrive at the target before the previous one and be rejected with a
+    /// conflict; such a rejection or an error of a later RPC rolls the sending back to the last
+    /// matching log id, and the rest of the logs are returned as the next action.
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn send_log_entries_pipelined(
+        &mut self,
+        log_ids: DataWithId<LogIdRange<C::NodeId>>,
+    ) -> Result<Option<Data<C>>, ReplicationError<C>> {
+        let request_id = log_ids.request_id();
+        let rng = *log_ids.data();
+        let end = rng.last.next_index();
+
+        let max_entries = self.max_entries_per_rpc();
+
+        let leader_time = InstantOf::<C>::now();
+        let the_timeout = Duration::from_millis(self.config.heartbeat_interval);
+
+        tracing::debug!(
+            window = self.pipeline_networks.len(),
+            now = debug(leader_time),
+            "start sending pipelined append_entries, timeout: {:?}",
+            the_timeout
+        );
+
+        let send = |mut net: N::Network, sending_range: LogIdRange<C::NodeId>, payload: AppendEntriesRequest<C>| async move {
+            let option = RPCOption::new(the_timeout);
+            let res = AsyncRuntimeOf::<C>::timeout(the_timeout, net.append_entries(payload, option)).await;
+            (net, sending_range, res)
+        };
+
+        let mut idle = std::mem::take(&mut self.pipeline_networks);
+        let mut inflight = FuturesOrdered::new();
+
+        // The last log id that is sent, the next RPC starts after it.
+        let mut sent = rng.prev;
+        let mut is_first = true;
+
+        let res = 'pipeline: loop {
+            // Fill up the window.
+            while sent.next_index() < end {
+                let Some(net) = idle.pop() else {
+                    break;
+                };
+
+                let start = sent.next_index();
+                let chunk_end = std::cmp::min(start + max_entries, end);
+
+                let logs = match self.read_payload(start, chunk_end).await {
+                    Ok(logs) => logs,
+                    Err(e) => {
+                        idle.push(net);
+                        break 'pipeline Err(e.into());
+                    }
+                };
+
+                let last = logs.last().map(|ent| *ent.get_log_id());
+
+                let payload = AppendEntriesRequest {
+                    vote: self.session_id.vote,
+                    prev_log_id: sent,
+                    leader_commit: self.committed,
+                    entries: logs,
+                };
+
+                inflight.push_back(send(net, LogIdRange::new(sent, last), payload));
+                sent = last;
+            }
+
+            let Some((net, sending_range, res)) = inflight.next().await else {
+                break Ok(self.logs_after_matching(request_id, rng));
+            };
+            idle.push(net);
+
+            let first = is_first;
+            is_first = false;
+
+            tracing::debug!(
+                req = display(&sending_range),
+                res = debug(&res),
+                "pipelined append_entries resp"
+            );
+
+            let append_resp = match res {
+                Ok(Ok(resp)) => resp,
+                Ok(Err(e)) if first => break Err(e.into()),
+                Err(_e) if first => break Err(self.append_entries_timeout(the_timeout).into()),
+                _ => break Ok(self.logs_after_matching(request_id, rng)),
+            };
+
+            match append_resp {
+                AppendEntriesResponse::Success => {
+                    let matching = sending_range.last;
+                    self.send_progress(request_id, ReplicationResult::new(leader_time, Ok(matching)));
+                }
+                AppendEntriesResponse::PartialSuccess(matching) => {
+                    Self::debug_assert_partial_success(&sending_range, &matching);
+                    self.send_progress(request_id, ReplicationResult::new(leader_time, Ok(matching)));
+                    break Ok(self.logs_after_matching(request_id, rng));
+                }
+                AppendEntriesResponse::HigherVote(vote) => {
+                    tracing::debug!(%vote, "append entries failed. converting to follower");
+
+                    break Err(ReplicationError::HigherVote(HigherVote {
+                        higher: vote,
+                        mine: self.session_id.vote,
+                    }));
+                }
+                AppendEntriesResponse::Conflict => {
+                    if !first {
+                        tracing::debug!(
+                            req = display(&sending_range),
+                            "pipelined append_entries conflict, resend from last matching: {}",
+                            self.matching.display()
+                        );
+                        break Ok(self.logs_after_matching(request_id, rng));
+                    }
+
+                    let conflict = sending_range.prev;
+                    debug_assert!(conflict.is_some(), "prev_log_id=None never conflict");
+
+                    let conflict = conflict.unwrap();
+                    self.send_progress(request_id, ReplicationResult::new(leader_time, Err(conflict)));
+
+                    break Ok(None);
+                }
+            }
+        };
+
+        // Wait for the RPCs still in flight to get their network clients back.
+        // Their responses are ignored: the sending is rolled back to the last matching log id.
+        while let Some((net, _sending_range, _res)) = inflight.next().await {
+            idle.push(net);
+        }
+        self.pipeline_networks = idle;
+
+        res
+    }
+
+    /// Return the logs in `rng` after the last matching log id as the next action, if there are.
+    fn logs_after_matching(&self, request_id: RequestId, rng: LogIdRange<C::NodeId>) -> Option<Data<C>> {
+        if self.matching < rng.last {
+            Some(Data::new_logs(request_id, LogIdRange::new(self.matching, rng.last)))
+        } else {
+            None
+        }
+    }
+
+    /// The max number of entries to send in one AppendEntries RPC.
+    fn max_entries_per_rpc(&mut self) -> u64 {
+        self.entries_hint.get().unwrap_or(self.config.max_payload_entries)
+    }
+
+    /// Read the entries in `[start, end)` to send in one AppendEntries RPC.
+    ///
+    /// If a byte budget is configured, entries are read in small batches and reading stops once
+    /// their total [`RaftEntry::approx_size()`] reaches [`Config::target_payload_bytes`], or
+    /// before an entry that would make the total exceed [`Config::max_payload_bytes`]. Thus the
+    /// byte budget decides how many entries are read and sent, and `end` is only an upper bound.
+    /// The first entry is always sent, so that replication makes progress even if a single entry
+    /// is larger than the limit.
+    ///
+    /// The size of an entry sent to a witness is counted after its payload is stripped.
+    async fn read_payload(&mut self, start: u64, end: u64) -> Result<Vec<C::Entry>, StorageError<C::NodeId>> {
+        let target = self.config.target_payload_bytes;
+        let max = self.config.max_payload_bytes;
+
+        if target == 0 && max == 0 {
+            let logs = self.log_reader.try_get_log_entries(start..end).await?;
+            debug_assert_eq!(
+                logs.len(),
+                (end - start) as usize,
+                "expect logs {}..{} but got only {} entries, first: {}, last: {}",
+                start,
+                end,
+                logs.len(),
+                logs.first().map(|ent| ent.get_log_id()).display(),
+                logs.last().map(|ent| ent.get_log_id()).display()
+            );
+
+            let logs = if self.witness { Self::strip_payload(logs) } else { logs };
+            return Ok(logs);
+        }
+
+        let step = 64;
+
+        let mut logs = Vec::new();
+        let mut total = 0;
+        let mut step_start = start;
+
+        'read: while step_start < end {
+            let step_end = std::cmp::min(step_start + step, end);
+            let batch = self.log_reader.try_get_log_entries(step_start..step_end).await?;
+            debug_assert_eq!(batch.len(), (step_end - step_start) as usize);
+
+            let batch = if self.witness {
+                Self::strip_payload(batch)
+            } else {
+                batch
+            };
+
+            for ent in batch {
+                let size = ent.approx_size();
+                if !logs.is_empty() && max > 0 && total + size > max {
+                    break 'read;
+                }
+
+                total += size;
+                logs.push(ent);
+
+                if target > 0 && total >= target {
+                    break 'read;
+                }
+            }
+
+            step_start = step_end;
+        }
+
+        tracing::debug!(
+            n = logs.len(),
+            total,
+            "read AppendEntries payload in [{}, {}) by size",
+            start,
+            end
+        );
+
+        Ok(logs)
+    }
+
+    fn append_entries_timeout(&self, timeout: Duration) -> RPCError<C, RaftError<C>> {
+        RPCError::Timeout(Timeout {
+            action: RPCTypes::AppendEntries,
+            id: self.session_id.vote.leader_id().voted_for().unwrap(),
+            target: self.target,
+            timeout,
+        })
+    }
+
+    /// Replace every entry that is not a membership entry with a blank entry of the same log id.
+    ///
+    /// A witness stores only log ids and membership configs, thus application data is not sent to
+    /// it.
+    fn strip_payload(logs: Vec<C::Entry>) -> Vec<C::Entry> {
+        logs.into_iter()
+            .map(|ent| {
+                if ent.get_membership().is_some() {
+                    ent
+                } else {
+                    C::Entry::new_blank(*ent.get_log_id())
+                }
+            })
+            .collect()
+    }
+
+    /// Replace the data of a snapshot with an empty snapshot data, keeping only the meta.
+    ///
+    /// A witness stores only log ids and membership configs, thus application data in a snapshot
+    /// is not sent to it, just like [`Self::strip_payload`] does for entries.
+    async fn strip_snapshot_data(&mut self, snapshot: Snapshot<C>) -> Result<Snapshot<C>, ReplicationError<C>> {
+        let data = self.snapshot_reader.get_empty_snapshot_data().await.map_err(|reason| {
+            tracing::warn!(
+                error = display(&reason),
+                "failed to get empty snapshot data from state machine"
+            );
+            ReplicationClosed::new(reason)
+        })?;
+
+        Ok(Snapshot::new(snapshot.meta, data))
+    }
+
     /// Send the error result to RaftCore.
     /// RaftCore will then submit another replication command.
     fn send_progress_error(&mut self, request_id: RequestId, err: RPCError<C, RaftError<C>>) {
@@ -586,6 +955,10 @@ where
             tracing::debug!("backoff timeout: {:?}", sleep_duration);
 
             select! {
+                // Poll in a fixed order: the random order of an unbiased `select!` makes a
+                // simulation not reproducible.
+                biased;
+
                 _ = sleep => {
                     tracing::debug!("backoff timeout");
                     return Ok(());
@@ -708,14 +1081,39 @@ where
 
         tracing::info!(request_id = display(request_id), "{}", func_name!());
 
-        let snapshot = self.snapshot_reader.get_snapshot().await.map_err(|reason| {
-            tracing::warn!(error = display(&reason), "failed to get snapshot from state machine");
-            ReplicationClosed::new(reason)
-        })?;
+        // A witness receives only the snapshot meta, a delta is not needed.
+        let base = if self.config.enable_snapshot_delta && !self.witness {
+            self.snapshot_base.clone()
+        } else {
+            None
+        };
+
+        let delta = match &base {
+            None => None,
+            Some(base) => self.snapshot_reader.get_snapshot_delta(base.clone()).await.map_err(|reason| {
+                tracing::warn!(
+                    error = display(&reason),
+                    "failed to get snapshot delta from state machine"
+                );
+                ReplicationClosed::new(reason)
+            })?,
+        };
+
+        let (base, snapshot) = match delta {
+            Some(delta) => (base, Some(delta)),
+            None => {
+                let snapshot = self.snapshot_reader.get_snapshot().await.map_err(|reason| {
+                    tracing::warn!(error = display(&reason), "failed to get snapshot from state machine");
+                    ReplicationClosed::new(reason)
+                })?;
+                (None, snapshot)
+            }
+        };
 
         tracing::info!(
-            "received snapshot: request_id={}; meta:{}",
+            "received snapshot: request_id={}; base: {:?}; meta:{}",
             request_id,
+            base,
             snapshot.as_ref().map(|x| &x.meta).display()
         );
 
@@ -728,15 +1126,25 @@ where
             Some(x) => x,
         };
 
+        let snapshot = if self.witness {
+            self.strip_snapshot_data(snapshot).await?
+        } else {
+            snapshot
+        };
+
         let mut option = RPCOption::new(self.config.install_snapshot_timeout());
         option.snapshot_chunk_size = Some(self.config.snapshot_max_chunk_size as usize);
+        option.snapshot_rate_limiter = self.snapshot_throttle.rate_limiter();
+        option.snapshot_progress = Some(self.status.start_snapshot());
 
         let (tx_cancel, rx_cancel) = oneshot::channel();
 
         let jh = AsyncRuntimeOf::<C>::spawn(Self::send_snapshot(
             request_id,
             self.snapshot_network.clone(),
+            self.snapshot_throttle.clone(),
             self.session_id.vote,
+            base,
             snapshot,
             option,
             rx_cancel,
@@ -751,17 +1159,32 @@ where
         Ok(None)
     }
 
+    /// Send a full snapshot if `base` is `None`, otherwise a snapshot delta upon `base`.
+    #[allow(clippy::too_many_arguments)]
     async fn send_snapshot(
         request_id: RequestId,
         network: Arc<Mutex<N::Network>>,
+        throttle: Arc<SnapshotThrottle<C>>,
         vote: Vote<C::NodeId>,
+        base: Option<SnapshotSignature<C::NodeId>>,
         snapshot: Snapshot<C>,
         option: RPCOption,
-        cancel: oneshot::Receiver<()>,
+        mut cancel: oneshot::Receiver<()>,
         weak_tx: mpsc::WeakUnboundedSender<Replicate<C>>,
     ) {
         let meta = snapshot.meta.clone();
 
+        // Wait in the queue if too many snapshots are being sent.
+        let _permit = select! {
+            biased;
+
+            permit = throttle.acquire_stream() => permit,
+            _ = &mut cancel => {
+                tracing::info!("ReplicationCore is dropped while snapshot is queued");
+                return;
+            }
+        };
+
         let mut net = network.lock().await;
 
         let start_time = InstantOf::<C>::now();
@@ -771,13 +1194,20 @@ where
             ReplicationClosed::new("ReplicationCore is dropped")
         };
 
-        let res = net.full_snapshot(vote, snapshot, cancel, option).await;
+        let (res, delta_rejected) = match base {
+            None => (net.full_snapshot(vote, snapshot, cancel, option).await, None),
+            Some(base) => {
+                let res = net.snapshot_delta(vote, base.clone(), snapshot, cancel, option).await;
+                let rejected = Self::delta_rejected(&base, &res);
+                (res.map_err(Self::delta_error), rejected)
+            }
+        };
         if let Err(e) = &res {
             tracing::warn!(error = display(e), "failed to send snapshot");
         }
 
         if let Some(tx_noty) = weak_tx.upgrade() {
-            let data = Data::new_snapshot_callback(request_id, start_time, meta, res);
+            let data = Data::new_snapshot_callback(request_id, start_time, meta, res, delta_rejected);
             let send_res = tx_noty.send(Replicate::new_data(data));
             if send_res.is_err() {
                 tracing::warn!("weak_tx failed to send snapshot result to ReplicationCore");
@@ -787,6 +1217,54 @@ where
         }
     }
 
+    /// Check if a snapshot delta upon `base` is rejected by the target, and should be resent at
+    /// once, without backoff.
+    ///
+    /// [`Unreachable`] is returned by a network that does not implement
+    /// [`RaftNetworkV2::snapshot_delta`]. It is treated as a rejection so that a full snapshot is
+    /// sent at once. If the target is actually unreachable, the full snapshot fails too and
+    /// backs off.
+    ///
+    /// [`Unreachable`]: crate::error::Unreachable
+    fn delta_rejected(
+        base: &SnapshotSignature<C::NodeId>,
+        res: &Result<SnapshotResponse<C>, StreamingError<C, RaftError<C, SnapshotBaseMismatch<C>>>>,
+    ) -> Option<DeltaRejected<C>> {
+        match res {
+            Err(StreamingError::Unreachable(_)) => Some(DeltaRejected { current: None }),
+            Err(StreamingError::RemoteError(RemoteError {
+                source: RaftError::APIError(mismatch),
+                ..
+            })) => {
+                let current = Some(mismatch.expect.clone()).filter(|c| c.last_log_id.is_some() && c != base);
+                Some(DeltaRejected { current })
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert an error of sending a snapshot delta to the error of sending a full snapshot.
+    ///
+    /// A rejected delta is converted to a [`NetworkError`]; it is then resent by
+    /// [`Self::handle_snapshot_callback`] according to [`Self::delta_rejected`].
+    fn delta_error(e: StreamingError<C, RaftError<C, SnapshotBaseMismatch<C>>>) -> StreamingError<C, Fatal<C>> {
+        match e {
+            StreamingError::Closed(e) => StreamingError::Closed(e),
+            StreamingError::StorageError(e) => StreamingError::StorageError(e),
+            StreamingError::Timeout(e) => StreamingError::Timeout(e),
+            StreamingError::Unreachable(e) => StreamingError::Unreachable(e),
+            StreamingError::Network(e) => StreamingError::Network(e),
+            StreamingError::RemoteError(e) => match e.source {
+                RaftError::Fatal(fatal) => StreamingError::RemoteError(RemoteError {
+                    target: e.target,
+                    target_node: e.target_node,
+                    source: fatal,
+                }),
+                RaftError::APIError(mismatch) => StreamingError::Network(NetworkError::new(&mismatch)),
+            },
+        }
+    }
+
     fn handle_snapshot_callback(
         &mut self,
         callback: DataWithId<SnapshotCallback<C>>,
@@ -799,15 +1277,32 @@ where
         );
 
         self.snapshot_state = None;
+        self.status.finish_snapshot();
 
         let request_id = callback.request_id();
         let SnapshotCallback {
             start_time,
             result,
             snapshot_meta,
+            delta_rejected,
         } = callback.into_data();
 
-        let resp = result?;
+        let resp = match result {
+            Ok(x) => x,
+            Err(e) => {
+                if let Some(rejected) = delta_rejected {
+                    tracing::info!(
+                        error = display(&e),
+                        "snapshot delta is rejected, resend at once upon the target snapshot: {:?}",
+                        rejected.current
+                    );
+                    self.snapshot_base = rejected.current;
+                    return Ok(Some(Data::new_snapshot(request_id, snapshot_meta.last_log_id)));
+                }
+
+                return Err(e.into());
+            }
+        };
 
         // Handle response conditions.
         if resp.vote > self.session_id.vote {
@@ -817,6 +1312,8 @@ where
             }));
         }
 
+        self.snapshot_base = resp.snapshot;
+
         self.send_progress(
             request_id,
             ReplicationResult::new(start_time, Ok(snapshot_meta.last_log_id)),
@@ -0,0 +1,135 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The state of a replication stream that only `ReplicationCore` knows, shared with `RaftCore` to
+/// report metrics.
+#[derive(Debug, Default)]
+pub(crate) struct ReplicationStatus {
+    /// The current backoff interval in milliseconds, or 0 if not backing off.
+    backoff_millis: AtomicU64,
+
+    /// Whether a snapshot is being sent.
+    sending_snapshot: AtomicBool,
+
+    /// The progress of the current snapshot.
+    ///
+    /// It is shared with the snapshot transport through
+    /// [`RPCOption`](crate::network::RPCOption).
+    snapshot_progress: Arc<SnapshotProgress>,
+}
+
+/// The bytes of a snapshot received by the target, reported by the snapshot transport.
+#[derive(Debug, Default)]
+pub(crate) struct SnapshotProgress {
+    received: AtomicU64,
+
+    /// Whether the transport has reported any progress of the current snapshot.
+    reported: AtomicBool,
+}
+
+impl SnapshotProgress {
+    pub(crate) fn set_received(&self, received: u64) {
+        self.received.store(received, Ordering::Relaxed);
+        self.reported.store(true, Ordering::Relaxed);
+    }
+
+    /// The bytes received by the target, or `None` if the transport has not reported any.
+    pub(crate) fn received(&self) -> Option<u64> {
+        if self.reported.load(Ordering::Relaxed) {
+            Some(self.received.load(Ordering::Relaxed))
+        } else {
+            None
+        }
+    }
+
+    fn reset(&self) {
+        self.reported.store(false, Ordering::Relaxed);
+        self.received.store(0, Ordering::Relaxed);
+    }
+}
+
+impl ReplicationStatus {
+    pub(crate) fn set_backoff(&self, backoff: Option<Duration>) {
+        let millis = backoff.map(|d| std::cmp::max(d.as_millis() as u64, 1)).unwrap_or_default();
+        self.backoff_millis.store(millis, Ordering::Relaxed);
+    }
+
+    pub(crate) fn backoff(&self) -> Option<Duration> {
+        match self.backoff_millis.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(Duration::from_millis(millis)),
+        }
+    }
+
+    /// Start tracking the progress of a new snapshot, and return the progress for the transport
+    /// to report to.
+    pub(crate) fn start_snapshot(&self) -> Arc<SnapshotProgress> {
+        self.snapshot_progress.reset();
+        self.sending_snapshot.store(true, Ordering::Relaxed);
+        self.snapshot_progress.clone()
+    }
+
+    pub(crate) fn finish_snapshot(&self) {
+        self.sending_snapshot.store(false, Ordering::Relaxed);
+    }
+
+    /// The bytes of the snapshot being sent that are received by the target.
+    ///
+    /// `None` if no snapshot is being sent, or the transport has not reported any progress.
+    pub(crate) fn snapshot_sent_bytes(&self) -> Option<u64> {
+        if self.sending_snapshot.load(Ordering::Relaxed) {
+            self.snapshot_progress.received()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::replication::replication_status::ReplicationStatus;
+
+    #[test]
+    fn test_backoff() {
+        let st = ReplicationStatus::default();
+        assert_eq!(None, st.backoff());
+
+        st.set_backoff(Some(Duration::from_millis(200)));
+        assert_eq!(Some(Duration::from_millis(200)), st.backoff());
+
+        st.set_backoff(Some(Duration::from_micros(10)));
+        assert_eq!(Some(Duration::from_millis(1)), st.backoff(), "rounded up to 1 ms");
+
+        st.set_backoff(None);
+        assert_eq!(None, st.backoff());
+    }
+
+    #[test]
+    fn test_snapshot_sent_bytes() {
+        let st = ReplicationStatus::default();
+        assert_eq!(None, st.snapshot_sent_bytes());
+
+        let progress = st.start_snapshot();
+        assert_eq!(None, st.snapshot_sent_bytes(), "not reported by the transport");
+
+        progress.set_received(10);
+        assert_eq!(Some(10), st.snapshot_sent_bytes());
+
+        progress.set_received(0);
+        assert_eq!(Some(0), st.snapshot_sent_bytes(), "the target starts over");
+
+        st.finish_snapshot();
+        assert_eq!(None, st.snapshot_sent_bytes());
+
+        let progress = st.start_snapshot();
+        assert_eq!(None, st.snapshot_sent_bytes(), "reset for a new snapshot");
+
+        progress.set_received(5);
+        assert_eq!(Some(5), st.snapshot_sent_bytes());
+    }
+}
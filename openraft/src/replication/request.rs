@@ -48,6 +48,7 @@ use crate::error::Fatal;
 use crate::error::StreamingError;
 use crate::log_id_range::LogIdRange;
 use crate::raft::SnapshotResponse;
+use crate::replication::callbacks::DeltaRejected;
 use crate::replication::callbacks::SnapshotCallback;
 use crate::replication::request_id::RequestId;
 use crate::type_config::alias::InstantOf;
@@ -135,10 +136,11 @@ where C: RaftTypeConfig
         start_time: InstantOf<C>,
         snapshot_meta: SnapshotMeta<C>,
         result: Result<SnapshotResponse<C>, StreamingError<C, Fatal<C>>>,
+        delta_rejected: Option<DeltaRejected<C>>,
     ) -> Self {
         Self::SnapshotCallback(DataWithId::new(
             request_id,
-            SnapshotCallback::new(start_time, snapshot_meta, result),
+            SnapshotCallback::new(start_time, snapshot_meta, result, delta_rejected),
         ))
     }
 
@@ -0,0 +1,183 @@
+//! Limits the bandwidth and the number of concurrent snapshot streams sent by a leader.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
+
+use crate::config::Config;
+use crate::core::notify::Notify;
+use crate::metrics::SnapshotStreamMetrics;
+use crate::network::rate_limiter::RateLimiter;
+use crate::network::rate_limiter::Reserve;
+use crate::RaftTypeConfig;
+
+/// Limits shared by all of the snapshot streams sent by this node.
+///
+/// A stream waits in a queue for a [`SnapshotStreamPermit`] if there are already
+/// [`Config::max_concurrent_snapshot_streams`] streams being sent, and every stream sends data
+/// at the rate limited by [`Config::snapshot_max_bytes_per_sec`].
+///
+/// `RaftCore` is notified whenever a stream is queued, starts sending or finishes, to report the
+/// updated [`SnapshotStreamMetrics`].
+#[derive(Debug)]
+pub(crate) struct SnapshotThrottle<C: RaftTypeConfig> {
+    /// Limits the number of concurrent streams. `None` if unlimited.
+    streams: Option<Arc<Semaphore>>,
+
+    /// Limits the total bandwidth of all streams. `None` if unlimited.
+    rate_limiter: Option<Arc<RateLimiter<C>>>,
+
+    /// Number of streams being sent.
+    sending: Arc<AtomicU64>,
+
+    /// Number of streams waiting for a permit to send.
+    queued: AtomicU64,
+
+    /// Notifies `RaftCore` when the number of sending or queued streams changes.
+    tx_notify: mpsc::UnboundedSender<Notify<C>>,
+}
+
+impl<C: RaftTypeConfig> SnapshotThrottle<C> {
+    pub(crate) fn new(config: &Config, tx_notify: mpsc::UnboundedSender<Notify<C>>) -> Self {
+        let streams = match config.max_concurrent_snapshot_streams {
+            0 => None,
+            n => Some(Arc::new(Semaphore::new(n as usize))),
+        };
+
+        let rate_limiter = match config.snapshot_max_bytes_per_sec {
+            0 => None,
+            n => Some(Arc::new(RateLimiter::new(n))),
+        };
+
+        Self {
+            streams,
+            rate_limiter,
+            sending: Arc::new(AtomicU64::new(0)),
+            queued: AtomicU64::new(0),
+            tx_notify,
+        }
+    }
+
+    pub(crate) fn rate_limiter(&self) -> Option<Arc<dyn Reserve>> {
+        self.rate_limiter.clone().map(|x| x as Arc<dyn Reserve>)
+    }
+
+    /// Wait in the queue until a stream is allowed to send.
+    ///
+    /// The stream is counted as sending until the returned permit is dropped.
+    pub(crate) async fn acquire_stream(&self) -> SnapshotStreamPermit<C> {
+        let permit = if let Some(streams) = &self.streams {
+            self.queued.fetch_add(1, Ordering::Relaxed);
+            let _queued = DecreaseOnDrop(&self.queued, &self.tx_notify);
+            notify_changed(&self.tx_notify);
+
+            // Safe unwrap: the semaphore is never closed.
+            Some(streams.clone().acquire_owned().await.unwrap())
+        } else {
+            None
+        };
+
+        self.sending.fetch_add(1, Ordering::Relaxed);
+        notify_changed(&self.tx_notify);
+
+        SnapshotStreamPermit {
+            _permit: permit,
+            sending: self.sending.clone(),
+            tx_notify: self.tx_notify.clone(),
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> SnapshotStreamMetrics {
+        SnapshotStreamMetrics {
+            sending: self.sending.load(Ordering::Relaxed),
+            queued: self.queued.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Wake up `RaftCore` to report the changed [`SnapshotStreamMetrics`].
+///
+/// The error is ignored: `RaftCore` is shutting down.
+fn notify_changed<C: RaftTypeConfig>(tx_notify: &mpsc::UnboundedSender<Notify<C>>) {
+    let _ = tx_notify.send(Notify::SnapshotStreams);
+}
+
+/// Allows a snapshot stream to be sent, until it is dropped.
+pub(crate) struct SnapshotStreamPermit<C: RaftTypeConfig> {
+    _permit: Option<OwnedSemaphorePermit>,
+    sending: Arc<AtomicU64>,
+    tx_notify: mpsc::UnboundedSender<Notify<C>>,
+}
+
+impl<C: RaftTypeConfig> Drop for SnapshotStreamPermit<C> {
+    fn drop(&mut self) {
+        self.sending.fetch_sub(1, Ordering::Relaxed);
+        notify_changed(&self.tx_notify);
+    }
+}
+
+/// Decrease a counter when dropped, even if the future holding it is canceled.
+struct DecreaseOnDrop<'a, C: RaftTypeConfig>(&'a AtomicU64, &'a mpsc::UnboundedSender<Notify<C>>);
+
+impl<'a, C: RaftTypeConfig> Drop for DecreaseOnDrop<'a, C> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+        notify_changed(self.1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+    use tokio::sync::mpsc;
+
+    use crate::core::notify::Notify;
+    use crate::engine::testing::UTConfig;
+    use crate::metrics::SnapshotStreamMetrics;
+    use crate::replication::snapshot_throttle::SnapshotThrottle;
+    use crate::Config;
+
+    #[tokio::test]
+    async fn test_acquire_stream() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let throttle = SnapshotThrottle::<UTConfig>::new(
+            &Config {
+                max_concurrent_snapshot_streams: 1,
+                ..Default::default()
+            },
+            tx,
+        );
+
+        let mut notified = || {
+            let mut n = 0;
+            while let Ok(Notify::SnapshotStreams) = rx.try_recv() {
+                n += 1;
+            }
+            n
+        };
+
+        let p1 = throttle.acquire_stream().await;
+        assert_eq!(SnapshotStreamMetrics { sending: 1, queued: 0 }, throttle.metrics());
+        assert_eq!(3, notified(), "queued, dequeued, sending");
+
+        let mut fu = Box::pin(throttle.acquire_stream());
+        assert!((&mut fu).now_or_never().is_none());
+        assert_eq!(SnapshotStreamMetrics { sending: 1, queued: 1 }, throttle.metrics());
+        assert_eq!(1, notified(), "queued");
+
+        drop(p1);
+        assert_eq!(1, notified(), "released");
+
+        let p2 = fu.await;
+        assert_eq!(SnapshotStreamMetrics { sending: 1, queued: 0 }, throttle.metrics());
+        assert_eq!(2, notified(), "dequeued, sending");
+
+        drop(p2);
+        assert_eq!(SnapshotStreamMetrics { sending: 0, queued: 0 }, throttle.metrics());
+        assert_eq!(1, notified(), "released");
+    }
+}
@@ -29,6 +29,11 @@ where C: RaftTypeConfig
         Self { log_io_id, tx }
     }
 
+    /// Build a callback of the same log io, that reports the completion to `tx`.
+    pub(crate) fn with_sender(&self, tx: OneshotSenderOf<C, Result<LogIOId<C::NodeId>, io::Error>>) -> Self {
+        Self::new(self.log_io_id, tx)
+    }
+
     /// Report log io completion event.
     ///
     /// It will be called when the log is successfully appended to the storage or an error occurs.
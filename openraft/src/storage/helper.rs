@@ -3,10 +3,13 @@ use std::sync::Arc;
 
 use crate::display_ext::DisplayOptionExt;
 use crate::engine::LogIdList;
+use crate::entry::RaftEntry;
 use crate::entry::RaftPayload;
 use crate::log_id::RaftLogId;
 use crate::raft_state::io_state::log_io_id::LogIOId;
 use crate::raft_state::IOState;
+use crate::raft_state::LogBytes;
+use crate::raft_state::LogStateReader;
 use crate::storage::RaftLogReaderExt;
 use crate::storage::RaftLogStorage;
 use crate::storage::RaftStateMachine;
@@ -169,9 +172,44 @@ where
             io_state,
             snapshot_streaming: None,
             purge_upto: last_purged_log_id,
+            lease_disabled_vote: None,
+            log_bytes: Default::default(),
+            snapshot_time: None,
         })
     }
 
+    /// Load the sizes of the logs that are not yet included in the snapshot into `state`, for
+    /// size based snapshot policies.
+    ///
+    /// It reads every such log from the log store, thus it is called only if the snapshot policy
+    /// uses the log sizes.
+    pub(crate) async fn load_log_bytes(&mut self, state: &mut RaftState<C>) -> Result<(), StorageError<C::NodeId>> {
+        let start = std::cmp::max(
+            state.last_purged_log_id().next_index(),
+            state.snapshot_last_log_id().next_index(),
+        );
+        let end = state.last_log_id().next_index();
+
+        tracing::info!("load log sizes: [{}..{})", start, end);
+
+        let step = 64;
+        let mut log_bytes = LogBytes::default();
+
+        let mut step_start = start;
+        while step_start < end {
+            let step_end = std::cmp::min(step_start + step, end);
+            let entries = self.log_store.get_log_entries(step_start..step_end).await?;
+
+            let bytes = entries.iter().map(|ent| ent.approx_size()).sum::<u64>();
+            log_bytes.append(step_start, step_end, bytes);
+
+            step_start = step_end;
+        }
+
+        state.log_bytes = log_bytes;
+        Ok(())
+    }
+
     /// Returns the last 2 membership config found in log or state machine.
     ///
     /// A raft node needs to store at most 2 membership config log:
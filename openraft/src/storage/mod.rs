@@ -98,7 +98,6 @@ where C: RaftTypeConfig
 impl<C> Snapshot<C>
 where C: RaftTypeConfig
 {
-    #[allow(dead_code)]
     pub(crate) fn new(meta: SnapshotMeta<C>, snapshot: Box<C::SnapshotData>) -> Self {
         Self { meta, snapshot }
     }
@@ -5,10 +5,12 @@
 
 mod raft_log_storage_ext;
 
+use anyerror::AnyError;
 use openraft_macros::add_async_trait;
 pub use raft_log_storage_ext::RaftLogStorageExt;
 
 use crate::storage::callback::LogFlushed;
+use crate::storage::SnapshotSignature;
 use crate::LogId;
 use crate::LogState;
 use crate::OptionalSend;
@@ -19,6 +21,7 @@ use crate::RaftTypeConfig;
 use crate::Snapshot;
 use crate::SnapshotMeta;
 use crate::StorageError;
+use crate::StorageIOError;
 use crate::StoredMembership;
 use crate::Vote;
 
@@ -219,12 +222,67 @@ where C: RaftTypeConfig
     ///
     /// A snapshot created from an earlier call to `begin_receiving_snapshot` which provided the
     /// snapshot.
+    ///
+    /// A witness receives a snapshot with only the meta: the data is an empty snapshot data
+    /// returned by `begin_receiving_snapshot` on the leader. The state machine of a witness should
+    /// install it as a state machine without application data, whose last applied log id and
+    /// membership are those in `meta`.
     async fn install_snapshot(
         &mut self,
         meta: &SnapshotMeta<C>,
         snapshot: Box<C::SnapshotData>,
     ) -> Result<(), StorageError<C::NodeId>>;
 
+    /// Get a delta that brings a state machine with snapshot `base` to the current snapshot.
+    ///
+    /// The returned [`Snapshot`] has the `meta` of the current snapshot, and its data contains only
+    /// the changes since `base`. It is sent to a follower whose current snapshot is `base`, in
+    /// place of a full snapshot, if [`Config::enable_snapshot_delta`] is enabled.
+    ///
+    /// An implementation that supports deltas usually keeps a base snapshot and the deltas built
+    /// upon it, keyed by [`SnapshotMeta::signature()`].
+    ///
+    /// It returns `None` if a delta since `base` is not available, e.g., `base` has been discarded,
+    /// and a full snapshot will be sent instead. The default implementation always returns `None`.
+    ///
+    /// [`Config::enable_snapshot_delta`]: crate::Config::enable_snapshot_delta
+    async fn get_snapshot_delta(
+        &mut self,
+        _base: &SnapshotSignature<C::NodeId>,
+    ) -> Result<Option<Snapshot<C>>, StorageError<C::NodeId>> {
+        Ok(None)
+    }
+
+    /// Install a snapshot delta which has finished streaming from the leader.
+    ///
+    /// The delta is built by [`Self::get_snapshot_delta`] on the leader, upon the snapshot
+    /// `base`. Openraft calls this method only if `base` is the current snapshot of this state
+    /// machine.
+    ///
+    /// Before this method returns, the same as [`Self::install_snapshot`]:
+    /// - The state machine should be replaced with the contents of the base snapshot with the delta
+    ///   applied. It must not apply the delta to the live state machine, which may have applied
+    ///   logs after `base`, whose effects the delta does not undo.
+    /// - the snapshot identified by `meta` should be saved, i.e., [`Self::get_current_snapshot`]
+    ///   should return it.
+    ///
+    /// The default implementation returns an error, because it should never be called if
+    /// [`Self::get_snapshot_delta`] is not implemented.
+    #[allow(clippy::boxed_local)]
+    async fn install_snapshot_delta(
+        &mut self,
+        base: &SnapshotSignature<C::NodeId>,
+        _meta: &SnapshotMeta<C>,
+        _delta: Box<C::SnapshotData>,
+    ) -> Result<(), StorageError<C::NodeId>> {
+        Err(StorageError::IO {
+            source: StorageIOError::write_snapshot(
+                Some(base.clone()),
+                AnyError::error("install_snapshot_delta is not implemented"),
+            ),
+        })
+    }
+
     /// Get a readable handle to the current snapshot.
     ///
     /// ### implementation algorithm
@@ -0,0 +1,245 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use maplit::btreemap;
+
+use crate::error::Fatal;
+use crate::error::RaftError;
+use crate::storage::RaftLogStorage;
+use crate::testing::fault::FaultyLogStore;
+use crate::testing::fault::FaultyStateMachine;
+use crate::testing::fault::StorageFaults;
+use crate::testing::fault::StorageOp;
+use crate::testing::fault::Trigger;
+use crate::testing::sim;
+use crate::testing::sim::SimConfig;
+use crate::testing::sim::SimLogStore;
+use crate::testing::sim::SimNetwork;
+use crate::testing::sim::SimRequest;
+use crate::testing::sim::SimRuntime;
+use crate::testing::sim::SimStateMachine;
+use crate::testing::sim::SimStorage;
+use crate::testing::sim::Simulation;
+use crate::AsyncRuntime;
+use crate::BasicNode;
+use crate::Config;
+use crate::Raft;
+use crate::RaftLogReader;
+use crate::ServerState;
+
+/// A single node cluster whose storage injects faults.
+struct Node {
+    raft: Raft<SimConfig>,
+    log_store: FaultyLogStore<SimConfig, SimLogStore>,
+    sm: SimStateMachine,
+}
+
+async fn start(storage: &SimStorage, faults: &StorageFaults<SimConfig>) -> Node {
+    let log_store = FaultyLogStore::new(storage.log_store(), faults.clone());
+    let sm = storage.state_machine().unwrap();
+
+    let raft = sim::spawn_on(
+        1,
+        Raft::new(
+            1,
+            Arc::new(Config::default().validate().unwrap()),
+            SimNetwork::default().factory(1),
+            log_store.clone(),
+            FaultyStateMachine::new(sm.clone(), faults.clone()),
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    Node { raft, log_store, sm }
+}
+
+/// Start and initialize a single node cluster, and wait for it to become leader.
+async fn start_leader(storage: &SimStorage, faults: &StorageFaults<SimConfig>) -> Node {
+    let n = start(storage, faults).await;
+    n.raft.initialize(btreemap! {1 => BasicNode::default()}).await.unwrap();
+    n.raft.wait(timeout()).state(ServerState::Leader, "leader").await.unwrap();
+    n
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_secs(10))
+}
+
+/// Wait for Raft to stop with a storage error.
+async fn wait_storage_error(raft: &Raft<SimConfig>) -> String {
+    let m = raft.wait(timeout()).metrics(|m| m.running_state.is_err(), "stopped").await.unwrap();
+    match m.running_state {
+        Err(Fatal::StorageError(e)) => e.to_string(),
+        other => panic!("expect storage error, got: {:?}", other),
+    }
+}
+
+#[test]
+fn test_fail_save_vote() {
+    Simulation::new(0).block_on(async {
+        let faults = StorageFaults::new();
+        faults.fail(StorageOp::SaveVote, Trigger::Always);
+
+        let n = start(&SimStorage::new(), &faults).await;
+        let res = n.raft.initialize(btreemap! {1 => BasicNode::default()}).await;
+        assert!(
+            matches!(res, Err(RaftError::Fatal(Fatal::StorageError(_)))),
+            "{:?}",
+            res
+        );
+
+        let err = wait_storage_error(&n.raft).await;
+        assert!(err.contains("injected fault: SaveVote #1"), "{}", err);
+
+        let res = n.raft.client_write(SimRequest::new("a", "1")).await;
+        assert!(matches!(res, Err(RaftError::Fatal(_))));
+    });
+}
+
+#[test]
+fn test_fail_nth_append() {
+    Simulation::new(0).block_on(async {
+        let faults = StorageFaults::new();
+        let n = start_leader(&SimStorage::new(), &faults).await;
+
+        n.raft.client_write(SimRequest::new("a", "1")).await.unwrap();
+
+        faults.fail(StorageOp::Append, Trigger::Nth(faults.calls(StorageOp::Append) + 2));
+        n.raft.client_write(SimRequest::new("b", "1")).await.unwrap();
+
+        let res = n.raft.client_write(SimRequest::new("c", "1")).await;
+        assert!(matches!(res, Err(RaftError::Fatal(_))), "{:?}", res);
+
+        let err = wait_storage_error(&n.raft).await;
+        assert!(err.contains("injected fault: Append"), "{}", err);
+        assert_eq!(None, n.sm.get("c"));
+    });
+}
+
+#[test]
+fn test_fail_flush() {
+    Simulation::new(0).block_on(async {
+        let faults = StorageFaults::new();
+        let n = start_leader(&SimStorage::new(), &faults).await;
+
+        faults.fail(StorageOp::Flush, Trigger::Always);
+
+        let res = n.raft.client_write(SimRequest::new("a", "1")).await;
+        assert!(matches!(res, Err(RaftError::Fatal(_))), "{:?}", res);
+
+        wait_storage_error(&n.raft).await;
+        assert_eq!(None, n.sm.get("a"));
+    });
+}
+
+#[test]
+fn test_fail_apply() {
+    Simulation::new(0).block_on(async {
+        let faults = StorageFaults::new();
+        let n = start_leader(&SimStorage::new(), &faults).await;
+
+        faults.fail(StorageOp::Apply, Trigger::From(faults.calls(StorageOp::Apply) + 1));
+
+        let res = n.raft.client_write(SimRequest::new("a", "1")).await;
+        assert!(matches!(res, Err(RaftError::Fatal(_))), "{:?}", res);
+
+        let err = wait_storage_error(&n.raft).await;
+        assert!(err.contains("injected fault: Apply"), "{}", err);
+    });
+}
+
+#[test]
+fn test_delay_flush() {
+    let sim = Simulation::new(0);
+
+    sim.block_on(async {
+        let faults = StorageFaults::new();
+        let n = start_leader(&SimStorage::new(), &faults).await;
+
+        faults.delay(StorageOp::Flush, Duration::from_secs(5));
+
+        let start = sim.elapsed();
+        n.raft.client_write(SimRequest::new("a", "1")).await.unwrap();
+        assert!(sim.elapsed() - start >= Duration::from_secs(5));
+    });
+}
+
+#[test]
+fn test_hold_flush_then_release() {
+    Simulation::new(0).block_on(async {
+        let faults = StorageFaults::new();
+        let n = start_leader(&SimStorage::new(), &faults).await;
+
+        faults.hold_flush();
+
+        let raft = n.raft.clone();
+        let write = SimRuntime::spawn(async move { raft.client_write(SimRequest::new("a", "1")).await });
+
+        // Logs that are not flushed must not be committed.
+        SimRuntime::sleep(Duration::from_secs(5)).await;
+        assert_eq!(None, n.sm.get("a"));
+
+        faults.release_flush();
+        write.await.unwrap().unwrap();
+        assert_eq!(Some("1".to_string()), n.sm.get("a"));
+    });
+}
+
+#[test]
+fn test_drop_unflushed_on_crash() {
+    Simulation::new(0).block_on(async {
+        let storage = SimStorage::new();
+        let faults = StorageFaults::new();
+        let mut n = start_leader(&storage, &faults).await;
+
+        n.raft.client_write(SimRequest::new("a", "1")).await.unwrap();
+
+        faults.hold_flush();
+
+        let raft = n.raft.clone();
+        let write = SimRuntime::spawn(async move { raft.client_write(SimRequest::new("b", "1")).await });
+        SimRuntime::sleep(Duration::from_secs(1)).await;
+
+        // The process crashes and the unflushed log of "b" is torn.
+        sim::crash(1);
+        assert!(write.await.unwrap().is_err());
+
+        let since = n.log_store.drop_unflushed().await.unwrap().unwrap();
+        let logs = n.log_store.inner().logs();
+        assert_eq!(since.index, logs.last().unwrap().log_id.index + 1);
+
+        let n = start(&storage, &faults).await;
+        n.raft.wait(timeout()).state(ServerState::Leader, "restarted").await.unwrap();
+        n.raft.client_write(SimRequest::new("c", "1")).await.unwrap();
+
+        assert_eq!(Some("1".to_string()), n.sm.get("a"));
+        assert_eq!(None, n.sm.get("b"));
+    });
+}
+
+#[test]
+fn test_corrupt_reads() {
+    Simulation::new(0).block_on(async {
+        let faults = StorageFaults::new();
+        let mut n = start_leader(&SimStorage::new(), &faults).await;
+
+        for i in 0..3 {
+            n.raft.client_write(SimRequest::new("a", i)).await.unwrap();
+        }
+
+        let mut reader = n.log_store.get_log_reader().await;
+
+        faults.corrupt_reads(Trigger::Nth(faults.calls(StorageOp::ReadLogs) + 1));
+        let corrupted = reader.try_get_log_entries(1..4).await.unwrap();
+        let indexes = corrupted.iter().map(|e| e.log_id.index).collect::<Vec<_>>();
+        assert_eq!(vec![1, 3], indexes);
+
+        let entries = reader.try_get_log_entries(1..4).await.unwrap();
+        assert_eq!(3, entries.len());
+
+        faults.fail(StorageOp::ReadLogs, Trigger::Always);
+        assert!(reader.try_get_log_entries(1..4).await.is_err());
+    });
+}
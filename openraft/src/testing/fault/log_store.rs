@@ -0,0 +1,185 @@
+use std::fmt::Debug;
+use std::ops::RangeBounds;
+
+use crate::log_id::RaftLogId;
+use crate::storage::LogFlushed;
+use crate::storage::RaftLogStorage;
+use crate::testing::fault::Fault;
+use crate::testing::fault::StorageFaults;
+use crate::testing::fault::StorageOp;
+use crate::AsyncRuntime;
+use crate::LogId;
+use crate::LogState;
+use crate::OptionalSend;
+use crate::RaftLogReader;
+use crate::RaftTypeConfig;
+use crate::StorageError;
+use crate::Vote;
+
+/// Wraps a [`RaftLogStorage`] or a [`RaftLogReader`] and injects the faults in a
+/// [`StorageFaults`].
+///
+/// The log reader it returns injects the same faults.
+pub struct FaultyLogStore<C, S>
+where C: RaftTypeConfig
+{
+    inner: S,
+    faults: StorageFaults<C>,
+}
+
+impl<C, S> Clone for FaultyLogStore<C, S>
+where
+    C: RaftTypeConfig,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            faults: self.faults.clone(),
+        }
+    }
+}
+
+impl<C, S> FaultyLogStore<C, S>
+where C: RaftTypeConfig
+{
+    pub fn new(inner: S, faults: StorageFaults<C>) -> Self {
+        Self { inner, faults }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn faults(&self) -> &StorageFaults<C> {
+        &self.faults
+    }
+}
+
+impl<C, S> FaultyLogStore<C, S>
+where
+    C: RaftTypeConfig,
+    S: RaftLogStorage<C>,
+{
+    /// Truncate the logs that are appended but not reported as flushed, as a torn write does when
+    /// the process crashes, and forget the held [`LogFlushed`] callbacks. Callbacks are not held
+    /// any more after this call.
+    ///
+    /// It must be called when Raft is not running, e.g., after a shutdown and before a restart.
+    /// Returns the log id the logs are truncated since.
+    pub async fn drop_unflushed(&mut self) -> Result<Option<LogId<C::NodeId>>, StorageError<C::NodeId>> {
+        let since = self.faults.take_unflushed();
+        if let Some(log_id) = since {
+            self.inner.truncate(log_id).await?;
+        }
+        Ok(since)
+    }
+}
+
+impl<C, S> RaftLogReader<C> for FaultyLogStore<C, S>
+where
+    C: RaftTypeConfig,
+    S: RaftLogReader<C>,
+{
+    async fn try_get_log_entries<RB: RangeBounds<u64> + Clone + Debug + OptionalSend>(
+        &mut self,
+        range: RB,
+    ) -> Result<Vec<C::Entry>, StorageError<C::NodeId>> {
+        let fault = self.faults.on_call(StorageOp::ReadLogs).await;
+        if let Some((Fault::Fail, n)) = fault {
+            return Err(StorageOp::ReadLogs.error(super::injected(StorageOp::ReadLogs, n)));
+        }
+
+        let mut entries = self.inner.try_get_log_entries(range).await?;
+
+        if let Some((Fault::Corrupt, _)) = fault {
+            if entries.len() >= 3 {
+                entries.remove(entries.len() / 2);
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn read_vote(&mut self) -> Result<Option<Vote<C::NodeId>>, StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::ReadVote).await?;
+        self.inner.read_vote().await
+    }
+}
+
+impl<C, S> RaftLogStorage<C> for FaultyLogStore<C, S>
+where
+    C: RaftTypeConfig,
+    S: RaftLogStorage<C>,
+{
+    type LogReader = FaultyLogStore<C, S::LogReader>;
+
+    async fn get_log_state(&mut self) -> Result<LogState<C>, StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::GetLogState).await?;
+        self.inner.get_log_state().await
+    }
+
+    async fn get_log_reader(&mut self) -> Self::LogReader {
+        FaultyLogStore::new(self.inner.get_log_reader().await, self.faults.clone())
+    }
+
+    async fn save_vote(&mut self, vote: &Vote<C::NodeId>) -> Result<(), StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::SaveVote).await?;
+        self.inner.save_vote(vote).await
+    }
+
+    async fn save_committed(&mut self, committed: Option<LogId<C::NodeId>>) -> Result<(), StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::SaveCommitted).await?;
+        self.inner.save_committed(committed).await
+    }
+
+    async fn read_committed(&mut self) -> Result<Option<LogId<C::NodeId>>, StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::ReadCommitted).await?;
+        self.inner.read_committed().await
+    }
+
+    async fn append<I>(&mut self, entries: I, callback: LogFlushed<C>) -> Result<(), StorageError<C::NodeId>>
+    where
+        I: IntoIterator<Item = C::Entry> + OptionalSend,
+        I::IntoIter: OptionalSend,
+    {
+        self.faults.check(StorageOp::Append).await?;
+
+        let entries = entries.into_iter().collect::<Vec<_>>();
+        let Some(first) = entries.first().map(|ent| *ent.get_log_id()) else {
+            return self.inner.append(entries, callback).await;
+        };
+
+        let seq = self.faults.add_unflushed(first);
+
+        // Intercept the flush callback of the wrapped store, to delay, fail or hold it.
+        let (tx, rx) = C::AsyncRuntime::oneshot();
+        let res = self.inner.append(entries, callback.with_sender(tx)).await;
+        if res.is_err() {
+            self.faults.remove_unflushed(seq);
+            return res;
+        }
+
+        let faults = self.faults.clone();
+        let forward = C::AsyncRuntime::spawn(async move {
+            match rx.await {
+                Ok(flushed) => faults.flush(seq, callback, flushed.map(|_| ())).await,
+                // The wrapped store dropped the callback without calling it.
+                Err(_) => faults.remove_unflushed(seq),
+            }
+        });
+        // Detach the task.
+        drop(forward);
+
+        Ok(())
+    }
+
+    async fn truncate(&mut self, log_id: LogId<C::NodeId>) -> Result<(), StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::Truncate).await?;
+        self.inner.truncate(log_id).await
+    }
+
+    async fn purge(&mut self, log_id: LogId<C::NodeId>) -> Result<(), StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::Purge).await?;
+        self.inner.purge(log_id).await
+    }
+}
@@ -0,0 +1,333 @@
+//! Storage wrappers that inject faults, to test how Raft reacts to a failing storage.
+//!
+//! [`FaultyLogStore`] and [`FaultyStateMachine`] wrap any [`RaftLogStorage`] or
+//! [`RaftStateMachine`] and consult a shared [`StorageFaults`] before each operation. A test keeps
+//! a clone of the [`StorageFaults`] and changes the faults while Raft is running:
+//!
+//! ```ignore
+//! let faults = StorageFaults::new();
+//! let log_store = FaultyLogStore::new(log_store, faults.clone());
+//! let sm = FaultyStateMachine::new(sm, faults.clone());
+//! let raft = Raft::new(1, config, network, log_store.clone(), sm).await?;
+//!
+//! // Fail the 3rd append from now.
+//! faults.fail(StorageOp::Append, Trigger::Nth(faults.calls(StorageOp::Append) + 3));
+//!
+//! // Do not report appended logs as flushed, then lose them as if the process crashed.
+//! faults.hold_flush();
+//! raft.shutdown().await?;
+//! log_store.drop_unflushed().await?;
+//! ```
+//!
+//! [`RaftLogStorage`]: crate::storage::RaftLogStorage
+//! [`RaftStateMachine`]: crate::storage::RaftStateMachine
+
+mod log_store;
+mod state_machine;
+
+#[cfg(test)] mod fault_test;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyerror::AnyError;
+pub use log_store::FaultyLogStore;
+pub use state_machine::FaultyStateMachine;
+
+use crate::storage::LogFlushed;
+use crate::AsyncRuntime;
+use crate::LogId;
+use crate::RaftTypeConfig;
+use crate::StorageError;
+use crate::StorageIOError;
+
+/// A storage operation that a fault can be injected into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StorageOp {
+    /// [`RaftLogReader::try_get_log_entries()`](crate::RaftLogReader::try_get_log_entries)
+    ReadLogs,
+    ReadVote,
+    ReadCommitted,
+    GetLogState,
+    SaveVote,
+    SaveCommitted,
+    Append,
+
+    /// Calling the [`LogFlushed`] callback of an append.
+    Flush,
+    Truncate,
+    Purge,
+
+    AppliedState,
+    Apply,
+    BuildSnapshot,
+    BeginReceivingSnapshot,
+    InstallSnapshot,
+    GetCurrentSnapshot,
+}
+
+impl fmt::Display for StorageOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl StorageOp {
+    fn error<NID: crate::NodeId>(&self, source: AnyError) -> StorageError<NID> {
+        let io = match self {
+            StorageOp::ReadLogs | StorageOp::GetLogState => StorageIOError::read_logs(source),
+            StorageOp::ReadVote => StorageIOError::read_vote(source),
+            StorageOp::ReadCommitted => StorageIOError::read(source),
+            StorageOp::SaveVote => StorageIOError::write_vote(source),
+            StorageOp::SaveCommitted => StorageIOError::write(source),
+            StorageOp::Append | StorageOp::Flush | StorageOp::Truncate | StorageOp::Purge => {
+                StorageIOError::write_logs(source)
+            }
+            StorageOp::AppliedState | StorageOp::BuildSnapshot => StorageIOError::read_state_machine(source),
+            StorageOp::Apply => StorageIOError::write_state_machine(source),
+            StorageOp::BeginReceivingSnapshot | StorageOp::InstallSnapshot => {
+                StorageIOError::write_snapshot(None, source)
+            }
+            StorageOp::GetCurrentSnapshot => StorageIOError::read_snapshot(None, source),
+        };
+        io.into()
+    }
+}
+
+/// When a fault is triggered, by the number of calls to an operation, counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// Every call.
+    Always,
+
+    /// Only the n-th call.
+    Nth(u64),
+
+    /// The n-th call and every call after it.
+    From(u64),
+}
+
+impl Trigger {
+    fn matches(&self, n: u64) -> bool {
+        match self {
+            Trigger::Always => true,
+            Trigger::Nth(x) => n == *x,
+            Trigger::From(x) => n >= *x,
+        }
+    }
+}
+
+/// What an injected fault does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fault {
+    /// Return an error.
+    Fail,
+
+    /// Return corrupted data: a log read misses an entry in the middle of the range.
+    Corrupt,
+}
+
+/// A flush callback that is held until [`StorageFaults::release_flush()`].
+struct HeldFlush<C>
+where C: RaftTypeConfig
+{
+    seq: u64,
+    callback: LogFlushed<C>,
+    result: Result<(), io::Error>,
+}
+
+struct FaultState<C>
+where C: RaftTypeConfig
+{
+    faults: BTreeMap<StorageOp, (Fault, Trigger)>,
+    delays: BTreeMap<StorageOp, Duration>,
+    calls: BTreeMap<StorageOp, u64>,
+
+    hold_flush: bool,
+    held: Vec<HeldFlush<C>>,
+
+    /// The first log id of every append that is not reported as flushed yet, by sequence number.
+    unflushed: BTreeMap<u64, LogId<C::NodeId>>,
+    next_seq: u64,
+}
+
+/// The faults injected into a [`FaultyLogStore`] and a [`FaultyStateMachine`].
+///
+/// It is cheap to clone and all clones share the same faults.
+pub struct StorageFaults<C>
+where C: RaftTypeConfig
+{
+    state: Arc<Mutex<FaultState<C>>>,
+}
+
+impl<C> Clone for StorageFaults<C>
+where C: RaftTypeConfig
+{
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<C> Default for StorageFaults<C>
+where C: RaftTypeConfig
+{
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FaultState {
+                faults: BTreeMap::new(),
+                delays: BTreeMap::new(),
+                calls: BTreeMap::new(),
+                hold_flush: false,
+                held: vec![],
+                unflushed: BTreeMap::new(),
+                next_seq: 0,
+            })),
+        }
+    }
+}
+
+impl<C> StorageFaults<C>
+where C: RaftTypeConfig
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `op` return an error when `trigger` matches.
+    ///
+    /// For [`StorageOp::Flush`], the [`LogFlushed`] callback is called with an error.
+    pub fn fail(&self, op: StorageOp, trigger: Trigger) {
+        self.state.lock().unwrap().faults.insert(op, (Fault::Fail, trigger));
+    }
+
+    /// Make a log read return a range with an entry missing in the middle, i.e., a hole, when
+    /// `trigger` matches.
+    pub fn corrupt_reads(&self, trigger: Trigger) {
+        self.state.lock().unwrap().faults.insert(StorageOp::ReadLogs, (Fault::Corrupt, trigger));
+    }
+
+    /// Delay every call to `op` by `delay`.
+    ///
+    /// For [`StorageOp::Flush`], the [`LogFlushed`] callback is called `delay` after the entries
+    /// are flushed by the wrapped store.
+    pub fn delay(&self, op: StorageOp, delay: Duration) {
+        self.state.lock().unwrap().delays.insert(op, delay);
+    }
+
+    /// Hold the [`LogFlushed`] callbacks of the following appends until
+    /// [`release_flush()`](Self::release_flush), i.e., the appended logs are not reported as
+    /// persisted.
+    pub fn hold_flush(&self) {
+        self.state.lock().unwrap().hold_flush = true;
+    }
+
+    /// Stop holding [`LogFlushed`] callbacks and call all of the held ones in order.
+    pub fn release_flush(&self) {
+        let held = {
+            let mut st = self.state.lock().unwrap();
+            st.hold_flush = false;
+            let held = std::mem::take(&mut st.held);
+            for h in held.iter() {
+                if h.result.is_ok() {
+                    st.unflushed.remove(&h.seq);
+                }
+            }
+            held
+        };
+
+        for h in held {
+            h.callback.log_io_completed(h.result);
+        }
+    }
+
+    /// Remove all the faults and delays. Held flush callbacks are not released.
+    pub fn clear(&self) {
+        let mut st = self.state.lock().unwrap();
+        st.faults.clear();
+        st.delays.clear();
+    }
+
+    /// Return the number of calls to `op` so far.
+    pub fn calls(&self, op: StorageOp) -> u64 {
+        self.state.lock().unwrap().calls.get(&op).copied().unwrap_or_default()
+    }
+
+    /// Count a call to `op`, wait for its delay, and return the fault to inject, if any.
+    async fn on_call(&self, op: StorageOp) -> Option<(Fault, u64)> {
+        let (n, fault, delay) = {
+            let mut st = self.state.lock().unwrap();
+            let n = st.calls.entry(op).or_default();
+            *n += 1;
+            let n = *n;
+
+            let fault = st.faults.get(&op).and_then(|(f, t)| t.matches(n).then_some(*f));
+            (n, fault, st.delays.get(&op).copied())
+        };
+
+        if let Some(d) = delay {
+            C::AsyncRuntime::sleep(d).await;
+        }
+
+        fault.map(|f| (f, n))
+    }
+
+    /// Count a call to `op`, and return an error if a failure is injected.
+    async fn check(&self, op: StorageOp) -> Result<(), StorageError<C::NodeId>> {
+        match self.on_call(op).await {
+            Some((Fault::Fail, n)) => Err(op.error(injected(op, n))),
+            _ => Ok(()),
+        }
+    }
+
+    fn add_unflushed(&self, log_id: LogId<C::NodeId>) -> u64 {
+        let mut st = self.state.lock().unwrap();
+        let seq = st.next_seq;
+        st.next_seq += 1;
+        st.unflushed.insert(seq, log_id);
+        seq
+    }
+
+    fn remove_unflushed(&self, seq: u64) {
+        self.state.lock().unwrap().unflushed.remove(&seq);
+    }
+
+    /// Report the result of a flush to Raft, or hold it.
+    async fn flush(&self, seq: u64, callback: LogFlushed<C>, mut result: Result<(), io::Error>) {
+        if let Some((Fault::Fail, n)) = self.on_call(StorageOp::Flush).await {
+            result = Err(io::Error::new(io::ErrorKind::Other, injected(StorageOp::Flush, n)));
+        }
+
+        {
+            let mut st = self.state.lock().unwrap();
+            if st.hold_flush {
+                st.held.push(HeldFlush { seq, callback, result });
+                return;
+            }
+            if result.is_ok() {
+                st.unflushed.remove(&seq);
+            }
+        }
+
+        callback.log_io_completed(result);
+    }
+
+    /// Forget the held flush callbacks, stop holding new ones, and return the smallest log id that
+    /// is not flushed.
+    fn take_unflushed(&self) -> Option<LogId<C::NodeId>> {
+        let mut st = self.state.lock().unwrap();
+        st.hold_flush = false;
+        st.held.clear();
+        let unflushed = std::mem::take(&mut st.unflushed);
+        unflushed.into_values().min()
+    }
+}
+
+fn injected(op: StorageOp, n: u64) -> AnyError {
+    AnyError::error(format!("injected fault: {} #{}", op, n))
+}
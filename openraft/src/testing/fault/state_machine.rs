@@ -0,0 +1,128 @@
+use crate::storage::RaftStateMachine;
+use crate::storage::SnapshotSignature;
+use crate::testing::fault::StorageFaults;
+use crate::testing::fault::StorageOp;
+use crate::LogId;
+use crate::OptionalSend;
+use crate::RaftSnapshotBuilder;
+use crate::RaftTypeConfig;
+use crate::Snapshot;
+use crate::SnapshotMeta;
+use crate::StorageError;
+use crate::StoredMembership;
+
+/// Wraps a [`RaftStateMachine`] or a [`RaftSnapshotBuilder`] and injects the faults in a
+/// [`StorageFaults`].
+///
+/// The snapshot builder it returns injects the same faults.
+pub struct FaultyStateMachine<C, S>
+where C: RaftTypeConfig
+{
+    inner: S,
+    faults: StorageFaults<C>,
+}
+
+impl<C, S> Clone for FaultyStateMachine<C, S>
+where
+    C: RaftTypeConfig,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            faults: self.faults.clone(),
+        }
+    }
+}
+
+impl<C, S> FaultyStateMachine<C, S>
+where C: RaftTypeConfig
+{
+    pub fn new(inner: S, faults: StorageFaults<C>) -> Self {
+        Self { inner, faults }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn faults(&self) -> &StorageFaults<C> {
+        &self.faults
+    }
+}
+
+impl<C, S> RaftSnapshotBuilder<C> for FaultyStateMachine<C, S>
+where
+    C: RaftTypeConfig,
+    S: RaftSnapshotBuilder<C>,
+{
+    async fn build_snapshot(&mut self) -> Result<Snapshot<C>, StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::BuildSnapshot).await?;
+        self.inner.build_snapshot().await
+    }
+}
+
+impl<C, S> RaftStateMachine<C> for FaultyStateMachine<C, S>
+where
+    C: RaftTypeConfig,
+    S: RaftStateMachine<C>,
+{
+    type SnapshotBuilder = FaultyStateMachine<C, S::SnapshotBuilder>;
+
+    async fn applied_state(
+        &mut self,
+    ) -> Result<(Option<LogId<C::NodeId>>, StoredMembership<C>), StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::AppliedState).await?;
+        self.inner.applied_state().await
+    }
+
+    async fn apply<I>(&mut self, entries: I) -> Result<Vec<C::R>, StorageError<C::NodeId>>
+    where
+        I: IntoIterator<Item = C::Entry> + OptionalSend,
+        I::IntoIter: OptionalSend,
+    {
+        self.faults.check(StorageOp::Apply).await?;
+        self.inner.apply(entries).await
+    }
+
+    async fn get_snapshot_builder(&mut self) -> Self::SnapshotBuilder {
+        FaultyStateMachine::new(self.inner.get_snapshot_builder().await, self.faults.clone())
+    }
+
+    async fn begin_receiving_snapshot(&mut self) -> Result<Box<C::SnapshotData>, StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::BeginReceivingSnapshot).await?;
+        self.inner.begin_receiving_snapshot().await
+    }
+
+    async fn install_snapshot(
+        &mut self,
+        meta: &SnapshotMeta<C>,
+        snapshot: Box<C::SnapshotData>,
+    ) -> Result<(), StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::InstallSnapshot).await?;
+        self.inner.install_snapshot(meta, snapshot).await
+    }
+
+    async fn get_snapshot_delta(
+        &mut self,
+        base: &SnapshotSignature<C::NodeId>,
+    ) -> Result<Option<Snapshot<C>>, StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::GetCurrentSnapshot).await?;
+        self.inner.get_snapshot_delta(base).await
+    }
+
+    async fn install_snapshot_delta(
+        &mut self,
+        base: &SnapshotSignature<C::NodeId>,
+        meta: &SnapshotMeta<C>,
+        delta: Box<C::SnapshotData>,
+    ) -> Result<(), StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::InstallSnapshot).await?;
+        self.inner.install_snapshot_delta(base, meta, delta).await
+    }
+
+    async fn get_current_snapshot(&mut self) -> Result<Option<Snapshot<C>>, StorageError<C::NodeId>> {
+        self.faults.check(StorageOp::GetCurrentSnapshot).await?;
+        self.inner.get_current_snapshot().await
+    }
+}
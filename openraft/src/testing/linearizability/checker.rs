@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::testing::linearizability::Op;
+use crate::testing::linearizability::Operation;
+use crate::testing::linearizability::Output;
+
+/// A history that is not linearizable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearizabilityError<K, V> {
+    /// The key whose operations can not be linearized.
+    pub key: K,
+
+    /// All the operations on `key`.
+    pub operations: Vec<Operation<K, V>>,
+}
+
+impl<K, V> fmt::Display for LinearizabilityError<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "history of key {:?} is not linearizable:", self.key)?;
+
+        for op in self.operations.iter() {
+            write!(f, "\n  client {}: [{}, ", op.client, op.invoked_at)?;
+            match &op.completed {
+                Some((t, output)) => write!(f, "{}] {:?} -> {:?}", t, op.op, output)?,
+                None => write!(f, "?] {:?} -> ?", op.op)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K, V> std::error::Error for LinearizabilityError<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+}
+
+/// Check if a history of a key-value store is linearizable.
+///
+/// Operations on different keys are independent, thus each key is checked on its own, as a
+/// register. The register of each key is searched with the algorithm of Wing, Gong and Lowe: an
+/// operation can be linearized next only if it is invoked before any other pending operation
+/// completes. The visited `(linearized operations, value)` states are cached to cut the search.
+///
+/// A write with unknown outcome may be linearized at any time after its invocation, or never. A
+/// read with unknown outcome tells nothing and is ignored.
+pub(crate) fn check<K, V>(operations: &[Operation<K, V>]) -> Result<(), LinearizabilityError<K, V>>
+where
+    K: Ord + Clone,
+    V: Eq + Ord + Clone,
+{
+    let mut by_key: BTreeMap<&K, Vec<&Operation<K, V>>> = BTreeMap::new();
+    for op in operations {
+        by_key.entry(op.op.key()).or_default().push(op);
+    }
+
+    for (key, ops) in by_key {
+        if !Register::new(&ops).search(None) {
+            return Err(LinearizabilityError {
+                key: key.clone(),
+                operations: ops.into_iter().cloned().collect(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+enum Step<'a, V> {
+    Write(&'a V),
+    Read(&'a Option<V>),
+}
+
+struct Call<'a, V> {
+    step: Step<'a, V>,
+    invoked_at: u64,
+
+    /// `u64::MAX` if the outcome is unknown.
+    completed_at: u64,
+}
+
+/// The search state of the operations on one key.
+struct Register<'a, V> {
+    calls: Vec<Call<'a, V>>,
+
+    /// A bitmap of the linearized calls.
+    linearized: Vec<u64>,
+
+    /// The number of completed calls that are not linearized yet.
+    remaining: usize,
+
+    visited: BTreeSet<(Vec<u64>, Option<&'a V>)>,
+}
+
+impl<'a, V> Register<'a, V>
+where V: Eq + Ord
+{
+    fn new<K>(ops: &[&'a Operation<K, V>]) -> Self {
+        let mut calls = vec![];
+
+        for op in ops {
+            let completed_at = op.completed.as_ref().map(|(t, _)| *t).unwrap_or(u64::MAX);
+
+            let step = match (&op.op, &op.completed) {
+                (Op::Write { value, .. }, _) => Step::Write(value),
+                (Op::Read { .. }, Some((_, Output::Read(v)))) => Step::Read(v),
+                // A read with unknown outcome
+                (Op::Read { .. }, _) => continue,
+            };
+
+            calls.push(Call {
+                step,
+                invoked_at: op.invoked_at,
+                completed_at,
+            });
+        }
+
+        let remaining = calls.iter().filter(|c| c.completed_at != u64::MAX).count();
+
+        Self {
+            linearized: vec![0; (calls.len() + 63) / 64],
+            calls,
+            remaining,
+            visited: BTreeSet::new(),
+        }
+    }
+
+    fn is_linearized(&self, i: usize) -> bool {
+        self.linearized[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn toggle(&mut self, i: usize) {
+        self.linearized[i / 64] ^= 1 << (i % 64);
+    }
+
+    /// Search for a linearization of the rest calls, with the register holding `value`.
+    fn search(&mut self, value: Option<&'a V>) -> bool {
+        if self.remaining == 0 {
+            return true;
+        }
+
+        let first_completed =
+            (0..self.calls.len()).filter(|i| !self.is_linearized(*i)).map(|i| self.calls[i].completed_at).min();
+        let Some(first_completed) = first_completed else {
+            return true;
+        };
+
+        for i in 0..self.calls.len() {
+            if self.is_linearized(i) || self.calls[i].invoked_at > first_completed {
+                continue;
+            }
+
+            let next = match self.calls[i].step {
+                Step::Write(v) => Some(v),
+                Step::Read(v) => {
+                    if v.as_ref() != value {
+                        continue;
+                    }
+                    value
+                }
+            };
+
+            let completed = self.calls[i].completed_at != u64::MAX;
+
+            self.toggle(i);
+            if completed {
+                self.remaining -= 1;
+            }
+
+            if self.visited.insert((self.linearized.clone(), next)) && self.search(next) {
+                return true;
+            }
+
+            self.toggle(i);
+            if completed {
+                self.remaining += 1;
+            }
+        }
+
+        false
+    }
+}
@@ -0,0 +1,210 @@
+use std::error::Error;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::error::CheckIsLeaderError;
+use crate::error::ClientWriteError;
+use crate::error::RaftError;
+use crate::raft::ClientWriteResponse;
+use crate::raft::ClientWriteResult;
+use crate::raft::ReadPolicy;
+use crate::testing::linearizability::checker;
+use crate::testing::linearizability::LinearizabilityError;
+use crate::type_config::alias::ResponderReceiverOf;
+use crate::OptionalSend;
+use crate::Raft;
+use crate::RaftTypeConfig;
+
+/// An operation on a key-value store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op<K, V> {
+    Write { key: K, value: V },
+    Read { key: K },
+}
+
+impl<K, V> Op<K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Op::Write { key, .. } => key,
+            Op::Read { key } => key,
+        }
+    }
+}
+
+/// The output of a completed [`Op`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output<V> {
+    Written,
+
+    /// The value read, or `None` if the key is not set.
+    Read(Option<V>),
+}
+
+/// An operation in a [`History`], with its invocation and completion time.
+///
+/// The time is logical: it is the position of the event in the history, so that the invocation
+/// or completion of two operations are never at the same time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation<K, V> {
+    /// The client that invokes the operation.
+    pub client: u64,
+
+    pub op: Op<K, V>,
+
+    pub invoked_at: u64,
+
+    /// The time and output when the operation completes.
+    ///
+    /// `None` if the outcome is unknown, e.g., the client timed out, or the node crashed before
+    /// responding. Such a write may or may not have taken effect.
+    pub completed: Option<(u64, Output<V>)>,
+}
+
+/// The index of an invoked operation in a [`History`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpId(usize);
+
+#[derive(Debug)]
+struct HistoryInner<K, V> {
+    clock: u64,
+
+    /// The operations that may have taken effect, by [`OpId`]. A failed operation is `None`.
+    ops: Vec<Option<Operation<K, V>>>,
+}
+
+/// Records the operations invoked by concurrent clients of a key-value store and their results,
+/// to [`check()`](Self::check) whether the history is linearizable.
+///
+/// The store is assumed to be empty when the history starts.
+///
+/// It is cheap to clone and all clones record to the same history.
+#[derive(Debug, Clone)]
+pub struct History<K, V> {
+    inner: Arc<Mutex<HistoryInner<K, V>>>,
+}
+
+impl<K, V> Default for History<K, V> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(HistoryInner { clock: 0, ops: vec![] })),
+        }
+    }
+}
+
+impl<K, V> History<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the invocation of `op` by `client`.
+    ///
+    /// If the operation is not completed or failed later, its outcome is unknown.
+    pub fn invoke(&self, client: u64, op: Op<K, V>) -> OpId {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+
+        let operation = Operation {
+            client,
+            op,
+            invoked_at: inner.clock,
+            completed: None,
+        };
+        inner.ops.push(Some(operation));
+        OpId(inner.ops.len() - 1)
+    }
+
+    /// Record the completion of an operation with its output.
+    pub fn complete(&self, id: OpId, output: Output<V>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+
+        let clock = inner.clock;
+        if let Some(op) = inner.ops[id.0].as_mut() {
+            op.completed = Some((clock, output));
+        }
+    }
+
+    /// Record that an operation failed without taking effect, and remove it from the history.
+    pub fn fail(&self, id: OpId) {
+        self.inner.lock().unwrap().ops[id.0] = None;
+    }
+
+    /// Return all the operations that may have taken effect, in invocation order.
+    pub fn operations(&self) -> Vec<Operation<K, V>> {
+        self.inner.lock().unwrap().ops.iter().flatten().cloned().collect()
+    }
+
+    /// Check if the recorded history is linearizable.
+    pub fn check(&self) -> Result<(), LinearizabilityError<K, V>>
+    where
+        K: Ord,
+        V: Eq + Ord,
+    {
+        checker::check(&self.operations())
+    }
+
+    /// Write `value` to `key` with [`Raft::client_write()`], and record it.
+    ///
+    /// `app_data` is the application request that sets `key` to `value`.
+    ///
+    /// A write rejected with [`ClientWriteError`] is not proposed and is recorded as failed. A
+    /// write that fails otherwise, or whose future is dropped before completion, has an unknown
+    /// outcome.
+    pub async fn write<C, E>(
+        &self,
+        client: u64,
+        raft: &Raft<C>,
+        key: K,
+        value: V,
+        app_data: C::D,
+    ) -> Result<ClientWriteResponse<C>, RaftError<C, ClientWriteError<C>>>
+    where
+        C: RaftTypeConfig,
+        ResponderReceiverOf<C>: Future<Output = Result<ClientWriteResult<C>, E>>,
+        E: Error + OptionalSend,
+    {
+        let id = self.invoke(client, Op::Write { key, value });
+
+        let res = raft.client_write(app_data).await;
+        match &res {
+            Ok(_) => self.complete(id, Output::Written),
+            Err(RaftError::APIError(_)) => self.fail(id),
+            Err(RaftError::Fatal(_)) => {}
+        }
+        res
+    }
+
+    /// Read `key` after [`Raft::ensure_linearizable_with()`], and record it.
+    ///
+    /// `read` reads the value of `key` from the local state machine of `raft`. A read that fails
+    /// has no effect and is recorded as failed.
+    pub async fn read<C, F, Fu>(
+        &self,
+        client: u64,
+        raft: &Raft<C>,
+        read_policy: ReadPolicy,
+        key: K,
+        read: F,
+    ) -> Result<Option<V>, RaftError<C, CheckIsLeaderError<C>>>
+    where
+        C: RaftTypeConfig,
+        F: FnOnce(K) -> Fu,
+        Fu: Future<Output = Option<V>>,
+    {
+        let id = self.invoke(client, Op::Read { key: key.clone() });
+
+        if let Err(e) = raft.ensure_linearizable_with(read_policy).await {
+            self.fail(id);
+            return Err(e);
+        }
+
+        let value = read(key).await;
+        self.complete(id, Output::Read(value.clone()));
+        Ok(value)
+    }
+}
@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::raft::ReadPolicy;
+use crate::testing::linearizability::History;
+use crate::testing::linearizability::Op;
+use crate::testing::linearizability::Output;
+use crate::testing::sim::run_seeds;
+use crate::testing::sim::SimCluster;
+use crate::testing::sim::SimNetworkConfig;
+use crate::testing::sim::SimNode;
+use crate::testing::sim::SimRequest;
+use crate::testing::sim::SimRuntime;
+use crate::testing::sim::Simulation;
+use crate::AsyncRuntime;
+use crate::Config;
+
+fn w(key: &str, value: u64) -> Op<String, u64> {
+    Op::Write {
+        key: key.to_string(),
+        value,
+    }
+}
+
+fn r(key: &str) -> Op<String, u64> {
+    Op::Read { key: key.to_string() }
+}
+
+#[test]
+fn test_sequential() {
+    let h = History::new();
+
+    let a = h.invoke(1, w("a", 1));
+    h.complete(a, Output::Written);
+    let b = h.invoke(2, r("a"));
+    h.complete(b, Output::Read(Some(1)));
+    assert!(h.check().is_ok());
+
+    // Stale read
+    let c = h.invoke(2, r("a"));
+    h.complete(c, Output::Read(None));
+    let err = h.check().unwrap_err();
+    assert_eq!("a", err.key);
+    assert_eq!(3, err.operations.len());
+}
+
+#[test]
+fn test_concurrent() {
+    // A read concurrent with a write may see either value, but a later read must not go back.
+    let h = History::new();
+
+    let a = h.invoke(1, w("a", 1));
+    let b = h.invoke(2, r("a"));
+    let c = h.invoke(3, r("a"));
+    h.complete(b, Output::Read(Some(1)));
+    h.complete(c, Output::Read(None));
+    h.complete(a, Output::Written);
+    assert!(h.check().is_ok());
+
+    let d = h.invoke(2, r("a"));
+    let e = h.invoke(3, r("a"));
+    h.complete(d, Output::Read(Some(1)));
+    h.complete(e, Output::Read(Some(1)));
+    assert!(h.check().is_ok());
+
+    let h = History::new();
+
+    let a = h.invoke(1, w("a", 1));
+    let b = h.invoke(2, r("a"));
+    h.complete(b, Output::Read(Some(1)));
+    let c = h.invoke(3, r("a"));
+    h.complete(c, Output::Read(None));
+    h.complete(a, Output::Written);
+    assert!(h.check().is_err());
+}
+
+#[test]
+fn test_unknown_and_failed() {
+    // A write with unknown outcome may take effect at any time after it is invoked.
+    let h = History::new();
+
+    let a = h.invoke(1, w("a", 1));
+    let b = h.invoke(2, r("a"));
+    h.complete(b, Output::Read(None));
+    let c = h.invoke(2, r("a"));
+    h.complete(c, Output::Read(Some(1)));
+    let _unknown_read = h.invoke(3, r("a"));
+    assert!(h.check().is_ok());
+
+    h.complete(a, Output::Written);
+    assert!(h.check().is_ok());
+
+    // A failed write never takes effect.
+    let h = History::new();
+
+    let a = h.invoke(1, w("a", 1));
+    h.fail(a);
+    let b = h.invoke(2, r("a"));
+    h.complete(b, Output::Read(Some(1)));
+    assert!(h.check().is_err());
+    assert!(h.check().unwrap_err().to_string().starts_with(r#"history of key "a" is not linearizable:"#));
+}
+
+#[test]
+fn test_keys_are_independent() {
+    let h = History::new();
+
+    let a = h.invoke(1, w("a", 1));
+    h.complete(a, Output::Written);
+    let b = h.invoke(1, w("b", 2));
+    h.complete(b, Output::Written);
+    let c = h.invoke(2, r("b"));
+    h.complete(c, Output::Read(Some(2)));
+    let d = h.invoke(2, r("a"));
+    h.complete(d, Output::Read(Some(1)));
+    assert!(h.check().is_ok());
+
+    let e = h.invoke(2, r("b"));
+    h.complete(e, Output::Read(Some(1)));
+    assert_eq!("b", h.check().unwrap_err().key);
+}
+
+/// Concurrent clients write and read through the leader while the network drops, duplicates and
+/// partitions messages.
+fn run_clients(seed: u64, read_policy: ReadPolicy) {
+    Simulation::new(seed).block_on(async {
+        let net_config = SimNetworkConfig {
+            drop_rate: 0.02,
+            duplicate_rate: 0.02,
+            ..Default::default()
+        };
+        let mut cluster = SimCluster::new(Config::default(), net_config).unwrap();
+        let ids = [1, 2, 3];
+        cluster.initialize(ids).await.unwrap();
+        cluster.wait_for_leader(Duration::from_secs(10)).await.unwrap();
+
+        let nodes = ids.map(|id| cluster.node(id).unwrap().clone());
+        let history = History::<String, String>::new();
+
+        let mut clients = vec![];
+        for client in 0..4 {
+            let nodes = nodes.clone();
+            let history = history.clone();
+
+            clients.push(SimRuntime::spawn(async move {
+                for i in 0..10 {
+                    let node = leader_of(&nodes);
+                    let key = format!("k{}", SimRuntime::thread_rng().gen_range(0..2));
+                    let timeout = Duration::from_secs(1);
+
+                    if SimRuntime::thread_rng().gen_bool(0.5) {
+                        let value = format!("{}-{}", client, i);
+                        let req = SimRequest::new(&key, &value);
+                        let write = history.write(client, &node.raft, key, value, req);
+                        let _ = SimRuntime::timeout(timeout, write).await;
+                    } else {
+                        let sm = node.state_machine.clone();
+                        let read = history.read(client, &node.raft, read_policy, key, |k| async move { sm.get(&k) });
+                        let _ = SimRuntime::timeout(timeout, read).await;
+                    }
+                }
+            }));
+        }
+
+        for _ in 0..5 {
+            let ms = SimRuntime::thread_rng().gen_range(100..500);
+            SimRuntime::sleep(Duration::from_millis(ms)).await;
+
+            if SimRuntime::thread_rng().gen_bool(0.5) {
+                let id = ids[SimRuntime::thread_rng().gen_range(0..ids.len())];
+                cluster.network().isolate(id, ids);
+            } else {
+                cluster.network().heal();
+            }
+        }
+        cluster.network().heal();
+
+        for c in clients {
+            c.await.unwrap();
+        }
+
+        assert!(history.operations().iter().any(|op| op.completed.is_some()));
+        history.check().unwrap_or_else(|e| panic!("{}", e));
+    });
+}
+
+/// Return the node that a randomly chosen node believes is the leader.
+fn leader_of(nodes: &[SimNode]) -> &SimNode {
+    let i = SimRuntime::thread_rng().gen_range(0..nodes.len());
+    let leader = nodes[i].raft.metrics().borrow().current_leader;
+
+    leader.and_then(|id| nodes.iter().find(|n| n.raft.metrics().borrow().id == id)).unwrap_or(&nodes[i])
+}
+
+#[test]
+fn test_cluster_read_index() {
+    run_seeds(10, |seed| run_clients(seed, ReadPolicy::ReadIndex));
+}
+
+#[test]
+fn test_cluster_lease_read() {
+    run_seeds(10, |seed| run_clients(seed, ReadPolicy::LeaseRead));
+}
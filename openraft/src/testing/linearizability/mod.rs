@@ -0,0 +1,23 @@
+//! A history recorder and a linearizability checker for a key-value store built on openraft.
+//!
+//! Clients record their operations in a [`History`] with [`History::write()`] and
+//! [`History::read()`], which wrap [`Raft::client_write()`] and
+//! [`Raft::ensure_linearizable_with()`]. After the test, including any injected faults,
+//! [`History::check()`] checks that the results observed by the clients can be explained by a
+//! single key-value store, in which every operation takes effect at one instant between its
+//! invocation and completion.
+//!
+//! [`Raft::client_write()`]: crate::Raft::client_write
+//! [`Raft::ensure_linearizable_with()`]: crate::Raft::ensure_linearizable_with
+
+mod checker;
+mod history;
+
+#[cfg(test)] mod linearizability_test;
+
+pub use checker::LinearizabilityError;
+pub use history::History;
+pub use history::Op;
+pub use history::OpId;
+pub use history::Operation;
+pub use history::Output;
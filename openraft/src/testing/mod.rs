@@ -1,5 +1,8 @@
 //! Testing utilities for OpenRaft.
 
+pub mod fault;
+pub mod linearizability;
+pub mod sim;
 mod store_builder;
 mod suite;
 
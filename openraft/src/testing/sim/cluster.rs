@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::ClientWriteError;
+use crate::error::Fatal;
+use crate::error::InitializeError;
+use crate::error::RaftError;
+use crate::raft::ClientWriteResponse;
+use crate::storage::RaftLogStorage;
+use crate::testing::sim::executor;
+use crate::testing::sim::SimConfig;
+use crate::testing::sim::SimInstant;
+use crate::testing::sim::SimLogStore;
+use crate::testing::sim::SimNetwork;
+use crate::testing::sim::SimNetworkConfig;
+use crate::testing::sim::SimRequest;
+use crate::testing::sim::SimRuntime;
+use crate::testing::sim::SimStateMachine;
+use crate::testing::sim::SimStorage;
+use crate::AsyncRuntime;
+use crate::BasicNode;
+use crate::Config;
+use crate::ConfigError;
+use crate::Instant;
+use crate::Raft;
+use crate::RaftLogReader;
+use crate::ServerState;
+
+/// A running node in a [`SimCluster`].
+#[derive(Clone)]
+pub struct SimNode {
+    pub raft: Raft<SimConfig>,
+    pub log_store: SimLogStore,
+    pub state_machine: SimStateMachine,
+}
+
+/// A cluster of [`Raft`] nodes running in a [`Simulation`], connected by a [`SimNetwork`].
+///
+/// The storage of a node survives [`crash()`](Self::crash), so that a node can be restarted
+/// with its vote, logs and snapshot, like a process restarting on the same disk.
+///
+/// [`Simulation`]: crate::testing::sim::Simulation
+pub struct SimCluster {
+    config: Arc<Config>,
+    net: SimNetwork,
+    storages: BTreeMap<u64, SimStorage>,
+    nodes: BTreeMap<u64, SimNode>,
+}
+
+impl SimCluster {
+    pub fn new(config: Config, net_config: SimNetworkConfig) -> Result<Self, ConfigError> {
+        Ok(Self {
+            config: Arc::new(config.validate()?),
+            net: SimNetwork::new(net_config),
+            storages: BTreeMap::new(),
+            nodes: BTreeMap::new(),
+        })
+    }
+
+    pub fn network(&self) -> &SimNetwork {
+        &self.net
+    }
+
+    /// Return the node `id` if it is running.
+    pub fn node(&self, id: u64) -> Option<&SimNode> {
+        self.nodes.get(&id)
+    }
+
+    /// Return the ids of the running nodes.
+    pub fn running(&self) -> Vec<u64> {
+        self.nodes.keys().copied().collect()
+    }
+
+    /// Start the node `id`, with the storage it used before crashing, or with an empty one.
+    ///
+    /// All the tasks of the node are spawned on node `id`, so that they are dropped by
+    /// [`crash()`](Self::crash).
+    pub async fn start(&mut self, id: u64) -> Result<Raft<SimConfig>, Fatal<SimConfig>> {
+        assert!(!self.nodes.contains_key(&id), "node {} is already running", id);
+
+        let storage = self.storages.entry(id).or_default().clone();
+        let log_store = storage.log_store();
+        let state_machine = storage.state_machine().map_err(Fatal::StorageError)?;
+
+        let raft = {
+            let config = self.config.clone();
+            let network = self.net.factory(id);
+            let (ls, sm) = (log_store.clone(), state_machine.clone());
+
+            executor::spawn_on(Some(id), Raft::new(id, config, network, ls, sm))
+                .await
+                .map_err(|_| Fatal::Panicked)??
+        };
+
+        self.net.register(id, raft.clone());
+        self.nodes.insert(id, SimNode {
+            raft: raft.clone(),
+            log_store,
+            state_machine,
+        });
+
+        Ok(raft)
+    }
+
+    /// Crash the node `id`: drop all of its tasks at once, without shutting down gracefully.
+    ///
+    /// Returns `false` if the node is not running.
+    pub fn crash(&mut self, id: u64) -> bool {
+        self.net.unregister(id);
+        let Some(node) = self.nodes.remove(&id) else {
+            return false;
+        };
+
+        executor::crash(id);
+        drop(node);
+        true
+    }
+
+    /// Crash the node `id` if it is running, then start it again.
+    pub async fn restart(&mut self, id: u64) -> Result<Raft<SimConfig>, Fatal<SimConfig>> {
+        self.crash(id);
+        self.start(id).await
+    }
+
+    /// Start the nodes in `ids` that are not running, and initialize the cluster with them as
+    /// voters.
+    pub async fn initialize(
+        &mut self,
+        ids: impl IntoIterator<Item = u64>,
+    ) -> Result<(), RaftError<SimConfig, InitializeError<SimConfig>>> {
+        let ids = ids.into_iter().collect::<Vec<_>>();
+
+        for id in ids.iter().copied() {
+            if !self.nodes.contains_key(&id) {
+                self.start(id).await?;
+            }
+        }
+
+        let members = ids.iter().map(|id| (*id, BasicNode::new(format!("sim-{}", id)))).collect::<BTreeMap<_, _>>();
+        let first = self.nodes[&ids[0]].raft.clone();
+        first.initialize(members).await
+    }
+
+    /// Return a running node that believes it is the leader, with the greatest term.
+    pub fn leader(&self) -> Option<u64> {
+        self.nodes
+            .iter()
+            .filter_map(|(id, n)| {
+                let m = n.raft.metrics().borrow().clone();
+                (m.state == ServerState::Leader).then_some((m.current_term, *id))
+            })
+            .max()
+            .map(|(_, id)| id)
+    }
+
+    /// Wait in virtual time until there is a leader, or return `None` after `timeout`.
+    pub async fn wait_for_leader(&self, timeout: Duration) -> Option<u64> {
+        let deadline = SimInstant::now() + timeout;
+
+        loop {
+            if let Some(id) = self.leader() {
+                return Some(id);
+            }
+            if SimInstant::now() >= deadline {
+                return None;
+            }
+            SimRuntime::sleep(Duration::from_millis(self.config.heartbeat_interval)).await;
+        }
+    }
+
+    /// Propose a write through the node `id`.
+    pub async fn write(
+        &self,
+        id: u64,
+        req: SimRequest,
+    ) -> Result<ClientWriteResponse<SimConfig>, RaftError<SimConfig, ClientWriteError<SimConfig>>> {
+        let raft = self.nodes.get(&id).map(|n| n.raft.clone()).ok_or(RaftError::Fatal(Fatal::Stopped))?;
+        raft.client_write(req).await
+    }
+
+    /// Check the safety of the running nodes, and panic with a description if it is violated:
+    ///
+    /// - The logs of two nodes are identical up to the smaller of their last applied indexes.
+    /// - Two nodes with the same last applied log id have the same state machine.
+    pub async fn assert_consistent(&self) {
+        let mut applied = BTreeMap::new();
+
+        for (id, n) in self.nodes.iter() {
+            let (last_applied, kvs) = n.state_machine.state();
+            applied.insert(*id, (last_applied, kvs));
+        }
+
+        for (a, (applied_a, kvs_a)) in applied.iter() {
+            for (b, (applied_b, kvs_b)) in applied.range(a + 1..) {
+                if applied_a == applied_b {
+                    assert_eq!(
+                        kvs_a, kvs_b,
+                        "node {} and node {} applied up to {:?} but have different state",
+                        a, b, applied_a
+                    );
+                }
+
+                let upto = std::cmp::min(applied_a.map(|x| x.index), applied_b.map(|x| x.index));
+                let Some(upto) = upto else {
+                    continue;
+                };
+
+                let logs_a = self.applied_log_ids(*a, upto).await;
+                let logs_b = self.applied_log_ids(*b, upto).await;
+
+                for (index, log_id) in logs_a.iter() {
+                    if let Some(other) = logs_b.get(index) {
+                        assert_eq!(
+                            log_id, other,
+                            "node {} and node {} have different applied log at index {}",
+                            a, b, index
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    async fn applied_log_ids(&self, id: u64, upto: u64) -> BTreeMap<u64, crate::LogId<u64>> {
+        let mut log_store = self.nodes[&id].log_store.clone();
+        let mut reader = log_store.get_log_reader().await;
+        let entries = reader.try_get_log_entries(..=upto).await.unwrap();
+        entries.into_iter().map(|e| (e.log_id.index, e.log_id)).collect()
+    }
+}
@@ -0,0 +1,353 @@
+//! A single threaded executor with virtual time and seeded scheduling.
+
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::pin;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::task::Waker;
+use std::time::Duration;
+
+use futures::FutureExt;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::testing::sim::runtime::SimJoinError;
+use crate::testing::sim::runtime::SimJoinHandle;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Executor>>> = const { RefCell::new(None) };
+}
+
+type TaskId = u64;
+
+/// The id of the future passed to [`Simulation::block_on()`].
+const MAIN_TASK: TaskId = 0;
+
+struct Task {
+    /// The node this task belongs to. It is dropped when the node crashes.
+    node: Option<u64>,
+    fut: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+/// Puts the task id into the ready set when woken.
+struct TaskWaker {
+    id: TaskId,
+    ready: Arc<Mutex<BTreeSet<TaskId>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.ready.lock().unwrap().insert(self.id);
+    }
+}
+
+pub(crate) struct Executor {
+    rng: RefCell<StdRng>,
+
+    /// Virtual time elapsed since the simulation started.
+    now: Cell<Duration>,
+
+    tasks: RefCell<BTreeMap<TaskId, Task>>,
+
+    /// Tasks that are woken and wait to be polled.
+    ///
+    /// A `Waker` has to be `Send + Sync`, thus it is shared with a `Mutex`.
+    ready: Arc<Mutex<BTreeSet<TaskId>>>,
+
+    /// Pending timers keyed by `(deadline, seq)`.
+    timers: RefCell<BTreeMap<(Duration, u64), Waker>>,
+
+    next_id: Cell<u64>,
+
+    /// The node of the task being polled, inherited by the tasks it spawns.
+    current_node: Cell<Option<u64>>,
+}
+
+impl Executor {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+            now: Cell::new(Duration::ZERO),
+            tasks: RefCell::new(BTreeMap::new()),
+            ready: Arc::new(Mutex::new(BTreeSet::new())),
+            timers: RefCell::new(BTreeMap::new()),
+            next_id: Cell::new(MAIN_TASK + 1),
+            current_node: Cell::new(None),
+        }
+    }
+
+    pub(crate) fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    pub(crate) fn next_seq(&self) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    pub(crate) fn with_rng<T>(&self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        f(&mut self.rng.borrow_mut())
+    }
+
+    pub(crate) fn set_timer(&self, deadline: Duration, seq: u64, waker: Waker) {
+        self.timers.borrow_mut().insert((deadline, seq), waker);
+    }
+
+    pub(crate) fn remove_timer(&self, deadline: Duration, seq: u64) {
+        self.timers.borrow_mut().remove(&(deadline, seq));
+    }
+
+    fn waker(&self, id: TaskId) -> Waker {
+        Waker::from(Arc::new(TaskWaker {
+            id,
+            ready: self.ready.clone(),
+        }))
+    }
+
+    fn spawn_task(&self, node: Option<u64>, fut: Pin<Box<dyn Future<Output = ()>>>) {
+        let id = self.next_seq();
+        self.tasks.borrow_mut().insert(id, Task { node, fut });
+        self.ready.lock().unwrap().insert(id);
+    }
+
+    /// Remove a randomly chosen task from the ready set.
+    fn pop_ready(&self) -> Option<TaskId> {
+        let mut ready = self.ready.lock().unwrap();
+        if ready.is_empty() {
+            return None;
+        }
+
+        let i = self.rng.borrow_mut().gen_range(0..ready.len());
+        let id = *ready.iter().nth(i).unwrap();
+        ready.remove(&id);
+        Some(id)
+    }
+
+    fn poll_task(&self, id: TaskId) {
+        // The task is taken out while being polled, so that it can spawn other tasks.
+        let Some(mut task) = self.tasks.borrow_mut().remove(&id) else {
+            // Finished or crashed.
+            return;
+        };
+
+        let waker = self.waker(id);
+        let prev = self.current_node.replace(task.node);
+        let res = task.fut.as_mut().poll(&mut Context::from_waker(&waker));
+        self.current_node.set(prev);
+
+        if res.is_pending() {
+            self.tasks.borrow_mut().insert(id, task);
+        }
+    }
+
+    /// Advance the virtual time to the earliest timer and wake up all due timers.
+    ///
+    /// Returns `false` if there is no timer.
+    fn fire_timers(&self) -> bool {
+        let mut due = vec![];
+        {
+            let mut timers = self.timers.borrow_mut();
+            let Some((deadline, _)) = timers.keys().next().copied() else {
+                return false;
+            };
+
+            if deadline > self.now.get() {
+                self.now.set(deadline);
+            }
+
+            while let Some(entry) = timers.first_entry() {
+                if entry.key().0 > deadline {
+                    break;
+                }
+                due.push(entry.remove());
+            }
+        }
+
+        for waker in due {
+            waker.wake();
+        }
+        true
+    }
+
+    /// Drop the tasks that match `pred`, outside of any borrow, because dropping a task may
+    /// access the executor.
+    fn drop_tasks(&self, pred: impl Fn(&Task) -> bool) -> usize {
+        let dropped = {
+            let mut tasks = self.tasks.borrow_mut();
+            let ids = tasks.iter().filter(|(_, t)| pred(t)).map(|(id, _)| *id).collect::<Vec<_>>();
+            ids.into_iter().filter_map(|id| tasks.remove(&id)).collect::<Vec<_>>()
+        };
+
+        let n = dropped.len();
+        drop(dropped);
+        n
+    }
+}
+
+pub(crate) fn try_with_executor<T>(f: impl FnOnce(&Executor) -> T) -> Option<T> {
+    let ex = CURRENT.try_with(|c| c.borrow().clone()).ok().flatten()?;
+    Some(f(&ex))
+}
+
+pub(crate) fn with_executor<T>(f: impl FnOnce(&Executor) -> T) -> T {
+    try_with_executor(f).expect("not in a simulation: call it inside `Simulation::block_on()`")
+}
+
+/// Spawn a task on the node `node`.
+pub(crate) fn spawn_on<F>(node: Option<u64>, fut: F) -> SimJoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    let (handle, guard) = SimJoinHandle::new();
+
+    let task = async move {
+        let res = AssertUnwindSafe(fut).catch_unwind().await;
+        guard.complete(res.map_err(SimJoinError::from_panic));
+    };
+
+    with_executor(|ex| ex.spawn_task(node, Box::pin(task)));
+    handle
+}
+
+/// Spawn a task on the node of the current task.
+pub(crate) fn spawn<F>(fut: F) -> SimJoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    let node = with_executor(|ex| ex.current_node.get());
+    spawn_on(node, fut)
+}
+
+/// Drop all tasks of the node `node`, as if the process crashed.
+///
+/// Returns the number of tasks dropped.
+pub(crate) fn crash(node: u64) -> usize {
+    with_executor(|ex| ex.drop_tasks(|t| t.node == Some(node)))
+}
+
+/// A deterministic simulation.
+///
+/// It runs a future and all the tasks it spawns with [`SimRuntime`] in the current thread, with
+/// virtual time: when no task can make progress, the clock jumps to the next timer at once. The
+/// next task to poll among the ready ones is chosen by a random number generator seeded with
+/// `seed`, as are the values returned by [`SimRuntime::thread_rng()`].
+///
+/// Thus a run is fully determined by the seed: a failing seed is replayed exactly by running it
+/// again.
+///
+/// [`SimRuntime`]: crate::testing::sim::SimRuntime
+/// [`SimRuntime::thread_rng()`]: crate::AsyncRuntime::thread_rng
+pub struct Simulation {
+    seed: u64,
+    executor: Rc<Executor>,
+}
+
+impl Simulation {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            executor: Rc::new(Executor::new(seed)),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Run `fut` to completion, and return its output.
+    ///
+    /// The tasks that are still running when `fut` completes are dropped.
+    ///
+    /// # Panics
+    ///
+    /// It panics if it is called inside another simulation, or if no task can make progress and
+    /// there is no pending timer, i.e., the simulation is deadlocked.
+    pub fn block_on<F: Future>(&self, fut: F) -> F::Output {
+        let _guard = EnterGuard::enter(self.executor.clone());
+        let ex = &*self.executor;
+
+        let mut fut = pin!(fut);
+        let main_waker = ex.waker(MAIN_TASK);
+        ex.ready.lock().unwrap().insert(MAIN_TASK);
+
+        let output = loop {
+            let Some(id) = ex.pop_ready() else {
+                if !ex.fire_timers() {
+                    panic!(
+                        "simulation(seed={}) is deadlocked at {:?}: no task is ready and no timer is pending",
+                        self.seed,
+                        ex.now()
+                    );
+                }
+                continue;
+            };
+
+            if id != MAIN_TASK {
+                ex.poll_task(id);
+                continue;
+            }
+
+            let prev = ex.current_node.replace(None);
+            let res = fut.as_mut().poll(&mut Context::from_waker(&main_waker));
+            ex.current_node.set(prev);
+
+            if let Poll::Ready(output) = res {
+                break output;
+            }
+        };
+
+        ex.drop_tasks(|_| true);
+        ex.ready.lock().unwrap().clear();
+
+        output
+    }
+
+    /// Virtual time elapsed since the simulation started.
+    pub fn elapsed(&self) -> Duration {
+        self.executor.now()
+    }
+}
+
+/// Install an executor as the current one, and uninstall it when dropped, including on panic.
+struct EnterGuard;
+
+impl EnterGuard {
+    fn enter(ex: Rc<Executor>) -> Self {
+        CURRENT.with(|c| {
+            let mut c = c.borrow_mut();
+            assert!(c.is_none(), "can not run a simulation inside another simulation");
+            *c = Some(ex);
+        });
+        EnterGuard
+    }
+}
+
+impl Drop for EnterGuard {
+    fn drop(&mut self) {
+        let ex = CURRENT.with(|c| c.borrow_mut().take());
+
+        // Drop the remaining tasks while no executor is installed: they may be left after a panic.
+        if let Some(ex) = ex {
+            let tasks = std::mem::take(&mut *ex.tasks.borrow_mut());
+            drop(tasks);
+        }
+    }
+}
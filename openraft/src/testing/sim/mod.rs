@@ -0,0 +1,131 @@
+//! A deterministic simulator to test a Raft cluster with faults.
+//!
+//! A [`Simulation`] runs a cluster in a single thread with [`SimRuntime`], an [`AsyncRuntime`]
+//! with virtual time: a sleep returns as soon as no task can make progress, so a simulated
+//! minute takes a few milliseconds. Which ready task runs next, the network delays and the
+//! injected faults are all drawn from one random number generator seeded with the seed of the
+//! simulation. A run is therefore determined by its seed, and a failing seed is replayed exactly.
+//!
+//! - [`SimNetwork`] delivers messages with a random delay, thus reordered, and can drop, duplicate
+//!   or partition them.
+//! - [`SimStorage`] keeps the vote, logs and snapshot of a node across a crash. The state machine
+//!   is rebuilt from the snapshot on restart.
+//! - [`SimCluster`] starts, crashes and restarts nodes and checks the safety of the cluster.
+//!
+//! Example:
+//!
+//! ```ignore
+//! use openraft::testing::sim::*;
+//!
+//! run_seeds(100, |seed| {
+//!     Simulation::new(seed).block_on(async {
+//!         let mut cluster = SimCluster::new(Config::default(), SimNetworkConfig::default()).unwrap();
+//!         cluster.initialize([1, 2, 3]).await.unwrap();
+//!
+//!         let leader = cluster.wait_for_leader(Duration::from_secs(10)).await.unwrap();
+//!         cluster.write(leader, SimRequest::new("foo", "bar")).await.unwrap();
+//!
+//!         cluster.crash(leader);
+//!         cluster.assert_consistent().await;
+//!     })
+//! });
+//! ```
+//!
+//! [`AsyncRuntime`]: crate::AsyncRuntime
+
+mod cluster;
+mod executor;
+mod network;
+mod runtime;
+mod store;
+
+#[cfg(test)] mod sim_test;
+
+use std::io::Cursor;
+use std::panic::catch_unwind;
+use std::panic::AssertUnwindSafe;
+
+pub use cluster::SimCluster;
+pub use cluster::SimNode;
+pub use executor::Simulation;
+pub use network::SimConnection;
+pub use network::SimNetwork;
+pub use network::SimNetworkConfig;
+pub use network::SimNetworkFactory;
+pub use runtime::SimElapsed;
+pub use runtime::SimInstant;
+pub use runtime::SimJoinError;
+pub use runtime::SimJoinHandle;
+pub use runtime::SimRng;
+pub use runtime::SimRuntime;
+pub use runtime::SimSleep;
+pub use runtime::SimTimeout;
+pub use store::SimLogStore;
+pub use store::SimRequest;
+pub use store::SimResponse;
+pub use store::SimStateMachine;
+pub use store::SimStorage;
+
+crate::declare_raft_types!(
+    /// The type config of a simulated cluster.
+    pub SimConfig:
+        D = SimRequest,
+        R = SimResponse,
+        NodeId = u64,
+        Node = crate::BasicNode,
+        Entry = crate::Entry<SimConfig>,
+        SnapshotData = Cursor<Vec<u8>>,
+        AsyncRuntime = SimRuntime,
+);
+
+/// The env var that specifies the number of seeds [`run_seeds()`] runs.
+pub const SEEDS_ENV: &str = "OPENRAFT_SIM_SEEDS";
+
+/// The env var that specifies the only seed [`run_seeds()`] runs, to replay a failure.
+pub const SEED_ENV: &str = "OPENRAFT_SIM_SEED";
+
+/// Run `f` with seeds `0..n`, and panic with the first seed that fails.
+///
+/// `n` is `default_seeds`, unless it is overridden by env var [`SEEDS_ENV`], e.g., to run
+/// thousands of seeds in CI. If env var [`SEED_ENV`] is set, only that seed is run.
+pub fn run_seeds(default_seeds: u64, f: impl Fn(u64)) {
+    if let Some(seed) = env_u64(SEED_ENV) {
+        f(seed);
+        return;
+    }
+
+    let n = env_u64(SEEDS_ENV).unwrap_or(default_seeds);
+
+    for seed in 0..n {
+        let res = catch_unwind(AssertUnwindSafe(|| f(seed)));
+        if res.is_err() {
+            panic!(
+                "simulation failed with seed {}; replay it with: {}={}",
+                seed, SEED_ENV, seed
+            );
+        }
+    }
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    let v = std::env::var(name).ok()?;
+    let n = v.parse().unwrap_or_else(|e| panic!("invalid env var {}={:?}: {}", name, v, e));
+    Some(n)
+}
+
+/// Drop all tasks of the node `id` in the current [`Simulation`], as if the process crashed.
+///
+/// Returns the number of tasks dropped. Usually a node is crashed with [`SimCluster::crash()`].
+pub fn crash(id: u64) -> usize {
+    executor::crash(id)
+}
+
+/// Spawn a task on the node `id` in the current [`Simulation`], so that the task is dropped when
+/// the node crashes.
+pub fn spawn_on<F>(id: u64, fut: F) -> SimJoinHandle<F::Output>
+where
+    F: std::future::Future + 'static,
+    F::Output: 'static,
+{
+    executor::spawn_on(Some(id), fut)
+}
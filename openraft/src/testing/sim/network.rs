@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::InstallSnapshotError;
+use crate::error::RPCError;
+use crate::error::RaftError;
+use crate::error::RemoteError;
+use crate::error::Timeout;
+use crate::error::Unreachable;
+use crate::network::RPCOption;
+use crate::network::RPCTypes;
+use crate::raft::AppendEntriesRequest;
+use crate::raft::AppendEntriesResponse;
+use crate::raft::HandoffLeaderRequest;
+use crate::raft::HandoffLeaderResponse;
+use crate::raft::InstallSnapshotRequest;
+use crate::raft::InstallSnapshotResponse;
+use crate::raft::ReadIndexRequest;
+use crate::raft::ReadIndexResponse;
+use crate::raft::TransferLeaderRequest;
+use crate::raft::VoteRequest;
+use crate::raft::VoteResponse;
+use crate::testing::sim::executor;
+use crate::testing::sim::executor::with_executor;
+use crate::testing::sim::SimConfig;
+use crate::testing::sim::SimRuntime;
+use crate::AnyError;
+use crate::AsyncRuntime;
+use crate::BasicNode;
+use crate::Raft;
+use crate::RaftNetwork;
+use crate::RaftNetworkFactory;
+
+/// Faults injected by [`SimNetwork`] into every message.
+#[derive(Debug, Clone)]
+pub struct SimNetworkConfig {
+    /// The minimal one way delay of a message.
+    pub min_delay: Duration,
+
+    /// The maximal one way delay of a message.
+    ///
+    /// Every message is delayed by a random duration in `[min_delay, max_delay]`, thus messages
+    /// are reordered.
+    pub max_delay: Duration,
+
+    /// The probability that a request or a response is lost.
+    pub drop_rate: f64,
+
+    /// The probability that a request is delivered twice.
+    pub duplicate_rate: f64,
+}
+
+impl Default for SimNetworkConfig {
+    fn default() -> Self {
+        Self {
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            drop_rate: 0.0,
+            duplicate_rate: 0.0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct NetworkState {
+    config: SimNetworkConfig,
+
+    /// The running nodes that receive messages.
+    nodes: BTreeMap<u64, Raft<SimConfig>>,
+
+    /// Directed links `(from, to)` that lose every message.
+    cut: BTreeSet<(u64, u64)>,
+}
+
+/// A simulated network that delivers messages between the [`Raft`] nodes in a [`Simulation`].
+///
+/// A message is handled by a task of the target node, thus when the target crashes, the sender
+/// receives an [`Unreachable`] error. A lost message, or a message on a cut link, is reported as
+/// a [`Timeout`] after [`RPCOption::soft_ttl()`].
+///
+/// [`Simulation`]: crate::testing::sim::Simulation
+#[derive(Clone, Default)]
+pub struct SimNetwork {
+    state: Arc<Mutex<NetworkState>>,
+}
+
+impl SimNetwork {
+    pub fn new(config: SimNetworkConfig) -> Self {
+        let net = Self::default();
+        net.set_config(config);
+        net
+    }
+
+    pub fn config(&self) -> SimNetworkConfig {
+        self.state.lock().unwrap().config.clone()
+    }
+
+    pub fn set_config(&self, config: SimNetworkConfig) {
+        self.state.lock().unwrap().config = config;
+    }
+
+    /// Start delivering messages to the node `id`.
+    pub fn register(&self, id: u64, raft: Raft<SimConfig>) {
+        self.state.lock().unwrap().nodes.insert(id, raft);
+    }
+
+    /// Stop delivering messages to the node `id`.
+    pub fn unregister(&self, id: u64) -> Option<Raft<SimConfig>> {
+        self.state.lock().unwrap().nodes.remove(&id)
+    }
+
+    /// Cut the links between every node in `a` and every node in `b`, in both directions.
+    pub fn partition(&self, a: impl IntoIterator<Item = u64>, b: impl IntoIterator<Item = u64>) {
+        let b = b.into_iter().collect::<Vec<_>>();
+        let mut st = self.state.lock().unwrap();
+        for x in a {
+            for y in b.iter().copied() {
+                st.cut.insert((x, y));
+                st.cut.insert((y, x));
+            }
+        }
+    }
+
+    /// Cut all the links of the node `id`.
+    pub fn isolate(&self, id: u64, others: impl IntoIterator<Item = u64>) {
+        self.partition([id], others);
+    }
+
+    /// Restore all the cut links.
+    pub fn heal(&self) {
+        self.state.lock().unwrap().cut.clear();
+    }
+
+    /// Build a [`RaftNetworkFactory`] for the node `id`.
+    pub fn factory(&self, id: u64) -> SimNetworkFactory {
+        SimNetworkFactory { id, net: self.clone() }
+    }
+
+    fn is_cut(&self, from: u64, to: u64) -> bool {
+        self.state.lock().unwrap().cut.contains(&(from, to))
+    }
+
+    fn node(&self, id: u64) -> Option<Raft<SimConfig>> {
+        self.state.lock().unwrap().nodes.get(&id).cloned()
+    }
+}
+
+/// Builds [`SimConnection`]s of a node.
+pub struct SimNetworkFactory {
+    id: u64,
+    net: SimNetwork,
+}
+
+impl RaftNetworkFactory<SimConfig> for SimNetworkFactory {
+    type Network = SimConnection;
+
+    async fn new_client(&mut self, target: u64, _node: &BasicNode) -> Self::Network {
+        SimConnection {
+            id: self.id,
+            target,
+            net: self.net.clone(),
+        }
+    }
+}
+
+/// Sends messages from node `id` to node `target` through a [`SimNetwork`].
+pub struct SimConnection {
+    id: u64,
+    target: u64,
+    net: SimNetwork,
+}
+
+impl SimConnection {
+    /// Deliver a request with `handle` on the target node and return the response, with faults
+    /// injected.
+    async fn call<Resp, E, F, Fu>(
+        &self,
+        action: RPCTypes,
+        option: &RPCOption,
+        handle: F,
+    ) -> Result<Resp, RPCError<SimConfig, E>>
+    where
+        F: Fn(Raft<SimConfig>) -> Fu,
+        Fu: Future<Output = Result<Resp, E>> + 'static,
+        Resp: 'static,
+        E: Error + 'static,
+    {
+        let config = self.net.config();
+
+        // The request
+        self.transmit(self.id, self.target, action, option, &config).await?;
+
+        let Some(raft) = self.net.node(self.target) else {
+            return Err(self.unreachable("target node is down"));
+        };
+
+        if random_bool(config.duplicate_rate) {
+            // The response of the duplicate is discarded.
+            drop(executor::spawn_on(Some(self.target), handle(raft.clone())));
+        }
+
+        let res = executor::spawn_on(Some(self.target), handle(raft))
+            .await
+            .map_err(|e| RPCError::Unreachable(Unreachable::new(&e)))?;
+
+        // The response
+        self.transmit(self.target, self.id, action, option, &config).await?;
+
+        res.map_err(|e| RPCError::RemoteError(RemoteError::new(self.target, e)))
+    }
+
+    /// Delay a message from `from` to `to`, or lose it.
+    async fn transmit<E: Error>(
+        &self,
+        from: u64,
+        to: u64,
+        action: RPCTypes,
+        option: &RPCOption,
+        config: &SimNetworkConfig,
+    ) -> Result<(), RPCError<SimConfig, E>> {
+        let delay = with_executor(|ex| {
+            ex.with_rng(|r| {
+                if config.max_delay > config.min_delay {
+                    r.gen_range(config.min_delay..=config.max_delay)
+                } else {
+                    config.min_delay
+                }
+            })
+        });
+
+        if random_bool(config.drop_rate) || self.net.is_cut(from, to) {
+            // The sender does not know the message is lost until it times out.
+            let timeout = option.soft_ttl();
+            SimRuntime::sleep(timeout).await;
+            return Err(RPCError::Timeout(Timeout {
+                action,
+                id: self.id,
+                target: self.target,
+                timeout,
+            }));
+        }
+
+        SimRuntime::sleep(delay).await;
+        Ok(())
+    }
+
+    fn unreachable<E: Error>(&self, msg: &str) -> RPCError<SimConfig, E> {
+        RPCError::Unreachable(Unreachable::new(&AnyError::error(format!("{}: {}", msg, self.target))))
+    }
+}
+
+fn random_bool(p: f64) -> bool {
+    p > 0.0 && with_executor(|ex| ex.with_rng(|r| r.gen_bool(p.min(1.0))))
+}
+
+impl RaftNetwork<SimConfig> for SimConnection {
+    async fn append_entries(
+        &mut self,
+        rpc: AppendEntriesRequest<SimConfig>,
+        option: RPCOption,
+    ) -> Result<AppendEntriesResponse<SimConfig>, RPCError<SimConfig, RaftError<SimConfig>>> {
+        self.call(RPCTypes::AppendEntries, &option, |raft| {
+            let rpc = rpc.clone();
+            async move { raft.append_entries(rpc).await }
+        })
+        .await
+    }
+
+    async fn install_snapshot(
+        &mut self,
+        rpc: InstallSnapshotRequest<SimConfig>,
+        option: RPCOption,
+    ) -> Result<InstallSnapshotResponse<SimConfig>, RPCError<SimConfig, RaftError<SimConfig, InstallSnapshotError>>>
+    {
+        self.call(RPCTypes::InstallSnapshot, &option, |raft| {
+            let rpc = rpc.clone();
+            async move { raft.install_snapshot(rpc).await }
+        })
+        .await
+    }
+
+    async fn vote(
+        &mut self,
+        rpc: VoteRequest<SimConfig>,
+        option: RPCOption,
+    ) -> Result<VoteResponse<SimConfig>, RPCError<SimConfig, RaftError<SimConfig>>> {
+        self.call(RPCTypes::Vote, &option, |raft| {
+            let rpc = rpc.clone();
+            async move { raft.vote(rpc).await }
+        })
+        .await
+    }
+
+    async fn pre_vote(
+        &mut self,
+        rpc: VoteRequest<SimConfig>,
+        option: RPCOption,
+    ) -> Result<VoteResponse<SimConfig>, RPCError<SimConfig, RaftError<SimConfig>>> {
+        self.call(RPCTypes::PreVote, &option, |raft| {
+            let rpc = rpc.clone();
+            async move { raft.pre_vote(rpc).await }
+        })
+        .await
+    }
+
+    async fn read_index(
+        &mut self,
+        rpc: ReadIndexRequest<SimConfig>,
+        option: RPCOption,
+    ) -> Result<
+        ReadIndexResponse<SimConfig>,
+        RPCError<SimConfig, RaftError<SimConfig, crate::error::CheckIsLeaderError<SimConfig>>>,
+    > {
+        self.call(RPCTypes::ReadIndex, &option, |raft| {
+            let rpc = rpc.clone();
+            async move { raft.handle_read_index(rpc).await }
+        })
+        .await
+    }
+
+    async fn handoff_leader(
+        &mut self,
+        rpc: HandoffLeaderRequest<SimConfig>,
+        option: RPCOption,
+    ) -> Result<HandoffLeaderResponse, RPCError<SimConfig, RaftError<SimConfig>>> {
+        self.call(RPCTypes::HandoffLeader, &option, |raft| {
+            let rpc = rpc.clone();
+            async move { raft.handle_handoff_leader(rpc).await }
+        })
+        .await
+    }
+
+    async fn transfer_leader(
+        &mut self,
+        req: TransferLeaderRequest<SimConfig>,
+        option: RPCOption,
+    ) -> Result<(), RPCError<SimConfig, RaftError<SimConfig>>> {
+        self.call(RPCTypes::TransferLeader, &option, |raft| {
+            let req = req.clone();
+            async move { raft.handle_transfer_leader(req).await.map_err(RaftError::Fatal) }
+        })
+        .await
+    }
+}
@@ -0,0 +1,348 @@
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+use std::time::Duration;
+
+use rand::RngCore;
+
+use crate::async_runtime::TokioOneShotSender;
+use crate::testing::sim::executor;
+use crate::testing::sim::executor::try_with_executor;
+use crate::testing::sim::executor::with_executor;
+use crate::AsyncRuntime;
+use crate::Instant;
+use crate::OptionalSend;
+
+/// An [`AsyncRuntime`] that runs tasks in a [`Simulation`] with virtual time.
+///
+/// All of its methods must be called inside [`Simulation::block_on()`].
+///
+/// [`Simulation`]: crate::testing::sim::Simulation
+/// [`Simulation::block_on()`]: crate::testing::sim::Simulation::block_on
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SimRuntime;
+
+impl AsyncRuntime for SimRuntime {
+    type JoinError = SimJoinError;
+    type JoinHandle<T: OptionalSend + 'static> = SimJoinHandle<T>;
+    type Sleep = SimSleep;
+    type Instant = SimInstant;
+    type TimeoutError = SimElapsed;
+    type Timeout<R, T: Future<Output = R> + OptionalSend> = SimTimeout<T>;
+    type ThreadLocalRng = SimRng;
+    type OneshotSender<T: OptionalSend> = TokioOneShotSender<T>;
+    type OneshotReceiverError = tokio::sync::oneshot::error::RecvError;
+    type OneshotReceiver<T: OptionalSend> = tokio::sync::oneshot::Receiver<T>;
+
+    fn spawn<T>(future: T) -> Self::JoinHandle<T::Output>
+    where
+        T: Future + OptionalSend + 'static,
+        T::Output: OptionalSend + 'static,
+    {
+        executor::spawn(future)
+    }
+
+    fn sleep(duration: Duration) -> Self::Sleep {
+        SimSleep::new(SimInstant::now() + duration)
+    }
+
+    fn sleep_until(deadline: Self::Instant) -> Self::Sleep {
+        SimSleep::new(deadline)
+    }
+
+    fn timeout<R, F: Future<Output = R> + OptionalSend>(duration: Duration, future: F) -> Self::Timeout<R, F> {
+        Self::timeout_at(SimInstant::now() + duration, future)
+    }
+
+    fn timeout_at<R, F: Future<Output = R> + OptionalSend>(deadline: Self::Instant, future: F) -> Self::Timeout<R, F> {
+        SimTimeout {
+            fut: Box::pin(future),
+            sleep: SimSleep::new(deadline),
+        }
+    }
+
+    fn is_panic(join_error: &Self::JoinError) -> bool {
+        matches!(join_error, SimJoinError::Panicked(_))
+    }
+
+    fn thread_rng() -> Self::ThreadLocalRng {
+        SimRng
+    }
+
+    fn oneshot<T>() -> (Self::OneshotSender<T>, Self::OneshotReceiver<T>)
+    where T: OptionalSend {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        (TokioOneShotSender(tx), rx)
+    }
+}
+
+/// An instant of the virtual clock, i.e., the virtual time elapsed since the simulation started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SimInstant(Duration);
+
+impl SimInstant {
+    /// The virtual time elapsed since the simulation started.
+    pub fn since_start(&self) -> Duration {
+        self.0
+    }
+}
+
+impl Instant for SimInstant {
+    fn now() -> Self {
+        SimInstant(with_executor(|ex| ex.now()))
+    }
+}
+
+impl Add<Duration> for SimInstant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self {
+        SimInstant(self.0 + rhs)
+    }
+}
+
+impl AddAssign<Duration> for SimInstant {
+    fn add_assign(&mut self, rhs: Duration) {
+        self.0 += rhs;
+    }
+}
+
+/// The virtual clock starts at zero, thus subtraction saturates at zero.
+impl Sub<Duration> for SimInstant {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self {
+        SimInstant(self.0.saturating_sub(rhs))
+    }
+}
+
+impl SubAssign<Duration> for SimInstant {
+    fn sub_assign(&mut self, rhs: Duration) {
+        self.0 = self.0.saturating_sub(rhs);
+    }
+}
+
+impl Sub<SimInstant> for SimInstant {
+    type Output = Duration;
+
+    fn sub(self, rhs: SimInstant) -> Duration {
+        self.0.saturating_sub(rhs.0)
+    }
+}
+
+/// A future that completes when the virtual clock reaches `deadline`.
+#[derive(Debug)]
+pub struct SimSleep {
+    deadline: SimInstant,
+
+    /// The sequence number of the registered timer.
+    timer: Option<u64>,
+}
+
+impl SimSleep {
+    fn new(deadline: SimInstant) -> Self {
+        Self { deadline, timer: None }
+    }
+}
+
+impl Future for SimSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let deadline = this.deadline.0;
+
+        with_executor(|ex| {
+            if ex.now() >= deadline {
+                if let Some(seq) = this.timer.take() {
+                    ex.remove_timer(deadline, seq);
+                }
+                return Poll::Ready(());
+            }
+
+            let seq = *this.timer.get_or_insert_with(|| ex.next_seq());
+            ex.set_timer(deadline, seq, cx.waker().clone());
+            Poll::Pending
+        })
+    }
+}
+
+impl Drop for SimSleep {
+    fn drop(&mut self) {
+        if let Some(seq) = self.timer.take() {
+            try_with_executor(|ex| ex.remove_timer(self.deadline.0, seq));
+        }
+    }
+}
+
+/// The error returned by [`SimTimeout`] when the deadline is reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimElapsed;
+
+impl fmt::Display for SimElapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "deadline has elapsed")
+    }
+}
+
+impl std::error::Error for SimElapsed {}
+
+/// Requires a future to complete before a virtual deadline.
+pub struct SimTimeout<T> {
+    fut: Pin<Box<T>>,
+    sleep: SimSleep,
+}
+
+impl<T: Future> Future for SimTimeout<T> {
+    type Output = Result<T::Output, SimElapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(v) = this.fut.as_mut().poll(cx) {
+            return Poll::Ready(Ok(v));
+        }
+
+        match Pin::new(&mut this.sleep).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(SimElapsed)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The error of a simulated task that did not finish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimJoinError {
+    /// The task is dropped before finishing, because its node crashed or the simulation ended.
+    Cancelled,
+
+    /// The task panicked, with the panic message.
+    Panicked(String),
+}
+
+impl SimJoinError {
+    pub(crate) fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let msg = if let Some(s) = payload.downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        SimJoinError::Panicked(msg)
+    }
+}
+
+impl fmt::Display for SimJoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimJoinError::Cancelled => write!(f, "task was cancelled"),
+            SimJoinError::Panicked(msg) => write!(f, "task panicked: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SimJoinError {}
+
+struct JoinState<T> {
+    result: Option<Result<T, SimJoinError>>,
+    done: bool,
+    waker: Option<Waker>,
+}
+
+/// Waits for a simulated task to finish.
+pub struct SimJoinHandle<T> {
+    state: Arc<Mutex<JoinState<T>>>,
+}
+
+impl<T> SimJoinHandle<T> {
+    pub(crate) fn new() -> (Self, JoinGuard<T>) {
+        let state = Arc::new(Mutex::new(JoinState {
+            result: None,
+            done: false,
+            waker: None,
+        }));
+        (Self { state: state.clone() }, JoinGuard { state })
+    }
+}
+
+impl<T> Future for SimJoinHandle<T> {
+    type Output = Result<T, SimJoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(res) => Poll::Ready(res),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Held by a task to deliver its result to the [`SimJoinHandle`].
+///
+/// If the task is dropped before finishing, the handle receives [`SimJoinError::Cancelled`].
+pub(crate) struct JoinGuard<T> {
+    state: Arc<Mutex<JoinState<T>>>,
+}
+
+impl<T> JoinGuard<T> {
+    pub(crate) fn complete(&self, res: Result<T, SimJoinError>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.result = Some(res);
+            state.done = true;
+            state.waker.take()
+        };
+
+        if let Some(w) = waker {
+            w.wake();
+        }
+    }
+}
+
+impl<T> Drop for JoinGuard<T> {
+    fn drop(&mut self) {
+        let done = self.state.lock().unwrap().done;
+        if !done {
+            self.complete(Err(SimJoinError::Cancelled));
+        }
+    }
+}
+
+/// A random number generator that draws from the seeded generator of the current
+/// [`Simulation`].
+///
+/// [`Simulation`]: crate::testing::sim::Simulation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimRng;
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        with_executor(|ex| ex.with_rng(|r| r.next_u32()))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        with_executor(|ex| ex.with_rng(|r| r.next_u64()))
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        with_executor(|ex| ex.with_rng(|r| r.fill_bytes(dest)))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        with_executor(|ex| ex.with_rng(|r| r.try_fill_bytes(dest)))
+    }
+}
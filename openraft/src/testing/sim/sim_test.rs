@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::testing::sim::run_seeds;
+use crate::testing::sim::spawn_on;
+use crate::testing::sim::SimCluster;
+use crate::testing::sim::SimInstant;
+use crate::testing::sim::SimJoinError;
+use crate::testing::sim::SimNetworkConfig;
+use crate::testing::sim::SimRequest;
+use crate::testing::sim::SimRuntime;
+use crate::testing::sim::Simulation;
+use crate::AsyncRuntime;
+use crate::Config;
+use crate::Instant;
+
+#[test]
+fn test_virtual_time() {
+    let sim = Simulation::new(0);
+
+    let elapsed = sim.block_on(async {
+        let start = SimInstant::now();
+        SimRuntime::sleep(Duration::from_secs(3600)).await;
+
+        let res = SimRuntime::timeout(Duration::from_secs(1), SimRuntime::sleep(Duration::from_secs(2))).await;
+        assert!(res.is_err());
+
+        SimInstant::now() - start
+    });
+
+    assert_eq!(Duration::from_secs(3601), elapsed);
+    assert_eq!(Duration::from_secs(3601), sim.elapsed());
+}
+
+#[test]
+fn test_crash_drops_tasks_of_node() {
+    Simulation::new(0).block_on(async {
+        let h1 = spawn_on(1, async {
+            SimRuntime::sleep(Duration::from_secs(1)).await;
+            1
+        });
+        let h2 = spawn_on(2, async {
+            SimRuntime::sleep(Duration::from_secs(1)).await;
+            2
+        });
+
+        assert_eq!(1, super::crash(1));
+
+        assert_eq!(Err(SimJoinError::Cancelled), h1.await);
+        assert_eq!(Ok(2), h2.await);
+    });
+}
+
+#[test]
+fn test_same_seed_same_run() {
+    // The order in which ready tasks run is random, but determined by the seed.
+    let trace = |seed: u64| {
+        Simulation::new(seed).block_on(async {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            for i in 0..10 {
+                let tx = tx.clone();
+                SimRuntime::spawn(async move {
+                    let ms = SimRuntime::thread_rng().gen_range(0..3);
+                    SimRuntime::sleep(Duration::from_millis(ms)).await;
+                    tx.send(i).unwrap();
+                });
+            }
+            drop(tx);
+
+            let mut got = vec![];
+            while let Some(i) = rx.recv().await {
+                got.push(i);
+            }
+            got
+        })
+    };
+
+    assert_eq!(trace(7), trace(7));
+    assert!((0..10).any(|seed| trace(seed) != trace(7)));
+}
+
+/// Write to a cluster while messages are lost, duplicated and reordered, nodes crash and the
+/// network partitions, then check that every acknowledged write is applied on every node.
+fn run_cluster_with_faults(seed: u64) -> (Duration, BTreeMap<String, String>) {
+    let sim = Simulation::new(seed);
+
+    let state = sim.block_on(async {
+        let net_config = SimNetworkConfig {
+            drop_rate: 0.05,
+            duplicate_rate: 0.05,
+            ..Default::default()
+        };
+        let mut cluster = SimCluster::new(Config::default(), net_config).unwrap();
+        let ids = [1, 2, 3];
+        cluster.initialize(ids).await.unwrap();
+
+        let mut acked = BTreeMap::new();
+
+        for i in 0..20 {
+            let Some(leader) = cluster.wait_for_leader(Duration::from_secs(10)).await else {
+                continue;
+            };
+
+            let req = SimRequest::new(format!("k{}", i % 5), format!("v{}", i));
+            let res = SimRuntime::timeout(Duration::from_secs(1), cluster.write(leader, req.clone())).await;
+            if let Ok(Ok(_)) = res {
+                acked.insert(req.key, req.value);
+            }
+
+            match SimRuntime::thread_rng().gen_range(0..10) {
+                0 => {
+                    cluster.crash(leader);
+                }
+                1 => {
+                    let id = ids[SimRuntime::thread_rng().gen_range(0..ids.len())];
+                    cluster.network().isolate(id, ids);
+                }
+                2 => cluster.network().heal(),
+                _ => {}
+            }
+
+            for id in ids {
+                if cluster.node(id).is_none() && SimRuntime::thread_rng().gen_bool(0.5) {
+                    cluster.start(id).await.unwrap();
+                }
+            }
+        }
+
+        // Recover from all faults and wait for every node to apply the last log.
+
+        cluster.network().heal();
+        for id in ids {
+            if cluster.node(id).is_none() {
+                cluster.start(id).await.unwrap();
+            }
+        }
+
+        let last = loop {
+            let leader = cluster.wait_for_leader(Duration::from_secs(10)).await.unwrap();
+            let res = SimRuntime::timeout(
+                Duration::from_secs(1),
+                cluster.write(leader, SimRequest::new("end", "")),
+            )
+            .await;
+            if let Ok(Ok(resp)) = res {
+                break resp.log_id.index;
+            }
+        };
+
+        for id in ids {
+            let raft = &cluster.node(id).unwrap().raft;
+            raft.wait(Some(Duration::from_secs(10)))
+                .applied_index_at_least(Some(last), "all applied")
+                .await
+                .unwrap();
+        }
+
+        cluster.assert_consistent().await;
+
+        // A later write to the same key may be applied without being acknowledged, thus only the
+        // last acknowledged write of a key can be checked.
+        let (_, kvs) = cluster.node(1).unwrap().state_machine.state();
+        for (k, v) in acked.iter() {
+            let got = kvs.get(k).unwrap_or_else(|| panic!("acknowledged key {} is lost", k));
+            let want_i = v[1..].parse::<u64>().unwrap();
+            let got_i = got[1..].parse::<u64>().unwrap();
+            assert!(
+                got_i >= want_i,
+                "acknowledged {}={} is overridden by an older value {}",
+                k,
+                v,
+                got
+            );
+        }
+
+        kvs
+    });
+
+    (sim.elapsed(), state)
+}
+
+#[test]
+fn test_cluster_with_faults() {
+    run_seeds(20, |seed| {
+        run_cluster_with_faults(seed);
+    });
+}
+
+#[test]
+fn test_cluster_replay_seed() {
+    assert_eq!(run_cluster_with_faults(3), run_cluster_with_faults(3));
+}
@@ -0,0 +1,350 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::io;
+use std::io::Cursor;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::network::codec::decode_struct;
+use crate::network::codec::encode_struct;
+use crate::network::codec::Decode;
+use crate::network::codec::Encode;
+use crate::storage::LogFlushed;
+use crate::storage::RaftLogStorage;
+use crate::storage::RaftStateMachine;
+use crate::testing::sim::SimConfig;
+use crate::Entry;
+use crate::EntryPayload;
+use crate::LogId;
+use crate::LogState;
+use crate::OptionalSend;
+use crate::RaftLogReader;
+use crate::RaftSnapshotBuilder;
+use crate::Snapshot;
+use crate::SnapshotMeta;
+use crate::StorageError;
+use crate::StorageIOError;
+use crate::StoredMembership;
+use crate::Vote;
+
+/// A request to the simulated key-value state machine: set `key` to `value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SimRequest {
+    pub key: String,
+    pub value: String,
+}
+
+impl SimRequest {
+    pub fn new(key: impl ToString, value: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// The response of a [`SimRequest`]: the value of the key before it is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SimResponse {
+    pub prev: Option<String>,
+}
+
+/// The durable storage of a simulated node, which survives a crash.
+///
+/// It holds the vote, the logs and the last snapshot. The state machine is volatile: after a
+/// restart it is rebuilt from the snapshot, and then the committed logs are applied again.
+#[derive(Debug, Clone, Default)]
+pub struct SimStorage {
+    log: Arc<Mutex<LogData>>,
+    snapshot: Arc<Mutex<Option<StoredSnapshot>>>,
+}
+
+#[derive(Debug, Default)]
+struct LogData {
+    vote: Option<Vote<u64>>,
+    committed: Option<LogId<u64>>,
+    last_purged_log_id: Option<LogId<u64>>,
+    logs: BTreeMap<u64, Entry<SimConfig>>,
+}
+
+#[derive(Debug, Clone)]
+struct StoredSnapshot {
+    meta: SnapshotMeta<SimConfig>,
+    data: Vec<u8>,
+}
+
+impl SimStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the log store of this storage.
+    pub fn log_store(&self) -> SimLogStore {
+        SimLogStore { data: self.log.clone() }
+    }
+
+    /// Open a state machine, restored from the last snapshot.
+    pub fn state_machine(&self) -> Result<SimStateMachine, StorageError<u64>> {
+        let snapshot = self.snapshot.lock().unwrap().clone();
+
+        let sm = match snapshot {
+            None => SmData::default(),
+            Some(s) => SmData::decode_snapshot(&s)?,
+        };
+
+        Ok(SimStateMachine {
+            sm: Arc::new(Mutex::new(sm)),
+            snapshot: self.snapshot.clone(),
+        })
+    }
+}
+
+/// A [`RaftLogStorage`] of a simulated node.
+#[derive(Debug, Clone)]
+pub struct SimLogStore {
+    data: Arc<Mutex<LogData>>,
+}
+
+impl SimLogStore {
+    /// Return a copy of all logs in the store.
+    pub fn logs(&self) -> Vec<Entry<SimConfig>> {
+        self.data.lock().unwrap().logs.values().cloned().collect()
+    }
+}
+
+impl RaftLogReader<SimConfig> for SimLogStore {
+    async fn try_get_log_entries<RB: RangeBounds<u64> + Clone + Debug + OptionalSend>(
+        &mut self,
+        range: RB,
+    ) -> Result<Vec<Entry<SimConfig>>, StorageError<u64>> {
+        let d = self.data.lock().unwrap();
+        Ok(d.logs.range(range).map(|(_, ent)| ent.clone()).collect())
+    }
+
+    async fn read_vote(&mut self) -> Result<Option<Vote<u64>>, StorageError<u64>> {
+        Ok(self.data.lock().unwrap().vote)
+    }
+}
+
+impl RaftLogStorage<SimConfig> for SimLogStore {
+    type LogReader = Self;
+
+    async fn get_log_state(&mut self) -> Result<LogState<SimConfig>, StorageError<u64>> {
+        let d = self.data.lock().unwrap();
+        let last = d.logs.values().next_back().map(|ent| ent.log_id).or(d.last_purged_log_id);
+
+        Ok(LogState {
+            last_purged_log_id: d.last_purged_log_id,
+            last_log_id: last,
+        })
+    }
+
+    async fn get_log_reader(&mut self) -> Self::LogReader {
+        self.clone()
+    }
+
+    async fn save_vote(&mut self, vote: &Vote<u64>) -> Result<(), StorageError<u64>> {
+        self.data.lock().unwrap().vote = Some(*vote);
+        Ok(())
+    }
+
+    async fn save_committed(&mut self, committed: Option<LogId<u64>>) -> Result<(), StorageError<u64>> {
+        self.data.lock().unwrap().committed = committed;
+        Ok(())
+    }
+
+    async fn read_committed(&mut self) -> Result<Option<LogId<u64>>, StorageError<u64>> {
+        Ok(self.data.lock().unwrap().committed)
+    }
+
+    async fn append<I>(&mut self, entries: I, callback: LogFlushed<SimConfig>) -> Result<(), StorageError<u64>>
+    where I: IntoIterator<Item = Entry<SimConfig>> + OptionalSend {
+        {
+            let mut d = self.data.lock().unwrap();
+            for ent in entries {
+                d.logs.insert(ent.log_id.index, ent);
+            }
+        }
+
+        callback.log_io_completed(Ok(()));
+        Ok(())
+    }
+
+    async fn truncate(&mut self, log_id: LogId<u64>) -> Result<(), StorageError<u64>> {
+        let mut d = self.data.lock().unwrap();
+        d.logs.split_off(&log_id.index);
+        Ok(())
+    }
+
+    async fn purge(&mut self, log_id: LogId<u64>) -> Result<(), StorageError<u64>> {
+        let mut d = self.data.lock().unwrap();
+        d.last_purged_log_id = Some(log_id);
+        d.logs = d.logs.split_off(&(log_id.index + 1));
+        Ok(())
+    }
+}
+
+/// The volatile data of a state machine.
+#[derive(Debug, Clone, Default)]
+struct SmData {
+    last_applied: Option<LogId<u64>>,
+    last_membership: StoredMembership<SimConfig>,
+    kvs: BTreeMap<String, String>,
+    snapshot_idx: u64,
+}
+
+impl SmData {
+    fn encode_snapshot(&self) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        encode_struct(&mut buf, 1, |b| {
+            self.last_applied.encode(b)?;
+            self.last_membership.encode(b)?;
+            self.kvs.encode(b)
+        })?;
+        Ok(buf)
+    }
+
+    fn decode_snapshot(s: &StoredSnapshot) -> Result<Self, StorageError<u64>> {
+        let mut buf = s.data.as_slice();
+        let res = decode_struct(&mut buf, |_version, b| {
+            Ok(SmData {
+                last_applied: Option::decode(b)?,
+                last_membership: StoredMembership::decode(b)?,
+                kvs: BTreeMap::decode(b)?,
+                snapshot_idx: 0,
+            })
+        });
+
+        res.map_err(|e| StorageIOError::read_snapshot(Some(s.meta.signature()), &e).into())
+    }
+}
+
+/// A key-value [`RaftStateMachine`] of a simulated node.
+#[derive(Debug, Clone)]
+pub struct SimStateMachine {
+    sm: Arc<Mutex<SmData>>,
+    snapshot: Arc<Mutex<Option<StoredSnapshot>>>,
+}
+
+impl SimStateMachine {
+    /// Read the value of `key`.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.sm.lock().unwrap().kvs.get(key).cloned()
+    }
+
+    /// Return a copy of all key-values and the last applied log id.
+    pub fn state(&self) -> (Option<LogId<u64>>, BTreeMap<String, String>) {
+        let sm = self.sm.lock().unwrap();
+        (sm.last_applied, sm.kvs.clone())
+    }
+}
+
+impl RaftSnapshotBuilder<SimConfig> for SimStateMachine {
+    async fn build_snapshot(&mut self) -> Result<Snapshot<SimConfig>, StorageError<u64>> {
+        let (meta, data) = {
+            let mut sm = self.sm.lock().unwrap();
+            sm.snapshot_idx += 1;
+
+            let data = sm.encode_snapshot().map_err(|e| StorageIOError::read_state_machine(&e))?;
+
+            let snapshot_id = match sm.last_applied {
+                Some(last) => format!("{}-{}-{}", last.leader_id, last.index, sm.snapshot_idx),
+                None => format!("--{}", sm.snapshot_idx),
+            };
+
+            let meta = SnapshotMeta {
+                last_log_id: sm.last_applied,
+                last_membership: sm.last_membership.clone(),
+                snapshot_id,
+            };
+            (meta, data)
+        };
+
+        *self.snapshot.lock().unwrap() = Some(StoredSnapshot {
+            meta: meta.clone(),
+            data: data.clone(),
+        });
+
+        Ok(Snapshot {
+            meta,
+            snapshot: Box::new(Cursor::new(data)),
+        })
+    }
+}
+
+impl RaftStateMachine<SimConfig> for SimStateMachine {
+    type SnapshotBuilder = Self;
+
+    async fn applied_state(&mut self) -> Result<(Option<LogId<u64>>, StoredMembership<SimConfig>), StorageError<u64>> {
+        let sm = self.sm.lock().unwrap();
+        Ok((sm.last_applied, sm.last_membership.clone()))
+    }
+
+    async fn apply<I>(&mut self, entries: I) -> Result<Vec<SimResponse>, StorageError<u64>>
+    where
+        I: IntoIterator<Item = Entry<SimConfig>> + OptionalSend,
+        I::IntoIter: OptionalSend,
+    {
+        let mut sm = self.sm.lock().unwrap();
+        let mut res = Vec::new();
+
+        for ent in entries {
+            sm.last_applied = Some(ent.log_id);
+
+            match ent.payload {
+                EntryPayload::Blank => res.push(SimResponse::default()),
+                EntryPayload::Normal(req) => {
+                    let prev = sm.kvs.insert(req.key, req.value);
+                    res.push(SimResponse { prev });
+                }
+                EntryPayload::Membership(m) => {
+                    sm.last_membership = StoredMembership::new(Some(ent.log_id), m);
+                    res.push(SimResponse::default());
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
+    async fn get_snapshot_builder(&mut self) -> Self::SnapshotBuilder {
+        self.clone()
+    }
+
+    async fn begin_receiving_snapshot(&mut self) -> Result<Box<Cursor<Vec<u8>>>, StorageError<u64>> {
+        Ok(Box::new(Cursor::new(Vec::new())))
+    }
+
+    async fn install_snapshot(
+        &mut self,
+        meta: &SnapshotMeta<SimConfig>,
+        snapshot: Box<Cursor<Vec<u8>>>,
+    ) -> Result<(), StorageError<u64>> {
+        let s = StoredSnapshot {
+            meta: meta.clone(),
+            data: snapshot.into_inner(),
+        };
+
+        let new_sm = SmData::decode_snapshot(&s)?;
+        {
+            let mut sm = self.sm.lock().unwrap();
+            let snapshot_idx = sm.snapshot_idx;
+            *sm = new_sm;
+            sm.snapshot_idx = snapshot_idx;
+        }
+
+        *self.snapshot.lock().unwrap() = Some(s);
+        Ok(())
+    }
+
+    async fn get_current_snapshot(&mut self) -> Result<Option<Snapshot<SimConfig>>, StorageError<u64>> {
+        let s = self.snapshot.lock().unwrap().clone();
+        Ok(s.map(|s| Snapshot {
+            meta: s.meta,
+            snapshot: Box::new(Cursor::new(s.data)),
+        }))
+    }
+}
@@ -73,6 +73,21 @@ pub trait RaftTypeConfig:
     /// [`Raft::client_write`]: `crate::raft::Raft::client_write`
     /// [`WriteResult`]: `crate::raft::message::ClientWriteResult`
     type Responder: Responder<Self>;
+
+    /// Return the approximate size in bytes of an application data, including the heap allocated
+    /// part.
+    ///
+    /// It is used by [`Entry::approx_size()`] to evaluate size based policies, such as
+    /// [`SnapshotPolicy::BytesSinceLast`].
+    /// The default implementation returns the in-memory size of `D`, which does not include heap
+    /// allocated data. An application should override it if `D` holds heap data, such as a
+    /// `String` or a `Vec`.
+    ///
+    /// [`Entry::approx_size()`]: crate::entry::RaftEntry::approx_size
+    /// [`SnapshotPolicy::BytesSinceLast`]: crate::SnapshotPolicy::BytesSinceLast
+    fn approx_data_size(data: &Self::D) -> u64 {
+        std::mem::size_of_val(data) as u64
+    }
 }
 
 #[allow(dead_code)]
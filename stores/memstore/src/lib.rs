@@ -12,6 +12,7 @@ use std::sync::Arc;
 use std::sync::Mutex;
 
 use openraft::alias::SnapshotDataOf;
+use openraft::impls::OneshotResponder;
 use openraft::storage::LogFlushed;
 use openraft::storage::LogState;
 use openraft::storage::RaftLogReader;
@@ -19,15 +20,19 @@ use openraft::storage::RaftLogStorage;
 use openraft::storage::RaftSnapshotBuilder;
 use openraft::storage::RaftStateMachine;
 use openraft::storage::Snapshot;
+use openraft::storage::SnapshotSignature;
+use openraft::AnyError;
 use openraft::Entry;
 use openraft::EntryPayload;
 use openraft::LogId;
 use openraft::OptionalSend;
 use openraft::RaftLogId;
+use openraft::RaftTypeConfig;
 use openraft::SnapshotMeta;
 use openraft::StorageError;
 use openraft::StorageIOError;
 use openraft::StoredMembership;
+use openraft::TokioRuntime;
 use openraft::Vote;
 use serde::Deserialize;
 use serde::Serialize;
@@ -73,13 +78,27 @@ pub struct ClientResponse(pub Option<String>);
 
 pub type MemNodeId = u64;
 
-openraft::declare_raft_types!(
-    /// Declare the type configuration for `MemStore`.
-    pub TypeConfig:
-        D = ClientRequest,
-        R = ClientResponse,
-        Node = (),
-);
+/// Declare the type configuration for `MemStore`.
+///
+/// It is implemented by hand instead of with `declare_raft_types!`, to count the heap allocated
+/// strings of a [`ClientRequest`] in the size of a log entry.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TypeConfig {}
+
+impl RaftTypeConfig for TypeConfig {
+    type D = ClientRequest;
+    type R = ClientResponse;
+    type NodeId = MemNodeId;
+    type Node = ();
+    type Entry = Entry<TypeConfig>;
+    type SnapshotData = Cursor<Vec<u8>>;
+    type AsyncRuntime = TokioRuntime;
+    type Responder = OneshotResponder<TypeConfig>;
+
+    fn approx_data_size(data: &ClientRequest) -> u64 {
+        (std::mem::size_of_val(data) + data.client.len() + data.status.len()) as u64
+    }
+}
 
 /// The application snapshot type which the `MemStore` works with.
 #[derive(Debug)]
@@ -90,6 +109,20 @@ pub struct MemStoreSnapshot {
     pub data: Vec<u8>,
 }
 
+/// The changes of the state machine between two snapshots, which is the data of a snapshot delta.
+///
+/// A key mapped to `None` is removed.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct MemStoreSnapshotDelta {
+    pub last_applied_log: Option<LogId<MemNodeId>>,
+
+    pub last_membership: StoredMembership<TypeConfig>,
+
+    pub client_serial_responses: HashMap<String, Option<(u64, Option<String>)>>,
+
+    pub client_status: HashMap<String, Option<String>>,
+}
+
 /// The state machine of the `MemStore`.
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct MemStoreStateMachine {
@@ -103,6 +136,53 @@ pub struct MemStoreStateMachine {
     pub client_status: HashMap<String, String>,
 }
 
+impl MemStoreStateMachine {
+    /// Build a delta that brings `base` to `self`.
+    pub fn delta_since(&self, base: &MemStoreStateMachine) -> MemStoreSnapshotDelta {
+        MemStoreSnapshotDelta {
+            last_applied_log: self.last_applied_log,
+            last_membership: self.last_membership.clone(),
+            client_serial_responses: map_delta(&base.client_serial_responses, &self.client_serial_responses),
+            client_status: map_delta(&base.client_status, &self.client_status),
+        }
+    }
+
+    /// Apply a delta built by [`Self::delta_since`].
+    pub fn apply_delta(&mut self, delta: MemStoreSnapshotDelta) {
+        self.last_applied_log = delta.last_applied_log;
+        self.last_membership = delta.last_membership;
+        apply_map_delta(&mut self.client_serial_responses, delta.client_serial_responses);
+        apply_map_delta(&mut self.client_status, delta.client_status);
+    }
+}
+
+fn map_delta<V: Clone + PartialEq>(base: &HashMap<String, V>, curr: &HashMap<String, V>) -> HashMap<String, Option<V>> {
+    let mut delta = HashMap::new();
+
+    for (k, v) in curr {
+        if base.get(k) != Some(v) {
+            delta.insert(k.clone(), Some(v.clone()));
+        }
+    }
+
+    for k in base.keys() {
+        if !curr.contains_key(k) {
+            delta.insert(k.clone(), None);
+        }
+    }
+
+    delta
+}
+
+fn apply_map_delta<V>(m: &mut HashMap<String, V>, delta: HashMap<String, Option<V>>) {
+    for (k, v) in delta {
+        match v {
+            Some(v) => m.insert(k, v),
+            None => m.remove(&k),
+        };
+    }
+}
+
 #[derive(Debug, Clone)]
 #[derive(PartialEq, Eq)]
 #[derive(PartialOrd, Ord)]
@@ -177,6 +257,10 @@ pub struct MemStateMachine {
     /// The current snapshot.
     current_snapshot: RwLock<Option<MemStoreSnapshot>>,
 
+    /// The most recent earlier snapshots, oldest first, kept as the bases to build snapshot
+    /// deltas upon.
+    previous_snapshots: RwLock<Vec<MemStoreSnapshot>>,
+
     /// Block operations for testing purposes.
     pub block: BlockConfig,
 }
@@ -190,10 +274,27 @@ impl MemStateMachine {
             sm,
             snapshot_idx: Arc::new(Mutex::new(0)),
             current_snapshot,
+            previous_snapshots: RwLock::new(Vec::new()),
             block,
         }
     }
 
+    /// The max number of earlier snapshots to keep as the bases of snapshot deltas.
+    const MAX_PREVIOUS_SNAPSHOTS: usize = 8;
+
+    /// Replace the current snapshot with `snapshot`, and keep the replaced one as a delta base.
+    async fn set_current_snapshot(&self, snapshot: MemStoreSnapshot) {
+        let mut current = self.current_snapshot.write().await;
+        let mut previous = self.previous_snapshots.write().await;
+
+        if let Some(prev) = current.replace(snapshot) {
+            previous.push(prev);
+            if previous.len() > Self::MAX_PREVIOUS_SNAPSHOTS {
+                previous.remove(0);
+            }
+        }
+    }
+
     /// Remove the current snapshot.
     ///
     /// This method is only used for testing purposes.
@@ -295,10 +396,7 @@ impl RaftSnapshotBuilder<TypeConfig> for Arc<MemStateMachine> {
             data: data.clone(),
         };
 
-        {
-            let mut current_snapshot = self.current_snapshot.write().await;
-            *current_snapshot = Some(snapshot);
-        }
+        self.set_current_snapshot(snapshot).await;
 
         tracing::info!(snapshot_size, "log compaction complete");
 
@@ -487,11 +585,23 @@ impl RaftStateMachine<TypeConfig> for Arc<MemStateMachine> {
             "decoding snapshot for installation"
         );
 
-        let new_snapshot = MemStoreSnapshot {
+        let mut new_snapshot = MemStoreSnapshot {
             meta: meta.clone(),
             data: snapshot.into_inner(),
         };
 
+        // A witness receives a snapshot without data: it is a state machine without application
+        // data.
+        if new_snapshot.data.is_empty() {
+            let new_sm = MemStoreStateMachine {
+                last_applied_log: meta.last_log_id,
+                last_membership: meta.last_membership.clone(),
+                ..Default::default()
+            };
+            new_snapshot.data =
+                serde_json::to_vec(&new_sm).map_err(|e| StorageIOError::write_snapshot(Some(meta.signature()), &e))?;
+        }
+
         {
             let t = &new_snapshot.data;
             let y = std::str::from_utf8(t).unwrap();
@@ -507,9 +617,89 @@ impl RaftStateMachine<TypeConfig> for Arc<MemStateMachine> {
             *sm = new_sm;
         }
 
-        // Update current snapshot.
-        let mut current_snapshot = self.current_snapshot.write().await;
-        *current_snapshot = Some(new_snapshot);
+        self.set_current_snapshot(new_snapshot).await;
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_snapshot_delta(
+        &mut self,
+        base: &SnapshotSignature<MemNodeId>,
+    ) -> Result<Option<Snapshot<TypeConfig>>, StorageError<MemNodeId>> {
+        let current = self.current_snapshot.read().await;
+        let Some(current) = &*current else {
+            return Ok(None);
+        };
+
+        let base_data = if current.meta.signature() == *base {
+            current.data.clone()
+        } else {
+            let previous = self.previous_snapshots.read().await;
+            match previous.iter().rev().find(|x| x.meta.signature() == *base) {
+                Some(x) => x.data.clone(),
+                None => return Ok(None),
+            }
+        };
+
+        let base_sm: MemStoreStateMachine =
+            serde_json::from_slice(&base_data).map_err(|e| StorageIOError::read_snapshot(Some(base.clone()), &e))?;
+        let curr_sm: MemStoreStateMachine = serde_json::from_slice(&current.data)
+            .map_err(|e| StorageIOError::read_snapshot(Some(current.meta.signature()), &e))?;
+
+        let delta = curr_sm.delta_since(&base_sm);
+        let data = serde_json::to_vec(&delta).map_err(|e| StorageIOError::read_snapshot(Some(base.clone()), &e))?;
+
+        tracing::info!(
+            { delta_size = data.len(), snapshot_size = current.data.len() },
+            "built snapshot delta"
+        );
+
+        Ok(Some(Snapshot {
+            meta: current.meta.clone(),
+            snapshot: Box::new(Cursor::new(data)),
+        }))
+    }
+
+    #[tracing::instrument(level = "trace", skip(self, delta))]
+    async fn install_snapshot_delta(
+        &mut self,
+        base: &SnapshotSignature<MemNodeId>,
+        meta: &SnapshotMeta<TypeConfig>,
+        delta: Box<SnapshotDataOf<TypeConfig>>,
+    ) -> Result<(), StorageError<MemNodeId>> {
+        let delta: MemStoreSnapshotDelta = serde_json::from_slice(delta.get_ref())
+            .map_err(|e| StorageIOError::read_snapshot(Some(meta.signature()), &e))?;
+
+        // Rebuild the state machine from the base snapshot, not from the live state machine.
+        let mut new_sm: MemStoreStateMachine = {
+            let current = self.current_snapshot.read().await;
+            let base_data = match &*current {
+                Some(x) if x.meta.signature() == *base => &x.data,
+                _ => {
+                    return Err(StorageIOError::read_snapshot(
+                        Some(base.clone()),
+                        AnyError::error("the base of the snapshot delta is not the current snapshot"),
+                    )
+                    .into());
+                }
+            };
+            serde_json::from_slice(base_data).map_err(|e| StorageIOError::read_snapshot(Some(base.clone()), &e))?
+        };
+        new_sm.apply_delta(delta);
+
+        let data =
+            serde_json::to_vec(&new_sm).map_err(|e| StorageIOError::write_snapshot(Some(meta.signature()), &e))?;
+
+        {
+            let mut sm = self.sm.write().await;
+            *sm = new_sm;
+        }
+
+        self.set_current_snapshot(MemStoreSnapshot {
+            meta: meta.clone(),
+            data,
+        })
+        .await;
         Ok(())
     }
 
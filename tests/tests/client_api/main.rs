@@ -15,5 +15,7 @@ mod t13_get_snapshot;
 mod t13_install_full_snapshot;
 mod t13_trigger_snapshot;
 mod t16_with_raft_state;
+mod t17_read_index;
+mod t18_lease_read;
 mod t50_lagging_network_write;
 mod t51_write_when_leader_quit;
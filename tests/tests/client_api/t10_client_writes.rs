@@ -3,6 +3,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use futures::prelude::*;
 use maplit::btreeset;
+use openraft::error::ClientWriteError;
 use openraft::raft::ClientWriteResponse;
 use openraft::CommittedLeaderId;
 use openraft::Config;
@@ -95,3 +96,62 @@ async fn client_write_ff() -> Result<()> {
 
     Ok(())
 }
+
+/// Test Raft::client_write_many,
+///
+/// - A batch written to the leader is appended in one go and every request gets its own response.
+/// - A batch written to a follower gets a `ForwardToLeader` error for every request.
+#[async_entry::test(worker_threads = 4, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn client_write_many() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_tick: false,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- write a batch to the leader");
+    {
+        let n0 = router.get_raft_handle(&0)?;
+
+        let batch = (0..10).map(|i| ClientRequest::make_request("foo", i)).collect::<Vec<_>>();
+        let results = n0.client_write_many(batch).await?;
+        assert_eq!(10, results.len());
+
+        for (i, res) in results.into_iter().enumerate() {
+            let resp = res?;
+            assert_eq!(log_index + 1 + i as u64, resp.log_id().index);
+        }
+        log_index += 10;
+
+        router.wait_for_log(&btreeset! {0,1,2}, Some(log_index), None, "write a batch").await?;
+
+        let results = n0.client_write_many(vec![]).await?;
+        assert!(results.is_empty());
+    }
+
+    tracing::info!(log_index, "--- write a batch to a follower");
+    {
+        let n1 = router.get_raft_handle(&1)?;
+
+        let batch = (0..3).map(|i| ClientRequest::make_request("bar", i)).collect::<Vec<_>>();
+        let results = n1.client_write_many(batch).await?;
+        assert_eq!(3, results.len());
+
+        for res in results {
+            let err = res.unwrap_err();
+            match err {
+                ClientWriteError::ForwardToLeader(f) => assert_eq!(Some(0), f.leader_id),
+                _ => panic!("unexpected error: {}", err),
+            }
+        }
+    }
+
+    Ok(())
+}
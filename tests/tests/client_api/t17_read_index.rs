@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use anyerror::AnyError;
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::error::NetworkError;
+use openraft::error::RPCError;
+use openraft::error::ReadIndexError;
+use openraft::Config;
+use openraft::LogIdOptionExt;
+use openraft::RPCTypes;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// Linearizable read on a follower or learner, with a read index from the leader.
+///
+/// - Read on the leader, a follower and a learner returns the read log id, after the state machine
+///   applied it.
+/// - Read on a node that does not know the leader fails with `LeaderUnknown`.
+/// - Read fails with `QuorumNotEnough` if the leader can not confirm its leadership.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn read_index() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            enable_elect: false,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {3}).await?;
+
+    log_index += router.client_request_many(0, "foo", 10).await?;
+
+    tracing::info!(log_index, "--- read index on every node");
+    {
+        for id in [0, 1, 2, 3] {
+            let n = router.get_raft_handle(&id)?;
+            let read_log_id = n.read_index().await?;
+            assert_eq!(Some(log_index), read_log_id.index(), "read index on node {}", id);
+
+            let applied = n.metrics().borrow().last_applied;
+            assert!(applied.index() >= read_log_id.index(), "node {} applied read index", id);
+        }
+
+        let n = router.get_rpc_count();
+        assert_eq!(
+            Some(&3),
+            n.get(&RPCTypes::ReadIndex),
+            "non-leader nodes send ReadIndex to leader"
+        );
+    }
+
+    tracing::info!(log_index, "--- read index on a node that does not know the leader");
+    {
+        router.new_raft_node(4).await;
+        let n4 = router.get_raft_handle(&4)?;
+
+        let err = n4.read_index().await.unwrap_err().into_api_error().unwrap();
+        assert!(matches!(err, ReadIndexError::LeaderUnknown(_)), "{}", err);
+    }
+
+    tracing::info!(log_index, "--- read index when the leader can not reach a quorum");
+    {
+        router.set_rpc_pre_hook(RPCTypes::AppendEntries, |_router, _req, _from, _to| {
+            Err(RPCError::Network(NetworkError::new(&AnyError::error(
+                "block append-entries",
+            ))))
+        });
+
+        let n1 = router.get_raft_handle(&1)?;
+        let err = n1.read_index().await.unwrap_err().into_api_error().unwrap();
+        assert!(matches!(err, ReadIndexError::QuorumNotEnough(_)), "{}", err);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyerror::AnyError;
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::error::CheckIsLeaderError;
+use openraft::error::NetworkError;
+use openraft::error::RPCError;
+use openraft::raft::ReadPolicy;
+use openraft::Config;
+use openraft::LogIdOptionExt;
+use openraft::RPCTypes;
+use tokio::time::sleep;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// Linearizable read with `ReadPolicy::LeaseRead`.
+///
+/// - The leader serves a read without heartbeat when the lease acknowledged by a quorum is valid.
+/// - The leader falls back to a round of heartbeats when the lease has expired.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn lease_read() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            enable_elect: false,
+            election_timeout_min: 500,
+            election_timeout_max: 1000,
+            lease_read_clock_drift: 100,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    let n0 = router.get_raft_handle(&0)?;
+
+    tracing::info!(log_index, "--- write to refresh the lease, then block heartbeat");
+    {
+        log_index += router.client_request_many(0, "foo", 1).await?;
+
+        router.set_rpc_pre_hook(RPCTypes::AppendEntries, |_router, _req, _from, _to| {
+            Err(RPCError::Network(NetworkError::new(&AnyError::error(
+                "block append-entries",
+            ))))
+        });
+    }
+
+    tracing::info!(log_index, "--- lease read is served without heartbeat");
+    {
+        let read_log_id = n0.ensure_linearizable_with(ReadPolicy::LeaseRead).await?;
+        assert_eq!(Some(log_index), read_log_id.index());
+    }
+
+    tracing::info!(log_index, "--- read index requires heartbeat");
+    {
+        let err = n0.ensure_linearizable_with(ReadPolicy::ReadIndex).await.unwrap_err();
+        let err = err.into_api_error().unwrap();
+        assert!(matches!(err, CheckIsLeaderError::QuorumNotEnough(_)), "{}", err);
+    }
+
+    tracing::info!(log_index, "--- lease expired, lease read falls back to heartbeat");
+    {
+        sleep(Duration::from_millis(config.election_timeout_max)).await;
+
+        let err = n0.ensure_linearizable_with(ReadPolicy::LeaseRead).await.unwrap_err();
+        let err = err.into_api_error().unwrap();
+        assert!(matches!(err, CheckIsLeaderError::QuorumNotEnough(_)), "{}", err);
+
+        router.rpc_pre_hook(RPCTypes::AppendEntries, None);
+
+        let read_log_id = n0.ensure_linearizable_with(ReadPolicy::LeaseRead).await?;
+        assert_eq!(Some(log_index), read_log_id.index());
+    }
+
+    Ok(())
+}
@@ -9,3 +9,6 @@ mod fixtures;
 
 mod t10_elect_compare_last_log;
 mod t11_elect_seize_leadership;
+mod t20_transfer_leader;
+mod t30_pre_vote;
+mod t40_election_priority;
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::error::ClientWriteError;
+use openraft::error::TransferLeaderError;
+use openraft::raft::EventStream;
+use openraft::raft::RaftEvent;
+use openraft::Config;
+use openraft::ServerState;
+use openraft_memstore::ClientRequest;
+use openraft_memstore::IntoMemClientRequest;
+use openraft_memstore::TypeConfig;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// The leader transfers leadership to a voter, without waiting for the leader lease to expire.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn transfer_leader() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            // Keep the leader lease from expiring during the test.
+            election_timeout_min: 2000,
+            election_timeout_max: 2001,
+            transfer_leader_timeout: 1000,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- create cluster of 0,1,2 and learner 3");
+    let log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {3}).await?;
+
+    let n0 = router.get_raft_handle(&0)?;
+    n0.wait(timeout()).state(ServerState::Leader, "node 0 becomes leader").await?;
+
+    tracing::info!(log_index, "--- transfer leader to a learner is rejected");
+    {
+        let err = n0.transfer_leader(3).await.unwrap_err().into_api_error().unwrap();
+        assert!(matches!(err, TransferLeaderError::NotInMembers(_)), "{}", err);
+    }
+
+    tracing::info!(
+        log_index,
+        "--- transfer leader on a follower is forwarded to the leader"
+    );
+    {
+        let n1 = router.get_raft_handle(&1)?;
+        let err = n1.transfer_leader(2).await.unwrap_err().into_api_error().unwrap();
+        let TransferLeaderError::ForwardToLeader(fwd) = err else {
+            panic!("expect ForwardToLeader, got: {}", err);
+        };
+        assert_eq!(Some(0), fwd.leader_id);
+    }
+
+    tracing::info!(log_index, "--- transfer leader to node 1");
+    {
+        let mut events = n0.subscribe_events();
+
+        n0.transfer_leader(1).await?;
+
+        let ev = recv_transfer_event(&mut events).await?;
+        assert_eq!(RaftEvent::LeaderTransferred { to: 1 }, ev);
+
+        let n1 = router.get_raft_handle(&1)?;
+        n1.wait(timeout()).state(ServerState::Leader, "node 1 becomes leader").await?;
+
+        for id in [0, 2, 3] {
+            router.wait(&id, timeout()).current_leader(1, "node 1 is seen as leader").await?;
+        }
+    }
+
+    tracing::info!(
+        log_index,
+        "--- the old leader rejects writes, the new leader accepts writes"
+    );
+    {
+        let err = router.send_client_request(0, ClientRequest::make_request("foo", 1)).await;
+        let err = err.unwrap_err().into_api_error().unwrap();
+        let ClientWriteError::ForwardToLeader(fwd) = err else {
+            panic!("expect ForwardToLeader, got: {}", err);
+        };
+        assert_eq!(Some(1), fwd.leader_id);
+
+        router.client_request_many(1, "foo", 1).await?;
+    }
+
+    Ok(())
+}
+
+/// Transferring leadership fails with `TargetLagging` if the target does not catch up in time,
+/// and the leader resumes accepting writes.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn transfer_leader_target_lagging() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_elect: false,
+            transfer_leader_timeout: 500,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- create cluster of 0,1,2");
+    let mut log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- isolate node 2 and write a log");
+    {
+        router.set_unreachable(2, true);
+        log_index += router.client_request_many(0, "foo", 1).await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), "log is committed without node 2").await?;
+    }
+
+    tracing::info!(log_index, "--- transfer leader to the lagging node 2");
+    {
+        let n0 = router.get_raft_handle(&0)?;
+        let mut events = n0.subscribe_events();
+
+        let err = n0.transfer_leader(2).await.unwrap_err().into_api_error().unwrap();
+        let TransferLeaderError::TargetLagging(lagging) = &err else {
+            panic!("expect TargetLagging, got: {}", err);
+        };
+        assert_eq!(2, lagging.target);
+        assert_eq!(Some(log_index), lagging.last_log_id.map(|x| x.index));
+
+        let ev = recv_transfer_event(&mut events).await?;
+        assert_eq!(
+            RaftEvent::LeaderTransferCancelled {
+                to: 2,
+                error: err.to_string()
+            },
+            ev
+        );
+    }
+
+    tracing::info!(log_index, "--- node 0 is still the leader and accepts writes");
+    {
+        log_index += router.client_request_many(0, "foo", 1).await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), "write after cancelled transfer").await?;
+        router.wait(&0, timeout()).state(ServerState::Leader, "node 0 is still leader").await?;
+    }
+
+    Ok(())
+}
+
+/// Receive events until a leadership transfer completes or is cancelled, and return that event.
+async fn recv_transfer_event(events: &mut EventStream<TypeConfig>) -> Result<RaftEvent<TypeConfig>> {
+    loop {
+        let ev = tokio::time::timeout(Duration::from_millis(2_000), events.recv()).await?;
+        let ev = ev.ok_or_else(|| anyhow::anyhow!("event stream ended"))?;
+
+        if matches!(
+            ev,
+            RaftEvent::LeaderTransferred { .. } | RaftEvent::LeaderTransferCancelled { .. }
+        ) {
+            return Ok(ev);
+        }
+    }
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(2000))
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::network::RPCTypes;
+use openraft::Config;
+use openraft::ServerState;
+use tokio::time::sleep;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// With PreVote enabled, a partitioned node does not increase its term,
+/// and does not disrupt the cluster when the partition heals.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn pre_vote_partitioned_node_does_not_disrupt() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_pre_vote: true,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- create cluster of 0,1,2");
+    let mut log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    let term = router.get_metrics(&0)?.current_term;
+
+    tracing::info!(log_index, "--- isolate node 2 for several election timeouts");
+    {
+        router.set_unreachable(2, true);
+        sleep(Duration::from_millis(config.election_timeout_max * 5)).await;
+
+        let m = router.get_metrics(&2)?;
+        assert_eq!(term, m.current_term, "partitioned node 2 does not increase its term");
+        assert_eq!(ServerState::Follower, m.state);
+
+        let pre_vote_count = router.get_rpc_count().get(&RPCTypes::PreVote).copied().unwrap_or_default();
+        assert!(pre_vote_count > 0, "node 2 sent PreVote");
+    }
+
+    tracing::info!(log_index, "--- heal node 2, the leader is not disrupted");
+    {
+        router.set_unreachable(2, false);
+
+        log_index += router.client_request_many(0, "foo", 1).await?;
+        router.wait(&2, timeout()).applied_index(Some(log_index), "node 2 catches up").await?;
+
+        for id in [0, 1, 2] {
+            let m = router.get_metrics(&id)?;
+            assert_eq!(term, m.current_term, "node {} term is not changed", id);
+            assert_eq!(Some(0), m.current_leader, "node {} sees node 0 as leader", id);
+        }
+    }
+
+    Ok(())
+}
+
+/// With PreVote enabled, the remaining nodes elect a new leader when the leader is partitioned,
+/// and the old leader becomes a follower when the partition heals.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn pre_vote_elect_when_leader_is_partitioned() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_pre_vote: true,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- create cluster of 0,1,2");
+    let log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    let term = router.get_metrics(&0)?.current_term;
+
+    tracing::info!(log_index, "--- isolate leader node 0");
+    router.set_unreachable(0, true);
+
+    let leader = {
+        let m = router
+            .wait(&1, timeout())
+            .metrics(
+                |m| m.current_term > term && m.current_leader.is_some() && m.current_leader != Some(0),
+                "node 1 sees a new leader",
+            )
+            .await?;
+        m.current_leader.unwrap()
+    };
+    tracing::info!(log_index, "--- new leader: {}", leader);
+
+    tracing::info!(log_index, "--- heal node 0, it follows the new leader");
+    {
+        router.set_unreachable(0, false);
+
+        router.client_request_many(leader, "foo", 1).await?;
+        let log_index = router.get_metrics(&leader)?.last_log_index;
+
+        router
+            .wait(&0, timeout())
+            .applied_index(log_index, "node 0 replicated the logs from the new leader")
+            .await?;
+        router.wait(&0, timeout()).current_leader(leader, "node 0 follows the new leader").await?;
+        router.wait(&0, timeout()).state(ServerState::Follower, "node 0 becomes follower").await?;
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(3000))
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::Config;
+use openraft::RPCTypes;
+use openraft::ServerState;
+use tokio::time::sleep;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// A follower with a higher election priority takes over leadership,
+/// and a follower with a lower priority than the leader does not.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn election_priority_handoff_leader() -> Result<()> {
+    let config = Arc::new(Config::default().validate()?);
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- create cluster of 0,1,2");
+    let log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- restart node 2 with priority 10");
+    {
+        let (n2, log_store, sm) = router.remove_node(2).unwrap();
+        n2.shutdown().await?;
+
+        let c = Arc::new(
+            Config {
+                election_priority: 10,
+                ..(*config).clone()
+            }
+            .validate()?,
+        );
+        router.new_raft_node_with_config(2, c, log_store, sm).await;
+    }
+
+    tracing::info!(log_index, "--- node 2 becomes the leader");
+    {
+        router.wait(&2, timeout()).state(ServerState::Leader, "node 2 becomes leader").await?;
+        for id in [0, 1] {
+            router.wait(&id, timeout()).current_leader(2, "node 2 is the leader").await?;
+        }
+
+        let n = router.get_rpc_count().get(&RPCTypes::HandoffLeader).copied().unwrap_or_default();
+        assert!(n > 0, "node 2 sent HandoffLeader");
+    }
+
+    tracing::info!(
+        log_index,
+        "--- restart node 1 with priority 5, it does not take over leadership"
+    );
+    {
+        let before = router.get_rpc_count().get(&RPCTypes::HandoffLeader).copied().unwrap_or_default();
+
+        let (n1, log_store, sm) = router.remove_node(1).unwrap();
+        n1.shutdown().await?;
+
+        let c = Arc::new(
+            Config {
+                election_priority: 5,
+                ..(*config).clone()
+            }
+            .validate()?,
+        );
+        router.new_raft_node_with_config(1, c, log_store, sm).await;
+
+        router.wait(&1, timeout()).current_leader(2, "node 1 follows node 2").await?;
+
+        router
+            .wait(&1, timeout())
+            .metrics(
+                |_m| router.get_rpc_count().get(&RPCTypes::HandoffLeader).copied().unwrap_or_default() > before,
+                "node 1 sent HandoffLeader",
+            )
+            .await?;
+
+        sleep(Duration::from_millis(500)).await;
+
+        for id in [0, 1, 2] {
+            let m = router.get_metrics(&id)?;
+            assert_eq!(Some(2), m.current_leader, "node {} sees node 2 as leader", id);
+        }
+    }
+
+    Ok(())
+}
+
+/// When several followers report a higher election priority, the leadership ends up on the one
+/// with the highest priority.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn election_priority_handoff_to_highest() -> Result<()> {
+    let config = Arc::new(Config::default().validate()?);
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- create cluster of 0,1,2");
+    let log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    tracing::info!(
+        log_index,
+        "--- restart node 1 with priority 5 and node 2 with priority 10"
+    );
+    {
+        for (id, priority) in [(1, 5), (2, 10)] {
+            let (n, log_store, sm) = router.remove_node(id).unwrap();
+            n.shutdown().await?;
+
+            let c = Arc::new(
+                Config {
+                    election_priority: priority,
+                    ..(*config).clone()
+                }
+                .validate()?,
+            );
+            router.new_raft_node_with_config(id, c, log_store, sm).await;
+        }
+    }
+
+    tracing::info!(log_index, "--- node 2 becomes the leader and keeps it");
+    {
+        router.wait(&2, timeout()).state(ServerState::Leader, "node 2 becomes leader").await?;
+        for id in [0, 1] {
+            router.wait(&id, timeout()).current_leader(2, "node 2 is the leader").await?;
+        }
+
+        sleep(Duration::from_millis(500)).await;
+
+        for id in [0, 1, 2] {
+            let m = router.get_metrics(&id)?;
+            assert_eq!(Some(2), m.current_leader, "node {} sees node 2 as leader", id);
+        }
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(5000))
+}
@@ -8,6 +8,7 @@ use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::env;
 use std::fmt;
+use std::future::Future;
 use std::panic::PanicInfo;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
@@ -30,6 +31,9 @@ use openraft::error::PayloadTooLarge;
 use openraft::error::RPCError;
 use openraft::error::RaftError;
 use openraft::error::RemoteError;
+use openraft::error::ReplicationClosed;
+use openraft::error::SnapshotBaseMismatch;
+use openraft::error::StreamingError;
 use openraft::error::Unreachable;
 use openraft::metrics::Wait;
 use openraft::network::RPCOption;
@@ -38,15 +42,24 @@ use openraft::network::RaftNetworkFactory;
 use openraft::raft::AppendEntriesRequest;
 use openraft::raft::AppendEntriesResponse;
 use openraft::raft::ClientWriteResponse;
+use openraft::raft::HandoffLeaderRequest;
+use openraft::raft::HandoffLeaderResponse;
 use openraft::raft::InstallSnapshotRequest;
 use openraft::raft::InstallSnapshotResponse;
+use openraft::raft::ReadIndexRequest;
+use openraft::raft::ReadIndexResponse;
+use openraft::raft::SnapshotResponse;
+use openraft::raft::TransferLeaderRequest;
 use openraft::raft::VoteRequest;
 use openraft::raft::VoteResponse;
 use openraft::storage::RaftLogStorage;
 use openraft::storage::RaftStateMachine;
+use openraft::storage::Snapshot;
+use openraft::storage::SnapshotSignature;
 use openraft::Config;
 use openraft::LogId;
 use openraft::LogIdOptionExt;
+use openraft::OptionalSend;
 use openraft::RPCTypes;
 use openraft::Raft;
 use openraft::RaftLogId;
@@ -180,12 +193,15 @@ impl RPCErrorType {
             RPCErrorType::Unreachable => Unreachable::new(&AnyError::error(msg)).into(),
             RPCErrorType::NetworkError => NetworkError::new(&AnyError::error(msg)).into(),
             RPCErrorType::PayloadTooLarge { action, entries_hint } => match action {
-                RPCTypes::Vote => {
+                RPCTypes::Vote | RPCTypes::PreVote => {
                     unreachable!("Vote RPC should not be too large")
                 }
                 RPCTypes::AppendEntries => PayloadTooLarge::new_entries_hint(*entries_hint).into(),
-                RPCTypes::InstallSnapshot => {
-                    unreachable!("InstallSnapshot RPC should not be too large")
+                RPCTypes::InstallSnapshot | RPCTypes::SnapshotDelta => {
+                    unreachable!("{} RPC should not be too large", action)
+                }
+                RPCTypes::TransferLeader | RPCTypes::ReadIndex | RPCTypes::HandoffLeader => {
+                    unreachable!("{} RPC should not be too large", action)
                 }
             },
         }
@@ -200,6 +216,12 @@ pub enum RPCRequest<C: RaftTypeConfig> {
     AppendEntries(AppendEntriesRequest<C>),
     InstallSnapshot(InstallSnapshotRequest<C>),
     Vote(VoteRequest<C>),
+    #[from(ignore)]
+    #[try_into(ignore)]
+    PreVote(VoteRequest<C>),
+    TransferLeader(TransferLeaderRequest<C>),
+    ReadIndex(ReadIndexRequest<C>),
+    HandoffLeader(HandoffLeaderRequest<C>),
 }
 
 impl<C: RaftTypeConfig> RPCRequest<C> {
@@ -208,6 +230,10 @@ impl<C: RaftTypeConfig> RPCRequest<C> {
             RPCRequest::AppendEntries(_) => RPCTypes::AppendEntries,
             RPCRequest::InstallSnapshot(_) => RPCTypes::InstallSnapshot,
             RPCRequest::Vote(_) => RPCTypes::Vote,
+            RPCRequest::PreVote(_) => RPCTypes::PreVote,
+            RPCRequest::TransferLeader(_) => RPCTypes::TransferLeader,
+            RPCRequest::ReadIndex(_) => RPCTypes::ReadIndex,
+            RPCRequest::HandoffLeader(_) => RPCTypes::HandoffLeader,
         }
     }
 }
@@ -439,7 +465,20 @@ impl TypedRaftRouter {
 
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn new_raft_node_with_sto(&mut self, id: MemNodeId, log_store: MemLogStore, sm: MemStateMachine) {
-        let node = Raft::new(id, self.config.clone(), self.clone(), log_store.clone(), sm.clone()).await.unwrap();
+        let config = self.config.clone();
+        self.new_raft_node_with_config(id, config, log_store, sm).await
+    }
+
+    /// Create and register a new Raft node with a config other than the one shared by all nodes.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn new_raft_node_with_config(
+        &mut self,
+        id: MemNodeId,
+        config: Arc<Config>,
+        log_store: MemLogStore,
+        sm: MemStateMachine,
+    ) {
+        let node = Raft::new(id, config, self.clone(), log_store.clone(), sm.clone()).await.unwrap();
         let mut rt = self.nodes.lock().unwrap();
         rt.insert(id, (node, log_store, sm));
     }
@@ -1062,6 +1101,32 @@ impl RaftNetwork<MemConfig> for RaftRouterNetwork {
         Ok(resp)
     }
 
+    /// Send a snapshot delta to the target Raft node.
+    async fn snapshot_delta(
+        &mut self,
+        vote: Vote<MemNodeId>,
+        base: SnapshotSignature<MemNodeId>,
+        delta: Snapshot<MemConfig>,
+        _cancel: impl Future<Output = ReplicationClosed> + OptionalSend + 'static,
+        _option: RPCOption,
+    ) -> Result<
+        SnapshotResponse<MemConfig>,
+        StreamingError<MemConfig, RaftError<MemConfig, SnapshotBaseMismatch<MemConfig>>>,
+    > {
+        let from_id = vote.leader_id().voted_for().unwrap();
+
+        self.owner.count_rpc(RPCTypes::SnapshotDelta);
+        self.owner.emit_rpc_error(from_id, self.target).map_err(to_streaming_error)?;
+        self.owner.rand_send_delay().await;
+
+        let node = self.owner.get_raft_handle(&self.target)?;
+
+        let resp = node.install_snapshot_delta(vote, base, delta).await;
+        let resp = resp.map_err(|e| RemoteError::new(self.target, e))?;
+
+        Ok(resp)
+    }
+
     /// Send a RequestVote RPC to the target Raft node (§5).
     async fn vote(
         &mut self,
@@ -1082,6 +1147,102 @@ impl RaftNetwork<MemConfig> for RaftRouterNetwork {
 
         Ok(resp)
     }
+
+    /// Send a PreVote RPC to the target Raft node.
+    async fn pre_vote(
+        &mut self,
+        rpc: VoteRequest<MemConfig>,
+        _option: RPCOption,
+    ) -> Result<VoteResponse<MemConfig>, RPCError<MemConfig, RaftError<MemConfig>>> {
+        let from_id = rpc.vote.leader_id().voted_for().unwrap();
+
+        self.owner.count_rpc(RPCTypes::PreVote);
+        self.owner.call_rpc_pre_hook(RPCRequest::PreVote(rpc.clone()), from_id, self.target)?;
+        self.owner.emit_rpc_error(from_id, self.target)?;
+        self.owner.rand_send_delay().await;
+
+        let node = self.owner.get_raft_handle(&self.target)?;
+
+        let resp = node.pre_vote(rpc).await;
+        let resp = resp.map_err(|e| RemoteError::new(self.target, e))?;
+
+        Ok(resp)
+    }
+
+    /// Send a ReadIndex RPC to the target Raft node.
+    async fn read_index(
+        &mut self,
+        rpc: ReadIndexRequest<MemConfig>,
+        _option: RPCOption,
+    ) -> Result<ReadIndexResponse<MemConfig>, RPCError<MemConfig, RaftError<MemConfig, CheckIsLeaderError<MemConfig>>>>
+    {
+        let from_id = rpc.from;
+
+        self.owner.count_rpc(RPCTypes::ReadIndex);
+        self.owner.call_rpc_pre_hook(rpc.clone(), from_id, self.target)?;
+        self.owner.emit_rpc_error(from_id, self.target)?;
+        self.owner.rand_send_delay().await;
+
+        let node = self.owner.get_raft_handle(&self.target)?;
+
+        let resp = node.handle_read_index(rpc).await;
+        let resp = resp.map_err(|e| RemoteError::new(self.target, e))?;
+
+        Ok(resp)
+    }
+
+    async fn handoff_leader(
+        &mut self,
+        rpc: HandoffLeaderRequest<MemConfig>,
+        _option: RPCOption,
+    ) -> Result<HandoffLeaderResponse, RPCError<MemConfig, RaftError<MemConfig>>> {
+        let from_id = rpc.from;
+
+        self.owner.count_rpc(RPCTypes::HandoffLeader);
+        self.owner.call_rpc_pre_hook(rpc.clone(), from_id, self.target)?;
+        self.owner.emit_rpc_error(from_id, self.target)?;
+        self.owner.rand_send_delay().await;
+
+        let node = self.owner.get_raft_handle(&self.target)?;
+
+        let resp = node.handle_handoff_leader(rpc).await;
+        let resp = resp.map_err(|e| RemoteError::new(self.target, e))?;
+
+        Ok(resp)
+    }
+
+    /// Send a TransferLeader message to the target Raft node.
+    async fn transfer_leader(
+        &mut self,
+        req: TransferLeaderRequest<MemConfig>,
+        _option: RPCOption,
+    ) -> Result<(), RPCError<MemConfig, RaftError<MemConfig>>> {
+        let from_id = req.from_leader().leader_id().voted_for().unwrap();
+
+        self.owner.count_rpc(RPCTypes::TransferLeader);
+        self.owner.call_rpc_pre_hook(req.clone(), from_id, self.target)?;
+        self.owner.emit_rpc_error(from_id, self.target)?;
+        self.owner.rand_send_delay().await;
+
+        let node = self.owner.get_raft_handle(&self.target)?;
+
+        node.handle_transfer_leader(req)
+            .await
+            .map_err(|e| RemoteError::new(self.target, RaftError::Fatal(e)))?;
+
+        Ok(())
+    }
+}
+
+/// Convert an error of a single RPC to the error of streaming data.
+fn to_streaming_error<E: std::error::Error>(e: RPCError<MemConfig, E>) -> StreamingError<MemConfig, E> {
+    match e {
+        RPCError::Timeout(e) => e.into(),
+        RPCError::Unreachable(e) => e.into(),
+        RPCError::PayloadTooLarge(e) => NetworkError::new(&e).into(),
+        RPCError::Network(e) => e.into(),
+        RPCError::RemoteError(e) => e.into(),
+    }
 }
 
 pub enum ValueTest<T> {
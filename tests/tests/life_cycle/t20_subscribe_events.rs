@@ -0,0 +1,163 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::error::Fatal;
+use openraft::raft::EventStream;
+use openraft::raft::RaftEvent;
+use openraft::testing::log_id;
+use openraft::Config;
+use openraft::Vote;
+use openraft_memstore::TypeConfig;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+#[allow(unused_imports)] use pretty_assertions::assert_ne;
+use tokio::time::timeout;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// `Raft::subscribe_events()` delivers every lifecycle transition in order.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn subscribe_events() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            enable_elect: false,
+            max_in_snapshot_log_to_keep: 0,
+            purge_batch_size: 1,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    router.new_raft_node(0).await;
+    let n0 = router.get_raft_handle(&0)?;
+    let mut events = n0.subscribe_events();
+
+    tracing::info!("--- initialize node-0, which becomes leader");
+    {
+        router.initialize(0).await?;
+
+        let got = recv_until(&mut events, |e| matches!(e, RaftEvent::MembershipCommitted { .. })).await?;
+        assert_eq!(
+            vec![RaftEvent::TermChanged { from: 0, to: 1 }, RaftEvent::BecameLeader {
+                vote: Vote::new_committed(1, 0)
+            },],
+            got[..got.len() - 1]
+        );
+
+        let RaftEvent::MembershipCommitted { membership } = got.last().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(&Some(log_id(0, 0, 0)), membership.log_id());
+    }
+
+    tracing::info!("--- add node-1 and node-2 as voters");
+    let mut log_index = 1;
+    {
+        for id in [1, 2] {
+            router.new_raft_node(id).await;
+            router.add_learner(0, id).await?;
+            log_index += 1;
+        }
+        n0.change_membership(btreeset! {0,1,2}, false).await?;
+        log_index += 2;
+
+        let mut committed = vec![];
+        while committed.len() < 4 {
+            let got = recv_until(&mut events, |e| matches!(e, RaftEvent::MembershipCommitted { .. })).await?;
+            let RaftEvent::MembershipCommitted { membership } = got.last().unwrap() else {
+                unreachable!()
+            };
+            committed.push(membership.log_id().unwrap().index);
+        }
+        assert_eq!(vec![2, 3, 4, 5], committed);
+    }
+
+    tracing::info!(log_index, "--- node-2 becomes unreachable then recovers");
+    {
+        router.set_unreachable(2, true);
+        router.client_request_many(0, "foo", 1).await?;
+        log_index += 1;
+
+        let got = recv_until(&mut events, |e| matches!(e, RaftEvent::FollowerUnreachable { .. })).await?;
+        let RaftEvent::FollowerUnreachable { target, .. } = got.last().unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(2, *target);
+
+        router.set_unreachable(2, false);
+        router.client_request_many(0, "foo", 1).await?;
+        log_index += 1;
+
+        let got = recv_until(&mut events, |e| matches!(e, RaftEvent::FollowerRecovered { .. })).await?;
+        assert_eq!(&RaftEvent::FollowerRecovered { target: 2 }, got.last().unwrap());
+    }
+
+    tracing::info!(log_index, "--- build a snapshot and purge logs");
+    {
+        n0.trigger().snapshot().await?;
+
+        let got = recv_until(&mut events, |e| matches!(e, RaftEvent::LogPurged { .. })).await?;
+        let built = got.iter().find_map(|e| match e {
+            RaftEvent::SnapshotBuilt { meta } => Some(meta.last_log_id),
+            _ => None,
+        });
+        assert_eq!(Some(Some(log_id(1, 0, log_index))), built);
+        assert_eq!(
+            &RaftEvent::LogPurged {
+                upto: log_id(1, 0, log_index)
+            },
+            got.last().unwrap()
+        );
+    }
+
+    tracing::info!(log_index, "--- node-1 elects itself, node-0 quits leader");
+    {
+        let n1 = router.get_raft_handle(&1)?;
+        n1.trigger().elect().await?;
+
+        let got = recv_until(&mut events, |e| matches!(e, RaftEvent::QuitLeader { .. })).await?;
+        assert_eq!(
+            vec![RaftEvent::TermChanged { from: 1, to: 2 }, RaftEvent::QuitLeader {
+                vote: Vote::new_committed(1, 0)
+            },],
+            got
+        );
+    }
+
+    tracing::info!(log_index, "--- shutdown node-0, the stream ends");
+    {
+        n0.shutdown().await?;
+
+        let got = recv_until(&mut events, |e| matches!(e, RaftEvent::Fatal { .. })).await?;
+        assert_eq!(&RaftEvent::Fatal { error: Fatal::Stopped }, got.last().unwrap());
+        assert_eq!(None, events.recv().await);
+
+        let mut late = n0.subscribe_events();
+        assert_eq!(None, late.recv().await);
+    }
+
+    Ok(())
+}
+
+/// Receive events until one matches `f`, return all the received events.
+async fn recv_until(
+    events: &mut EventStream<TypeConfig>,
+    f: impl Fn(&RaftEvent<TypeConfig>) -> bool,
+) -> Result<Vec<RaftEvent<TypeConfig>>> {
+    let mut got = vec![];
+    loop {
+        let ev = timeout(Duration::from_millis(2_000), events.recv()).await?;
+        let ev = ev.ok_or_else(|| anyhow::anyhow!("event stream ended, received: {:?}", got))?;
+        tracing::info!("received event: {}", ev);
+
+        let matched = f(&ev);
+        got.push(ev);
+        if matched {
+            return Ok(got);
+        }
+    }
+}
@@ -11,6 +11,7 @@ mod t10_learner_restart;
 mod t10_single_node;
 mod t11_add_learner;
 mod t12_concurrent_write_and_add_learner;
+mod t13_promote_learner;
 mod t20_change_membership;
 mod t21_change_membership_cases;
 mod t30_commit_joint_config;
@@ -18,6 +19,8 @@ mod t30_elect_with_new_config;
 mod t31_add_remove_follower;
 mod t31_remove_leader;
 mod t31_removed_follower;
+mod t32_add_remove_witness;
+mod t33_witness_snapshot;
 mod t51_remove_unreachable_follower;
 mod t99_issue_471_adding_learner_uses_uninit_leader_id;
 mod t99_issue_584_replication_state_reverted;
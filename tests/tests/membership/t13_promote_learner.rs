@@ -0,0 +1,99 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::error::ChangeMembershipError;
+use openraft::error::ClientWriteError;
+use openraft::error::LearnerNotFound;
+use openraft::Config;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// Promote a learner to a voter once it catches up with the leader.
+///
+/// - A learner that can not catch up in time is not promoted, and `LearnerLagging` is returned.
+/// - A learner that catches up is promoted through joint consensus.
+/// - Promoting a node that is not a learner returns `LearnerNotFound`.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn promote_learner() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            replication_lag_threshold: 0,
+            enable_heartbeat: false,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- add learner 1, which is unreachable");
+    {
+        router.new_raft_node(1).await;
+        router.set_unreachable(1, true);
+
+        let n0 = router.get_raft_handle(&0)?;
+        n0.add_learner(1, (), false).await?;
+        log_index += 1;
+
+        log_index += router.client_request_many(0, "foo", 10).await?;
+    }
+
+    tracing::info!(log_index, "--- learner 1 does not catch up in time");
+    {
+        let n0 = router.get_raft_handle(&0)?;
+        let err = n0.promote_learner(1, Duration::from_millis(500)).await.unwrap_err().into_api_error().unwrap();
+
+        match err {
+            ClientWriteError::ChangeMembershipError(ChangeMembershipError::LearnerLagging(e)) => {
+                assert_eq!(1, e.node_id);
+                assert_eq!(Some(log_index), e.last_log_index);
+                assert!(e.matching.is_none());
+            }
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        let m = router.get_metrics(&0)?;
+        assert_eq!(
+            vec![0],
+            m.membership_config.membership().voter_ids().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(log_index), m.last_log_index, "membership is not changed");
+    }
+
+    tracing::info!(log_index, "--- learner 1 catches up and is promoted");
+    {
+        router.set_unreachable(1, false);
+
+        let n0 = router.get_raft_handle(&0)?;
+        let resp = n0.promote_learner(1, Duration::from_millis(3_000)).await?;
+        log_index += 2; // joint and uniform config
+
+        assert_eq!(log_index, resp.log_id.index);
+        assert_eq!(Some(btreeset! {0,1}), resp.membership.map(|m| m.voter_ids().collect()));
+
+        router.wait_for_log(&btreeset! {0,1}, Some(log_index), timeout(), "node 1 is a voter").await?;
+    }
+
+    tracing::info!(log_index, "--- promote a node that is not a learner");
+    {
+        let n0 = router.get_raft_handle(&0)?;
+        let err = n0.promote_learner(2, Duration::from_millis(500)).await.unwrap_err().into_api_error().unwrap();
+
+        assert_eq!(
+            ClientWriteError::ChangeMembershipError(ChangeMembershipError::LearnerNotFound(LearnerNotFound {
+                node_id: 2
+            })),
+            err
+        );
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(2000))
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreemap;
+use maplit::btreeset;
+use openraft::ChangeMembers;
+use openraft::Config;
+use openraft::EntryPayload;
+use openraft::RaftLogReader;
+use openraft::ServerState;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// A witness votes and counts toward the quorum, but stores no application data and never becomes
+/// a leader.
+///
+/// - brings up a cluster of 2 voters, then adds node 2 as a witness.
+/// - asserts node 2 reports `Witness` state and stores only blank and membership entries.
+/// - asserts the leader commits with the witness when the other voter is unreachable.
+/// - asserts the witness does not elect itself.
+/// - removes the witness and asserts it is not retained as a learner.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn add_remove_witness() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            enable_elect: false,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    let mut log_index = router.new_cluster(btreeset! {0,1}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- add node 2 as a witness");
+    {
+        router.new_raft_node(2).await;
+
+        let node = router.get_raft_handle(&0)?;
+        node.change_membership(ChangeMembers::AddWitnesses(btreemap! {2=>()}), false).await?;
+        log_index += 2;
+
+        router.wait_for_log(&btreeset! {0,1,2}, Some(log_index), timeout(), "witness added").await?;
+        router.wait(&2, timeout()).state(ServerState::Witness, "node 2 is a witness").await?;
+
+        let m = router.get_metrics(&0)?;
+        assert_eq!(
+            vec![2],
+            m.membership_config.membership().witness_ids().collect::<Vec<_>>()
+        );
+    }
+
+    tracing::info!(log_index, "--- write 10 logs");
+    {
+        log_index += router.client_request_many(0, "client", 10).await?;
+        router.wait_for_log(&btreeset! {0,1,2}, Some(log_index), timeout(), "write 10 logs").await?;
+    }
+
+    tracing::info!(log_index, "--- witness stores no application data");
+    {
+        let (mut sto2, _sm2) = router.get_storage_handle(&2)?;
+        let logs = sto2.try_get_log_entries(..).await?;
+        assert_eq!(Some(log_index), logs.last().map(|ent| ent.log_id.index));
+        for ent in logs.iter() {
+            assert!(
+                !matches!(ent.payload, EntryPayload::Normal(_)),
+                "witness entry {} has no app data",
+                ent.log_id
+            );
+        }
+
+        let (mut sto1, _sm1) = router.get_storage_handle(&1)?;
+        let logs = sto1.try_get_log_entries(..).await?;
+        assert_eq!(
+            10,
+            logs.iter().filter(|ent| matches!(ent.payload, EntryPayload::Normal(_))).count(),
+            "full voter stores app data"
+        );
+    }
+
+    tracing::info!(log_index, "--- commit with the witness when node 1 is unreachable");
+    {
+        router.set_unreachable(1, true);
+
+        log_index += router.client_request_many(0, "client", 1).await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), "committed by 0 and witness 2").await?;
+
+        router.set_unreachable(1, false);
+        router.wait_for_log(&btreeset! {0,1,2}, Some(log_index), timeout(), "node 1 catches up").await?;
+    }
+
+    tracing::info!(log_index, "--- witness does not elect itself");
+    {
+        let term = router.get_metrics(&2)?.current_term;
+
+        let n2 = router.get_raft_handle(&2)?;
+        n2.trigger().elect().await?;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let m = router.get_metrics(&2)?;
+        assert_eq!(ServerState::Witness, m.state);
+        assert_eq!(term, m.current_term);
+        assert_eq!(Some(0), m.current_leader);
+    }
+
+    tracing::info!(log_index, "--- remove the witness");
+    {
+        let node = router.get_raft_handle(&0)?;
+        node.change_membership(ChangeMembers::RemoveWitnesses(btreeset! {2}), true).await?;
+        log_index += 2;
+
+        router.wait_for_log(&btreeset! {0,1}, Some(log_index), timeout(), "witness removed").await?;
+
+        let m = router.get_metrics(&0)?;
+        let membership = m.membership_config.membership();
+        assert_eq!(vec![0, 1], membership.voter_ids().collect::<Vec<_>>());
+        assert_eq!(
+            0,
+            membership.learner_ids().count(),
+            "witness is not retained as learner"
+        );
+        assert_eq!(0, membership.witness_ids().count());
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(2000))
+}
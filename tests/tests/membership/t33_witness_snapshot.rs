@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreemap;
+use maplit::btreeset;
+use openraft::testing::log_id;
+use openraft::ChangeMembers;
+use openraft::Config;
+use openraft::ServerState;
+use openraft::SnapshotPolicy;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// A witness that lacks purged logs receives a snapshot without application data.
+///
+/// - brings up a cluster of 2 voters, writes logs, builds a snapshot and purges logs on the leader.
+/// - adds node 2 as a witness, which has to receive a snapshot.
+/// - asserts the witness installs the snapshot meta, but no application data.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn witness_snapshot() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            snapshot_policy: SnapshotPolicy::Never,
+            max_in_snapshot_log_to_keep: 0,
+            purge_batch_size: 1,
+            enable_heartbeat: false,
+            enable_elect: false,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    let mut log_index = router.new_cluster(btreeset! {0,1}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- write 10 logs, build a snapshot and purge logs");
+    let snapshot_log_id = {
+        log_index += router.client_request_many(0, "client", 10).await?;
+        router.wait_for_log(&btreeset! {0,1}, Some(log_index), timeout(), "write 10 logs").await?;
+
+        let n0 = router.get_raft_handle(&0)?;
+        n0.trigger().snapshot().await?;
+
+        let snapshot_log_id = log_id(1, 0, log_index);
+        router.wait(&0, timeout()).snapshot(snapshot_log_id, "build snapshot").await?;
+
+        n0.trigger().purge_log(log_index).await?;
+        router.wait(&0, timeout()).purged(Some(snapshot_log_id), "purge logs").await?;
+
+        snapshot_log_id
+    };
+
+    tracing::info!(log_index, "--- add node 2 as a witness, which receives a snapshot");
+    {
+        router.new_raft_node(2).await;
+
+        let node = router.get_raft_handle(&0)?;
+        node.change_membership(ChangeMembers::AddWitnesses(btreemap! {2=>()}), false).await?;
+        log_index += 2;
+
+        router.wait_for_log(&btreeset! {0,1,2}, Some(log_index), timeout(), "witness added").await?;
+        router.wait(&2, timeout()).state(ServerState::Witness, "node 2 is a witness").await?;
+        router.wait(&2, timeout()).snapshot(snapshot_log_id, "witness installs snapshot").await?;
+    }
+
+    tracing::info!(log_index, "--- witness stores no application data");
+    {
+        let (_sto2, sm2) = router.get_storage_handle(&2)?;
+        let sm = sm2.get_state_machine().await;
+
+        assert_eq!(Some(log_index), sm.last_applied_log.map(|x| x.index));
+        assert!(sm.client_status.is_empty(), "witness has no app data");
+
+        let (_sto1, sm1) = router.get_storage_handle(&1)?;
+        let sm = sm1.get_state_machine().await;
+        assert_eq!(
+            Some(&"request-9".to_string()),
+            sm.client_status.get("client"),
+            "full voter has app data"
+        );
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(2000))
+}
@@ -12,5 +12,8 @@ mod t10_leader_last_ack;
 mod t10_purged;
 mod t10_server_metrics_and_data_metrics;
 mod t20_metrics_state_machine_consistency;
+mod t30_health;
 mod t30_leader_metrics;
+mod t30_perf_metrics;
+mod t30_replication_target_metrics;
 mod t40_metrics_wait;
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::alias::AsyncRuntimeOf;
+use openraft::metrics::HealthCheck;
+use openraft::metrics::HealthThresholds;
+use openraft::AsyncRuntime;
+use openraft::Config;
+use openraft_memstore::TypeConfig;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+#[allow(unused_imports)] use pretty_assertions::assert_ne;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// `Raft::health()` reports the leader, the quorum ack, the apply lag and the reachability and
+/// replication lag of followers.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn health() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            enable_elect: false,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    let thresholds = HealthThresholds {
+        max_millis_since_quorum_ack: 200,
+        max_apply_lag: 3,
+        max_replication_lag: 3,
+        max_millis_since_last_success: 200,
+    };
+
+    tracing::info!(log_index, "--- all nodes are ready");
+    {
+        let n0 = router.get_raft_handle(&0)?;
+        n0.trigger().heartbeat().await?;
+
+        let h = n0.health(&thresholds);
+        assert!(h.is_live());
+        assert!(h.is_ready(), "{}", h);
+        assert_eq!(HealthCheck::Pass, h.quorum_ack);
+        assert_eq!(btreeset! {1,2}, h.followers.keys().copied().collect());
+        assert!(h.followers.values().all(|f| f.reachable == HealthCheck::Pass), "{}", h);
+        assert!(h.followers.values().all(|f| f.caught_up == HealthCheck::Pass), "{}", h);
+
+        for id in [1, 2] {
+            let h = router.get_raft_handle(&id)?.health(&thresholds);
+            assert!(h.is_ready(), "{}", h);
+            assert_eq!(Some(0), h.current_leader);
+            assert_eq!(HealthCheck::Skip, h.quorum_ack);
+            assert!(h.followers.is_empty());
+        }
+    }
+
+    tracing::info!(log_index, "--- isolate node 2 and write 5 logs");
+    {
+        router.set_network_error(2, true);
+        log_index += router.client_request_many(0, "foo", 5).await?;
+
+        for id in [0, 1] {
+            router.wait(&id, timeout()).applied_index(Some(log_index), "applied").await?;
+        }
+
+        let h = router.get_raft_handle(&0)?.health(&thresholds);
+        assert!(h.is_ready(), "a lagging follower does not affect readiness: {}", h);
+        assert_eq!(HealthCheck::Pass, h.followers[&1].caught_up);
+        assert_eq!(HealthCheck::Fail, h.followers[&2].caught_up);
+        assert_eq!(5, h.followers[&2].lag);
+    }
+
+    tracing::info!(
+        log_index,
+        "--- without heartbeat, the quorum ack becomes stale and the isolated node is unreachable"
+    );
+    {
+        AsyncRuntimeOf::<TypeConfig>::sleep(Duration::from_millis(500)).await;
+
+        let h = router.get_raft_handle(&0)?.health(&thresholds);
+        assert!(h.is_live());
+        assert!(!h.is_ready(), "{}", h);
+        assert_eq!(HealthCheck::Fail, h.quorum_ack);
+        assert_eq!(HealthCheck::Fail, h.followers[&2].reachable);
+        assert!(h.followers[&2].consecutive_failures > 0, "{}", h);
+    }
+
+    tracing::info!(log_index, "--- a stopped node is not live");
+    {
+        let n1 = router.get_raft_handle(&1)?;
+        n1.shutdown().await?;
+
+        let h = n1.health(&thresholds);
+        assert!(!h.is_live());
+        assert!(!h.is_ready());
+        assert_eq!(HealthCheck::Pass, h.storage);
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(1000))
+}
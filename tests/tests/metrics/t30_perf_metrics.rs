@@ -0,0 +1,113 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::testing::log_id;
+use openraft::Config;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+#[allow(unused_imports)] use pretty_assertions::assert_ne;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// `Raft::perf_metrics()` reports the latency histograms and the counters of writes, elections,
+/// and snapshots.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn perf_metrics() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            enable_elect: false,
+            max_in_snapshot_log_to_keep: 0,
+            purge_batch_size: 1,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0,1,2}, btreeset! {}).await?;
+
+    let n0 = router.get_raft_handle(&0)?;
+    let perf0 = n0.perf_metrics();
+    let before = perf0.borrow().clone();
+
+    assert_eq!(1, before.elections);
+    assert_eq!(1, before.elections_won);
+    assert_eq!(0, before.pre_votes);
+
+    let n = 10;
+    tracing::info!(log_index, "--- write {} logs", n);
+    log_index += router.client_request_many(0, "foo", n).await?;
+
+    for id in [0, 1, 2] {
+        router.wait(&id, timeout()).applied_index(Some(log_index), "applied").await?;
+    }
+
+    tracing::info!(log_index, "--- check leader perf metrics");
+    {
+        let p = perf0.borrow().clone();
+
+        assert_eq!(
+            n as u64,
+            p.client_write_commit.count() - before.client_write_commit.count()
+        );
+        assert_eq!(
+            n as u64,
+            p.client_write_apply.count() - before.client_write_apply.count()
+        );
+        assert!(p.client_write_apply.sum() >= p.client_write_commit.sum());
+
+        assert_eq!(log_index + 1, p.appended_entries);
+        assert_eq!(log_index + 1, p.applied_entries);
+        assert!(p.log_flush.count() > before.log_flush.count());
+        assert!(p.apply_batch.count() > before.apply_batch.count());
+
+        assert_eq!(btreeset! {1, 2}, p.append_entries_rtt.keys().copied().collect());
+        assert!(p.append_entries_rtt.values().all(|h| h.count() > 0));
+
+        assert_eq!(1, p.elections);
+        assert_eq!(1, p.elections_won);
+    }
+
+    tracing::info!(log_index, "--- check follower perf metrics");
+    {
+        let p = router.get_raft_handle(&1)?.perf_metrics().borrow().clone();
+
+        assert_eq!(0, p.client_write_apply.count());
+        assert_eq!(0, p.elections);
+        assert_eq!(log_index + 1, p.appended_entries);
+        assert_eq!(log_index + 1, p.applied_entries);
+        assert!(p.append_entries_rtt.is_empty());
+    }
+
+    tracing::info!(log_index, "--- build a snapshot and purge logs");
+    {
+        n0.trigger().snapshot().await?;
+        n0.wait(timeout()).snapshot(log_id(1, 0, log_index), "build snapshot").await?;
+        n0.wait(timeout()).purged(Some(log_id(1, 0, log_index)), "purged").await?;
+
+        assert_eq!(1, perf0.borrow().snapshot_build.count());
+    }
+
+    tracing::info!(log_index, "--- add a learner, which installs the snapshot");
+    {
+        router.new_raft_node(3).await;
+        router.add_learner(0, 3).await?;
+        log_index += 1;
+
+        router.wait(&3, timeout()).applied_index(Some(log_index), "learner applied").await?;
+
+        let p = router.get_raft_handle(&3)?.perf_metrics().borrow().clone();
+        assert_eq!(1, p.snapshot_install.count());
+        assert_eq!(0, p.snapshot_build.count());
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(1_000))
+}
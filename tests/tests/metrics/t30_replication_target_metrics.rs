@@ -0,0 +1,146 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::metrics::InflightMetrics;
+use openraft::testing::log_id;
+use openraft::Config;
+use openraft::LogIdOptionExt;
+#[allow(unused_imports)] use pretty_assertions::assert_eq;
+#[allow(unused_imports)] use pretty_assertions::assert_ne;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// `RaftMetrics::replication_targets` reports the heartbeat RTT, the RPC failures, the backoff and
+/// the snapshot being sent to every target.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn replication_target_metrics() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_elect: false,
+            max_in_snapshot_log_to_keep: 0,
+            purge_batch_size: 1,
+            snapshot_max_chunk_size: 10,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0,1}, btreeset! {2}).await?;
+
+    let leader = router.get_raft_handle(&0)?;
+
+    tracing::info!(log_index, "--- heartbeats are accepted by every target");
+    {
+        let m = leader
+            .wait(timeout())
+            .metrics(
+                |m| {
+                    m.replication_targets.as_ref().map_or(false, |t| {
+                        t[&1].heartbeat_rtt.is_some() && t[&2].heartbeat_rtt.is_some()
+                    })
+                },
+                "heartbeat rtt reported",
+            )
+            .await?;
+
+        let targets = m.replication_targets.unwrap();
+        assert_eq!(btreeset! {0,1,2}, targets.keys().copied().collect());
+
+        for id in [1, 2] {
+            let t = &targets[&id];
+            assert_eq!(Some(log_index), t.matching.index());
+            assert!(t.millis_since_last_success.is_some());
+            assert_eq!(None, t.millis_since_last_failure);
+            assert_eq!(0, t.consecutive_failures);
+            assert_eq!(None, t.backoff);
+            assert_eq!(None, t.snapshot_sent_bytes);
+        }
+    }
+
+    tracing::info!(log_index, "--- an unreachable target fails and backs off");
+    {
+        router.set_unreachable(2, true);
+
+        let m = leader
+            .wait(timeout())
+            .metrics(
+                |m| m.replication_targets.as_ref().map_or(false, |t| t[&2].backoff.is_some()),
+                "target 2 backs off",
+            )
+            .await?;
+
+        let t = &m.replication_targets.unwrap()[&2];
+        assert!(t.consecutive_failures >= 1, "{}", t);
+        assert!(t.millis_since_last_failure.is_some(), "{}", t);
+        assert_eq!(Some(Duration::from_millis(500)), t.backoff);
+    }
+
+    tracing::info!(
+        log_index,
+        "--- build a snapshot and purge logs, target 2 needs a snapshot"
+    );
+    {
+        router.set_unreachable(2, false);
+        router.set_network_error(2, true);
+
+        log_index += router.client_request_many(0, "0", 10).await?;
+
+        leader.trigger().snapshot().await?;
+        leader.wait(timeout()).purged(Some(log_id(1, 0, log_index)), "purged").await?;
+
+        let m = leader
+            .wait(timeout())
+            .metrics(
+                |m| {
+                    m.replication_targets
+                        .as_ref()
+                        .map_or(false, |t| matches!(t[&2].inflight, InflightMetrics::Snapshot { .. }))
+                },
+                "snapshot is being sent to target 2",
+            )
+            .await?;
+
+        let t = &m.replication_targets.unwrap()[&2];
+        assert_eq!(
+            InflightMetrics::Snapshot {
+                last_log_id: Some(log_id(1, 0, log_index))
+            },
+            t.inflight
+        );
+        assert_eq!(None, t.snapshot_sent_bytes, "no chunk is received by target 2");
+    }
+
+    tracing::info!(log_index, "--- restore network, target 2 installs the snapshot");
+    {
+        router.set_network_error(2, false);
+
+        router.wait(&2, timeout()).snapshot(log_id(1, 0, log_index), "target 2 installed snapshot").await?;
+
+        let m = leader
+            .wait(timeout())
+            .metrics(
+                |m| {
+                    m.replication_targets.as_ref().map_or(false, |t| {
+                        t[&2].matching.index() == Some(log_index) && t[&2].consecutive_failures == 0
+                    })
+                },
+                "target 2 caught up",
+            )
+            .await?;
+
+        let t = &m.replication_targets.unwrap()[&2];
+        assert_eq!(None, t.snapshot_sent_bytes);
+        assert_eq!(None, t.backoff);
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(5_000))
+}
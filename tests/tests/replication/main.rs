@@ -5,8 +5,10 @@
 mod fixtures;
 
 mod t10_append_entries_partial_success;
+mod t20_pipelined_append_entries;
 mod t50_append_entries_backoff;
 mod t50_append_entries_backoff_rejoin;
 mod t51_append_entries_too_large;
+mod t52_append_entries_payload_bytes;
 #[cfg(feature = "loosen-follower-log-revert")]
 mod t60_feature_loosen_follower_log_revert;
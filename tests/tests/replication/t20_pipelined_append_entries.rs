@@ -0,0 +1,158 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyerror::AnyError;
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::error::Unreachable;
+use openraft::raft::AppendEntriesRequest;
+use openraft::Config;
+use openraft::RPCTypes;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// With `max_inflight_append_entries` > 1, a lagging learner is brought up to date by pipelined
+/// AppendEntries RPCs, none of which has more than `max_payload_entries` entries.
+///
+/// One of the RPCs fails, and replication resends the logs from the last matching log id.
+#[async_entry::test(worker_threads = 4, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn pipelined_append_entries() -> Result<()> {
+    let max_payload_entries = 5;
+
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            max_payload_entries,
+            max_inflight_append_entries: 4,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster of 1 node");
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {}).await?;
+
+    let n = 100u64;
+
+    tracing::info!(log_index, "--- write {} entries to leader", n);
+    {
+        log_index += router.client_request_many(0, "0", n as usize).await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), format!("{} writes", n)).await?;
+    }
+
+    let count = Arc::new(AtomicU64::new(0));
+    let max_entries = Arc::new(AtomicU64::new(0));
+
+    let c = count.clone();
+    let m = max_entries.clone();
+
+    tracing::info!(log_index, "--- the 3rd non-empty RPC to node-1 fails");
+    {
+        router.set_rpc_pre_hook(RPCTypes::AppendEntries, move |_router, req, _id, target| {
+            let r: AppendEntriesRequest<_> = req.try_into().unwrap();
+            if target == 1 && !r.entries.is_empty() {
+                m.fetch_max(r.entries.len() as u64, Ordering::Relaxed);
+
+                if c.fetch_add(1, Ordering::Relaxed) == 2 {
+                    let any_err = AnyError::error("unreachable");
+                    return Err(Unreachable::new(&any_err).into());
+                }
+            }
+            Ok(())
+        });
+    }
+
+    tracing::info!(log_index, "--- add node-1 as learner");
+    {
+        router.new_raft_node(1).await;
+        router.add_learner(0, 1).await?;
+        log_index += 1;
+
+        router.wait(&1, timeout()).applied_index(Some(log_index), "learner is up to date").await?;
+    }
+
+    assert!(
+        count.load(Ordering::Relaxed) >= n / max_payload_entries,
+        "logs are sent in RPCs of at most {} entries",
+        max_payload_entries
+    );
+    assert!(max_entries.load(Ordering::Relaxed) <= max_payload_entries);
+
+    Ok(())
+}
+
+/// The pipeline window slides: every time the oldest RPC is acknowledged, the next RPC is sent,
+/// even if the logs to send need more RPCs than the window size.
+///
+/// Every RPC has only one entry because of `target_payload_bytes`, thus the logs of one
+/// replication action are sent in many more RPCs than `max_inflight_append_entries`, in order and
+/// without any resending.
+#[async_entry::test(worker_threads = 4, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn pipelined_append_entries_sliding_window() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            max_payload_entries: 10,
+            target_payload_bytes: 1,
+            max_inflight_append_entries: 4,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster of 1 node");
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {}).await?;
+
+    let n = 100u64;
+
+    tracing::info!(log_index, "--- write {} entries to leader", n);
+    {
+        log_index += router.client_request_many(0, "0", n as usize).await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), format!("{} writes", n)).await?;
+    }
+
+    let sent = Arc::new(Mutex::new(Vec::new()));
+
+    let s = sent.clone();
+
+    tracing::info!(log_index, "--- record the first log index of every RPC to node-1");
+    {
+        router.set_rpc_pre_hook(RPCTypes::AppendEntries, move |_router, req, _id, target| {
+            let r: AppendEntriesRequest<_> = req.try_into().unwrap();
+            if target == 1 && !r.entries.is_empty() {
+                assert_eq!(1, r.entries.len(), "an RPC has only one entry");
+                s.lock().unwrap().push(r.entries[0].log_id.index);
+            }
+            Ok(())
+        });
+    }
+
+    tracing::info!(log_index, "--- add node-1 as learner");
+    {
+        router.new_raft_node(1).await;
+        router.add_learner(0, 1).await?;
+        log_index += 1;
+
+        router.wait(&1, timeout()).applied_index(Some(log_index), "learner is up to date").await?;
+    }
+
+    // The RPCs before the first log are probes for the matching log id, which conflict.
+    let sent = sent.lock().unwrap().clone();
+    let first = sent.iter().position(|index| *index == 0).unwrap();
+    let want = (0..=log_index).collect::<Vec<_>>();
+    assert_eq!(want, sent[first..], "every log is sent once, in order");
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(5_000))
+}
@@ -0,0 +1,186 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::entry::FromAppData;
+use openraft::entry::RaftEntry;
+use openraft::raft::AppendEntriesRequest;
+use openraft::Config;
+use openraft::Entry;
+use openraft::EntryPayload;
+use openraft::RPCTypes;
+use openraft_memstore::ClientRequest;
+use openraft_memstore::TypeConfig;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// With `target_payload_bytes` set, an AppendEntries RPC is filled up to the target size,
+/// even though `max_payload_entries` allows more entries.
+#[async_entry::test(worker_threads = 4, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn append_entries_payload_bytes() -> Result<()> {
+    // Every application entry in this test has the same approximate size.
+    let entry_size = Entry::<TypeConfig>::from_app_data(request(0)).approx_size();
+
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            max_payload_entries: 100,
+            target_payload_bytes: entry_size * 2,
+            max_payload_bytes: entry_size * 3,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster of 1 node");
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {}).await?;
+
+    let n = 20u64;
+
+    tracing::info!(log_index, "--- write {} entries to leader", n);
+    {
+        for serial in 0..n {
+            router.send_client_request(0, request(serial)).await?;
+        }
+        log_index += n;
+        router.wait(&0, timeout()).applied_index(Some(log_index), format!("{} writes", n)).await?;
+    }
+
+    let max_entries = Arc::new(AtomicU64::new(0));
+
+    let m = max_entries.clone();
+
+    tracing::info!(
+        log_index,
+        "--- record the max number of application entries in an RPC to node-1"
+    );
+    {
+        router.set_rpc_pre_hook(RPCTypes::AppendEntries, move |_router, req, _id, target| {
+            let r: AppendEntriesRequest<_> = req.try_into().unwrap();
+            if target == 1 {
+                let n = r.entries.iter().filter(|ent| matches!(ent.payload, EntryPayload::Normal(_))).count();
+                m.fetch_max(n as u64, Ordering::Relaxed);
+            }
+            Ok(())
+        });
+    }
+
+    tracing::info!(log_index, "--- add node-1 as learner");
+    {
+        router.new_raft_node(1).await;
+        router.add_learner(0, 1).await?;
+        log_index += 1;
+
+        router.wait(&1, timeout()).applied_index(Some(log_index), "learner is up to date").await?;
+    }
+
+    assert_eq!(
+        2,
+        max_entries.load(Ordering::Relaxed),
+        "an RPC is filled up to target_payload_bytes"
+    );
+
+    Ok(())
+}
+
+/// With `target_payload_bytes` set, the size of the entries decides how many entries an
+/// AppendEntries RPC has: an RPC of many small entries and an RPC of a few big entries.
+#[async_entry::test(worker_threads = 4, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn append_entries_payload_bytes_varied_entry_size() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            enable_heartbeat: false,
+            max_payload_entries: 1000,
+            target_payload_bytes: 20_000,
+            max_payload_bytes: 30_000,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster of 1 node");
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- write 100 small entries and 10 big entries to leader");
+    {
+        log_index += router.client_request_many(0, "0", 100).await?;
+
+        for serial in 100..110 {
+            let req = ClientRequest {
+                client: "0".to_string(),
+                serial,
+                status: "x".repeat(8_000),
+            };
+            router.send_client_request(0, req).await?;
+            log_index += 1;
+        }
+
+        router.wait(&0, timeout()).applied_index(Some(log_index), "110 writes").await?;
+    }
+
+    // The number of entries and the total size of every RPC to node-1.
+    let rpcs = Arc::new(Mutex::new(Vec::new()));
+
+    let r = rpcs.clone();
+
+    tracing::info!(log_index, "--- record the RPCs to node-1");
+    {
+        router.set_rpc_pre_hook(RPCTypes::AppendEntries, move |_router, req, _id, target| {
+            let req: AppendEntriesRequest<_> = req.try_into().unwrap();
+            if target == 1 && !req.entries.is_empty() {
+                let size = req.entries.iter().map(|ent| ent.approx_size()).sum::<u64>();
+                r.lock().unwrap().push((req.entries.len(), size));
+            }
+            Ok(())
+        });
+    }
+
+    tracing::info!(log_index, "--- add node-1 as learner");
+    {
+        router.new_raft_node(1).await;
+        router.add_learner(0, 1).await?;
+        log_index += 1;
+
+        router.wait(&1, timeout()).applied_index(Some(log_index), "learner is up to date").await?;
+    }
+
+    let rpcs = rpcs.lock().unwrap().clone();
+    tracing::info!("RPCs to node-1: {:?}", rpcs);
+
+    assert!(
+        rpcs.iter().any(|(n, _size)| *n > 100),
+        "small entries are sent in one RPC"
+    );
+    assert!(
+        rpcs.iter().any(|(n, size)| *n <= 3 && *size >= 20_000),
+        "big entries are sent a few in one RPC"
+    );
+    assert!(
+        rpcs.iter().all(|(n, size)| *n == 1 || *size <= 30_000),
+        "no RPC exceeds max_payload_bytes"
+    );
+
+    Ok(())
+}
+
+/// Build a request whose entry has the same size for every `serial`.
+fn request(serial: u64) -> ClientRequest {
+    ClientRequest {
+        client: "0".to_string(),
+        serial,
+        status: "x".repeat(100),
+    }
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(1_000))
+}
@@ -8,3 +8,5 @@ mod t10_build_snapshot;
 mod t35_building_snapshot_does_not_block_append;
 mod t35_building_snapshot_does_not_block_apply;
 mod t60_snapshot_policy_never;
+mod t61_snapshot_policy_time_since_last;
+mod t62_snapshot_policy_bytes_since_last;
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::Config;
+use openraft::SnapshotPolicy;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// Build a snapshot when enough time has elapsed since the last one.
+///
+/// - build a stable single node cluster with a time based snapshot policy.
+/// - assert no snapshot is built before the interval elapses.
+/// - write a log after the interval and assert a snapshot is built.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn snapshot_policy_time_since_last() -> Result<()> {
+    let interval = Duration::from_millis(3_000);
+
+    let config = Arc::new(
+        Config {
+            snapshot_policy: SnapshotPolicy::TimeSinceLast(interval),
+            enable_tick: false,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- no snapshot before the interval elapses");
+    {
+        log_index += router.client_request_many(0, "0", 10).await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), "write 10 logs").await?;
+
+        let m = router.get_metrics(&0)?;
+        assert!(m.snapshot.is_none(), "no snapshot should be built");
+    }
+
+    tracing::info!(log_index, "--- snapshot is built after the interval elapses");
+    {
+        tokio::time::sleep(interval).await;
+
+        log_index += router.client_request_many(0, "0", 1).await?;
+        router
+            .wait(&0, timeout())
+            .metrics(|m| m.snapshot.map(|x| x.index) == Some(log_index), "snapshot is built")
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(1_000))
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::Config;
+use openraft::ServerState;
+use openraft::SnapshotPolicy;
+use openraft_memstore::ClientRequest;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// A size based snapshot policy counts the payload of logs, including the logs written before a
+/// restart.
+///
+/// - build a single node cluster with a size based snapshot policy of 100 KB.
+/// - write many small logs and a big log, less than the threshold in total, then restart the node.
+/// - write another big log to reach the threshold and assert a snapshot is built.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn snapshot_policy_bytes_since_last_after_restart() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            snapshot_policy: SnapshotPolicy::BytesSinceLast(100_000),
+            ..Default::default()
+        }
+        .validate()?,
+    );
+    let mut router = RaftRouter::new(config.clone());
+
+    tracing::info!("--- initializing cluster");
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {}).await?;
+
+    tracing::info!(log_index, "--- no snapshot for many small logs");
+    {
+        log_index += router.client_request_many(0, "0", 100).await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), "write 100 small logs").await?;
+
+        let m = router.get_metrics(&0)?;
+        assert!(m.snapshot.is_none(), "no snapshot should be built");
+    }
+
+    tracing::info!(log_index, "--- no snapshot before the threshold is reached");
+    {
+        router.send_client_request(0, big_request(100, 50_000)).await?;
+        log_index += 1;
+        router.wait(&0, timeout()).applied_index(Some(log_index), "write a big log").await?;
+
+        let m = router.get_metrics(&0)?;
+        assert!(m.snapshot.is_none(), "no snapshot should be built");
+    }
+
+    tracing::info!(log_index, "--- restart node 0");
+    {
+        let (n0, log_store, sm) = router.remove_node(0).unwrap();
+        n0.shutdown().await?;
+
+        router.new_raft_node_with_config(0, config.clone(), log_store, sm).await;
+        router.wait(&0, timeout()).state(ServerState::Leader, "node 0 becomes leader").await?;
+        router.wait(&0, timeout()).applied_index(Some(log_index), "logs are re-applied").await?;
+
+        let m = router.get_metrics(&0)?;
+        assert!(m.snapshot.is_none(), "no snapshot should be built");
+    }
+
+    tracing::info!(
+        log_index,
+        "--- snapshot is built when the logs before restart are counted"
+    );
+    {
+        // The logs before restart take about 60 KB, and this log alone does not reach the threshold.
+        router.send_client_request(0, big_request(101, 50_000)).await?;
+        log_index += 1;
+        router
+            .wait(&0, timeout())
+            .metrics(|m| m.snapshot.map(|x| x.index) == Some(log_index), "snapshot is built")
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn big_request(serial: u64, size: usize) -> ClientRequest {
+    ClientRequest {
+        client: "0".to_string(),
+        serial,
+        status: "x".repeat(size),
+    }
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(5_000))
+}
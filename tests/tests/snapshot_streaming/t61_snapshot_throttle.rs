@@ -0,0 +1,136 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::raft::InstallSnapshotRequest;
+use openraft::CommittedLeaderId;
+use openraft::Config;
+use openraft::LogId;
+use openraft::RPCTypes;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// With `max_concurrent_snapshot_streams` and `snapshot_max_bytes_per_sec`, a leader sends
+/// snapshots to lagging learners one after another, no faster than the bandwidth limit.
+///
+/// - Bring up a leader and two isolated learners, build a snapshot and purge logs on the leader.
+/// - One snapshot stream is sending and the other is queued.
+/// - Restore the network: chunks of the two snapshots are not interleaved, and are sent within the
+///   bandwidth limit.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn snapshot_throttle() -> Result<()> {
+    let bytes_per_sec = 2000;
+
+    let config = Arc::new(
+        Config {
+            max_in_snapshot_log_to_keep: 0,
+            purge_batch_size: 1,
+            snapshot_max_chunk_size: 10,
+            snapshot_max_bytes_per_sec: bytes_per_sec,
+            max_concurrent_snapshot_streams: 1,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {1,2}).await?;
+
+    let leader = router.get_raft_handle(&0)?;
+
+    router.set_network_error(1, true);
+    router.set_network_error(2, true);
+
+    tracing::info!(log_index, "--- build snapshot on leader and purge logs");
+    {
+        log_index += router.client_request_many(0, "0", 10).await?;
+
+        leader.trigger().snapshot().await?;
+        leader
+            .wait(timeout())
+            .snapshot(LogId::new(CommittedLeaderId::new(1, 0), log_index), "built snapshot")
+            .await?;
+        leader
+            .wait(timeout())
+            .purged(Some(LogId::new(CommittedLeaderId::new(1, 0), log_index)), "purged")
+            .await?;
+    }
+
+    tracing::info!(log_index, "--- one snapshot is sending and the other is queued");
+    {
+        leader
+            .wait(timeout())
+            .metrics(
+                |m| m.snapshot_streams.sending == 1 && m.snapshot_streams.queued == 1,
+                "one snapshot queued",
+            )
+            .await?;
+    }
+
+    let sent = Arc::new(Mutex::new(vec![]));
+
+    tracing::info!(log_index, "--- restore network, record snapshot chunks");
+    {
+        let s = sent.clone();
+        router.set_rpc_pre_hook(RPCTypes::InstallSnapshot, move |_router, req, _id, target| {
+            let r: InstallSnapshotRequest<_> = req.try_into().unwrap();
+            s.lock().unwrap().push((target, r.data.len() as u64, Instant::now()));
+            Ok(())
+        });
+
+        router.set_network_error(1, false);
+        router.set_network_error(2, false);
+
+        for id in [1, 2] {
+            router
+                .wait(&id, timeout())
+                .snapshot(
+                    LogId::new(CommittedLeaderId::new(1, 0), log_index),
+                    "learner received snapshot",
+                )
+                .await?;
+        }
+
+        leader
+            .wait(timeout())
+            .metrics(
+                |m| m.snapshot_streams.sending == 0 && m.snapshot_streams.queued == 0,
+                "no snapshot is sending",
+            )
+            .await?;
+    }
+
+    let sent = sent.lock().unwrap().clone();
+
+    tracing::info!(log_index, "--- snapshots are not interleaved");
+    {
+        let switches = sent.windows(2).filter(|w| w[0].0 != w[1].0).count();
+        assert_eq!(1, switches, "chunks are sent to one target after another: {:?}", sent);
+    }
+
+    tracing::info!(log_index, "--- snapshots are sent within the bandwidth limit");
+    {
+        let bytes: u64 = sent.iter().map(|x| x.1).sum();
+        let last_chunk = sent.last().unwrap().1;
+        let elapsed = sent.last().unwrap().2 - sent.first().unwrap().2;
+
+        let min_elapsed = Duration::from_secs_f64((bytes - last_chunk) as f64 / bytes_per_sec as f64);
+        assert!(
+            elapsed >= min_elapsed,
+            "{} bytes sent in {:?}, expect at least {:?}",
+            bytes,
+            elapsed,
+            min_elapsed
+        );
+    }
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(5_000))
+}
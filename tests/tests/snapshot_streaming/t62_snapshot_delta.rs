@@ -0,0 +1,219 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use maplit::btreeset;
+use openraft::CommittedLeaderId;
+use openraft::Config;
+use openraft::LogId;
+use openraft::RPCTypes;
+
+use crate::fixtures::init_default_ut_tracing;
+use crate::fixtures::RaftRouter;
+
+/// With `enable_snapshot_delta`, a leader sends a snapshot delta to a lagging learner that has
+/// installed an earlier snapshot.
+///
+/// - Bring up a leader and an isolated learner, build a snapshot and purge logs on the leader.
+/// - Restore the network: the learner receives a full snapshot.
+/// - Isolate the learner again, build another snapshot and purge logs on the leader.
+/// - Restore the network: the learner receives a delta upon the first snapshot, and its state
+///   machine is the same as the leader's.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn snapshot_delta() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            max_in_snapshot_log_to_keep: 0,
+            purge_batch_size: 1,
+            enable_snapshot_delta: true,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {1}).await?;
+
+    let learner = router.get_raft_handle(&1)?;
+
+    tracing::info!(
+        log_index,
+        "--- isolate learner, build snapshot on leader and purge logs"
+    );
+    {
+        router.set_network_error(1, true);
+
+        log_index += router.client_request_many(0, "0", 10).await?;
+        build_snapshot_and_purge(&router, log_index).await?;
+    }
+
+    tracing::info!(log_index, "--- restore network, learner receives a full snapshot");
+    {
+        router.set_network_error(1, false);
+
+        learner
+            .wait(timeout())
+            .snapshot(
+                LogId::new(CommittedLeaderId::new(1, 0), log_index),
+                "learner full snapshot",
+            )
+            .await?;
+        learner.wait(timeout()).applied_index(Some(log_index), "learner applied").await?;
+
+        let counts = router.get_rpc_count();
+        assert!(counts.get(&RPCTypes::InstallSnapshot).copied().unwrap_or_default() > 0);
+        assert_eq!(None, counts.get(&RPCTypes::SnapshotDelta));
+    }
+
+    let full_snapshot_rpcs = router.get_rpc_count()[&RPCTypes::InstallSnapshot];
+
+    tracing::info!(
+        log_index,
+        "--- isolate learner again, build another snapshot and purge logs"
+    );
+    {
+        router.set_network_error(1, true);
+
+        log_index += router.client_request_many(0, "1", 10).await?;
+        build_snapshot_and_purge(&router, log_index).await?;
+    }
+
+    tracing::info!(log_index, "--- restore network, learner receives a snapshot delta");
+    {
+        router.set_network_error(1, false);
+
+        learner
+            .wait(timeout())
+            .snapshot(LogId::new(CommittedLeaderId::new(1, 0), log_index), "learner delta")
+            .await?;
+
+        let counts = router.get_rpc_count();
+        assert_eq!(
+            full_snapshot_rpcs,
+            counts[&RPCTypes::InstallSnapshot],
+            "no full snapshot is sent"
+        );
+        assert!(counts.get(&RPCTypes::SnapshotDelta).copied().unwrap_or_default() > 0);
+
+        let (_, leader_sm) = router.get_storage_handle(&0)?;
+        let (_, learner_sm) = router.get_storage_handle(&1)?;
+        let want = leader_sm.get_state_machine().await;
+        let got = learner_sm.get_state_machine().await;
+
+        assert_eq!(want.last_applied_log, got.last_applied_log);
+        assert_eq!(want.client_status, got.client_status);
+        assert_eq!(want.client_serial_responses, got.client_serial_responses);
+    }
+
+    Ok(())
+}
+
+/// A leader sends a full snapshot at once if the learner rejects a delta because it built its own
+/// snapshot, which the leader does not have.
+///
+/// - The learner receives a full snapshot from the leader, then builds a snapshot of its own.
+/// - Isolate the learner, build another snapshot and purge logs on the leader.
+/// - Restore the network: the learner rejects the delta upon the first snapshot, and receives a
+///   full snapshot.
+#[async_entry::test(worker_threads = 8, init = "init_default_ut_tracing()", tracing_span = "debug")]
+async fn snapshot_delta_rejected() -> Result<()> {
+    let config = Arc::new(
+        Config {
+            max_in_snapshot_log_to_keep: 0,
+            purge_batch_size: 1,
+            enable_snapshot_delta: true,
+            ..Default::default()
+        }
+        .validate()?,
+    );
+
+    let mut router = RaftRouter::new(config.clone());
+    let mut log_index = router.new_cluster(btreeset! {0}, btreeset! {1}).await?;
+
+    let learner = router.get_raft_handle(&1)?;
+
+    tracing::info!(log_index, "--- learner receives a full snapshot");
+    {
+        router.set_network_error(1, true);
+
+        log_index += router.client_request_many(0, "0", 10).await?;
+        build_snapshot_and_purge(&router, log_index).await?;
+
+        router.set_network_error(1, false);
+
+        learner
+            .wait(timeout())
+            .snapshot(
+                LogId::new(CommittedLeaderId::new(1, 0), log_index),
+                "learner full snapshot",
+            )
+            .await?;
+    }
+
+    tracing::info!(log_index, "--- learner builds its own snapshot");
+    {
+        log_index += router.client_request_many(0, "1", 5).await?;
+        learner.wait(timeout()).applied_index(Some(log_index), "learner applied").await?;
+
+        learner.trigger().snapshot().await?;
+        learner
+            .wait(timeout())
+            .snapshot(
+                LogId::new(CommittedLeaderId::new(1, 0), log_index),
+                "learner built snapshot",
+            )
+            .await?;
+    }
+
+    let full_snapshot_rpcs = router.get_rpc_count()[&RPCTypes::InstallSnapshot];
+
+    tracing::info!(log_index, "--- isolate learner, build another snapshot and purge logs");
+    {
+        router.set_network_error(1, true);
+
+        log_index += router.client_request_many(0, "2", 10).await?;
+        build_snapshot_and_purge(&router, log_index).await?;
+    }
+
+    tracing::info!(
+        log_index,
+        "--- restore network, learner rejects the delta and receives a full snapshot"
+    );
+    {
+        router.set_network_error(1, false);
+
+        learner
+            .wait(timeout())
+            .snapshot(
+                LogId::new(CommittedLeaderId::new(1, 0), log_index),
+                "learner full snapshot",
+            )
+            .await?;
+
+        let counts = router.get_rpc_count();
+        assert!(counts[&RPCTypes::SnapshotDelta] > 0);
+        assert!(counts[&RPCTypes::InstallSnapshot] > full_snapshot_rpcs);
+    }
+
+    Ok(())
+}
+
+async fn build_snapshot_and_purge(router: &RaftRouter, log_index: u64) -> Result<()> {
+    let leader = router.get_raft_handle(&0)?;
+
+    leader.trigger().snapshot().await?;
+    leader
+        .wait(timeout())
+        .snapshot(LogId::new(CommittedLeaderId::new(1, 0), log_index), "built snapshot")
+        .await?;
+    leader
+        .wait(timeout())
+        .purged(Some(LogId::new(CommittedLeaderId::new(1, 0), log_index)), "purged")
+        .await?;
+
+    Ok(())
+}
+
+fn timeout() -> Option<Duration> {
+    Some(Duration::from_millis(5_000))
+}